@@ -27,6 +27,8 @@ mod l1_mock_determinism {
             schema_name: None,
             json_schema: None,
             constraints: Default::default(),
+            template: None,
+            tool: None,
         }
     }
 
@@ -84,6 +86,8 @@ mod l1_mock_determinism {
             schema_name: None,
             json_schema: None,
             constraints: Default::default(),
+            template: None,
+            tool: None,
         };
         let mut hashes = Vec::new();
         for _ in 0..100 {
@@ -109,6 +113,8 @@ mod l1_mock_determinism {
             schema_name: Some("Test".into()),
             json_schema: Some(serde_json::json!({"type": "object"})),
             constraints: Default::default(),
+            template: None,
+            tool: None,
         };
         let r1 = p.generate(req.clone()).unwrap();
         let r2 = p.generate(req).unwrap();
@@ -126,7 +132,7 @@ mod l2_mock_boundary {
         CompletionRequest {
             run_id: "b".into(), seed, system_prompt: "S".into(),
             user_prompt: "U".into(), temperature: temp, max_tokens: 100,
-            schema_name: None, json_schema: None, constraints: Default::default(),
+            schema_name: None, json_schema: None, constraints: Default::default(), template: None, tool: None,
         }
     }
 
@@ -166,7 +172,7 @@ mod l2_mock_boundary {
         let r = CompletionRequest {
             run_id: "e".into(), seed: 42, system_prompt: "".into(),
             user_prompt: "".into(), temperature: 0.0, max_tokens: 100,
-            schema_name: None, json_schema: None, constraints: Default::default(),
+            schema_name: None, json_schema: None, constraints: Default::default(), template: None, tool: None,
         };
         assert!(p.generate(r).is_ok());
         println!("âœ… L2-005: Empty prompts OK");
@@ -179,7 +185,7 @@ mod l2_mock_boundary {
         let r = CompletionRequest {
             run_id: "l".into(), seed: 42, system_prompt: big.clone(),
             user_prompt: big, temperature: 0.0, max_tokens: 100,
-            schema_name: None, json_schema: None, constraints: Default::default(),
+            schema_name: None, json_schema: None, constraints: Default::default(), template: None, tool: None,
         };
         assert!(p.generate(r).is_ok());
         println!("âœ… L2-006: 100KB OK");
@@ -204,7 +210,7 @@ mod l3_mock_chaos {
                 let req = CompletionRequest {
                     run_id: "c".into(), seed: 42, system_prompt: "S".into(),
                     user_prompt: "U".into(), temperature: 0.0, max_tokens: 100,
-                    schema_name: None, json_schema: None, constraints: Default::default(),
+                    schema_name: None, json_schema: None, constraints: Default::default(), template: None, tool: None,
                 };
                 let res = pp.generate(req).unwrap();
                 rr.lock().unwrap().push(res.response_hash);
@@ -232,7 +238,7 @@ mod l3_mock_chaos {
                 let req = CompletionRequest {
                     run_id: format!("s{}", seed), seed, system_prompt: "S".into(),
                     user_prompt: "U".into(), temperature: 0.0, max_tokens: 100,
-                    schema_name: None, json_schema: None, constraints: Default::default(),
+                    schema_name: None, json_schema: None, constraints: Default::default(), template: None, tool: None,
                 };
                 let res = pp.generate(req).unwrap();
                 rr.lock().unwrap().insert(seed, res.response_hash);
@@ -255,7 +261,7 @@ mod l3_mock_chaos {
             let req = CompletionRequest {
                 run_id: format!("r{}", i), seed: i, system_prompt: "S".into(),
                 user_prompt: "U".into(), temperature: 0.0, max_tokens: 100,
-                schema_name: None, json_schema: None, constraints: Default::default(),
+                schema_name: None, json_schema: None, constraints: Default::default(), template: None, tool: None,
             };
             p.generate(req).unwrap();
         }
@@ -276,7 +282,7 @@ mod l4_mock_differential {
             system_prompt: "You are OMEGA".into(),
             user_prompt: "Analyze emotions".into(),
             temperature: 0.0, max_tokens: 100,
-            schema_name: None, json_schema: None, constraints: Default::default(),
+            schema_name: None, json_schema: None, constraints: Default::default(), template: None, tool: None,
         };
         let res = p.generate(req).unwrap();
         
@@ -309,7 +315,7 @@ mod l4_mock_differential {
         let req = CompletionRequest {
             run_id: "d".into(), seed: 42, system_prompt: "S".into(),
             user_prompt: "U".into(), temperature: 0.0, max_tokens: 100,
-            schema_name: None, json_schema: None, constraints: Default::default(),
+            schema_name: None, json_schema: None, constraints: Default::default(), template: None, tool: None,
         };
         let r1 = p.generate(req.clone()).unwrap();
         let r2 = p.generate(req).unwrap();
@@ -0,0 +1,283 @@
+//! OMEGA Async — exécution asynchrone, annulable et bornée des appels provider
+//! NASA-Grade: tout appel a une échéance et peut être annulé; une annulation n'est jamais comptée comme une panne
+//!
+//! Les providers HTTP implémentent `AsyncLLMProvider` (reqwest async, backoff tokio). Les appelants synchrones
+//! (PipelineRunner, analyseurs, tests) passent par le shim `generate_blocking`, qui applique le `CallControl`
+//! ambiant posé par `CallControl::scope` (commandes Tauri): l'annulation traverse ainsi les couches
+//! synchrones (cache, ledger, fallback) sans changer leurs signatures. Les streams lisent le corps asynchrone
+//! via `open_stream`, sous les mêmes échéances.
+
+use crate::ai::interface::LLMProvider;
+use crate::ai::models::*;
+use crate::ai::stream::CancelToken;
+use std::io::BufReader;
+use crate::error::{OmegaError, OmegaResult};
+use std::cell::RefCell;
+use std::env;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::Poll;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// Intervalle de scrutation du jeton d'annulation pendant un appel
+const CANCEL_POLL: Duration = Duration::from_millis(20);
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// CONTRÔLE D'APPEL
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Annulation + échéances d'une opération (une analyse, un stream)
+#[derive(Debug, Clone, Default)]
+pub struct CallControl {
+    pub cancel: CancelToken,
+    /// Échéance de toute l'opération
+    pub deadline: Option<Instant>,
+    /// Durée max de chaque appel provider (retries compris); dépassée → échec du provider, pas annulation
+    pub call_timeout: Option<Duration>,
+}
+
+thread_local! {
+    static CURRENT: RefCell<Option<CallControl>> = const { RefCell::new(None) };
+}
+
+impl CallControl {
+    pub fn new() -> Self { Self::default() }
+
+    /// OMEGA_AI_CALL_TIMEOUT_MS: durée max par appel; OMEGA_AI_DEADLINE_MS: échéance de l'opération (à partir de maintenant)
+    pub fn from_env() -> Self {
+        Self {
+            call_timeout: env_millis("OMEGA_AI_CALL_TIMEOUT_MS"),
+            deadline: env_millis("OMEGA_AI_DEADLINE_MS").map(|d| Instant::now() + d),
+            ..Self::default()
+        }
+    }
+
+    pub fn with_cancel(self, cancel: CancelToken) -> Self { Self { cancel, ..self } }
+    pub fn with_deadline(self, deadline: Instant) -> Self { Self { deadline: Some(deadline), ..self } }
+    pub fn with_timeout(self, timeout: Duration) -> Self { self.with_deadline(Instant::now() + timeout) }
+    pub fn with_call_timeout(self, timeout: Duration) -> Self { Self { call_timeout: Some(timeout), ..self } }
+
+    /// Erreur typée si annulé ou échu
+    pub fn check(&self) -> OmegaResult<()> {
+        if self.cancel.is_cancelled() { return Err(OmegaError::Cancelled("CALL_CANCELLED".into())); }
+        if self.deadline.is_some_and(|d| Instant::now() >= d) { return Err(OmegaError::Cancelled("DEADLINE_EXCEEDED".into())); }
+        Ok(())
+    }
+
+    /// Exécute `fut` jusqu'à son terme, l'annulation ou l'échéance (le futur abandonné ferme la connexion HTTP).
+    /// Annulation et échéance de l'opération → Cancelled (tout s'arrête); timeout d'appel → ProviderError (le fallback bascule)
+    pub async fn run<T>(&self, fut: impl Future<Output = OmegaResult<T>>) -> OmegaResult<T> {
+        self.run_until(self.call_timeout.map(|t| Instant::now() + t), fut).await
+    }
+
+    /// `run` avec une fin d'appel fixée par l'appelant (stream: comptée depuis l'envoi, pas depuis chaque chunk)
+    pub async fn run_until<T>(&self, call_end: Option<Instant>, fut: impl Future<Output = OmegaResult<T>>) -> OmegaResult<T> {
+        self.check()?;
+        let per_call = call_end.filter(|c| self.deadline.is_none_or(|d| *c < d));
+        let stop = async {
+            let expired = async {
+                match per_call.or(self.deadline) {
+                    Some(d) => tokio::time::sleep_until(d.into()).await,
+                    None => std::future::pending().await,
+                }
+            };
+            let mut expired = std::pin::pin!(expired);
+            loop {
+                if self.cancel.is_cancelled() { return OmegaError::Cancelled("CALL_CANCELLED".into()); }
+                if tokio::time::timeout(CANCEL_POLL, &mut expired).await.is_ok() {
+                    return match (per_call, self.call_timeout) {
+                        (Some(_), Some(t)) => OmegaError::ProviderError(format!("CALL_TIMEOUT: {}ms", t.as_millis())),
+                        _ => OmegaError::Cancelled("DEADLINE_EXCEEDED".into()),
+                    };
+                }
+            }
+        };
+        let mut fut = std::pin::pin!(fut);
+        let mut stop = std::pin::pin!(stop);
+        std::future::poll_fn(|cx| {
+            if let Poll::Ready(result) = fut.as_mut().poll(cx) { return Poll::Ready(result); }
+            stop.as_mut().poll(cx).map(Err)
+        }).await
+    }
+
+    /// Pose `self` comme contrôle ambiant du thread pendant `f` (restauré ensuite, même en cas de panique)
+    pub fn scope<R>(&self, f: impl FnOnce() -> R) -> R {
+        struct Restore(Option<CallControl>);
+        impl Drop for Restore {
+            fn drop(&mut self) { CURRENT.with(|c| *c.borrow_mut() = self.0.take()); }
+        }
+        let _restore = Restore(CURRENT.with(|c| c.borrow_mut().replace(self.clone())));
+        f()
+    }
+
+    /// Contrôle ambiant du thread (par défaut: OMEGA_AI_CALL_TIMEOUT_MS seul)
+    pub fn current() -> Self {
+        CURRENT.with(|c| c.borrow().clone()).unwrap_or_else(|| Self { call_timeout: env_millis("OMEGA_AI_CALL_TIMEOUT_MS"), ..Self::default() })
+    }
+}
+
+fn env_millis(key: &str) -> Option<Duration> {
+    env::var(key).ok().and_then(|v| v.parse::<u64>().ok()).filter(|ms| *ms > 0).map(Duration::from_millis)
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// PROVIDER ASYNCHRONE
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Variante asynchrone de `LLMProvider::generate`: l'appel s'arrête (connexion abandonnée) dès annulation ou échéance
+pub trait AsyncLLMProvider: LLMProvider {
+    fn generate_async<'a>(&'a self, req: CompletionRequest, ctl: &'a CallControl) -> BoxFuture<'a, OmegaResult<CompletionResponse>>;
+}
+
+impl AsyncLLMProvider for crate::ai::MockDeterministicProvider {
+    fn generate_async<'a>(&'a self, req: CompletionRequest, ctl: &'a CallControl) -> BoxFuture<'a, OmegaResult<CompletionResponse>> {
+        Box::pin(ctl.run(async move { self.generate(req) }))
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// SHIM BLOQUANT
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Runtime partagé des appels synchrones
+fn runtime() -> &'static tokio::runtime::Runtime {
+    static RUNTIME: OnceLock<tokio::runtime::Runtime> = OnceLock::new();
+    RUNTIME.get_or_init(|| {
+        tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(2)
+            .thread_name("omega-ai")
+            .enable_all()
+            .build()
+            .expect("OMEGA_ASYNC: tokio runtime")
+    })
+}
+
+/// Attend `fut` depuis du code synchrone (hors runtime, ou depuis un thread d'un runtime tokio/Tauri)
+pub fn block_on<F>(fut: F) -> F::Output
+where
+    F: Future + Send,
+    F::Output: Send,
+{
+    if tokio::runtime::Handle::try_current().is_ok() {
+        // Déjà dans un contexte tokio: un runtime ne peut pas en bloquer un autre sur le même thread
+        std::thread::scope(|s| s.spawn(|| runtime().block_on(fut)).join().expect("OMEGA_ASYNC: blocking call panicked"))
+    } else {
+        runtime().block_on(fut)
+    }
+}
+
+/// Shim de `LLMProvider::generate` pour un provider asynchrone, sous le contrôle ambiant
+pub fn generate_blocking(provider: &dyn AsyncLLMProvider, req: CompletionRequest) -> OmegaResult<CompletionResponse> {
+    let ctl = CallControl::current();
+    block_on(provider.generate_async(req, &ctl))
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// STREAMS
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Corps d'une réponse asynchrone lu en synchrone (`drive_sse`): chaque lecture sous le contrôle d'appel
+pub struct BlockingBody {
+    resp: reqwest::Response,
+    ctl: CallControl,
+    call_end: Option<Instant>,
+    buf: Vec<u8>,
+    pos: usize,
+}
+
+impl std::io::Read for BlockingBody {
+    fn read(&mut self, out: &mut [u8]) -> std::io::Result<usize> {
+        while self.pos >= self.buf.len() {
+            let Self { resp, ctl, call_end, .. } = self;
+            let read = async { resp.chunk().await.map_err(|e| OmegaError::ProviderError(format!("STREAM_READ: {}", e))) };
+            // L'erreur OMEGA (annulation, échéance) traverse io::Error jusqu'à drive_sse
+            match block_on(ctl.run_until(*call_end, read)).map_err(std::io::Error::other)? {
+                Some(chunk) => { self.buf = chunk.to_vec(); self.pos = 0; }
+                None => return Ok(0),
+            }
+        }
+        let n = out.len().min(self.buf.len() - self.pos);
+        out[..n].copy_from_slice(&self.buf[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+/// Ouvre un stream HTTP: envoi (retries) puis lecture bornés par l'échéance et le timeout d'appel ambiants,
+/// annulés par le jeton du stream
+pub fn open_stream(cancel: &CancelToken, send: impl Future<Output = OmegaResult<reqwest::Response>> + Send) -> OmegaResult<BufReader<BlockingBody>> {
+    cancel.check()?;
+    let ambient = CallControl::current();
+    ambient.check()?;
+    let ctl = ambient.with_cancel(cancel.clone());
+    let call_end = ctl.call_timeout.map(|t| Instant::now() + t);
+    let resp = block_on(ctl.run_until(call_end, send))?;
+    Ok(BufReader::new(BlockingBody { resp, ctl, call_end, buf: Vec::new(), pos: 0 }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::MockDeterministicProvider;
+
+    /// Provider asynchrone qui ne répond qu'après `delay`
+    struct Slow { delay: Duration }
+
+    impl LLMProvider for Slow {
+        fn id(&self) -> ProviderId { "slow".into() }
+        fn capabilities(&self) -> ProviderCapabilities { MockDeterministicProvider::default().capabilities() }
+        fn generate(&self, req: CompletionRequest) -> OmegaResult<CompletionResponse> { generate_blocking(self, req) }
+        fn embed(&self, _req: EmbeddingRequest) -> OmegaResult<EmbeddingResponse> { Err(OmegaError::NotSupported("embed".into())) }
+        fn health(&self) -> bool { true }
+    }
+
+    impl AsyncLLMProvider for Slow {
+        fn generate_async<'a>(&'a self, req: CompletionRequest, ctl: &'a CallControl) -> BoxFuture<'a, OmegaResult<CompletionResponse>> {
+            Box::pin(ctl.run(async move {
+                tokio::time::sleep(self.delay).await;
+                MockDeterministicProvider::default().generate(req)
+            }))
+        }
+    }
+
+    fn req() -> CompletionRequest {
+        CompletionRequest { run_id: "t".into(), seed: 1, system_prompt: "S".into(), user_prompt: "U".into(), temperature: 0.0, max_tokens: 10, schema_name: None, json_schema: None, constraints: Default::default(), template: None, tool: None }
+    }
+
+    #[test]
+    fn test_deadline_and_cancel() {
+        let slow = Slow { delay: Duration::from_secs(30) };
+        let start = Instant::now();
+        let ctl = CallControl::new().with_call_timeout(Duration::from_millis(50));
+        assert!(matches!(block_on(slow.generate_async(req(), &ctl)), Err(OmegaError::ProviderError(m)) if m == "CALL_TIMEOUT: 50ms"));
+        let ctl = CallControl::new().with_timeout(Duration::from_millis(50)).with_call_timeout(Duration::from_secs(10));
+        assert!(matches!(block_on(slow.generate_async(req(), &ctl)), Err(OmegaError::Cancelled(m)) if m == "DEADLINE_EXCEEDED"));
+        assert!(matches!(ctl.check(), Err(OmegaError::Cancelled(_))));
+
+        let ctl = CallControl::new();
+        let cancel = ctl.cancel.clone();
+        std::thread::spawn(move || { std::thread::sleep(Duration::from_millis(50)); cancel.cancel(); });
+        assert!(matches!(block_on(slow.generate_async(req(), &ctl)), Err(OmegaError::Cancelled(m)) if m == "CALL_CANCELLED"));
+        assert!(start.elapsed() < Duration::from_secs(5));
+
+        let fast = Slow { delay: Duration::ZERO };
+        assert!(generate_blocking(&fast, req()).is_ok());
+    }
+
+    #[test]
+    fn test_ambient_scope_reaches_blocking_shim() {
+        let ctl = CallControl::new();
+        ctl.cancel.cancel();
+        let fast = Slow { delay: Duration::ZERO };
+        assert!(matches!(ctl.scope(|| fast.generate(req())), Err(OmegaError::Cancelled(m)) if m == "CALL_CANCELLED"));
+        assert!(fast.generate(req()).is_ok(), "scope restored");
+
+        // Le shim fonctionne aussi depuis un thread du runtime (commandes Tauri async)
+        let from_runtime = block_on(async { tokio::task::spawn_blocking(|| Slow { delay: Duration::ZERO }.generate(req())).await });
+        assert!(from_runtime.unwrap().is_ok());
+        assert!(block_on(async { Slow { delay: Duration::ZERO }.generate(req()) }).is_ok());
+    }
+}
@@ -0,0 +1,514 @@
+//! OMEGA Completion Cache — cache disque adressé par contenu
+//! NASA-Grade: clé = sha256(JSON canonique de la requête), intégrité vérifiée à chaque lecture
+
+use crate::ai::interface::LLMProvider;
+use crate::ai::models::*;
+use crate::ai::stream::{CancelToken, StreamDelta};
+use crate::error::{OmegaError, OmegaResult};
+use crate::pipeline::fs_utils::{canonicalize_json, ensure_dir, read_json, sha256_str, write_json};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// 2: max_tokens dans la clé (les entrées v1 ne sont plus lues)
+pub const CACHE_SCHEMA_VERSION: u32 = 2;
+
+/// Mode du cache
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CacheMode {
+    /// Pas de cache
+    Off,
+    /// Lecture + écriture (défaut)
+    ReadWrite,
+    /// Lecture seule: un miss échoue au lieu d'appeler le réseau
+    Offline,
+}
+
+impl CacheMode {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "off" | "none" | "disabled" => Some(CacheMode::Off),
+            "rw" | "readwrite" | "read-write" | "on" => Some(CacheMode::ReadWrite),
+            "offline" | "readonly" | "read-only" => Some(CacheMode::Offline),
+            _ => None,
+        }
+    }
+}
+
+/// Politique: emplacement, mode, durée de vie, taille maximale
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachePolicy {
+    pub dir: PathBuf,
+    pub mode: CacheMode,
+    /// Durée de vie en secondes (None = illimitée)
+    pub ttl_secs: Option<u64>,
+    /// Taille totale maximale (octets); les entrées les plus anciennes sont évincées
+    pub max_bytes: u64,
+}
+
+impl CachePolicy {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into(), mode: CacheMode::ReadWrite, ttl_secs: Some(30 * 24 * 3600), max_bytes: 256 * 1024 * 1024 }
+    }
+    /// OMEGA_CACHE (off|readwrite|offline), OMEGA_CACHE_DIR, OMEGA_CACHE_TTL_SECS, OMEGA_CACHE_MAX_BYTES
+    pub fn from_env(default_dir: impl Into<PathBuf>) -> Self {
+        let var = |k: &str| std::env::var(k).ok().filter(|v| !v.is_empty());
+        let mut policy = Self::new(var("OMEGA_CACHE_DIR").map(PathBuf::from).unwrap_or_else(|| default_dir.into()));
+        if let Some(mode) = var("OMEGA_CACHE").and_then(|m| CacheMode::parse(&m)) { policy.mode = mode; }
+        if let Some(ttl) = var("OMEGA_CACHE_TTL_SECS").and_then(|v| v.parse::<u64>().ok()) { policy.ttl_secs = if ttl == 0 { None } else { Some(ttl) }; }
+        if let Some(max) = var("OMEGA_CACHE_MAX_BYTES").and_then(|v| v.parse().ok()) { policy.max_bytes = max; }
+        policy
+    }
+}
+
+/// Matériau de la clé: tout ce qui détermine la réponse (les `constraints`, métadonnées jamais envoyées au provider, n'en font pas partie)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CacheKeyMaterial {
+    pub provider_id: ProviderId,
+    pub model: Option<String>,
+    pub system_prompt: String,
+    pub user_prompt: String,
+    pub seed: u64,
+    /// Texte (f32 non canonique en JSON)
+    pub temperature: String,
+    /// Une réponse tronquée à 16 tokens ne sert pas une demande à 4096
+    #[serde(default)]
+    pub max_tokens: u32,
+    pub schema_name: Option<String>,
+    pub json_schema: Option<serde_json::Value>,
+    /// Absent des clés hors appel d'outil (clés antérieures inchangées)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool: Option<ToolSpec>,
+}
+
+impl CacheKeyMaterial {
+    pub fn new(provider_id: &str, model: Option<String>, req: &CompletionRequest) -> Self {
+        Self {
+            provider_id: provider_id.into(),
+            model,
+            system_prompt: req.system_prompt.clone(),
+            user_prompt: req.user_prompt.clone(),
+            seed: req.seed,
+            temperature: format!("{}", req.temperature),
+            max_tokens: req.max_tokens,
+            schema_name: req.schema_name.clone(),
+            json_schema: req.json_schema.clone(),
+            tool: req.tool.clone(),
+        }
+    }
+    pub fn key(&self) -> String {
+        sha256_str(&canonicalize_json(&serde_json::to_value(self).unwrap_or_default()))
+    }
+}
+
+/// Fichier d'entrée: <dir>/<key>.json
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheEntry {
+    pub schema_version: u32,
+    pub key: String,
+    pub created_at: String,
+    pub created_unix: u64,
+    pub request: CacheKeyMaterial,
+    pub response: CompletionResponse,
+    /// sha256 du JSON canonique de `response`
+    pub integrity: String,
+}
+
+impl CacheEntry {
+    fn integrity_of(response: &CompletionResponse) -> String {
+        sha256_str(&canonicalize_json(&serde_json::to_value(response).unwrap_or_default()))
+    }
+    /// Vérifie clé (recalculée depuis la requête) et intégrité de la réponse
+    pub fn verify(&self) -> OmegaResult<()> {
+        if self.schema_version != CACHE_SCHEMA_VERSION {
+            return Err(OmegaError::HashMismatch(format!("CACHE_SCHEMA: {} != {}", self.schema_version, CACHE_SCHEMA_VERSION)));
+        }
+        if self.request.key() != self.key {
+            return Err(OmegaError::HashMismatch(format!("CACHE_KEY: {}", self.key)));
+        }
+        if Self::integrity_of(&self.response) != self.integrity {
+            return Err(OmegaError::HashMismatch(format!("CACHE_INTEGRITY: {}", self.key)));
+        }
+        Ok(())
+    }
+}
+
+/// Résumé pour la commande list
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheEntrySummary {
+    pub key: String,
+    pub provider_id: String,
+    pub model: Option<String>,
+    pub created_at: String,
+    pub age_secs: u64,
+    pub bytes: u64,
+    pub expired: bool,
+    pub valid: bool,
+}
+
+/// Portée d'une purge
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PurgeScope {
+    All,
+    Expired,
+    /// Entrées corrompues (clé ou intégrité invalide)
+    Invalid,
+    Key(String),
+}
+
+fn now_unix() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+fn valid_key(key: &str) -> bool {
+    key.len() == 64 && key.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Cache disque des completions
+#[derive(Debug, Clone)]
+pub struct CompletionCache {
+    policy: CachePolicy,
+}
+
+impl CompletionCache {
+    pub fn new(policy: CachePolicy) -> Self { Self { policy } }
+    pub fn policy(&self) -> &CachePolicy { &self.policy }
+
+    fn path(&self, key: &str) -> PathBuf { self.policy.dir.join(format!("{}.json", key)) }
+
+    fn is_expired(&self, entry: &CacheEntry, now: u64) -> bool {
+        self.policy.ttl_secs.map(|ttl| now.saturating_sub(entry.created_unix) > ttl).unwrap_or(false)
+    }
+
+    /// Lit une entrée valide et non expirée; entrée corrompue = erreur HashMismatch
+    pub fn get(&self, key: &str) -> OmegaResult<Option<CacheEntry>> {
+        let path = self.path(key);
+        if !valid_key(key) || !path.exists() { return Ok(None); }
+        let entry: CacheEntry = read_json(&path)?;
+        entry.verify()?;
+        if self.is_expired(&entry, now_unix()) { return Ok(None); }
+        Ok(Some(entry))
+    }
+
+    /// Écrit une entrée puis applique la limite de taille
+    pub fn put(&self, request: CacheKeyMaterial, response: &CompletionResponse) -> OmegaResult<String> {
+        ensure_dir(&self.policy.dir)?;
+        let key = request.key();
+        let entry = CacheEntry {
+            schema_version: CACHE_SCHEMA_VERSION,
+            key: key.clone(),
+            created_at: chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Nanos, true),
+            created_unix: now_unix(),
+            request,
+            integrity: CacheEntry::integrity_of(response),
+            response: response.clone(),
+        };
+        write_json(&self.path(&key), &entry)?;
+        self.enforce_size_limit(&key)?;
+        Ok(key)
+    }
+
+    /// Entrée complète (commande inspect), intégrité vérifiée
+    pub fn inspect(&self, key: &str) -> OmegaResult<CacheEntry> {
+        if !valid_key(key) { return Err(OmegaError::ConfigError(format!("Invalid cache key: {}", key))); }
+        let entry: CacheEntry = read_json(&self.path(key))?;
+        entry.verify()?;
+        Ok(entry)
+    }
+
+    /// Toutes les entrées, plus récentes en premier (entrées illisibles en dernier)
+    pub fn list(&self) -> OmegaResult<Vec<CacheEntrySummary>> {
+        let now = now_unix();
+        let mut out: Vec<CacheEntrySummary> = self.files()?.into_iter().map(|(key, path, bytes)| {
+            match read_json::<CacheEntry>(&path) {
+                Ok(entry) => CacheEntrySummary {
+                    valid: entry.verify().is_ok(),
+                    expired: self.is_expired(&entry, now),
+                    age_secs: now.saturating_sub(entry.created_unix),
+                    provider_id: entry.request.provider_id,
+                    model: entry.request.model,
+                    created_at: entry.created_at,
+                    key,
+                    bytes,
+                },
+                Err(_) => CacheEntrySummary { key, provider_id: String::new(), model: None, created_at: String::new(), age_secs: 0, bytes, expired: false, valid: false },
+            }
+        }).collect();
+        out.sort_by(|a, b| b.created_at.cmp(&a.created_at).then_with(|| a.key.cmp(&b.key)));
+        Ok(out)
+    }
+
+    /// Supprime les entrées de la portée; retourne le nombre supprimé
+    pub fn purge(&self, scope: &PurgeScope) -> OmegaResult<usize> {
+        let doomed: Vec<String> = match scope {
+            PurgeScope::Key(key) => if self.path(key).exists() && valid_key(key) { vec![key.clone()] } else { vec![] },
+            _ => self.list()?.into_iter().filter(|e| match scope {
+                PurgeScope::All => true,
+                PurgeScope::Expired => e.expired,
+                PurgeScope::Invalid => !e.valid,
+                PurgeScope::Key(_) => false,
+            }).map(|e| e.key).collect(),
+        };
+        for key in &doomed {
+            fs::remove_file(self.path(key)).map_err(|e| OmegaError::WriteError(e.to_string()))?;
+        }
+        Ok(doomed.len())
+    }
+
+    /// Taille totale sur disque
+    pub fn total_bytes(&self) -> OmegaResult<u64> {
+        Ok(self.files()?.iter().map(|(_, _, b)| b).sum())
+    }
+
+    fn files(&self) -> OmegaResult<Vec<(String, PathBuf, u64)>> {
+        if !self.policy.dir.exists() { return Ok(vec![]); }
+        let mut out = Vec::new();
+        for item in fs::read_dir(&self.policy.dir)? {
+            let path = item?.path();
+            let key = path.file_stem().and_then(|s| s.to_str()).unwrap_or_default().to_string();
+            if path.extension().and_then(|e| e.to_str()) == Some("json") && valid_key(&key) {
+                let bytes = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+                out.push((key, path, bytes));
+            }
+        }
+        Ok(out)
+    }
+
+    /// Évince les entrées les plus anciennes (sauf `keep`) tant que la taille dépasse max_bytes
+    fn enforce_size_limit(&self, keep: &str) -> OmegaResult<()> {
+        let mut entries = self.list()?;
+        let mut total: u64 = entries.iter().map(|e| e.bytes).sum();
+        entries.retain(|e| e.key != keep);
+        while total > self.policy.max_bytes {
+            let Some(oldest) = entries.pop() else { break };
+            fs::remove_file(self.path(&oldest.key)).map_err(|e| OmegaError::WriteError(e.to_string()))?;
+            total -= oldest.bytes;
+        }
+        Ok(())
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// CACHED PROVIDER
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Provider avec cache disque; `CompletionResponse.cache` indique hit/miss
+pub struct CachedProvider {
+    inner: Arc<dyn LLMProvider>,
+    cache: CompletionCache,
+    stats: Mutex<(u32, u32)>,
+}
+
+impl CachedProvider {
+    pub fn new(inner: Arc<dyn LLMProvider>, policy: CachePolicy) -> Self {
+        Self { inner, cache: CompletionCache::new(policy), stats: Mutex::new((0, 0)) }
+    }
+    pub fn cache(&self) -> &CompletionCache { &self.cache }
+    /// (hits, misses) depuis la création
+    pub fn stats(&self) -> (u32, u32) { *self.stats.lock().unwrap() }
+
+    fn material(&self, req: &CompletionRequest) -> CacheKeyMaterial {
+        CacheKeyMaterial::new(&self.inner.id(), self.inner.model(), req)
+    }
+
+    /// Hit éventuel; entrée corrompue = miss (mode rw) ou erreur (mode offline)
+    fn lookup(&self, material: &CacheKeyMaterial) -> OmegaResult<Option<CompletionResponse>> {
+        let key = material.key();
+        let found = match self.cache.get(&key) {
+            Ok(found) => found,
+            Err(e) if self.cache.policy.mode == CacheMode::Offline => return Err(e),
+            Err(e) => { eprintln!("[CACHE] Ignoring corrupt entry {}: {}", key, e); None }
+        };
+        let mut stats = self.stats.lock().unwrap();
+        match found {
+            Some(entry) => {
+                stats.0 += 1;
+                Ok(Some(CompletionResponse { cache: Some(CacheOutcome::Hit), ..entry.response }))
+            }
+            None if self.cache.policy.mode == CacheMode::Offline => Err(OmegaError::ProviderError(format!("OFFLINE_CACHE_MISS: {}", key))),
+            None => { stats.1 += 1; Ok(None) }
+        }
+    }
+
+    /// Stocke la réponse, sauf réponse du mock de secours derrière un provider réel
+    /// ou d'un maillon de bascule: la clé est celle du premier maillon, pas du provider qui a répondu
+    fn store(&self, material: CacheKeyMaterial, mut resp: CompletionResponse) -> OmegaResult<CompletionResponse> {
+        let degraded = resp.provider_id.starts_with("mock") && !self.inner.id().starts_with("mock");
+        let fallback_used = resp.fallback.as_ref().is_some_and(|f| f.fallback_used);
+        if !degraded && !fallback_used {
+            // La trace de bascule décrit l'appel d'origine, pas un hit futur
+            self.cache.put(material, &CompletionResponse { cache: None, fallback: None, ..resp.clone() })?;
+        }
+        resp.cache = Some(CacheOutcome::Miss);
+        Ok(resp)
+    }
+}
+
+impl LLMProvider for CachedProvider {
+    fn id(&self) -> ProviderId { self.inner.id() }
+    fn model(&self) -> Option<String> { self.inner.model() }
+    fn capabilities(&self) -> ProviderCapabilities { self.inner.capabilities() }
+    fn health(&self) -> bool { self.cache.policy.mode == CacheMode::Offline || self.inner.health() }
+    fn is_local(&self) -> bool { self.inner.is_local() }
+    fn reachable_models(&self) -> Vec<(ProviderId, Option<String>)> { self.inner.reachable_models() }
+    fn embed(&self, req: EmbeddingRequest) -> OmegaResult<EmbeddingResponse> { self.inner.embed(req) }
+    fn embed_batch(&self, reqs: Vec<EmbeddingRequest>) -> OmegaResult<Vec<EmbeddingResponse>> { self.inner.embed_batch(reqs) }
+
+    fn generate(&self, req: CompletionRequest) -> OmegaResult<CompletionResponse> {
+        if self.cache.policy.mode == CacheMode::Off { return self.inner.generate(req); }
+        let material = self.material(&req);
+        if let Some(hit) = self.lookup(&material)? { return Ok(hit); }
+        let resp = self.inner.generate(req)?;
+        self.store(material, resp)
+    }
+
+    fn generate_stream(&self, req: CompletionRequest, on_delta: &mut dyn FnMut(StreamDelta), cancel: &CancelToken) -> OmegaResult<CompletionResponse> {
+        if self.cache.policy.mode == CacheMode::Off { return self.inner.generate_stream(req, on_delta, cancel); }
+        let material = self.material(&req);
+        if let Some(hit) = self.lookup(&material)? {
+            cancel.check()?;
+            if !hit.content.is_empty() { on_delta(StreamDelta { index: 0, text: hit.content.clone() }); }
+            return Ok(hit);
+        }
+        let resp = self.inner.generate_stream(req, on_delta, cancel)?;
+        self.store(material, resp)
+    }
+}
+
+/// Répertoire de cache d'un chemin de sortie (omega-ui-output/ai-cache)
+pub fn default_cache_dir(output_dir: &Path) -> PathBuf { output_dir.join("ai-cache") }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::MockDeterministicProvider;
+
+    fn temp_policy() -> CachePolicy {
+        CachePolicy::new(std::env::temp_dir().join(format!("omega-cache-{}", uuid::Uuid::new_v4())))
+    }
+
+    fn req(prompt: &str) -> CompletionRequest {
+        CompletionRequest {
+            run_id: "test".into(),
+            seed: 42,
+            system_prompt: "System".into(),
+            user_prompt: prompt.into(),
+            temperature: 0.0,
+            max_tokens: 100,
+            schema_name: None,
+            json_schema: None,
+            constraints: Default::default(),
+            template: None,
+            tool: None,
+        }
+    }
+
+    #[test]
+    fn test_key_canonical_and_sensitive() {
+        let a = CacheKeyMaterial::new("openai", Some("gpt-4".into()), &req("A"));
+        let mut r = req("A");
+        r.run_id = "other-run".into();
+        r.constraints.insert("redaction".into(), serde_json::json!({"applied": true}));
+        assert_eq!(a.key(), CacheKeyMaterial::new("openai", Some("gpt-4".into()), &r).key(), "run_id/constraints hors clé");
+        r.max_tokens = 5;
+        assert_ne!(a.key(), CacheKeyMaterial::new("openai", Some("gpt-4".into()), &r).key(), "max_tokens dans la clé");
+        assert_ne!(a.key(), CacheKeyMaterial::new("openai", Some("gpt-4o".into()), &req("A")).key());
+        r.seed = 7;
+        assert_ne!(a.key(), CacheKeyMaterial::new("openai", Some("gpt-4".into()), &r).key());
+    }
+
+    #[test]
+    fn test_hit_miss_and_offline() {
+        let policy = temp_policy();
+        let provider = CachedProvider::new(Arc::new(MockDeterministicProvider::new()), policy.clone());
+        let r1 = provider.generate(req("A")).unwrap();
+        let r2 = provider.generate(req("A")).unwrap();
+        assert_eq!(r1.cache, Some(CacheOutcome::Miss));
+        assert_eq!(r2.cache, Some(CacheOutcome::Hit));
+        assert_eq!(r1.response_hash, r2.response_hash);
+        assert_eq!(provider.stats(), (1, 1));
+
+        let offline = CachedProvider::new(Arc::new(MockDeterministicProvider::new()), CachePolicy { mode: CacheMode::Offline, ..policy.clone() });
+        assert_eq!(offline.generate(req("A")).unwrap().cache, Some(CacheOutcome::Hit));
+        let err = offline.generate(req("B")).unwrap_err();
+        assert!(err.to_string().contains("OFFLINE_CACHE_MISS"));
+        let _ = fs::remove_dir_all(&policy.dir);
+    }
+
+    #[test]
+    fn test_integrity_and_purge() {
+        let policy = temp_policy();
+        let provider = CachedProvider::new(Arc::new(MockDeterministicProvider::new()), policy.clone());
+        provider.generate(req("A")).unwrap();
+        provider.generate(req("B")).unwrap();
+        let cache = provider.cache();
+        let list = cache.list().unwrap();
+        assert_eq!(list.len(), 2);
+        assert!(list.iter().all(|e| e.valid && !e.expired));
+
+        // Altération du contenu → entrée invalide, ignorée en lecture
+        let key = list[0].key.clone();
+        let path = policy.dir.join(format!("{}.json", key));
+        let tampered = fs::read_to_string(&path).unwrap().replace("[MOCK]", "[EDIT]");
+        fs::write(&path, tampered).unwrap();
+        assert!(matches!(cache.inspect(&key), Err(OmegaError::HashMismatch(_))));
+        assert_eq!(cache.purge(&PurgeScope::Invalid).unwrap(), 1);
+        assert_eq!(cache.purge(&PurgeScope::All).unwrap(), 1);
+        assert!(cache.list().unwrap().is_empty());
+        let _ = fs::remove_dir_all(&policy.dir);
+    }
+
+    #[test]
+    fn test_fallback_response_not_cached() {
+        /// Maillon nommé: réponse du mock sous un autre id, ou panne
+        struct Named(&'static str, bool);
+        impl LLMProvider for Named {
+            fn id(&self) -> ProviderId { self.0.into() }
+            fn model(&self) -> Option<String> { Some(format!("{}-model", self.0)) }
+            fn capabilities(&self) -> ProviderCapabilities { MockDeterministicProvider::new().capabilities() }
+            fn health(&self) -> bool { true }
+            fn generate(&self, req: CompletionRequest) -> OmegaResult<CompletionResponse> {
+                if !self.1 { return Err(OmegaError::ProviderError("HTTP_500".into())); }
+                Ok(CompletionResponse { provider_id: self.0.into(), ..MockDeterministicProvider::new().generate(req)? })
+            }
+            fn embed(&self, _req: EmbeddingRequest) -> OmegaResult<EmbeddingResponse> { Err(OmegaError::NotSupported("embed".into())) }
+        }
+        let policy = temp_policy();
+        let chain = crate::ai::FallbackProvider::new(Some(Arc::new(Named("primary", false))), Some(Arc::new(Named("secondary", true))));
+        let provider = CachedProvider::new(Arc::new(chain), policy.clone());
+        let r1 = provider.generate(req("A")).unwrap();
+        assert_eq!(r1.provider_id, "secondary");
+        assert_eq!(r1.cache, Some(CacheOutcome::Miss));
+        assert!(provider.cache().list().unwrap().is_empty(), "réponse du secondaire hors clé du primaire");
+        assert_eq!(provider.generate(req("A")).unwrap().cache, Some(CacheOutcome::Miss));
+        let _ = fs::remove_dir_all(&policy.dir);
+    }
+
+    #[test]
+    fn test_ttl_and_size_limit() {
+        let policy = CachePolicy { ttl_secs: Some(60), ..temp_policy() };
+        let cache = CompletionCache::new(policy.clone());
+        let mock = MockDeterministicProvider::new();
+        let material = CacheKeyMaterial::new("mock", None, &req("A"));
+        let key = cache.put(material.clone(), &mock.generate(req("A")).unwrap()).unwrap();
+
+        // Entrée vieillie au-delà du TTL (intégrité préservée: created_unix hors hash)
+        let path = policy.dir.join(format!("{}.json", key));
+        let mut entry: CacheEntry = read_json(&path).unwrap();
+        entry.created_unix -= 3600;
+        write_json(&path, &entry).unwrap();
+        assert!(cache.get(&key).unwrap().is_none());
+        assert_eq!(cache.purge(&PurgeScope::Expired).unwrap(), 1);
+
+        // Limite de taille: seule la dernière entrée tient
+        let one = { cache.put(material.clone(), &mock.generate(req("A")).unwrap()).unwrap(); cache.total_bytes().unwrap() };
+        let small = CompletionCache::new(CachePolicy { max_bytes: one + one / 2, ..policy.clone() });
+        small.put(CacheKeyMaterial::new("mock", None, &req("B")), &mock.generate(req("B")).unwrap()).unwrap();
+        assert_eq!(small.list().unwrap().len(), 1);
+        let _ = fs::remove_dir_all(&policy.dir);
+    }
+}
@@ -0,0 +1,271 @@
+//! OMEGA Chunking — découpage d'un texte long en extraits pour les appels IA
+//! NASA-Grade: découpage aux frontières de paragraphe/phrase (jamais au milieu d'un caractère UTF-8),
+//! plan déterministe (mêmes limites + même texte → mêmes extraits, mêmes hashes)
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::env;
+
+/// Estimation prudente pour le français (accents, élisions): ~3 caractères par token
+pub const CHARS_PER_TOKEN: usize = 3;
+
+/// Taille minimale d'un extrait, même pour une fenêtre de contexte très petite
+pub const MIN_CHUNK_TOKENS: u32 = 64;
+
+/// Estimation du nombre de tokens d'un texte
+pub fn estimate_tokens(text: &str) -> u32 {
+    text.chars().count().div_ceil(CHARS_PER_TOKEN) as u32
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// CONFIG
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ChunkConfig {
+    /// Taille max d'un extrait (bornée en plus par la fenêtre de contexte du provider)
+    pub max_chunk_tokens: u32,
+    /// Budget total d'une analyse: extraits + surcoût de chaque appel
+    pub token_budget: u32,
+}
+
+impl Default for ChunkConfig {
+    fn default() -> Self { Self { max_chunk_tokens: 2000, token_budget: 24_000 } }
+}
+
+impl ChunkConfig {
+    /// OMEGA_AI_CHUNK_TOKENS, OMEGA_AI_TOKEN_BUDGET
+    pub fn from_env() -> Self {
+        let var = |k: &str| env::var(k).ok().and_then(|v| v.parse::<u32>().ok()).filter(|n| *n > 0);
+        let mut config = Self::default();
+        if let Some(n) = var("OMEGA_AI_CHUNK_TOKENS") { config.max_chunk_tokens = n; }
+        if let Some(n) = var("OMEGA_AI_TOKEN_BUDGET") { config.token_budget = n; }
+        config
+    }
+
+    /// Limites effectives: `overhead_tokens` = prompt hors extrait + completion max d'un appel
+    pub fn limits(&self, max_context_window: u32, overhead_tokens: u32) -> ChunkLimits {
+        let room = max_context_window.saturating_sub(overhead_tokens);
+        ChunkLimits {
+            chunk_tokens: self.max_chunk_tokens.min(room).max(MIN_CHUNK_TOKENS),
+            token_budget: self.token_budget,
+            overhead_tokens,
+        }
+    }
+}
+
+/// Limites d'un plan (enregistrées avec un record pour re-planifier à l'identique en Replay)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChunkLimits {
+    pub chunk_tokens: u32,
+    pub token_budget: u32,
+    pub overhead_tokens: u32,
+}
+
+impl ChunkLimits {
+    /// Aucun découpage (records antérieurs au chunking)
+    pub const UNBOUNDED: Self = Self { chunk_tokens: u32::MAX, token_budget: u32::MAX, overhead_tokens: 0 };
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// PLAN
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TextChunk {
+    pub index: u32,
+    /// Plage d'octets dans le texte source (frontières de caractères)
+    pub start: usize,
+    pub end: usize,
+    pub text: String,
+    pub tokens: u32,
+    /// SHA-256 du texte de l'extrait
+    pub hash: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChunkPlan {
+    pub limits: ChunkLimits,
+    /// Extraits envoyés, dans l'ordre du texte
+    pub chunks: Vec<TextChunk>,
+    /// Extraits hors budget (fin du texte non analysée)
+    pub skipped: u32,
+    /// SHA-256 des limites et des hashes d'extraits
+    pub plan_hash: String,
+}
+
+impl ChunkPlan {
+    /// Découpe `text`; le premier extrait est toujours retenu, les suivants tant que le budget le permet
+    pub fn new(text: &str, limits: ChunkLimits) -> Self {
+        let max_chars = (limits.chunk_tokens as usize).saturating_mul(CHARS_PER_TOKEN);
+        let mut units = Vec::new();
+        for (start, end) in paragraphs(text) {
+            split_unit(text, start, end, max_chars, &mut units);
+        }
+
+        let mut ranges: Vec<(usize, usize)> = Vec::new();
+        for (start, end) in units {
+            match ranges.last_mut() {
+                Some(last) if text[last.0..end].chars().count() <= max_chars => last.1 = end,
+                _ => ranges.push((start, end)),
+            }
+        }
+
+        let mut chunks = Vec::new();
+        let mut spent: u64 = 0;
+        for (start, end) in &ranges {
+            let chunk_text = &text[*start..*end];
+            let tokens = estimate_tokens(chunk_text);
+            let cost = tokens as u64 + limits.overhead_tokens as u64;
+            if !chunks.is_empty() && spent + cost > limits.token_budget as u64 { break; }
+            spent += cost;
+            chunks.push(TextChunk {
+                index: chunks.len() as u32,
+                start: *start,
+                end: *end,
+                text: chunk_text.to_string(),
+                tokens,
+                hash: sha256_hex(chunk_text),
+            });
+        }
+
+        let skipped = (ranges.len() - chunks.len()) as u32;
+        let mut material = format!("{}|{}|{}", limits.chunk_tokens, limits.token_budget, limits.overhead_tokens);
+        for c in &chunks { material.push('|'); material.push_str(&c.hash); }
+        Self { limits, chunks, skipped, plan_hash: sha256_hex(&material) }
+    }
+
+    /// Tokens estimés des extraits retenus
+    pub fn total_tokens(&self) -> u32 { self.chunks.iter().map(|c| c.tokens).sum() }
+}
+
+fn sha256_hex(s: &str) -> String { format!("{:x}", Sha256::digest(s.as_bytes())) }
+
+/// Paragraphes non vides (séparés par au moins une ligne blanche), bornes sans espaces
+fn paragraphs(text: &str) -> Vec<(usize, usize)> {
+    let mut out = Vec::new();
+    let mut start: Option<usize> = None;
+    let mut end = 0;
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        if line.trim().is_empty() {
+            if let Some(s) = start.take() { out.push((s, end)); }
+        } else {
+            let lead = line.len() - line.trim_start().len();
+            if start.is_none() { start = Some(offset + lead); }
+            end = offset + line.trim_end().len();
+        }
+        offset += line.len();
+    }
+    if let Some(s) = start { out.push((s, end)); }
+    out
+}
+
+/// Unité trop longue → phrases; phrase trop longue → coupe au dernier espace (ou caractère)
+fn split_unit(text: &str, start: usize, end: usize, max_chars: usize, out: &mut Vec<(usize, usize)>) {
+    if text[start..end].chars().count() <= max_chars {
+        out.push((start, end));
+        return;
+    }
+    let sentences = sentences(text, start, end);
+    if sentences.len() > 1 {
+        for (s, e) in sentences { split_unit(text, s, e, max_chars, out); }
+        return;
+    }
+
+    let mut s = start;
+    while s < end {
+        let rest = &text[s..end];
+        let limit = rest.char_indices().nth(max_chars.max(1)).map(|(i, _)| s + i).unwrap_or(end);
+        let cut = if limit == end { end } else {
+            text[s..limit].rfind(char::is_whitespace).map(|i| s + i).filter(|i| *i > s).unwrap_or(limit)
+        };
+        out.push((s, cut));
+        s = cut + text[cut..end].len() - text[cut..end].trim_start().len();
+    }
+}
+
+/// Phrases: fin sur . ! ? … (guillemets/parenthèses fermants inclus, « ? » français compris) suivie d'un espace
+fn sentences(text: &str, start: usize, end: usize) -> Vec<(usize, usize)> {
+    let chars: Vec<(usize, char)> = text[start..end].char_indices().map(|(i, c)| (start + i, c)).collect();
+    let is_end = |c: char| matches!(c, '.' | '!' | '?' | '…');
+    let is_close = |c: char| matches!(c, '»' | '"' | ')' | '\u{201D}');
+    let mut out = Vec::new();
+    let mut s = start;
+    let mut i = 0;
+    while i < chars.len() {
+        if !is_end(chars[i].1) { i += 1; continue; }
+        let mut j = i + 1;
+        loop {
+            match chars.get(j) {
+                Some(&(_, c)) if is_end(c) || is_close(c) => j += 1,
+                // « Déjà ? » : espace insécable/fine avant le guillemet fermant
+                Some(&(_, c)) if c.is_whitespace() && chars.get(j + 1).is_some_and(|&(_, n)| n == '»') => j += 2,
+                _ => break,
+            }
+        }
+        if chars.get(j).is_some_and(|&(_, c)| c.is_whitespace()) {
+            let e = chars[j].0;
+            while chars.get(j).is_some_and(|&(_, c)| c.is_whitespace()) { j += 1; }
+            out.push((s, e));
+            s = chars.get(j).map(|&(k, _)| k).unwrap_or(end);
+        }
+        i = j;
+    }
+    if s < end { out.push((s, end)); }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limits(chunk_tokens: u32, token_budget: u32) -> ChunkLimits {
+        ChunkLimits { chunk_tokens, token_budget, overhead_tokens: 0 }
+    }
+
+    #[test]
+    fn test_short_text_single_chunk() {
+        let plan = ChunkPlan::new("Il avait peur.", ChunkConfig::default().limits(128_000, 1600));
+        assert_eq!(plan.chunks.len(), 1);
+        assert_eq!(plan.chunks[0].text, "Il avait peur.");
+        assert_eq!(plan.skipped, 0);
+        assert_eq!(ChunkPlan::new("Il avait peur.", ChunkLimits::UNBOUNDED).chunks.len(), 1);
+    }
+
+    #[test]
+    fn test_paragraph_and_sentence_boundaries_utf8() {
+        let para = "Élodie était là. À l'aube, la peur revint… « Déjà ? » Puis le silence.";
+        let text = format!("{}\n\n{}\n\n{}", para, para, "é".repeat(300));
+        let plan = ChunkPlan::new(&text, limits(20, u32::MAX));
+        assert!(plan.chunks.len() > 3);
+        for c in &plan.chunks {
+            assert_eq!(&text[c.start..c.end], c.text);
+            assert!(c.text.chars().count() <= 60, "{:?}", c.text);
+            assert_eq!(c.text, c.text.trim());
+        }
+        assert_eq!(plan.chunks[0].text, "Élodie était là. À l'aube, la peur revint… « Déjà ? »");
+        // Petits paragraphes regroupés, coupe à la fin de phrase suivante
+        assert_eq!(plan.chunks[1].text, "Puis le silence.\n\nÉlodie était là. À l'aube, la peur revint…");
+        // Répété à l'identique: mêmes extraits, mêmes hashes
+        assert_eq!(plan, ChunkPlan::new(&text, limits(20, u32::MAX)));
+        assert_eq!(plan.chunks[0].hash, sha256_hex(&plan.chunks[0].text));
+    }
+
+    #[test]
+    fn test_paragraphs_packed_and_budget() {
+        let text = ["Un.", "Deux.", "Trois.", "Quatre."].join("\n\n");
+        let packed = ChunkPlan::new(&text, limits(100, u32::MAX));
+        assert_eq!(packed.chunks.len(), 1);
+        assert_eq!(packed.chunks[0].text, text);
+
+        let plan = ChunkPlan::new(&text, limits(3, 4));
+        assert_eq!(plan.chunks.iter().map(|c| c.text.as_str()).collect::<Vec<_>>(), vec!["Un.", "Deux."]);
+        assert_eq!(plan.skipped, 2);
+        assert_ne!(plan.plan_hash, ChunkPlan::new(&text, limits(3, 100)).plan_hash);
+
+        // Fenêtre de contexte trop petite: taille plancher
+        assert_eq!(ChunkConfig::default().limits(1000, 1600).chunk_tokens, MIN_CHUNK_TOKENS);
+        assert_eq!(ChunkConfig::default().limits(8192, 1600).chunk_tokens, 2000);
+    }
+}
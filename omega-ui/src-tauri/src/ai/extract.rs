@@ -0,0 +1,163 @@
+//! OMEGA Extract — extraction structurée typée, indépendante du provider
+//! Appel d'outil natif (OpenAI function calling, Anthropic tool use) quand le provider le supporte,
+//! sinon consigne JSON dans le prompt. Dans les deux cas: validation JSON Schema (réparation bornée)
+//! puis désérialisation dans le type demandé.
+
+use crate::ai::interface::LLMProvider;
+use crate::ai::models::*;
+use crate::ai::schema::{self, json_instruction};
+use crate::error::{OmegaError, OmegaResult};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Longueur max d'un nom d'outil (OpenAI et Anthropic)
+pub const MAX_TOOL_NAME_LEN: usize = 64;
+
+/// Type extractible: struct serde + JSON Schema de ses champs
+pub trait Extraction: DeserializeOwned {
+    /// Nom de l'outil et du schéma ([a-zA-Z0-9_-], 64 caractères au plus)
+    const NAME: &'static str;
+    /// Consigne donnée au modèle pour remplir l'outil
+    const DESCRIPTION: &'static str;
+    fn json_schema() -> Value;
+}
+
+/// Voie utilisée pour obtenir la sortie structurée
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExtractionMode { ToolCall, JsonPrompt }
+
+impl ExtractionMode {
+    pub fn for_capabilities(caps: &ProviderCapabilities) -> Self {
+        if caps.supports_tool_calling { Self::ToolCall } else { Self::JsonPrompt }
+    }
+}
+
+/// Valeur typée + traçabilité de l'appel
+#[derive(Debug, Clone)]
+pub struct Extracted<T> {
+    pub value: T,
+    pub mode: ExtractionMode,
+    /// Re-prompts nécessaires (0 = conforme du premier coup)
+    pub repairs: u32,
+    /// Usage cumulé de toutes les tentatives
+    pub usage: Usage,
+    pub response: CompletionResponse,
+}
+
+fn valid_tool_name(name: &str) -> bool {
+    !name.is_empty() && name.len() <= MAX_TOOL_NAME_LEN && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+/// Requête d'extraction de `T` à partir de `req` (prompts, seed, température conservés)
+pub fn extraction_request<T: Extraction>(req: CompletionRequest, mode: ExtractionMode) -> OmegaResult<CompletionRequest> {
+    if !valid_tool_name(T::NAME) {
+        return Err(OmegaError::ConfigError(format!("TOOL_NAME_INVALID: '{}'", T::NAME)));
+    }
+    let schema = T::json_schema();
+    Ok(match mode {
+        ExtractionMode::ToolCall => CompletionRequest {
+            schema_name: Some(T::NAME.into()),
+            json_schema: Some(schema),
+            tool: Some(ToolSpec { name: T::NAME.into(), description: T::DESCRIPTION.into() }),
+            ..req
+        },
+        ExtractionMode::JsonPrompt => CompletionRequest {
+            system_prompt: format!("{}\n\n{}\n\n{}", req.system_prompt, T::DESCRIPTION, json_instruction(&schema)),
+            schema_name: Some(T::NAME.into()),
+            json_schema: Some(schema),
+            tool: None,
+            ..req
+        },
+    })
+}
+
+/// Extrait un `T` (voie choisie selon les capacités du provider); hors schéma après réparations → InvalidResponse détaillé
+pub fn extract<T: Extraction>(provider: &dyn LLMProvider, req: CompletionRequest, max_repairs: u32) -> OmegaResult<Extracted<T>> {
+    let mode = ExtractionMode::for_capabilities(&provider.capabilities());
+    let validated = schema::generate_validated(provider, extraction_request::<T>(req, mode)?, max_repairs)?;
+    let value = serde_json::from_value(validated.value)
+        .map_err(|e| OmegaError::invalid_response(format!("EXTRACT_DESERIALIZE: {}: {}", T::NAME, e)))?;
+    Ok(Extracted { value, mode, repairs: validated.repairs, usage: validated.usage, response: validated.response })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::MockDeterministicProvider;
+    use std::sync::Mutex;
+
+    #[derive(Debug, Deserialize)]
+    struct Beat { title: String, tension: f64 }
+
+    impl Extraction for Beat {
+        const NAME: &'static str = "draft_beat";
+        const DESCRIPTION: &'static str = "Propose le prochain beat de la scène.";
+        fn json_schema() -> Value {
+            serde_json::json!({
+                "type": "object",
+                "required": ["title", "tension"],
+                "properties": {"title": {"type": "string", "minLength": 1}, "tension": {"type": "number", "minimum": 0, "maximum": 1}}
+            })
+        }
+    }
+
+    fn req() -> CompletionRequest {
+        CompletionRequest { run_id: "t".into(), seed: 7, system_prompt: "S".into(), user_prompt: "U".into(), temperature: 0.0, max_tokens: 200, schema_name: None, json_schema: None, constraints: Default::default(), template: None, tool: None }
+    }
+
+    #[test]
+    fn test_json_prompt_fallback_without_tools() {
+        let beat = extract::<Beat>(&MockDeterministicProvider::new(), req(), 0).unwrap();
+        assert_eq!(beat.mode, ExtractionMode::JsonPrompt);
+        assert_eq!(beat.value.title, "x");
+        assert_eq!(beat.value.tension, 0.0);
+
+        let r = extraction_request::<Beat>(req(), ExtractionMode::JsonPrompt).unwrap();
+        assert!(r.tool.is_none());
+        assert!(r.system_prompt.starts_with("S\n\nPropose le prochain beat") && r.system_prompt.contains("\"tension\""));
+        assert_eq!(r.schema_name.as_deref(), Some("draft_beat"));
+    }
+
+    /// Provider avec outils: mémorise la requête, répond les arguments donnés
+    struct ToolProvider { reply: &'static str, seen: Mutex<Vec<CompletionRequest>> }
+
+    impl LLMProvider for ToolProvider {
+        fn id(&self) -> ProviderId { "tools".into() }
+        fn capabilities(&self) -> ProviderCapabilities { ProviderCapabilities { supports_tool_calling: true, ..MockDeterministicProvider::default().capabilities() } }
+        fn health(&self) -> bool { true }
+        fn embed(&self, _req: EmbeddingRequest) -> OmegaResult<EmbeddingResponse> { Err(OmegaError::NotSupported("embed".into())) }
+        fn generate(&self, req: CompletionRequest) -> OmegaResult<CompletionResponse> {
+            self.seen.lock().unwrap().push(req);
+            Ok(CompletionResponse { provider_id: self.id(), content: self.reply.into(), parsed: None, usage: Usage { prompt_tokens: 1, completion_tokens: 1, total_tokens: 2 }, latency_ms: 0, response_hash: String::new(), cache: None, fallback: None })
+        }
+    }
+
+    #[test]
+    fn test_tool_call_when_supported() {
+        let p = ToolProvider { reply: r#"{"title":"La porte s'ouvre","tension":0.8}"#, seen: Mutex::new(vec![]) };
+        let beat = extract::<Beat>(&p, req(), 0).unwrap();
+        assert_eq!(beat.mode, ExtractionMode::ToolCall);
+        assert_eq!(beat.value.title, "La porte s'ouvre");
+        let seen = p.seen.lock().unwrap();
+        assert_eq!(seen[0].system_prompt, "S", "tool path: schema travels with the tool, not the prompt");
+        assert_eq!(seen[0].tool.as_ref().map(|t| t.name.as_str()), Some("draft_beat"));
+        assert_eq!(seen[0].json_schema, Some(Beat::json_schema()));
+    }
+
+    #[test]
+    fn test_out_of_schema_and_bad_name_rejected() {
+        let p = ToolProvider { reply: r#"{"title":"","tension":3}"#, seen: Mutex::new(vec![]) };
+        assert!(matches!(extract::<Beat>(&p, req(), 1), Err(OmegaError::InvalidResponse { schema: Some(f), .. }) if f.attempts == 2 && f.errors.len() == 2));
+
+        #[derive(Debug, Deserialize)]
+        struct Bad {}
+        impl Extraction for Bad {
+            const NAME: &'static str = "fait canon";
+            const DESCRIPTION: &'static str = "";
+            fn json_schema() -> Value { serde_json::json!({"type": "object"}) }
+        }
+        assert!(matches!(extraction_request::<Bad>(req(), ExtractionMode::ToolCall), Err(OmegaError::ConfigError(m)) if m.starts_with("TOOL_NAME_INVALID")));
+    }
+}
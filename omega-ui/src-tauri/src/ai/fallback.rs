@@ -1,72 +1,172 @@
 ﻿//! OMEGA FallbackProvider — Resilient AI with automatic fallback
 //! NASA-Grade: Si provider down → fallback → Mock local (JAMAIS d'échec total)
 
+use crate::ai::async_provider::CallControl;
 use crate::ai::interface::LLMProvider;
 use crate::ai::models::*;
 use crate::ai::mock::MockDeterministicProvider;
-use crate::ai::providers::config::{ProviderConfig, ProviderType};
+use crate::ai::resilience::{BreakerPolicy, CircuitBreaker, CircuitState, RateLimit, TokenBucket};
+use crate::ai::stream::{CancelToken, StreamDelta};
+use crate::ai::providers::config::ProviderConfig;
+use crate::ai::providers::profiles::{build_provider, ProviderProfiles};
 use crate::ai::providers::{get_provider_with_fallback};
-use crate::error::OmegaResult;
+use crate::error::{OmegaError, OmegaResult};
 use std::sync::Arc;
+use std::time::Duration;
 use std::env;
 
+/// Disjoncteur + limiteur appliqués à chaque provider réel de la chaîne
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ResiliencePolicy {
+    pub breaker: BreakerPolicy,
+    pub rate_limit: Option<RateLimit>,
+}
+
+impl ResiliencePolicy {
+    /// OMEGA_BREAKER_THRESHOLD, OMEGA_BREAKER_COOLDOWN_SECS, OMEGA_RATE_LIMIT_RPM (+ OMEGA_RATE_LIMIT_BURST, OMEGA_RATE_LIMIT_MAX_WAIT_MS)
+    pub fn from_env() -> Self {
+        let var = |k: &str| env::var(k).ok().and_then(|v| v.parse::<u64>().ok());
+        let mut policy = Self::default();
+        if let Some(n) = var("OMEGA_BREAKER_THRESHOLD") { policy.breaker.failure_threshold = n.max(1) as u32; }
+        if let Some(secs) = var("OMEGA_BREAKER_COOLDOWN_SECS") { policy.breaker.cool_down = Duration::from_secs(secs); }
+        if let Some(rpm) = var("OMEGA_RATE_LIMIT_RPM").filter(|r| *r > 0) {
+            policy.rate_limit = Some(RateLimit {
+                capacity: var("OMEGA_RATE_LIMIT_BURST").unwrap_or(rpm.min(10)) as u32,
+                per_minute: rpm as u32,
+                max_wait: Duration::from_millis(var("OMEGA_RATE_LIMIT_MAX_WAIT_MS").unwrap_or(5000)),
+            });
+        }
+        policy
+    }
+}
+
+/// OMEGA_HEALTH_PROBE=1|true|on
+fn health_probe_from_env() -> bool {
+    matches!(env::var("OMEGA_HEALTH_PROBE").unwrap_or_default().to_lowercase().as_str(), "1" | "true" | "on")
+}
+
+/// Maillon de la chaîne: provider + disjoncteur + limiteur
+struct Link {
+    id: String,
+    provider: Arc<dyn LLMProvider>,
+    breaker: CircuitBreaker,
+    limiter: Option<TokenBucket>,
+}
+
+impl Link {
+    fn new(id: String, provider: Arc<dyn LLMProvider>, policy: &ResiliencePolicy) -> Self {
+        Self { id, provider, breaker: CircuitBreaker::new(policy.breaker), limiter: policy.rate_limit.map(TokenBucket::new) }
+    }
+
+    /// Autorise l'appel ou retourne la raison du saut
+    fn admit(&self) -> Result<(), FallbackAttempt> {
+        let skip = |outcome, error: String| FallbackAttempt { provider: self.id.clone(), outcome, error: Some(error) };
+        match self.breaker.state() {
+            CircuitState::Open => return Err(skip(AttemptOutcome::CircuitOpen, "CIRCUIT_OPEN".into())),
+            // Essai de réouverture: la sonde (si activée) évite d'envoyer une vraie requête à un provider mort
+            CircuitState::HalfOpen if !self.provider.health() => {
+                self.breaker.on_failure();
+                return Err(skip(AttemptOutcome::CircuitOpen, "PROBE_FAILED".into()));
+            }
+            _ => {}
+        }
+        if let Some(limiter) = &self.limiter {
+            limiter.acquire().map_err(|e| skip(AttemptOutcome::RateLimited, e.to_string()))?;
+        }
+        if !self.breaker.allow() {
+            return Err(skip(AttemptOutcome::CircuitOpen, "HALF_OPEN_TRIAL_IN_FLIGHT".into()));
+        }
+        Ok(())
+    }
+
+    /// Enregistre l'échec (sauf annulation, qui n'est pas une panne du provider)
+    fn failed(&self, e: &OmegaError) -> FallbackAttempt {
+        if matches!(e, OmegaError::Cancelled(_)) { self.breaker.on_abandoned(); } else { self.breaker.on_failure(); }
+        let outcome = if matches!(e, OmegaError::RateLimit(_)) { AttemptOutcome::RateLimited } else { AttemptOutcome::Failed };
+        FallbackAttempt { provider: self.id.clone(), outcome, error: Some(e.to_string()) }
+    }
+
+    fn succeeded(&self) -> FallbackAttempt {
+        self.breaker.on_success();
+        FallbackAttempt { provider: self.id.clone(), outcome: AttemptOutcome::Ok, error: None }
+    }
+}
+
 /// Provider résilient avec fallback automatique
-/// Ordre: Primary → Secondary → Mock (toujours disponible)
+/// Ordre: chaîne de providers (profils omega.toml, ou Primary → Secondary) → Mock (toujours disponible)
+/// Un provider en panne est écarté par son disjoncteur jusqu'à la fin du cool-down
 pub struct FallbackProvider {
-    primary: Option<Arc<dyn LLMProvider>>,
-    secondary: Option<Arc<dyn LLMProvider>>,
+    chain: Vec<Link>,
     fallback: Arc<dyn LLMProvider>,
-    primary_id: String,
-    secondary_id: String,
 }
 
 impl FallbackProvider {
     /// Crée un FallbackProvider depuis les variables d'environnement
-    /// OPENAI_API_KEY → OpenAI primary
-    /// ANTHROPIC_API_KEY → Anthropic secondary
+    /// OMEGA_LOCAL_BASE_URL + OMEGA_LOCAL_MODEL → serveur OpenAI-compatible primary
+    /// OPENAI_API_KEY → OpenAI primary (secondary si serveur local)
+    /// ANTHROPIC_API_KEY → Anthropic secondary (si place libre)
+    /// OMEGA_HEALTH_PROBE=1 → health() interroge réellement les APIs
     /// Si aucune clé → Mock only (mode déterministe)
     pub fn from_env() -> Self {
         let openai_key = env::var("OPENAI_API_KEY").ok();
         let anthropic_key = env::var("ANTHROPIC_API_KEY").ok();
+        let health_probe = health_probe_from_env();
+        let policy = ResiliencePolicy::from_env();
         
-        let mut primary: Option<Arc<dyn LLMProvider>> = None;
-        let mut secondary: Option<Arc<dyn LLMProvider>> = None;
-        let mut primary_id = "none".to_string();
-        let mut secondary_id = "none".to_string();
+        // Candidats par ordre de préférence: local (manuscrits confidentiels) → OpenAI → Anthropic
+        let mut candidates: Vec<(ProviderConfig, &str)> = Vec::new();
+        if let Some(config) = ProviderConfig::local_from_env() {
+            candidates.push((config, "openai-compatible"));
+        }
+        if let Some(key) = openai_key.filter(|k| !k.is_empty()) {
+            candidates.push((ProviderConfig::openai(key), "openai"));
+        }
+        if let Some(key) = anthropic_key.filter(|k| !k.is_empty()) {
+            candidates.push((ProviderConfig::anthropic(key), "anthropic"));
+        }
         
-        // Primary: OpenAI si disponible
-        if let Some(key) = openai_key {
-            if !key.is_empty() {
-                let config = ProviderConfig::openai(key);
-                let provider = get_provider_with_fallback(&config);
-                if provider.id() != "mock-deterministic-v1" {
-                    primary = Some(provider);
-                    primary_id = "openai".to_string();
-                }
+        // Primary puis Secondary: deux premiers candidats valides
+        let mut chain = Vec::new();
+        for (config, id) in candidates {
+            let provider = get_provider_with_fallback(&ProviderConfig { health_probe, ..config });
+            if provider.id() == "mock-deterministic-v1" {
+                continue;
+            }
+            if chain.len() < 2 {
+                chain.push(Link::new(id.to_string(), provider, &policy));
             }
         }
         
-        // Secondary: Anthropic si disponible
-        if let Some(key) = anthropic_key {
-            if !key.is_empty() {
-                let config = ProviderConfig::anthropic(key);
-                let provider = get_provider_with_fallback(&config);
-                if provider.id() != "mock-deterministic-v1" {
-                    secondary = Some(provider);
-                    secondary_id = "anthropic".to_string();
+        Self::from_links(chain)
+    }
+    
+    /// Chaîne `[fallback] chain` des profils (maillons = noms de profils); un profil dont la clé manque est écarté
+    pub fn from_profiles(profiles: &ProviderProfiles, policy: ResiliencePolicy) -> OmegaResult<Self> {
+        if profiles.fallback.chain.is_empty() {
+            return Err(OmegaError::ConfigError("PROFILE_CHAIN_EMPTY: [fallback] chain lists no profile".into()));
+        }
+        let health_probe = health_probe_from_env();
+        let mut chain = Vec::new();
+        for name in &profiles.fallback.chain {
+            let config = match profiles.config(name) {
+                Ok(config) => config,
+                Err(e @ OmegaError::ConfigError(_)) if e.to_string().contains("PROFILE_KEY_MISSING") => {
+                    eprintln!("[FALLBACK] Skipping profile {}: {}", name, e);
+                    continue;
                 }
-            }
+                Err(e) => return Err(e),
+            };
+            let provider = build_provider(&ProviderConfig { health_probe: config.health_probe || health_probe, ..config })?;
+            chain.push(Link::new(name.clone(), provider, &policy));
         }
-        
-        // Fallback: Mock (toujours disponible)
-        let fallback: Arc<dyn LLMProvider> = Arc::new(MockDeterministicProvider::default());
-        
-        Self {
-            primary,
-            secondary,
-            fallback,
-            primary_id,
-            secondary_id,
+        Ok(Self::from_links(chain))
+    }
+    
+    /// Profils (OMEGA_CONFIG ou ./omega.toml) s'ils existent, sinon variables d'environnement
+    pub fn from_config() -> OmegaResult<Self> {
+        match ProviderProfiles::from_env()? {
+            Some(profiles) => Self::from_profiles(&profiles, ResiliencePolicy::from_env()),
+            None => Ok(Self::from_env()),
         }
     }
     
@@ -75,98 +175,207 @@ impl FallbackProvider {
         primary: Option<Arc<dyn LLMProvider>>,
         secondary: Option<Arc<dyn LLMProvider>>,
     ) -> Self {
-        let primary_id = primary.as_ref().map(|p| p.id()).unwrap_or_else(|| "none".into());
-        let secondary_id = secondary.as_ref().map(|p| p.id()).unwrap_or_else(|| "none".into());
-        
-        Self {
-            primary,
-            secondary,
-            fallback: Arc::new(MockDeterministicProvider::default()),
-            primary_id,
-            secondary_id,
-        }
+        Self::with_policy(primary, secondary, ResiliencePolicy::default())
+    }
+    
+    /// Providers explicites + politique disjoncteur/limiteur
+    pub fn with_policy(
+        primary: Option<Arc<dyn LLMProvider>>,
+        secondary: Option<Arc<dyn LLMProvider>>,
+        policy: ResiliencePolicy,
+    ) -> Self {
+        Self::from_links(primary.into_iter().chain(secondary).map(|p| Link::new(p.id(), p, &policy)).collect())
+    }
+    
+    fn from_links(chain: Vec<Link>) -> Self {
+        Self { chain, fallback: Arc::new(MockDeterministicProvider::default()) }
     }
     
     /// Retourne l'ID du provider qui sera utilisé
     pub fn active_provider_id(&self) -> String {
-        if self.primary.is_some() {
-            self.primary_id.clone()
-        } else if self.secondary.is_some() {
-            self.secondary_id.clone()
-        } else {
-            "mock-fallback".into()
-        }
+        self.chain.first().map(|l| l.id.clone()).unwrap_or_else(|| "mock-fallback".into())
     }
     
     /// Vérifie si on est en mode réel (avec API) ou mock
     pub fn is_real_ai(&self) -> bool {
-        self.primary.is_some() || self.secondary.is_some()
+        !self.chain.is_empty()
+    }
+    
+    /// Politique local_only: seuls les maillons locaux sont conservés (à défaut, le mock)
+    pub fn retain_local(mut self) -> Self {
+        self.chain.retain(|l| l.provider.is_local());
+        self
+    }
+    
+    /// Maillons dans l'ordre de la chaîne
+    pub fn chain_ids(&self) -> Vec<String> {
+        self.links().map(|l| l.id.clone()).collect()
+    }
+    
+    /// État des disjoncteurs, dans l'ordre de la chaîne
+    pub fn circuit_states(&self) -> Vec<(String, CircuitState)> {
+        self.links().map(|l| (l.id.clone(), l.breaker.state())).collect()
+    }
+    
+    fn links(&self) -> impl Iterator<Item = &Link> {
+        self.chain.iter()
+    }
+    
+    /// Réponse du mock de secours: toujours un fallback (aucun provider réel n'a répondu)
+    fn mock_response(&self, resp: CompletionResponse, mut attempts: Vec<FallbackAttempt>) -> CompletionResponse {
+        attempts.push(FallbackAttempt { provider: resp.provider_id.clone(), outcome: AttemptOutcome::Ok, error: None });
+        Self::traced(resp, None, true, attempts)
+    }
+    
+    /// Réponse d'un maillon réel: fallback si ce n'est pas le premier maillon tenté
+    fn traced(mut resp: CompletionResponse, model: Option<String>, fallback_used: bool, attempts: Vec<FallbackAttempt>) -> CompletionResponse {
+        resp.fallback = Some(FallbackTrace { responder: resp.provider_id.clone(), model, fallback_used, attempts });
+        resp
+    }
+    
+    /// Embeddings: même admission, disjoncteur et trace que generate
+    /// Un lot entier vient d'un seul provider: des vecteurs de modèles différents ne sont pas comparables
+    fn embed_chain(&self, call: &dyn Fn(&dyn LLMProvider) -> OmegaResult<Vec<EmbeddingResponse>>) -> OmegaResult<Vec<EmbeddingResponse>> {
+        CallControl::current().check()?;
+        let mut attempts = Vec::new();
+        for link in self.links() {
+            // Maillon sans embeddings (Anthropic): écarté sans compter comme panne
+            if !link.provider.capabilities().supports_embeddings {
+                attempts.push(FallbackAttempt { provider: link.id.clone(), outcome: AttemptOutcome::Failed, error: Some("EMBEDDINGS_NOT_SUPPORTED".into()) });
+                continue;
+            }
+            if let Err(skip) = link.admit() {
+                eprintln!("[FALLBACK] Skipping {} (embeddings): {}", link.id, skip.error.as_deref().unwrap_or_default());
+                attempts.push(skip);
+                continue;
+            }
+            match call(link.provider.as_ref()) {
+                Ok(resps) => {
+                    attempts.push(link.succeeded());
+                    let fallback_used = attempts.len() > 1;
+                    return Ok(Self::traced_embeddings(resps, fallback_used, attempts));
+                }
+                Err(e @ OmegaError::Cancelled(_)) => { link.failed(&e); return Err(e); }
+                Err(e) => {
+                    eprintln!("[FALLBACK] Embeddings ({}) failed: {}", link.id, e);
+                    attempts.push(link.failed(&e));
+                }
+            }
+        }
+        let resps = call(self.fallback.as_ref())?;
+        attempts.push(FallbackAttempt { provider: self.fallback.id(), outcome: AttemptOutcome::Ok, error: None });
+        Ok(Self::traced_embeddings(resps, true, attempts))
+    }
+    
+    fn traced_embeddings(mut resps: Vec<EmbeddingResponse>, fallback_used: bool, attempts: Vec<FallbackAttempt>) -> Vec<EmbeddingResponse> {
+        let trace = resps.first().map(|r| FallbackTrace { responder: r.provider_id.clone(), model: r.model.clone(), fallback_used, attempts });
+        for resp in &mut resps { resp.fallback = trace.clone(); }
+        resps
     }
 }
 
 impl LLMProvider for FallbackProvider {
     fn id(&self) -> ProviderId {
-        format!("fallback[{}->{}->mock]", self.primary_id, self.secondary_id)
+        // Au moins deux maillons affichés ("none" si absents): fallback[openai->none->mock]
+        let mut ids = self.chain_ids();
+        while ids.len() < 2 { ids.push("none".into()); }
+        format!("fallback[{}->mock]", ids.join("->"))
+    }
+    
+    fn model(&self) -> Option<String> {
+        self.links().next().and_then(|l| l.provider.model())
     }
     
     fn capabilities(&self) -> ProviderCapabilities {
-        if let Some(ref p) = self.primary {
-            return p.capabilities();
+        match self.links().next() {
+            Some(link) => link.provider.capabilities(),
+            None => self.fallback.capabilities(),
         }
-        if let Some(ref s) = self.secondary {
-            return s.capabilities();
-        }
-        self.fallback.capabilities()
     }
     
+    /// true si un provider réel est joignable (disjoncteur non ouvert + sonde), sinon santé du mock
     fn health(&self) -> bool {
-        if let Some(ref p) = self.primary {
-            if p.health() { return true; }
-        }
-        if let Some(ref s) = self.secondary {
-            if s.health() { return true; }
+        if self.links().any(|l| l.breaker.state() != CircuitState::Open && l.provider.health()) {
+            return true;
         }
         self.fallback.health()
     }
     
+    /// Local si tous les maillons le sont (le mock de secours l'est toujours)
+    fn is_local(&self) -> bool {
+        self.links().all(|l| l.provider.is_local())
+    }
+    
+    /// Tous les maillons: un repli peut atteindre un modèle plus cher que le principal
+    fn reachable_models(&self) -> Vec<(ProviderId, Option<String>)> {
+        self.links().flat_map(|l| l.provider.reachable_models()).collect()
+    }
+    
     fn generate(&self, req: CompletionRequest) -> OmegaResult<CompletionResponse> {
-        // Essayer Primary
-        if let Some(ref primary) = self.primary {
-            match primary.generate(req.clone()) {
-                Ok(resp) => return Ok(resp),
-                Err(e) => {
-                    eprintln!("[FALLBACK] Primary ({}) failed: {}", self.primary_id, e);
-                }
+        CallControl::current().check()?;
+        let mut attempts = Vec::new();
+        for link in self.links() {
+            if let Err(skip) = link.admit() {
+                eprintln!("[FALLBACK] Skipping {}: {}", link.id, skip.error.as_deref().unwrap_or_default());
+                attempts.push(skip);
+                continue;
             }
-        }
-        
-        // Essayer Secondary
-        if let Some(ref secondary) = self.secondary {
-            match secondary.generate(req.clone()) {
-                Ok(resp) => return Ok(resp),
+            match link.provider.generate(req.clone()) {
+                Ok(resp) => {
+                    attempts.push(link.succeeded());
+                    return Ok(Self::traced(resp, link.provider.model(), attempts.len() > 1, attempts));
+                }
+                // Annulation / échéance de l'opération: ni bascule ni réponse mock
+                Err(e @ OmegaError::Cancelled(_)) => { link.failed(&e); return Err(e); }
                 Err(e) => {
-                    eprintln!("[FALLBACK] Secondary ({}) failed: {}", self.secondary_id, e);
+                    eprintln!("[FALLBACK] {} failed: {}", link.id, e);
+                    attempts.push(link.failed(&e));
                 }
             }
         }
         
         // Fallback sur Mock (toujours réussit)
         eprintln!("[FALLBACK] Using mock provider (deterministic mode)");
-        self.fallback.generate(req)
+        let resp = self.fallback.generate(req)?;
+        Ok(self.mock_response(resp, attempts))
     }
     
-    fn embed(&self, req: EmbeddingRequest) -> OmegaResult<EmbeddingResponse> {
-        if let Some(ref primary) = self.primary {
-            if let Ok(resp) = primary.embed(req.clone()) {
-                return Ok(resp);
+    fn generate_stream(&self, req: CompletionRequest, on_delta: &mut dyn FnMut(StreamDelta), cancel: &CancelToken) -> OmegaResult<CompletionResponse> {
+        // Bascule uniquement si aucun delta n'a été émis (sinon l'UI recevrait deux textes mêlés)
+        let mut emitted = 0u32;
+        let mut attempts = Vec::new();
+        for link in self.links() {
+            if let Err(skip) = link.admit() {
+                attempts.push(skip);
+                continue;
             }
-        }
-        if let Some(ref secondary) = self.secondary {
-            if let Ok(resp) = secondary.embed(req.clone()) {
-                return Ok(resp);
+            let result = link.provider.generate_stream(req.clone(), &mut |d| { emitted += 1; on_delta(d) }, cancel);
+            match result {
+                Ok(resp) => {
+                    attempts.push(link.succeeded());
+                    return Ok(Self::traced(resp, link.provider.model(), attempts.len() > 1, attempts));
+                }
+                Err(e @ OmegaError::Cancelled(_)) => { link.failed(&e); return Err(e); }
+                Err(e) if emitted > 0 => { link.failed(&e); return Err(e); }
+                Err(e) => {
+                    eprintln!("[FALLBACK] Stream ({}) failed: {}", link.id, e);
+                    attempts.push(link.failed(&e));
+                }
             }
         }
-        self.fallback.embed(req)
+        eprintln!("[FALLBACK] Streaming from mock provider (deterministic mode)");
+        let resp = self.fallback.generate_stream(req, on_delta, cancel)?;
+        Ok(self.mock_response(resp, attempts))
+    }
+    
+    fn embed(&self, req: EmbeddingRequest) -> OmegaResult<EmbeddingResponse> {
+        self.embed_chain(&|p| p.embed(req.clone()).map(|r| vec![r]))?
+            .pop()
+            .ok_or_else(|| OmegaError::ProviderError("EMBEDDING_EMPTY".into()))
+    }
+    
+    fn embed_batch(&self, reqs: Vec<EmbeddingRequest>) -> OmegaResult<Vec<EmbeddingResponse>> {
+        self.embed_chain(&|p| p.embed_batch(reqs.clone()))
     }
 }
 
@@ -185,7 +394,37 @@ mod tests {
     fn test_fallback_always_succeeds() {
         let provider = FallbackProvider::new(None, None);
         
-        let req = CompletionRequest {
+        let result = provider.generate(req());
+        assert!(result.is_ok(), "Fallback should ALWAYS succeed");
+    }
+    
+    #[test]
+    fn test_fallback_stream_to_mock() {
+        let provider = FallbackProvider::new(None, None);
+        
+        let mut text = String::new();
+        let resp = provider.generate_stream(req(), &mut |d| text.push_str(&d.text), &CancelToken::new()).unwrap();
+        assert_eq!(text, resp.content);
+    }
+    
+    /// Provider réel toujours en échec (compte les appels effectivement envoyés)
+    struct DownProvider(std::sync::atomic::AtomicU32);
+    
+    impl LLMProvider for DownProvider {
+        fn id(&self) -> ProviderId { "down".into() }
+        fn capabilities(&self) -> ProviderCapabilities { MockDeterministicProvider::default().capabilities() }
+        fn health(&self) -> bool { true }
+        fn generate(&self, _req: CompletionRequest) -> OmegaResult<CompletionResponse> {
+            self.0.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Err(OmegaError::ProviderError("HTTP_500".into()))
+        }
+        fn embed(&self, _req: EmbeddingRequest) -> OmegaResult<EmbeddingResponse> {
+            Err(OmegaError::ProviderError("HTTP_500".into()))
+        }
+    }
+    
+    fn req() -> CompletionRequest {
+        CompletionRequest {
             run_id: "test".into(),
             seed: 42,
             system_prompt: "Test".into(),
@@ -195,9 +434,124 @@ mod tests {
             schema_name: None,
             json_schema: None,
             constraints: Default::default(),
+            template: None,
+            tool: None,
+        }
+    }
+    
+    #[test]
+    fn test_fallback_trace_truthful() {
+        let resp = FallbackProvider::new(None, None).generate(req()).unwrap();
+        let trace = resp.fallback.unwrap();
+        assert!(trace.fallback_used, "mock seul = fallback");
+        assert_eq!(trace.attempts.len(), 1);
+        
+        let down = Arc::new(DownProvider(Default::default()));
+        let resp = FallbackProvider::new(Some(down), None).generate(req()).unwrap();
+        let trace = resp.fallback.unwrap();
+        assert!(trace.fallback_used);
+        assert_eq!(trace.attempts[0].provider, "down");
+        assert_eq!(trace.attempts[0].outcome, AttemptOutcome::Failed);
+        assert_eq!(trace.responder, resp.provider_id);
+    }
+    
+    #[test]
+    fn test_breaker_skips_down_provider() {
+        let down = Arc::new(DownProvider(Default::default()));
+        let policy = ResiliencePolicy { breaker: BreakerPolicy { failure_threshold: 2, cool_down: Duration::from_secs(60) }, rate_limit: None };
+        let provider = FallbackProvider::with_policy(Some(down.clone()), None, policy);
+        for _ in 0..5 {
+            assert!(provider.generate(req()).is_ok());
+        }
+        assert_eq!(down.0.load(std::sync::atomic::Ordering::SeqCst), 2, "disjoncteur ouvert après 2 échecs");
+        assert_eq!(provider.circuit_states(), vec![("down".to_string(), CircuitState::Open)]);
+        let trace = provider.generate(req()).unwrap().fallback.unwrap();
+        assert_eq!(trace.attempts[0].outcome, AttemptOutcome::CircuitOpen);
+    }
+    
+    #[test]
+    fn test_chain_from_profiles() {
+        let profiles = ProviderProfiles::parse("[profiles.cloud]\ntype = \"openai\"\napi_key_env = \"OMEGA_TEST_UNSET_KEY\"\n\n[profiles.offline]\ntype = \"mock\"\n\n[fallback]\nchain = [\"cloud\", \"offline\"]", "test").unwrap();
+        let provider = FallbackProvider::from_profiles(&profiles, ResiliencePolicy::default()).unwrap();
+        assert_eq!(provider.chain_ids(), vec!["offline"], "profil sans clé écarté");
+        assert_eq!(provider.id(), "fallback[offline->none->mock]");
+        assert_eq!(provider.generate(req()).unwrap().fallback.unwrap().attempts[0].provider, "offline");
+        
+        let empty = ProviderProfiles::parse("[profiles.offline]\ntype = \"mock\"", "test").unwrap();
+        assert!(matches!(FallbackProvider::from_profiles(&empty, ResiliencePolicy::default()), Err(OmegaError::ConfigError(m)) if m.starts_with("PROFILE_CHAIN_EMPTY")));
+    }
+    
+    #[test]
+    fn test_scripted_rate_limit_falls_back_in_chain() {
+        let script = std::env::temp_dir().join(format!("omega-faults-{}.toml", uuid::Uuid::new_v4()));
+        std::fs::write(&script, "[[rule]]\nsteps = [{ fault = \"rate_limit\", retry_after_s = 1 }, { content = \"ok\" }]\n").unwrap();
+        let toml = format!("[profiles.faults]\ntype = \"mock-script\"\nscript = {:?}\n\n[profiles.offline]\ntype = \"mock\"\n\n[fallback]\nchain = [\"faults\", \"offline\"]", script.display().to_string());
+        let profiles = ProviderProfiles::parse(&toml, "test").unwrap();
+        let provider = FallbackProvider::from_profiles(&profiles, ResiliencePolicy::default()).unwrap();
+        
+        // 429 scripté: le maillon suivant répond, la bascule est tracée
+        let first = provider.generate(req()).unwrap();
+        let trace = first.fallback.unwrap();
+        assert_eq!((trace.attempts[0].provider.as_str(), trace.attempts[0].outcome), ("faults", AttemptOutcome::RateLimited));
+        assert!(trace.fallback_used);
+        assert_eq!(trace.responder, "mock-deterministic-v1");
+        
+        // Appel suivant: le scénario répond (disjoncteur toujours fermé)
+        let second = provider.generate(req()).unwrap();
+        assert_eq!(second.content, "ok");
+        assert!(!second.fallback.unwrap().fallback_used);
+        let _ = std::fs::remove_file(&script);
+    }
+    
+    #[test]
+    fn test_rate_limited_link_is_skipped() {
+        let down = Arc::new(DownProvider(Default::default()));
+        let policy = ResiliencePolicy {
+            breaker: BreakerPolicy { failure_threshold: 100, ..Default::default() },
+            rate_limit: Some(RateLimit { capacity: 1, per_minute: 1, max_wait: Duration::ZERO }),
         };
+        let provider = FallbackProvider::with_policy(Some(down.clone()), None, policy);
+        provider.generate(req()).unwrap();
+        let trace = provider.generate(req()).unwrap().fallback.unwrap();
+        assert_eq!(trace.attempts[0].outcome, AttemptOutcome::RateLimited);
+        assert_eq!(down.0.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+    
+    #[test]
+    fn test_embeddings_use_breaker_and_trace() {
+        let down = Arc::new(DownProvider(Default::default()));
+        let policy = ResiliencePolicy { breaker: BreakerPolicy { failure_threshold: 1, ..Default::default() }, rate_limit: None };
+        let provider = FallbackProvider::with_policy(Some(down), None, policy);
+        let embed_req = |input: &str| EmbeddingRequest { run_id: "test".into(), seed: 42, input: input.into() };
         
-        let result = provider.generate(req);
-        assert!(result.is_ok(), "Fallback should ALWAYS succeed");
+        let trace = provider.embed(embed_req("a")).unwrap().fallback.unwrap();
+        assert!(trace.fallback_used);
+        assert_eq!(trace.attempts[0].outcome, AttemptOutcome::Failed);
+        assert_eq!(provider.circuit_states()[0].1, CircuitState::Open, "échec d'embedding compté par le disjoncteur");
+        
+        let batch = provider.embed_batch(vec![embed_req("a"), embed_req("b")]).unwrap();
+        assert!(batch.iter().all(|r| r.fallback.as_ref().is_some_and(|t| t.attempts[0].outcome == AttemptOutcome::CircuitOpen)));
+        assert_eq!(batch[0].fallback.as_ref().unwrap().responder, batch[0].provider_id);
+    }
+    
+    #[test]
+    fn test_cancellation_not_swallowed() {
+        struct Cancelling;
+        impl LLMProvider for Cancelling {
+            fn id(&self) -> ProviderId { "cancelling".into() }
+            fn capabilities(&self) -> ProviderCapabilities { MockDeterministicProvider::default().capabilities() }
+            fn health(&self) -> bool { true }
+            fn generate(&self, _req: CompletionRequest) -> OmegaResult<CompletionResponse> { Err(OmegaError::Cancelled("CALL_CANCELLED".into())) }
+            fn embed(&self, _req: EmbeddingRequest) -> OmegaResult<EmbeddingResponse> { Err(OmegaError::NotSupported("embed".into())) }
+        }
+        let provider = FallbackProvider::new(Some(Arc::new(Cancelling)), None);
+        for _ in 0..5 {
+            assert!(matches!(provider.generate(req()), Err(OmegaError::Cancelled(_))), "pas de réponse mock après annulation");
+        }
+        assert_eq!(provider.circuit_states()[0].1, CircuitState::Closed, "annulation ≠ panne");
+        
+        let ctl = CallControl::new();
+        ctl.cancel.cancel();
+        assert!(matches!(ctl.scope(|| FallbackProvider::new(None, None).generate(req())), Err(OmegaError::Cancelled(_))));
     }
 }
@@ -1,10 +1,30 @@
 ﻿use crate::ai::models::*;
+use crate::ai::stream::{CancelToken, StreamDelta};
 use crate::error::OmegaResult;
 
 pub trait LLMProvider: Send + Sync {
     fn id(&self) -> ProviderId;
+    /// Modèle effectif (clé de cache, ledger); None si non applicable
+    fn model(&self) -> Option<String> { None }
     fn capabilities(&self) -> ProviderCapabilities;
     fn generate(&self, req: CompletionRequest) -> OmegaResult<CompletionResponse>;
     fn embed(&self, req: EmbeddingRequest) -> OmegaResult<EmbeddingResponse>;
+    /// Embeddings par lot (ordre conservé). Défaut: un appel embed() par entrée.
+    fn embed_batch(&self, reqs: Vec<EmbeddingRequest>) -> OmegaResult<Vec<EmbeddingResponse>> {
+        reqs.into_iter().map(|r| self.embed(r)).collect()
+    }
     fn health(&self) -> bool;
+    /// true si le texte ne quitte pas la machine (mock, serveur local); défaut prudent: distant
+    fn is_local(&self) -> bool { false }
+    /// (provider, modèle) que la requête peut atteindre (plafond USD pire cas). Défaut: ce provider seul
+    fn reachable_models(&self) -> Vec<(ProviderId, Option<String>)> { vec![(self.id(), self.model())] }
+    /// Génération en streaming: deltas via `on_delta`, réponse finale (usage + hash) en retour.
+    /// Défaut: generate() puis un delta unique (providers sans streaming natif).
+    fn generate_stream(&self, req: CompletionRequest, on_delta: &mut dyn FnMut(StreamDelta), cancel: &CancelToken) -> OmegaResult<CompletionResponse> {
+        cancel.check()?;
+        let resp = self.generate(req)?;
+        cancel.check()?;
+        if !resp.content.is_empty() { on_delta(StreamDelta { index: 0, text: resp.content.clone() }); }
+        Ok(resp)
+    }
 }
@@ -0,0 +1,543 @@
+//! OMEGA Usage Ledger — comptabilité des tokens, coût estimé, plafonds de budget
+//! NASA-Grade: chaque appel provider est journalisé (JSONL append-only); un plafond dépassé échoue AVANT l'envoi
+
+use crate::ai::interface::LLMProvider;
+use crate::ai::models::*;
+use crate::ai::stream::{CancelToken, StreamDelta};
+use crate::error::{OmegaError, OmegaResult};
+use crate::pipeline::fs_utils::ensure_dir;
+use chrono::{NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// Projet par défaut (aligné sur Holograph)
+pub const DEFAULT_PROJECT: &str = "default";
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// TABLE DE PRIX
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Prix USD par million de tokens
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ModelPrice {
+    pub input_per_mtok: f64,
+    pub output_per_mtok: f64,
+}
+
+impl ModelPrice {
+    pub fn cost(&self, prompt_tokens: u64, completion_tokens: u64) -> f64 {
+        (prompt_tokens as f64 * self.input_per_mtok + completion_tokens as f64 * self.output_per_mtok) / 1_000_000.0
+    }
+}
+
+/// Table modèle → prix; correspondance exacte puis plus long préfixe ("gpt-4o" couvre "gpt-4o-2024-08-06")
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PriceTable {
+    pub prices: BTreeMap<String, ModelPrice>,
+}
+
+impl Default for PriceTable {
+    fn default() -> Self {
+        let p = |i, o| ModelPrice { input_per_mtok: i, output_per_mtok: o };
+        let prices = [
+            ("gpt-4", p(30.0, 60.0)),
+            ("gpt-4-turbo", p(10.0, 30.0)),
+            ("gpt-4o", p(2.5, 10.0)),
+            ("gpt-4o-mini", p(0.15, 0.6)),
+            ("claude-3-5-sonnet", p(3.0, 15.0)),
+            ("claude-3-5-haiku", p(0.8, 4.0)),
+            ("claude-3-opus", p(15.0, 75.0)),
+        ].into_iter().map(|(m, price)| (m.to_string(), price)).collect();
+        Self { prices }
+    }
+}
+
+impl PriceTable {
+    /// Fichier JSON `{ "<model>": { "input_per_mtok": .., "output_per_mtok": .. } }` (remplace les défauts)
+    pub fn load(path: &Path) -> OmegaResult<Self> {
+        let content = fs::read_to_string(path).map_err(|e| OmegaError::ConfigError(format!("PRICE_TABLE {}: {}", path.display(), e)))?;
+        let prices: BTreeMap<String, ModelPrice> = serde_json::from_str(&content)
+            .map_err(|e| OmegaError::ConfigError(format!("PRICE_TABLE {}: {}", path.display(), e)))?;
+        Ok(Self { prices })
+    }
+
+    /// OMEGA_PRICE_TABLE (chemin JSON), sinon table intégrée
+    pub fn from_env() -> OmegaResult<Self> {
+        match std::env::var("OMEGA_PRICE_TABLE").ok().filter(|v| !v.is_empty()) {
+            Some(path) => Self::load(Path::new(&path)),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// Prix d'un appel; None = inconnu (mock, serveur local, modèle absent de la table)
+    pub fn price(&self, provider_id: &str, model: Option<&str>) -> Option<ModelPrice> {
+        if provider_id.starts_with("mock") { return Some(ModelPrice { input_per_mtok: 0.0, output_per_mtok: 0.0 }); }
+        let model = model?;
+        self.prices.get(model).copied().or_else(|| {
+            self.prices.iter()
+                .filter(|(k, _)| model.starts_with(k.as_str()))
+                .max_by_key(|(k, _)| k.len())
+                .map(|(_, p)| *p)
+        })
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// PLAFONDS
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Plafond d'une portée (tokens et/ou USD)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct BudgetCap {
+    pub max_tokens: Option<u64>,
+    pub max_cost_usd: Option<f64>,
+}
+
+impl BudgetCap {
+    pub fn is_set(&self) -> bool { self.max_tokens.is_some() || self.max_cost_usd.is_some() }
+}
+
+/// Plafonds par run, par jour (UTC, tous projets) et par projet (cumul)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct BudgetCaps {
+    pub run: BudgetCap,
+    pub day: BudgetCap,
+    pub project: BudgetCap,
+}
+
+impl BudgetCaps {
+    /// OMEGA_BUDGET_{RUN,DAY,PROJECT}_{TOKENS,USD}
+    pub fn from_env() -> Self {
+        let var = |k: &str| std::env::var(k).ok().filter(|v| !v.is_empty());
+        let cap = |scope: &str| BudgetCap {
+            max_tokens: var(&format!("OMEGA_BUDGET_{}_TOKENS", scope)).and_then(|v| v.parse().ok()),
+            max_cost_usd: var(&format!("OMEGA_BUDGET_{}_USD", scope)).and_then(|v| v.parse().ok()),
+        };
+        Self { run: cap("RUN"), day: cap("DAY"), project: cap("PROJECT") }
+    }
+}
+
+/// Estimation avant envoi: ~4 caractères par token pour le prompt, max_tokens pour la sortie (pire cas)
+pub fn estimate_prompt_tokens(req: &CompletionRequest) -> u64 {
+    let chars = req.system_prompt.chars().count() + req.user_prompt.chars().count();
+    chars.div_ceil(4) as u64
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// LEDGER
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Ligne du ledger: un appel provider
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UsageEntry {
+    pub timestamp: String,
+    /// Jour UTC (YYYY-MM-DD)
+    pub day: String,
+    pub run_id: String,
+    pub project: String,
+    /// Provider ayant répondu
+    pub provider: ProviderId,
+    pub model: Option<String>,
+    pub prompt_tokens: u32,
+    pub completion_tokens: u32,
+    pub total_tokens: u32,
+    pub latency_ms: u64,
+    /// Estimation utilisée pour le contrôle de budget
+    pub estimated_tokens: u64,
+    pub cost_usd: f64,
+    /// false si le modèle est absent de la table de prix (coût 0)
+    pub priced: bool,
+}
+
+/// Agrégats d'un ensemble d'appels
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UsageTotals {
+    pub calls: u64,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    pub total_tokens: u64,
+    pub latency_ms: u64,
+    pub cost_usd: f64,
+    /// Appels sans prix connu
+    pub unpriced_calls: u64,
+}
+
+impl UsageTotals {
+    pub fn add(&mut self, e: &UsageEntry) {
+        self.calls += 1;
+        self.prompt_tokens += e.prompt_tokens as u64;
+        self.completion_tokens += e.completion_tokens as u64;
+        self.total_tokens += e.total_tokens as u64;
+        self.latency_ms += e.latency_ms;
+        self.cost_usd += e.cost_usd;
+        if !e.priced { self.unpriced_calls += 1; }
+    }
+}
+
+/// Rapport d'usage sur une période (bornes incluses)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UsageReport {
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub project: Option<String>,
+    pub totals: UsageTotals,
+    pub by_day: BTreeMap<String, UsageTotals>,
+    pub by_provider: BTreeMap<String, UsageTotals>,
+    pub by_model: BTreeMap<String, UsageTotals>,
+    pub by_run: BTreeMap<String, UsageTotals>,
+}
+
+/// Journal JSONL append-only; chargé à la première utilisation
+#[derive(Debug)]
+pub struct UsageLedger {
+    path: PathBuf,
+    entries: Mutex<Option<Vec<UsageEntry>>>,
+}
+
+impl UsageLedger {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into(), entries: Mutex::new(None) }
+    }
+
+    pub fn path(&self) -> &Path { &self.path }
+
+    /// Lignes illisibles ignorées (écriture interrompue)
+    fn load(&self) -> OmegaResult<Vec<UsageEntry>> {
+        if !self.path.exists() { return Ok(Vec::new()); }
+        let content = fs::read_to_string(&self.path).map_err(|e| OmegaError::ReadError(format!("USAGE_LEDGER: {}", e)))?;
+        Ok(content.lines().filter_map(|l| serde_json::from_str(l).ok()).collect())
+    }
+
+    fn with_entries<T>(&self, f: impl FnOnce(&mut Vec<UsageEntry>) -> OmegaResult<T>) -> OmegaResult<T> {
+        let mut guard = self.entries.lock().unwrap();
+        if guard.is_none() { *guard = Some(self.load()?); }
+        f(guard.as_mut().unwrap())
+    }
+
+    pub fn append(&self, entry: UsageEntry) -> OmegaResult<()> {
+        self.with_entries(|entries| {
+            if let Some(parent) = self.path.parent() { ensure_dir(parent)?; }
+            let mut file = OpenOptions::new().create(true).append(true).open(&self.path)
+                .map_err(|e| OmegaError::WriteError(format!("USAGE_LEDGER: {}", e)))?;
+            writeln!(file, "{}", serde_json::to_string(&entry)?).map_err(|e| OmegaError::WriteError(format!("USAGE_LEDGER: {}", e)))?;
+            entries.push(entry);
+            Ok(())
+        })
+    }
+
+    pub fn entries(&self) -> OmegaResult<Vec<UsageEntry>> {
+        self.with_entries(|entries| Ok(entries.clone()))
+    }
+
+    /// Agrégats des entrées retenues par le filtre
+    pub fn totals(&self, filter: impl Fn(&UsageEntry) -> bool) -> OmegaResult<UsageTotals> {
+        self.with_entries(|entries| {
+            let mut totals = UsageTotals::default();
+            entries.iter().filter(|e| filter(e)).for_each(|e| totals.add(e));
+            Ok(totals)
+        })
+    }
+
+    /// Rapport sur [from, to] (YYYY-MM-DD, UTC), optionnellement limité à un projet
+    pub fn report(&self, from: Option<&str>, to: Option<&str>, project: Option<&str>) -> OmegaResult<UsageReport> {
+        for day in [from, to].into_iter().flatten() {
+            NaiveDate::parse_from_str(day, "%Y-%m-%d").map_err(|_| OmegaError::ConfigError(format!("Date invalide (YYYY-MM-DD): {}", day)))?;
+        }
+        let mut report = UsageReport {
+            from: from.map(String::from),
+            to: to.map(String::from),
+            project: project.map(String::from),
+            ..Default::default()
+        };
+        for e in self.entries()? {
+            if from.is_some_and(|f| e.day.as_str() < f) || to.is_some_and(|t| e.day.as_str() > t) { continue; }
+            if project.is_some_and(|p| e.project != p) { continue; }
+            report.totals.add(&e);
+            report.by_day.entry(e.day.clone()).or_default().add(&e);
+            report.by_provider.entry(e.provider.clone()).or_default().add(&e);
+            report.by_model.entry(e.model.clone().unwrap_or_else(|| "unknown".into())).or_default().add(&e);
+            report.by_run.entry(e.run_id.clone()).or_default().add(&e);
+        }
+        Ok(report)
+    }
+}
+
+/// Ledger d'un chemin de sortie (omega-ui-output/usage-ledger.jsonl)
+pub fn default_ledger_path(output_dir: &Path) -> PathBuf { output_dir.join("usage-ledger.jsonl") }
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// PROVIDER INSTRUMENTÉ
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Enveloppe un provider: contrôle des plafonds avant envoi, journalisation après réponse
+pub struct MeteredProvider {
+    inner: Arc<dyn LLMProvider>,
+    ledger: Arc<UsageLedger>,
+    prices: PriceTable,
+    caps: BudgetCaps,
+    project: String,
+    /// Run imputé (sinon run_id de la requête)
+    run_id: Option<String>,
+}
+
+impl MeteredProvider {
+    pub fn new(inner: Arc<dyn LLMProvider>, ledger: Arc<UsageLedger>, prices: PriceTable, caps: BudgetCaps) -> Self {
+        Self { inner, ledger, prices, caps, project: DEFAULT_PROJECT.into(), run_id: None }
+    }
+
+    pub fn with_project(mut self, project: &str) -> Self { self.project = project.into(); self }
+    pub fn with_run_id(mut self, run_id: &str) -> Self { self.run_id = Some(run_id.into()); self }
+
+    fn run_id(&self, req: &CompletionRequest) -> String {
+        self.run_id.clone().unwrap_or_else(|| req.run_id.clone())
+    }
+
+    /// Vérifie run / jour / projet; retourne l'estimation de tokens
+    fn check_budget(&self, req: &CompletionRequest) -> OmegaResult<u64> {
+        let prompt = estimate_prompt_tokens(req);
+        let estimated = prompt + req.max_tokens as u64;
+        // Coût pire cas: modèle le plus cher que la requête peut atteindre, replis compris
+        // (tarif inconnu = non plafonnable en USD)
+        let est_cost = self.inner.reachable_models().iter()
+            .filter_map(|(id, model)| self.prices.price(id, model.as_deref()))
+            .map(|p| p.cost(prompt, req.max_tokens as u64))
+            .fold(0.0, f64::max);
+        let run_id = self.run_id(req);
+        let today = Utc::now().format("%Y-%m-%d").to_string();
+        for (scope, cap) in [("RUN", self.caps.run), ("DAY", self.caps.day), ("PROJECT", self.caps.project)] {
+            if !cap.is_set() { continue; }
+            let used = self.ledger.totals(|e| match scope {
+                "RUN" => e.run_id == run_id,
+                "DAY" => e.day == today,
+                _ => e.project == self.project,
+            })?;
+            if let Some(max) = cap.max_tokens {
+                if used.total_tokens + estimated > max {
+                    return Err(OmegaError::BudgetExceeded(format!(
+                        "{}_TOKENS: used {} + estimated {} > cap {}", scope, used.total_tokens, estimated, max
+                    )));
+                }
+            }
+            if let Some(max) = cap.max_cost_usd {
+                if used.cost_usd + est_cost > max {
+                    return Err(OmegaError::BudgetExceeded(format!(
+                        "{}_USD: used {:.6} + estimated {:.6} > cap {:.6}", scope, used.cost_usd, est_cost, max
+                    )));
+                }
+            }
+        }
+        Ok(estimated)
+    }
+
+    fn record(&self, req: &CompletionRequest, estimated: u64, resp: &CompletionResponse) -> OmegaResult<()> {
+        // Derrière un FallbackProvider: modèle du maillon qui a répondu (repli mock: pas de modèle facturé)
+        let model = match &resp.fallback {
+            Some(trace) => trace.model.clone(),
+            None if resp.provider_id.starts_with("mock") => None,
+            None => self.inner.model(),
+        };
+        let price = self.prices.price(&resp.provider_id, model.as_deref());
+        let now = Utc::now();
+        self.ledger.append(UsageEntry {
+            timestamp: now.to_rfc3339(),
+            day: now.format("%Y-%m-%d").to_string(),
+            run_id: self.run_id(req),
+            project: self.project.clone(),
+            provider: resp.provider_id.clone(),
+            cost_usd: price.map(|p| p.cost(resp.usage.prompt_tokens as u64, resp.usage.completion_tokens as u64)).unwrap_or(0.0),
+            priced: price.is_some(),
+            model,
+            prompt_tokens: resp.usage.prompt_tokens,
+            completion_tokens: resp.usage.completion_tokens,
+            total_tokens: resp.usage.total_tokens,
+            latency_ms: resp.latency_ms,
+            estimated_tokens: estimated,
+        })
+    }
+}
+
+impl LLMProvider for MeteredProvider {
+    fn id(&self) -> ProviderId { self.inner.id() }
+    fn model(&self) -> Option<String> { self.inner.model() }
+    fn capabilities(&self) -> ProviderCapabilities { self.inner.capabilities() }
+    fn health(&self) -> bool { self.inner.health() }
+    fn is_local(&self) -> bool { self.inner.is_local() }
+    fn reachable_models(&self) -> Vec<(ProviderId, Option<String>)> { self.inner.reachable_models() }
+    fn embed(&self, req: EmbeddingRequest) -> OmegaResult<EmbeddingResponse> { self.inner.embed(req) }
+    fn embed_batch(&self, reqs: Vec<EmbeddingRequest>) -> OmegaResult<Vec<EmbeddingResponse>> { self.inner.embed_batch(reqs) }
+
+    fn generate(&self, req: CompletionRequest) -> OmegaResult<CompletionResponse> {
+        let estimated = self.check_budget(&req)?;
+        let resp = self.inner.generate(req.clone())?;
+        self.record(&req, estimated, &resp)?;
+        Ok(resp)
+    }
+
+    fn generate_stream(&self, req: CompletionRequest, on_delta: &mut dyn FnMut(StreamDelta), cancel: &CancelToken) -> OmegaResult<CompletionResponse> {
+        let estimated = self.check_budget(&req)?;
+        let resp = self.inner.generate_stream(req.clone(), on_delta, cancel)?;
+        self.record(&req, estimated, &resp)?;
+        Ok(resp)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::MockDeterministicProvider;
+
+    fn temp_ledger() -> Arc<UsageLedger> {
+        Arc::new(UsageLedger::new(std::env::temp_dir().join(format!("omega-ledger-{}.jsonl", uuid::Uuid::new_v4()))))
+    }
+
+    fn req(run_id: &str) -> CompletionRequest {
+        CompletionRequest {
+            run_id: run_id.into(),
+            seed: 42,
+            system_prompt: "System".into(),
+            user_prompt: "Analyse ce texte".into(),
+            temperature: 0.0,
+            max_tokens: 100,
+            schema_name: None,
+            json_schema: None,
+            constraints: Default::default(),
+            template: None,
+            tool: None,
+        }
+    }
+
+    fn metered(ledger: &Arc<UsageLedger>, caps: BudgetCaps) -> MeteredProvider {
+        MeteredProvider::new(Arc::new(MockDeterministicProvider::default()), Arc::clone(ledger), PriceTable::default(), caps)
+    }
+
+    #[test]
+    fn test_price_lookup_prefix_and_cost() {
+        let t = PriceTable::default();
+        assert_eq!(t.price("openai", Some("gpt-4o-mini-2024-07-18")).unwrap().input_per_mtok, 0.15);
+        assert_eq!(t.price("openai", Some("gpt-4o-2024-08-06")).unwrap().input_per_mtok, 2.5);
+        assert_eq!(t.price("mock-deterministic-v1", None).unwrap().cost(1000, 1000), 0.0);
+        assert!(t.price("openai-compatible", Some("llama3")).is_none());
+        let p = t.price("anthropic", Some("claude-3-5-sonnet-20241022")).unwrap();
+        assert!((p.cost(1_000_000, 100_000) - 4.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_ledger_records_and_persists() {
+        let ledger = temp_ledger();
+        let p = metered(&ledger, BudgetCaps::default()).with_project("roman");
+        p.generate(req("RUN_A")).unwrap();
+        p.generate(req("RUN_B")).unwrap();
+
+        let reloaded = UsageLedger::new(ledger.path());
+        let entries = reloaded.entries().unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].run_id, "RUN_A");
+        assert_eq!(entries[0].project, "roman");
+        assert_eq!(entries[0].total_tokens, 30);
+        assert_eq!(entries[0].estimated_tokens, estimate_prompt_tokens(&req("RUN_A")) + 100);
+        assert!(entries[0].priced);
+    }
+
+    #[test]
+    fn test_budget_blocks_before_send() {
+        let ledger = temp_ledger();
+        let caps = BudgetCaps { run: BudgetCap { max_tokens: Some(250), max_cost_usd: None }, ..Default::default() };
+        let p = metered(&ledger, caps).with_run_id("RUN_CAP");
+        // Estimation 106 tokens, 30 consommés par appel: le 6e (150 + 106 > 250) est refusé
+        for _ in 0..5 { p.generate(req("ignored")).unwrap(); }
+        let err = p.generate(req("ignored")).unwrap_err();
+        assert!(matches!(err, OmegaError::BudgetExceeded(ref m) if m.starts_with("RUN_TOKENS")));
+        assert_eq!(ledger.entries().unwrap().len(), 5, "appel refusé non journalisé");
+
+        // Autre run: plafond run non atteint, plafond projet atteint
+        let caps = BudgetCaps { project: BudgetCap { max_tokens: Some(200), max_cost_usd: None }, ..Default::default() };
+        let err = metered(&ledger, caps).generate(req("RUN_OTHER")).unwrap_err();
+        assert!(matches!(err, OmegaError::BudgetExceeded(ref m) if m.starts_with("PROJECT_TOKENS")));
+    }
+
+    #[test]
+    fn test_fallback_priced_by_responding_model() {
+        /// Maillon OpenAI simulé: réponse du mock sous l'id "openai", ou panne
+        struct Link(&'static str, bool);
+        impl LLMProvider for Link {
+            fn id(&self) -> ProviderId { "openai".into() }
+            fn model(&self) -> Option<String> { Some(self.0.into()) }
+            fn capabilities(&self) -> ProviderCapabilities { MockDeterministicProvider::default().capabilities() }
+            fn health(&self) -> bool { true }
+            fn generate(&self, req: CompletionRequest) -> OmegaResult<CompletionResponse> {
+                if !self.1 { return Err(OmegaError::ProviderError("HTTP_500".into())); }
+                Ok(CompletionResponse { provider_id: self.id(), ..MockDeterministicProvider::default().generate(req)? })
+            }
+            fn embed(&self, _req: EmbeddingRequest) -> OmegaResult<EmbeddingResponse> { Err(OmegaError::NotSupported("embed".into())) }
+        }
+        let ledger = temp_ledger();
+        let chain = crate::ai::FallbackProvider::new(Some(Arc::new(Link("gpt-4o", false))), Some(Arc::new(Link("gpt-4o-mini", true))));
+        let p = MeteredProvider::new(Arc::new(chain), Arc::clone(&ledger), PriceTable::default(), BudgetCaps::default());
+        p.generate(req("RUN_FB")).unwrap();
+        let entry = &ledger.entries().unwrap()[0];
+        assert_eq!(entry.model.as_deref(), Some("gpt-4o-mini"));
+        let mini = PriceTable::default().price("openai", Some("gpt-4o-mini")).unwrap();
+        assert!((entry.cost_usd - mini.cost(10, 20)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_usd_cap_priced_by_costliest_fallback_link() {
+        /// Maillon simulé: "openai-compatible" (local, non tarifé) ou "openai" (tarifé)
+        struct Link(&'static str, &'static str);
+        impl LLMProvider for Link {
+            fn id(&self) -> ProviderId { self.0.into() }
+            fn model(&self) -> Option<String> { Some(self.1.into()) }
+            fn capabilities(&self) -> ProviderCapabilities { MockDeterministicProvider::default().capabilities() }
+            fn health(&self) -> bool { true }
+            fn is_local(&self) -> bool { self.0 == "openai-compatible" }
+            fn generate(&self, req: CompletionRequest) -> OmegaResult<CompletionResponse> {
+                Ok(CompletionResponse { provider_id: self.id(), ..MockDeterministicProvider::default().generate(req)? })
+            }
+            fn embed(&self, _req: EmbeddingRequest) -> OmegaResult<EmbeddingResponse> { Err(OmegaError::NotSupported("embed".into())) }
+        }
+        let ledger = temp_ledger();
+        let chain = crate::ai::FallbackProvider::new(Some(Arc::new(Link("openai-compatible", "llama3"))), Some(Arc::new(Link("openai", "gpt-4o"))));
+        let caps = BudgetCaps { run: BudgetCap { max_tokens: None, max_cost_usd: Some(0.0001) }, ..Default::default() };
+        let p = MeteredProvider::new(Arc::new(chain), Arc::clone(&ledger), PriceTable::default(), caps);
+        // Principal local gratuit, mais le repli gpt-4o peut coûter 100 tokens de sortie (0,001 USD)
+        let err = p.generate(req("RUN_USD")).unwrap_err();
+        assert!(matches!(err, OmegaError::BudgetExceeded(ref m) if m.starts_with("RUN_USD")), "{}", err);
+        assert!(ledger.entries().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_report_period_and_groups() {
+        let ledger = temp_ledger();
+        let entry = |day: &str, run: &str, provider: &str, tokens: u32, cost: f64| UsageEntry {
+            timestamp: format!("{}T10:00:00Z", day),
+            day: day.into(),
+            run_id: run.into(),
+            project: DEFAULT_PROJECT.into(),
+            provider: provider.into(),
+            model: Some("gpt-4o".into()),
+            prompt_tokens: tokens,
+            completion_tokens: 0,
+            total_tokens: tokens,
+            latency_ms: 5,
+            estimated_tokens: tokens as u64,
+            cost_usd: cost,
+            priced: true,
+        };
+        ledger.append(entry("2026-01-01", "R1", "openai", 100, 0.5)).unwrap();
+        ledger.append(entry("2026-01-02", "R2", "openai", 200, 1.0)).unwrap();
+        ledger.append(entry("2026-01-03", "R3", "anthropic", 400, 2.0)).unwrap();
+
+        let r = ledger.report(Some("2026-01-02"), Some("2026-01-03"), None).unwrap();
+        assert_eq!(r.totals.calls, 2);
+        assert_eq!(r.totals.total_tokens, 600);
+        assert!((r.totals.cost_usd - 3.0).abs() < 1e-9);
+        assert_eq!(r.by_provider["openai"].calls, 1);
+        assert_eq!(r.by_day.len(), 2);
+        assert_eq!(ledger.report(None, None, Some("autre")).unwrap().totals.calls, 0);
+        assert!(matches!(ledger.report(Some("01/02/2026"), None, None), Err(OmegaError::ConfigError(_))));
+    }
+}
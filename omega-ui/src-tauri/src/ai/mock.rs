@@ -1,5 +1,6 @@
 ﻿use crate::ai::interface::LLMProvider;
 use crate::ai::models::*;
+use crate::ai::stream::{CancelToken, StreamDelta};
 use crate::error::{OmegaError, OmegaResult};
 use sha2::{Digest, Sha256};
 use std::time::{Duration, Instant};
@@ -9,11 +10,13 @@ use std::thread;
 pub struct MockDeterministicProvider {
     pub provider_id: ProviderId,
     pub latency_ms: u64,
+    /// Taille (en caractères) des deltas émis par generate_stream
+    pub stream_chunk_chars: usize,
 }
 
 impl Default for MockDeterministicProvider {
     fn default() -> Self {
-        Self { provider_id: "mock-deterministic-v1".into(), latency_ms: 10 }
+        Self { provider_id: "mock-deterministic-v1".into(), latency_ms: 10, stream_chunk_chars: 8 }
     }
 }
 
@@ -25,6 +28,27 @@ fn sha256_hex(data: &[u8]) -> String {
     format!("{:x}", Sha256::digest(data))
 }
 
+/// Modèle des pseudo-embeddings du mock
+pub const MOCK_EMBEDDING_MODEL: &str = "mock-feature-hash-v1";
+
+/// Dimension des pseudo-embeddings du mock
+pub const MOCK_EMBEDDING_DIMS: usize = 256;
+
+/// Pseudo-embedding stable (indépendant du seed): hachage des mots normalisés dans MOCK_EMBEDDING_DIMS cases signées, norme L2 = 1.
+/// Deux textes partageant du vocabulaire sont proches: la recherche sémantique se teste hors ligne.
+pub fn pseudo_embedding(input: &str) -> Vec<f32> {
+    let mut v = vec![0f32; MOCK_EMBEDDING_DIMS];
+    for word in crate::lexicon_fr_gold::normalize_fr(input).split_whitespace().filter(|w| w.chars().count() >= 3) {
+        let word = if word.len() > 3 { word.strip_suffix('s').unwrap_or(word) } else { word };
+        let digest = Sha256::digest(word.as_bytes());
+        let bucket = u16::from_be_bytes([digest[0], digest[1]]) as usize % MOCK_EMBEDDING_DIMS;
+        v[bucket] += if digest[2] & 1 == 0 { 1.0 } else { -1.0 };
+    }
+    let norm = v.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm > 0.0 { v.iter_mut().for_each(|x| *x /= norm); }
+    v
+}
+
 fn fingerprint(req: &CompletionRequest) -> String {
     format!("seed={}|sys={}|user={}", req.seed, req.system_prompt, req.user_prompt)
 }
@@ -32,12 +56,14 @@ fn fingerprint(req: &CompletionRequest) -> String {
 impl LLMProvider for MockDeterministicProvider {
     fn id(&self) -> ProviderId { self.provider_id.clone() }
     
+    fn is_local(&self) -> bool { true }
+    
     fn capabilities(&self) -> ProviderCapabilities {
         ProviderCapabilities {
             id: self.provider_id.clone(),
             max_context_window: 128_000,
             supports_json_mode: true,
-            supports_streaming: false,
+            supports_streaming: true,
             supports_tool_calling: false,
             supports_embeddings: true,
         }
@@ -46,9 +72,7 @@ impl LLMProvider for MockDeterministicProvider {
     fn health(&self) -> bool { true }
     
     fn embed(&self, req: EmbeddingRequest) -> OmegaResult<EmbeddingResponse> {
-        let hash = sha256_hex(format!("{}|{}", req.seed, req.input).as_bytes());
-        let v: Vec<f32> = (0..16).map(|i| (u32::from_str_radix(&hash[i*2..i*2+2], 16).unwrap_or(0) as f32) / 255.0).collect();
-        Ok(EmbeddingResponse { provider_id: self.provider_id.clone(), vectors: v })
+        Ok(EmbeddingResponse { provider_id: self.provider_id.clone(), model: Some(MOCK_EMBEDDING_MODEL.into()), vectors: pseudo_embedding(&req.input), fallback: None })
     }
     
     fn generate(&self, req: CompletionRequest) -> OmegaResult<CompletionResponse> {
@@ -62,8 +86,13 @@ impl LLMProvider for MockDeterministicProvider {
         let hash = sha256_hex(format!("{}|{}", req.seed, fp).as_bytes());
         let short = &hash[0..12];
         
-        let (content, parsed) = if req.json_schema.is_some() {
-            let j = serde_json::json!({"mock": true, "hash": short});
+        let (content, parsed) = if let Some(schema) = &req.json_schema {
+            // Instance minimale conforme au schéma + marqueurs mock (si le schéma les tolère)
+            let mut j = crate::ai::schema::minimal_instance(schema);
+            if let Some(obj) = j.as_object_mut().filter(|_| schema.get("additionalProperties") != Some(&serde_json::Value::Bool(false))) {
+                obj.insert("mock".into(), serde_json::json!(true));
+                obj.insert("hash".into(), serde_json::json!(short));
+            }
             (j.to_string(), Some(j))
         } else {
             (format!("[MOCK] seed={} hash={}", req.seed, short), None)
@@ -78,8 +107,21 @@ impl LLMProvider for MockDeterministicProvider {
             usage: Usage { prompt_tokens: 10, completion_tokens: 20, total_tokens: 30 },
             latency_ms: start.elapsed().as_millis() as u64,
             response_hash,
+            cache: None,
+            fallback: None,
         })
     }
+    
+    fn generate_stream(&self, req: CompletionRequest, on_delta: &mut dyn FnMut(StreamDelta), cancel: &CancelToken) -> OmegaResult<CompletionResponse> {
+        cancel.check()?;
+        let resp = self.generate(req)?;
+        let chars: Vec<char> = resp.content.chars().collect();
+        for (i, chunk) in chars.chunks(self.stream_chunk_chars.max(1)).enumerate() {
+            cancel.check()?;
+            on_delta(StreamDelta { index: i as u32, text: chunk.iter().collect() });
+        }
+        Ok(resp)
+    }
 }
 
 #[cfg(test)]
@@ -99,6 +141,8 @@ mod tests {
             schema_name: None,
             json_schema: None,
             constraints: Default::default(),
+            template: None,
+            tool: None,
         };
         
         let r1 = provider.generate(req.clone()).unwrap();
@@ -122,6 +166,8 @@ mod tests {
             schema_name: None,
             json_schema: None,
             constraints: Default::default(),
+            template: None,
+            tool: None,
         };
         let mut req2 = req1.clone();
         req2.seed = 99;
@@ -133,6 +179,63 @@ mod tests {
         println!("[PASS] seed=42: {} | seed=99: {}", r1.response_hash, r2.response_hash);
     }
     
+    #[test]
+    fn test_stream_chunks_deterministic() {
+        let provider = MockDeterministicProvider::new();
+        let req = CompletionRequest {
+            run_id: "test".into(),
+            seed: 42,
+            system_prompt: "System".into(),
+            user_prompt: "User".into(),
+            temperature: 0.0,
+            max_tokens: 100,
+            schema_name: None,
+            json_schema: None,
+            constraints: Default::default(),
+            template: None,
+            tool: None,
+        };
+        
+        let mut d1 = Vec::new();
+        let r1 = provider.generate_stream(req.clone(), &mut |d| d1.push(d), &CancelToken::new()).unwrap();
+        let mut d2 = Vec::new();
+        provider.generate_stream(req.clone(), &mut |d| d2.push(d), &CancelToken::new()).unwrap();
+        let full = provider.generate(req).unwrap();
+        
+        assert_eq!(d1, d2, "Stream chunks must be deterministic!");
+        assert!(d1.len() > 1);
+        assert!(d1.iter().all(|d| d.text.chars().count() <= 8));
+        assert_eq!(d1.iter().map(|d| d.text.as_str()).collect::<String>(), full.content);
+        assert_eq!(r1.response_hash, full.response_hash, "Stream hash must match generate()");
+        println!("[PASS] Stream: {} chunks", d1.len());
+    }
+    
+    #[test]
+    fn test_stream_cancelled() {
+        let provider = MockDeterministicProvider::new();
+        let req = CompletionRequest {
+            run_id: "test".into(),
+            seed: 42,
+            system_prompt: "System".into(),
+            user_prompt: "User".into(),
+            temperature: 0.0,
+            max_tokens: 100,
+            schema_name: None,
+            json_schema: None,
+            constraints: Default::default(),
+            template: None,
+            tool: None,
+        };
+        
+        let cancel = CancelToken::new();
+        let mut received = 0;
+        let result = provider.generate_stream(req, &mut |_| { received += 1; cancel.cancel(); }, &cancel);
+        
+        assert!(matches!(result, Err(OmegaError::Cancelled(_))));
+        assert_eq!(received, 1, "No delta after cancel!");
+        println!("[PASS] Stream cancellation works!");
+    }
+    
     #[test]
     fn test_temperature_guard() {
         let provider = MockDeterministicProvider::new();
@@ -146,10 +249,25 @@ mod tests {
             schema_name: None,
             json_schema: None,
             constraints: Default::default(),
+            template: None,
+            tool: None,
         };
         
         let result = provider.generate(req);
         assert!(result.is_err(), "Should fail with non-zero temperature!");
         println!("[PASS] Temperature guard works!");
     }
+    
+    #[test]
+    fn test_pseudo_embeddings_stable_and_lexical() {
+        let provider = MockDeterministicProvider::new();
+        let embed = |seed, input: &str| provider.embed(EmbeddingRequest { run_id: "test".into(), seed, input: input.into() }).unwrap().vectors;
+        let a = embed(1, "Elle doute de son père");
+        assert_eq!(a, embed(99, "Elle doute de son père"), "seed sans effet");
+        assert_eq!(a.len(), MOCK_EMBEDDING_DIMS);
+        let dot = |x: &[f32], y: &[f32]| x.iter().zip(y).map(|(p, q)| p * q).sum::<f32>();
+        assert!((dot(&a, &a) - 1.0).abs() < 1e-5);
+        assert!(dot(&a, &embed(1, "Les doutes envers le pere")) > dot(&a, &embed(1, "La mer est calme ce matin")));
+        assert!(embed(1, "").iter().all(|x| *x == 0.0));
+    }
 }
@@ -0,0 +1,403 @@
+//! OMEGA Mock Script — provider mock piloté par scénario (injection de pannes)
+//! NASA-Grade: les chemins d'erreur (fallback, retries, repli Hybrid) se testent de façon reproductible
+//!
+//! Script TOML: règles essayées dans l'ordre (regex sur system_prompt + "\n" + user_prompt),
+//! chaque règle déroule ses étapes appel après appel; aucune règle → réponse du mock déterministe.
+//!
+//! ```toml
+//! seed = 42
+//! timeout_ms = 500
+//!
+//! [latency]
+//! dist = "uniform"        # none | fixed (ms) | uniform (min_ms, max_ms) | exponential (mean_ms, max_ms)
+//! min_ms = 5
+//! max_ms = 40
+//!
+//! [[rule]]
+//! prompt = "(?i)émotions"
+//! cycle = false           # étapes épuisées: répéter la dernière (false) ou recommencer (true)
+//! steps = [
+//!     { times = 2 },                                  # réponse du mock déterministe
+//!     { content = '{"emotions": []}' },
+//!     { fault = "rate_limit", retry_after_s = 120 },
+//!     { fault = "timeout" },
+//!     { fault = "malformed_json" },
+//!     { fault = "truncated" },
+//!     { fault = "http_500" },
+//! ]
+//! ```
+
+use crate::ai::async_provider::{generate_blocking, AsyncLLMProvider, BoxFuture, CallControl};
+use crate::ai::interface::LLMProvider;
+use crate::ai::mock::MockDeterministicProvider;
+use crate::ai::models::*;
+use crate::error::{OmegaError, OmegaResult};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Préfixe de `omega_run --provider mock-script:<file>`
+pub const MOCK_SCRIPT_PREFIX: &str = "mock-script:";
+
+/// Contenu d'une réponse `malformed_json` (JSON invalide: clés non quotées, virgule finale)
+pub const MALFORMED_JSON: &str = "{emotions: [{emotion: 'joy', score: 0.8},]}";
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// SCRIPT
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Fault {
+    /// HTTP 429 → RateLimit (Retry-After optionnel)
+    RateLimit,
+    /// Attente de `timeout_ms` puis échec réseau (ou échéance du CallControl si plus proche)
+    Timeout,
+    /// Réponse 200 dont le contenu n'est pas du JSON valide
+    MalformedJson,
+    /// Réponse 200 coupée à mi-longueur (max_tokens atteint)
+    Truncated,
+    /// HTTP 500 → ProviderError
+    #[serde(rename = "http_500")]
+    Http500,
+}
+
+impl Fault {
+    fn label(&self) -> &'static str {
+        match self {
+            Self::RateLimit => "rate_limit",
+            Self::Timeout => "timeout",
+            Self::MalformedJson => "malformed_json",
+            Self::Truncated => "truncated",
+            Self::Http500 => "http_500",
+        }
+    }
+}
+
+fn one() -> u32 { 1 }
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ScriptStep {
+    /// Nombre d'appels couverts par l'étape
+    #[serde(default = "one")]
+    pub times: u32,
+    /// Contenu renvoyé (absent: réponse du mock déterministe)
+    #[serde(default)]
+    pub content: Option<String>,
+    #[serde(default)]
+    pub fault: Option<Fault>,
+    #[serde(default)]
+    pub retry_after_s: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ScriptRule {
+    /// Regex sur system_prompt + "\n" + user_prompt (absente: toute requête)
+    #[serde(default)]
+    pub prompt: Option<String>,
+    pub steps: Vec<ScriptStep>,
+    /// Étapes épuisées: recommencer (true) ou répéter la dernière (false)
+    #[serde(default)]
+    pub cycle: bool,
+}
+
+/// Distribution de latence, tirée de façon reproductible (seed, rang de l'appel)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "dist", rename_all = "snake_case", deny_unknown_fields)]
+pub enum Latency {
+    #[default]
+    None,
+    Fixed { ms: u64 },
+    Uniform { min_ms: u64, max_ms: u64 },
+    Exponential { mean_ms: u64, max_ms: u64 },
+}
+
+impl Latency {
+    /// Latence de l'appel de rang `n` (même seed → même suite)
+    pub fn sample(&self, seed: u64, n: u64) -> Duration {
+        let digest = Sha256::digest(format!("{}|{}", seed, n).as_bytes());
+        let u = u64::from_be_bytes(digest[..8].try_into().unwrap()) as f64 / u64::MAX as f64;
+        Duration::from_millis(match *self {
+            Self::None => 0,
+            Self::Fixed { ms } => ms,
+            Self::Uniform { min_ms, max_ms } => min_ms + (u * (max_ms - min_ms) as f64).round() as u64,
+            Self::Exponential { mean_ms, max_ms } => ((-(1.0 - u).max(f64::MIN_POSITIVE).ln()) * mean_ms as f64).round().min(max_ms as f64) as u64,
+        })
+    }
+}
+
+fn default_id() -> String { "mock-script".into() }
+fn default_timeout_ms() -> u64 { 1000 }
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MockScript {
+    #[serde(default = "default_id")]
+    pub id: String,
+    /// Seed des latences
+    #[serde(default)]
+    pub seed: u64,
+    #[serde(default)]
+    pub latency: Latency,
+    /// Durée d'une panne `timeout`
+    #[serde(default = "default_timeout_ms")]
+    pub timeout_ms: u64,
+    #[serde(default, rename = "rule")]
+    pub rules: Vec<ScriptRule>,
+}
+
+impl Default for MockScript {
+    fn default() -> Self {
+        Self { id: default_id(), seed: 0, latency: Latency::None, timeout_ms: default_timeout_ms(), rules: Vec::new() }
+    }
+}
+
+impl MockScript {
+    pub fn parse(content: &str, source: &str) -> OmegaResult<Self> {
+        toml::from_str(content).map_err(|e| OmegaError::ConfigError(format!("MOCK_SCRIPT_PARSE: {}: {}", source, e)))
+    }
+
+    pub fn load(path: &Path) -> OmegaResult<Self> {
+        let content = std::fs::read_to_string(path).map_err(|e| OmegaError::ReadError(format!("{}: {}", path.display(), e)))?;
+        Self::parse(&content, &path.display().to_string())
+    }
+
+    /// Règle unique sans regex: les étapes s'appliquent à toutes les requêtes
+    pub fn sequence(steps: Vec<ScriptStep>) -> Self {
+        Self { rules: vec![ScriptRule { prompt: None, steps, cycle: false }], ..Self::default() }
+    }
+
+    pub fn validate(&self) -> OmegaResult<()> {
+        let invalid = |msg: String| Err(OmegaError::ConfigError(format!("MOCK_SCRIPT_INVALID: {}", msg)));
+        if let Latency::Uniform { min_ms, max_ms } = self.latency {
+            if min_ms > max_ms { return invalid(format!("latency min_ms {} > max_ms {}", min_ms, max_ms)); }
+        }
+        for (i, rule) in self.rules.iter().enumerate() {
+            if rule.steps.is_empty() { return invalid(format!("rule {} has no steps", i)); }
+            for step in &rule.steps {
+                if step.times == 0 { return invalid(format!("rule {}: times must be >= 1", i)); }
+                if step.content.is_some() && matches!(step.fault, Some(Fault::RateLimit | Fault::Timeout | Fault::Http500 | Fault::MalformedJson)) {
+                    return invalid(format!("rule {}: content is only allowed with ok or truncated steps", i));
+                }
+                if step.retry_after_s.is_some() && step.fault != Some(Fault::RateLimit) {
+                    return invalid(format!("rule {}: retry_after_s requires fault = \"rate_limit\"", i));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// PROVIDER
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Trace d'un appel scripté
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ScriptedCall {
+    /// Règle appliquée (None: aucune règle, mock déterministe)
+    pub rule: Option<usize>,
+    /// "ok" ou libellé de la panne
+    pub outcome: String,
+    pub latency_ms: u64,
+}
+
+#[derive(Debug, Default)]
+struct ScriptState {
+    /// Appels consommés par règle
+    consumed: Vec<u32>,
+    calls: Vec<ScriptedCall>,
+}
+
+pub struct ScriptedProvider {
+    script: MockScript,
+    matchers: Vec<Option<Regex>>,
+    inner: MockDeterministicProvider,
+    state: Mutex<ScriptState>,
+}
+
+impl ScriptedProvider {
+    pub fn new(script: MockScript) -> OmegaResult<Self> {
+        script.validate()?;
+        let matchers = script.rules.iter().map(|r| r.prompt.as_deref().map(Regex::new).transpose())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| OmegaError::ConfigError(format!("MOCK_SCRIPT_REGEX: {}", e)))?;
+        let inner = MockDeterministicProvider { provider_id: script.id.clone(), latency_ms: 0, ..MockDeterministicProvider::default() };
+        let state = Mutex::new(ScriptState { consumed: vec![0; script.rules.len()], calls: Vec::new() });
+        Ok(Self { script, matchers, inner, state })
+    }
+
+    pub fn load(path: &Path) -> OmegaResult<Self> { Self::new(MockScript::load(path)?) }
+
+    /// `mock-script:<file>` → provider; None si `name` n'a pas le préfixe
+    pub fn from_spec(name: &str) -> Option<OmegaResult<Self>> {
+        name.strip_prefix(MOCK_SCRIPT_PREFIX).map(|path| Self::load(Path::new(path)))
+    }
+
+    /// Appels reçus, dans l'ordre
+    pub fn calls(&self) -> Vec<ScriptedCall> { self.state.lock().unwrap().calls.clone() }
+
+    /// Étape de l'appel courant (consomme un appel de la règle retenue) + latence tirée
+    fn next(&self, req: &CompletionRequest) -> (Option<usize>, Option<ScriptStep>, Duration) {
+        let prompt = format!("{}\n{}", req.system_prompt, req.user_prompt);
+        let mut state = self.state.lock().unwrap();
+        let latency = self.script.latency.sample(self.script.seed, state.calls.len() as u64);
+        let rule = self.matchers.iter().position(|m| m.as_ref().is_none_or(|re| re.is_match(&prompt)));
+        let step = rule.map(|i| {
+            let n = state.consumed[i];
+            state.consumed[i] += 1;
+            step_at(&self.script.rules[i], n).clone()
+        });
+        let outcome = step.as_ref().and_then(|s| s.fault).map_or("ok", |f| f.label());
+        state.calls.push(ScriptedCall { rule, outcome: outcome.into(), latency_ms: latency.as_millis() as u64 });
+        (rule, step, latency)
+    }
+
+    fn respond(&self, req: &CompletionRequest, content: String, start: Instant) -> CompletionResponse {
+        let parsed = req.json_schema.as_ref().and_then(|_| serde_json::from_str(&content).ok());
+        let response_hash = format!("{:x}", Sha256::digest(format!("{}|{}|{}", self.script.id, req.seed, content).as_bytes()));
+        CompletionResponse { provider_id: self.script.id.clone(), content, parsed, usage: Usage { prompt_tokens: 10, completion_tokens: 20, total_tokens: 30 }, latency_ms: start.elapsed().as_millis() as u64, response_hash, cache: None, fallback: None }
+    }
+}
+
+/// Étape du n-ième appel d'une règle (0-based)
+fn step_at(rule: &ScriptRule, n: u32) -> &ScriptStep {
+    let total: u32 = rule.steps.iter().map(|s| s.times).sum();
+    let mut n = if rule.cycle { n % total } else { n.min(total - 1) };
+    for step in &rule.steps {
+        if n < step.times { return step; }
+        n -= step.times;
+    }
+    rule.steps.last().expect("validated: rule has steps")
+}
+
+impl LLMProvider for ScriptedProvider {
+    fn id(&self) -> ProviderId { self.script.id.clone() }
+    fn capabilities(&self) -> ProviderCapabilities { self.inner.capabilities() }
+    fn health(&self) -> bool { true }
+    fn is_local(&self) -> bool { true }
+    fn embed(&self, req: EmbeddingRequest) -> OmegaResult<EmbeddingResponse> { self.inner.embed(req) }
+    fn generate(&self, req: CompletionRequest) -> OmegaResult<CompletionResponse> { generate_blocking(self, req) }
+}
+
+impl AsyncLLMProvider for ScriptedProvider {
+    fn generate_async<'a>(&'a self, req: CompletionRequest, ctl: &'a CallControl) -> BoxFuture<'a, OmegaResult<CompletionResponse>> {
+        Box::pin(ctl.run(async move {
+            let start = Instant::now();
+            let (rule, step, latency) = self.next(&req);
+            tokio::time::sleep(latency).await;
+            let step = step.unwrap_or(ScriptStep { times: 1, content: None, fault: None, retry_after_s: None });
+            let content = || match &step.content {
+                Some(content) => Ok(content.clone()),
+                None => self.inner.generate(CompletionRequest { temperature: 0.0, ..req.clone() }).map(|r| r.content),
+            };
+            let rule = rule.map_or("default".into(), |i| format!("rule {}", i));
+            match step.fault {
+                None => Ok(self.respond(&req, content()?, start)),
+                Some(Fault::RateLimit) => Err(OmegaError::RateLimit(format!("HTTP_429: retry_after={}", step.retry_after_s.map_or("none".into(), |s| format!("{}s", s))))),
+                Some(Fault::Timeout) => {
+                    tokio::time::sleep(Duration::from_millis(self.script.timeout_ms)).await;
+                    Err(OmegaError::ProviderError(format!("NETWORK: timeout after {}ms ({})", self.script.timeout_ms, rule)))
+                }
+                Some(Fault::MalformedJson) => Ok(self.respond(&req, MALFORMED_JSON.into(), start)),
+                Some(Fault::Truncated) => {
+                    let full = content()?;
+                    let half = full.chars().count() / 2;
+                    Ok(self.respond(&req, full.chars().take(half).collect(), start))
+                }
+                Some(Fault::Http500) => Err(OmegaError::ProviderError(format!("HTTP_500: scripted fault ({})", rule))),
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::FallbackProvider;
+    use std::sync::Arc;
+
+    fn req(user: &str) -> CompletionRequest {
+        CompletionRequest { run_id: "t".into(), seed: 42, system_prompt: "S".into(), user_prompt: user.into(), temperature: 0.7, max_tokens: 100, schema_name: None, json_schema: None, constraints: Default::default(), template: None, tool: None }
+    }
+
+    #[test]
+    fn test_script_sequence_and_faults() {
+        let script = MockScript::parse(r#"
+            timeout_ms = 10
+            [[rule]]
+            steps = [
+                { times = 2, content = '{"ok": true}' },
+                { fault = "rate_limit", retry_after_s = 120 },
+                { fault = "timeout" },
+                { fault = "malformed_json" },
+                { fault = "truncated", content = "abcdef" },
+                { fault = "http_500" },
+            ]
+        "#, "test").unwrap();
+        let p = ScriptedProvider::new(script).unwrap();
+        assert_eq!(p.generate(req("u")).unwrap().content, r#"{"ok": true}"#);
+        assert_eq!(p.generate(req("u")).unwrap().content, r#"{"ok": true}"#);
+        assert!(matches!(p.generate(req("u")), Err(OmegaError::RateLimit(m)) if m == "HTTP_429: retry_after=120s"));
+        assert!(matches!(p.generate(req("u")), Err(OmegaError::ProviderError(m)) if m.starts_with("NETWORK: timeout after 10ms")));
+        assert!(serde_json::from_str::<serde_json::Value>(&p.generate(req("u")).unwrap().content).is_err());
+        assert_eq!(p.generate(req("u")).unwrap().content, "abc");
+        assert!(matches!(p.generate(req("u")), Err(OmegaError::ProviderError(m)) if m.starts_with("HTTP_500")));
+        assert!(p.generate(req("u")).is_err(), "steps exhausted: last step repeats");
+        let outcomes: Vec<String> = p.calls().into_iter().map(|c| c.outcome).collect();
+        assert_eq!(outcomes, ["ok", "ok", "rate_limit", "timeout", "malformed_json", "truncated", "http_500", "http_500"]);
+    }
+
+    #[test]
+    fn test_prompt_regex_matching_and_seeded_latency() {
+        let script = MockScript::parse(r#"
+            seed = 7
+            [latency]
+            dist = "uniform"
+            min_ms = 0
+            max_ms = 5
+            [[rule]]
+            prompt = "(?i)chapitre \\d+"
+            cycle = true
+            steps = [{ fault = "http_500" }, { content = "reprise" }]
+        "#, "test").unwrap();
+        let p = ScriptedProvider::new(script.clone()).unwrap();
+        assert!(p.generate(req("CHAPITRE 3")).is_err());
+        assert!(p.generate(req("prologue")).unwrap().content.starts_with("[MOCK]"), "no rule: deterministic mock");
+        assert_eq!(p.generate(req("chapitre 4")).unwrap().content, "reprise");
+        assert!(p.generate(req("chapitre 5")).is_err(), "cycle");
+        assert_eq!(p.calls().iter().map(|c| c.rule).collect::<Vec<_>>(), vec![Some(0), None, Some(0), Some(0)]);
+
+        let again = ScriptedProvider::new(script).unwrap();
+        for _ in 0..4 { let _ = again.generate(req("x")); }
+        let latencies = |p: &ScriptedProvider| p.calls().iter().map(|c| c.latency_ms).collect::<Vec<_>>();
+        assert_eq!(latencies(&p), latencies(&again), "same seed, same latencies");
+        assert!(latencies(&p).iter().all(|ms| *ms <= 5));
+
+        assert!(matches!(MockScript::parse("[[rule]]\nprompt = \"(\"\nsteps = [{}]", "t").map(ScriptedProvider::new), Ok(Err(OmegaError::ConfigError(m))) if m.starts_with("MOCK_SCRIPT_REGEX")));
+        assert!(ScriptedProvider::new(MockScript::sequence(vec![])).is_err(), "rule without steps");
+        assert!(matches!(MockScript::sequence(vec![ScriptStep { times: 1, content: Some("x".into()), fault: Some(Fault::Http500), retry_after_s: None }]).validate(), Err(OmegaError::ConfigError(m)) if m.starts_with("MOCK_SCRIPT_INVALID")));
+    }
+
+    #[test]
+    fn test_fallback_survives_scripted_outage() {
+        let outage = |fault| ScriptStep { times: 1, content: None, fault: Some(fault), retry_after_s: None };
+        let script = Arc::new(ScriptedProvider::new(MockScript::sequence(vec![outage(Fault::Http500), outage(Fault::RateLimit), ScriptStep { times: 1, content: Some("ok".into()), fault: None, retry_after_s: None }])).unwrap());
+        let provider = FallbackProvider::new(Some(script.clone()), None);
+        let mut req = req("u");
+        req.temperature = 0.0;
+        for _ in 0..2 {
+            let trace = provider.generate(req.clone()).unwrap().fallback.unwrap();
+            assert!(trace.fallback_used);
+        }
+        let resp = provider.generate(req).unwrap();
+        assert_eq!(resp.content, "ok");
+        assert!(!resp.fallback.unwrap().fallback_used);
+        assert_eq!(script.calls().len(), 3);
+    }
+}
@@ -1,11 +1,33 @@
 ﻿pub mod models;
 pub mod interface;
 pub mod mock;
+pub mod mock_script;
+pub mod stream;
+pub mod cache;
+pub mod ledger;
+pub mod resilience;
+pub mod schema;
+pub mod chunking;
+pub mod prompts;
+pub mod extract;
+pub mod async_provider;
+pub mod redaction;
 pub use models::*;
 pub use interface::*;
 pub use mock::*;
+pub use stream::{CancelToken, StreamDelta};
+pub use cache::{CacheMode, CachePolicy, CachedProvider, CompletionCache};
+pub use ledger::{BudgetCaps, MeteredProvider, PriceTable, UsageLedger, UsageReport};
+pub use resilience::{BreakerPolicy, CircuitState, RateLimit};
+pub use schema::{SchemaError, ValidatedResponse, ValidationFailure};
+pub use chunking::{ChunkConfig, ChunkLimits, ChunkPlan, TextChunk};
+pub use prompts::{PromptDiff, PromptRegistry, PromptTemplate, PromptTemplateRef};
+pub use extract::{extract, Extracted, Extraction, ExtractionMode};
+pub use async_provider::{AsyncLLMProvider, CallControl};
+pub use mock_script::{Fault, MockScript, ScriptStep, ScriptedProvider};
+pub use redaction::{PrivacyPolicy, RedactingProvider, RedactionKey, RedactionMap, Redactor};
 
 pub mod fallback;
 pub mod providers;
-pub use fallback::FallbackProvider;
+pub use fallback::{FallbackProvider, ResiliencePolicy};
 
@@ -25,6 +25,19 @@ pub struct CompletionRequest {
     pub json_schema: Option<serde_json::Value>,
     #[serde(default)]
     pub constraints: HashMap<String, serde_json::Value>,
+    /// Template de prompt utilisé (id, version, hash); None pour un prompt libre
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<crate::ai::prompts::PromptTemplateRef>,
+    /// Sortie structurée par appel d'outil (function calling / tool use); arguments = `json_schema`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool: Option<ToolSpec>,
+}
+
+/// Outil unique imposé au modèle: la réponse est l'objet d'arguments (sérialisé dans `content`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolSpec {
+    pub name: String,
+    pub description: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -34,6 +47,11 @@ pub struct Usage {
     pub total_tokens: u32,
 }
 
+/// Résultat de la consultation du cache de completions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CacheOutcome { Hit, Miss }
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CompletionResponse {
     pub provider_id: ProviderId,
@@ -42,6 +60,36 @@ pub struct CompletionResponse {
     pub usage: Usage,
     pub latency_ms: u64,
     pub response_hash: String,
+    /// None = réponse hors cache
+    #[serde(default)]
+    pub cache: Option<CacheOutcome>,
+    /// Décisions de bascule (None = pas de FallbackProvider)
+    #[serde(default)]
+    pub fallback: Option<FallbackTrace>,
+}
+
+/// Issue d'une tentative dans la chaîne de fallback
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AttemptOutcome { Ok, Failed, CircuitOpen, RateLimited }
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FallbackAttempt {
+    pub provider: ProviderId,
+    pub outcome: AttemptOutcome,
+    pub error: Option<String>,
+}
+
+/// Trace d'un appel FallbackProvider: qui a répondu, et pourquoi les autres ont été écartés
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FallbackTrace {
+    pub responder: ProviderId,
+    /// Modèle du maillon qui a répondu (None = mock de secours ou modèle inconnu)
+    #[serde(default)]
+    pub model: Option<String>,
+    /// true si le premier provider de la chaîne n'a pas répondu
+    pub fallback_used: bool,
+    pub attempts: Vec<FallbackAttempt>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -54,5 +102,11 @@ pub struct EmbeddingRequest {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EmbeddingResponse {
     pub provider_id: ProviderId,
+    /// Modèle d'embedding (deux vecteurs ne sont comparables que s'ils viennent du même modèle)
+    #[serde(default)]
+    pub model: Option<String>,
     pub vectors: Vec<f32>,
+    /// Décisions de bascule (None = pas de FallbackProvider)
+    #[serde(default)]
+    pub fallback: Option<FallbackTrace>,
 }
@@ -0,0 +1,466 @@
+//! OMEGA Prompts — registre de templates versionnés
+//! NASA-Grade: un prompt publié est immuable (id + version → hash); toute modification passe par une nouvelle version
+//!
+//! - templates intégrés (défauts) + fichiers JSON/TOML d'un dossier (`OMEGA_PROMPTS_DIR`)
+//! - variables `{{nom}}` déclarées et vérifiées au chargement
+//! - chaque CompletionRequest porte la référence (id, version, hash) du template utilisé
+
+use crate::error::{OmegaError, OmegaResult};
+use crate::pipeline::fs_utils::{canonicalize_json, sha256_str};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// Version du schéma des fichiers de template
+pub const PROMPT_TEMPLATE_SCHEMA_VERSION: u32 = 1;
+
+/// Template d'analyse émotionnelle (AIAnalyzer)
+pub const EMOTION_ANALYSIS_TEMPLATE: &str = "emotion.analysis";
+/// En-tête du guidance VOICE_HYBRID (PromptBuilder)
+pub const VOICE_HYBRID_GUIDANCE_TEMPLATE: &str = "voice_hybrid.guidance";
+/// Requête de génération VOICE_HYBRID (AiLlmBridge)
+pub const VOICE_HYBRID_BRIDGE_TEMPLATE: &str = "voice_hybrid.bridge";
+
+fn default_schema_version() -> u32 { PROMPT_TEMPLATE_SCHEMA_VERSION }
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// TEMPLATE
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PromptTemplate {
+    #[serde(default = "default_schema_version")]
+    pub schema_version: u32,
+    pub id: String,
+    /// Version sémantique (x.y.z)
+    pub version: String,
+    #[serde(default)]
+    pub description: String,
+    /// Variables `{{nom}}` attendues par system / user
+    #[serde(default)]
+    pub variables: Vec<String>,
+    pub system: String,
+    pub user: String,
+}
+
+/// Référence tracée dans les requêtes et les records
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PromptTemplateRef {
+    pub id: String,
+    pub version: String,
+    pub hash: String,
+}
+
+impl std::fmt::Display for PromptTemplateRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}@{} ({})", self.id, self.version, &self.hash[..self.hash.len().min(12)])
+    }
+}
+
+/// Prompts rendus + référence du template
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderedPrompt {
+    pub system: String,
+    pub user: String,
+    pub template: PromptTemplateRef,
+}
+
+impl PromptTemplate {
+    /// Charge un template JSON ou TOML (selon l'extension), validé
+    pub fn load<P: AsRef<Path>>(path: P) -> OmegaResult<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| OmegaError::ReadError(format!("{}: {}", path.display(), e)))?;
+        let template: Self = match path.extension().and_then(|e| e.to_str()) {
+            Some("toml") => toml::from_str(&content)
+                .map_err(|e| OmegaError::ConfigError(format!("TOML invalide ({}): {}", path.display(), e)))?,
+            Some("json") => serde_json::from_str(&content)?,
+            other => return Err(OmegaError::ConfigError(format!(
+                "Extension de template non supportee: {:?} (json | toml)",
+                other
+            ))),
+        };
+        template.validate()?;
+        Ok(template)
+    }
+
+    /// Vérifie id, version et cohérence variables déclarées / utilisées
+    pub fn validate(&self) -> OmegaResult<()> {
+        let fail = |msg: String| Err(OmegaError::ConfigError(format!("PROMPT_TEMPLATE '{}': {}", self.id, msg)));
+        if self.schema_version != PROMPT_TEMPLATE_SCHEMA_VERSION {
+            return fail(format!("schema_version {} non supporte (attendu {})", self.schema_version, PROMPT_TEMPLATE_SCHEMA_VERSION));
+        }
+        if self.id.trim().is_empty() {
+            return fail("id vide".to_string());
+        }
+        if parse_version(&self.version).is_none() {
+            return fail(format!("version '{}' invalide (attendu x.y.z)", self.version));
+        }
+        let declared: BTreeSet<&str> = self.variables.iter().map(String::as_str).collect();
+        if declared.len() != self.variables.len() {
+            return fail("variable dupliquee".to_string());
+        }
+        let used = self.placeholders();
+        if let Some(v) = used.iter().find(|v| !declared.contains(v.as_str())) {
+            return fail(format!("variable non declaree: {{{{{}}}}}", v));
+        }
+        if let Some(v) = declared.iter().find(|v| !used.contains(**v)) {
+            return fail(format!("variable declaree mais inutilisee: {}", v));
+        }
+        Ok(())
+    }
+
+    /// Variables présentes dans system / user
+    pub fn placeholders(&self) -> BTreeSet<String> {
+        let mut out = BTreeSet::new();
+        for text in [&self.system, &self.user] {
+            let mut rest = text.as_str();
+            while let Some(i) = rest.find("{{") {
+                match rest[i + 2..].find("}}") {
+                    Some(j) => { out.insert(rest[i + 2..i + 2 + j].trim().to_string()); rest = &rest[i + 2 + j + 2..]; }
+                    None => break,
+                }
+            }
+        }
+        out
+    }
+
+    /// SHA-256 du JSON canonique (id, version, variables, system, user; description exclue)
+    pub fn hash(&self) -> String {
+        let value = serde_json::json!({
+            "id": self.id,
+            "version": self.version,
+            "variables": self.variables,
+            "system": self.system,
+            "user": self.user,
+        });
+        sha256_str(&canonicalize_json(&value))
+    }
+
+    pub fn reference(&self) -> PromptTemplateRef {
+        PromptTemplateRef { id: self.id.clone(), version: self.version.clone(), hash: self.hash() }
+    }
+
+    /// Substitution en une passe (les valeurs ne sont pas ré-interprétées); toute variable déclarée est requise
+    pub fn render(&self, vars: &[(&str, &str)]) -> OmegaResult<RenderedPrompt> {
+        let values: BTreeMap<&str, &str> = vars.iter().copied().collect();
+        if let Some(missing) = self.variables.iter().find(|v| !values.contains_key(v.as_str())) {
+            return Err(OmegaError::ConfigError(format!("PROMPT_VARIABLE_MISSING: {}@{} {{{{{}}}}}", self.id, self.version, missing)));
+        }
+        let fill = |text: &str| {
+            let mut out = String::with_capacity(text.len());
+            let mut rest = text;
+            while let Some(i) = rest.find("{{") {
+                let Some(j) = rest[i + 2..].find("}}") else { break };
+                out.push_str(&rest[..i]);
+                let name = rest[i + 2..i + 2 + j].trim();
+                out.push_str(values.get(name).copied().unwrap_or_default());
+                rest = &rest[i + 2 + j + 2..];
+            }
+            out.push_str(rest);
+            out
+        };
+        Ok(RenderedPrompt { system: fill(&self.system), user: fill(&self.user), template: self.reference() })
+    }
+}
+
+fn parse_version(v: &str) -> Option<(u32, u32, u32)> {
+    let parts: Vec<u32> = v.split('.').map(|p| p.parse().ok()).collect::<Option<_>>()?;
+    match parts[..] {
+        [major, minor, patch] => Some((major, minor, patch)),
+        _ => None,
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// REGISTRE
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[derive(Debug, Clone)]
+pub struct PromptRegistry {
+    /// id → version → template
+    templates: BTreeMap<String, BTreeMap<(u32, u32, u32), PromptTemplate>>,
+}
+
+impl Default for PromptRegistry {
+    fn default() -> Self { Self::builtin() }
+}
+
+impl PromptRegistry {
+    /// Templates intégrés (prompts historiques, à l'octet près)
+    pub fn builtin() -> Self {
+        let mut registry = Self { templates: BTreeMap::new() };
+        for template in builtin_templates() {
+            registry.insert(template).expect("templates intégrés valides");
+        }
+        registry
+    }
+
+    /// Intégrés + `*.json` / `*.toml` du dossier (ordre alphabétique)
+    pub fn with_dir(dir: &Path) -> OmegaResult<Self> {
+        let mut registry = Self::builtin();
+        let entries = std::fs::read_dir(dir)
+            .map_err(|e| OmegaError::ReadError(format!("{}: {}", dir.display(), e)))?;
+        let mut paths: Vec<_> = entries.filter_map(|e| e.ok().map(|e| e.path()))
+            .filter(|p| matches!(p.extension().and_then(|e| e.to_str()), Some("json" | "toml")))
+            .collect();
+        paths.sort();
+        for path in paths {
+            registry.insert(PromptTemplate::load(&path)?)?;
+        }
+        Ok(registry)
+    }
+
+    /// OMEGA_PROMPTS_DIR si défini; dossier invalide → intégrés seuls (signalé)
+    pub fn from_env() -> Self {
+        match std::env::var("OMEGA_PROMPTS_DIR") {
+            Ok(dir) if !dir.trim().is_empty() => Self::with_dir(Path::new(&dir)).unwrap_or_else(|e| {
+                eprintln!("[PROMPTS] {} ignore: {}", dir, e);
+                Self::builtin()
+            }),
+            _ => Self::builtin(),
+        }
+    }
+
+    /// Registre du processus (chargé une fois)
+    pub fn global() -> &'static PromptRegistry {
+        static REGISTRY: OnceLock<PromptRegistry> = OnceLock::new();
+        REGISTRY.get_or_init(Self::from_env)
+    }
+
+    /// Ajoute un template; une version publiée ne peut pas changer de contenu,
+    /// et une nouvelle version d'un template intégré garde ses variables (contrat de l'appelant)
+    pub fn insert(&mut self, template: PromptTemplate) -> OmegaResult<()> {
+        template.validate()?;
+        let version = parse_version(&template.version).unwrap_or_default();
+        if let Some(builtin) = builtin_templates().into_iter().find(|b| b.id == template.id) {
+            let expected: BTreeSet<&String> = builtin.variables.iter().collect();
+            if template.variables.iter().collect::<BTreeSet<_>>() != expected {
+                return Err(OmegaError::ConfigError(format!(
+                    "PROMPT_TEMPLATE_VARIABLES: {}@{} doit declarer {:?}",
+                    template.id, template.version, builtin.variables
+                )));
+            }
+        }
+        let versions = self.templates.entry(template.id.clone()).or_default();
+        if let Some(existing) = versions.get(&version) {
+            if existing.hash() != template.hash() {
+                return Err(OmegaError::HashMismatch(format!(
+                    "PROMPT_TEMPLATE_CONFLICT: {}@{} deja publie avec un autre contenu (creer une nouvelle version)",
+                    template.id, template.version
+                )));
+            }
+            return Ok(());
+        }
+        versions.insert(version, template);
+        Ok(())
+    }
+
+    pub fn ids(&self) -> Vec<String> { self.templates.keys().cloned().collect() }
+
+    /// Versions d'un template, croissantes
+    pub fn versions(&self, id: &str) -> Vec<String> {
+        self.templates.get(id).map(|v| v.values().map(|t| t.version.clone()).collect()).unwrap_or_default()
+    }
+
+    /// Version donnée, ou la plus récente si None
+    pub fn get(&self, id: &str, version: Option<&str>) -> OmegaResult<&PromptTemplate> {
+        let versions = self.templates.get(id)
+            .ok_or_else(|| OmegaError::ConfigError(format!("PROMPT_TEMPLATE_NOT_FOUND: {}", id)))?;
+        let found = match version {
+            Some(v) => parse_version(v).and_then(|k| versions.get(&k)),
+            None => versions.values().next_back(),
+        };
+        found.ok_or_else(|| OmegaError::ConfigError(format!("PROMPT_TEMPLATE_NOT_FOUND: {}@{}", id, version.unwrap_or("latest"))))
+    }
+
+    /// Rend la version active (la plus récente) d'un template
+    pub fn render(&self, id: &str, vars: &[(&str, &str)]) -> OmegaResult<RenderedPrompt> {
+        self.get(id, None)?.render(vars)
+    }
+
+    /// Différences ligne à ligne entre deux versions
+    pub fn diff(&self, id: &str, from: &str, to: &str) -> OmegaResult<PromptDiff> {
+        let (a, b) = (self.get(id, Some(from))?, self.get(id, Some(to))?);
+        Ok(PromptDiff {
+            from: a.reference(),
+            to: b.reference(),
+            variables_added: b.variables.iter().filter(|v| !a.variables.contains(v)).cloned().collect(),
+            variables_removed: a.variables.iter().filter(|v| !b.variables.contains(v)).cloned().collect(),
+            system: diff_lines(&a.system, &b.system),
+            user: diff_lines(&a.user, &b.user),
+        })
+    }
+}
+
+/// Rend un template intégré via le registre global; registre inutilisable → défaut intégré (signalé)
+pub fn render_active(id: &str, vars: &[(&str, &str)]) -> RenderedPrompt {
+    PromptRegistry::global().render(id, vars).unwrap_or_else(|e| {
+        eprintln!("[PROMPTS] {}: {}, template integre utilise", id, e);
+        PromptRegistry::builtin().render(id, vars).expect("template intégré rendu avec ses variables")
+    })
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// DIFF
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffOp { Equal, Added, Removed }
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DiffLine {
+    pub op: DiffOp,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PromptDiff {
+    pub from: PromptTemplateRef,
+    pub to: PromptTemplateRef,
+    pub variables_added: Vec<String>,
+    pub variables_removed: Vec<String>,
+    pub system: Vec<DiffLine>,
+    pub user: Vec<DiffLine>,
+}
+
+impl PromptDiff {
+    pub fn is_identical(&self) -> bool { self.from.hash == self.to.hash }
+
+    /// Format unifié lisible (CLI)
+    pub fn to_unified(&self) -> String {
+        let mut out = format!("--- {}\n+++ {}\n", self.from, self.to);
+        for v in &self.variables_removed { out.push_str(&format!("- variable {}\n", v)); }
+        for v in &self.variables_added { out.push_str(&format!("+ variable {}\n", v)); }
+        for (name, lines) in [("system", &self.system), ("user", &self.user)] {
+            out.push_str(&format!("@@ {} @@\n", name));
+            for line in lines {
+                let mark = match line.op { DiffOp::Equal => ' ', DiffOp::Added => '+', DiffOp::Removed => '-' };
+                out.push_str(&format!("{}{}\n", mark, line.text));
+            }
+        }
+        out
+    }
+}
+
+/// Diff ligne à ligne (plus longue sous-séquence commune)
+fn diff_lines(a: &str, b: &str) -> Vec<DiffLine> {
+    let (a, b): (Vec<&str>, Vec<&str>) = (a.lines().collect(), b.lines().collect());
+    let mut lcs = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] { lcs[i + 1][j + 1] + 1 } else { lcs[i + 1][j].max(lcs[i][j + 1]) };
+        }
+    }
+    let line = |op, text: &str| DiffLine { op, text: text.to_string() };
+    let (mut i, mut j, mut out) = (0, 0, Vec::new());
+    while i < a.len() && j < b.len() {
+        if a[i] == b[j] { out.push(line(DiffOp::Equal, a[i])); i += 1; j += 1; }
+        else if lcs[i + 1][j] >= lcs[i][j + 1] { out.push(line(DiffOp::Removed, a[i])); i += 1; }
+        else { out.push(line(DiffOp::Added, b[j])); j += 1; }
+    }
+    out.extend(a[i..].iter().map(|t| line(DiffOp::Removed, t)));
+    out.extend(b[j..].iter().map(|t| line(DiffOp::Added, t)));
+    out
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// TEMPLATES INTÉGRÉS
+// ═══════════════════════════════════════════════════════════════════════════════
+
+fn builtin_templates() -> Vec<PromptTemplate> {
+    let template = |id: &str, description: &str, variables: &[&str], system: &str, user: &str| PromptTemplate {
+        schema_version: PROMPT_TEMPLATE_SCHEMA_VERSION,
+        id: id.to_string(),
+        version: "1.0.0".to_string(),
+        description: description.to_string(),
+        variables: variables.iter().map(|v| v.to_string()).collect(),
+        system: system.to_string(),
+        user: user.to_string(),
+    };
+    vec![
+        template(
+            EMOTION_ANALYSIS_TEMPLATE,
+            "Ajustement IA des scores du lexicon (Boost / Hybrid)",
+            &["label", "text", "baseline", "emotions"],
+            "Tu es un expert en analyse émotionnelle littéraire. Analyse le texte et ajuste les scores émotionnels.",
+            "{{label}}:\n{{text}}\n\nBaseline Lexicon:\n{{baseline}}\n\nRetourne un JSON {\"emotions\": [{\"emotion\": ..., \"score\": ...}]} avec les émotions ajustées. Émotions autorisées: {{emotions}}. Scores entre 0 et 1.",
+        ),
+        template(
+            VOICE_HYBRID_GUIDANCE_TEMPLATE,
+            "En-tete du guidance VOICE_HYBRID",
+            &["policy_id", "policy_version", "lang", "base_profile_id", "base_corpus_hash"],
+            "You are OMEGA VOICE_HYBRID. Follow constraints exactly.",
+            "POLICY_ID: {{policy_id}}\nPOLICY_VERSION: {{policy_version}}\nLANG: {{lang}}\nBASE_PROFILE_ID: {{base_profile_id}}\nBASE_CORPUS_HASH: {{base_corpus_hash}}",
+        ),
+        template(
+            VOICE_HYBRID_BRIDGE_TEMPLATE,
+            "Generation VOICE_HYBRID via un provider IA",
+            &["guidance"],
+            "Tu es un assistant d'ecriture. Respecte strictement la voix et les directives fournies.",
+            "{{guidance}}",
+        ),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn v2(user: &str) -> PromptTemplate {
+        PromptTemplate { version: "1.1.0".into(), user: user.into(), ..PromptRegistry::builtin().get(EMOTION_ANALYSIS_TEMPLATE, None).unwrap().clone() }
+    }
+
+    #[test]
+    fn test_builtin_render_and_hash_stable() {
+        let registry = PromptRegistry::builtin();
+        let vars = [("label", "Texte"), ("text", "{{text}} reste littéral"), ("baseline", "[]"), ("emotions", "joy")];
+        let r = registry.render(EMOTION_ANALYSIS_TEMPLATE, &vars).unwrap();
+        assert!(r.user.starts_with("Texte:\n{{text}} reste littéral\n\nBaseline Lexicon:\n[]"));
+        assert_eq!(r.template.version, "1.0.0");
+        assert_eq!(r.template.hash, registry.get(EMOTION_ANALYSIS_TEMPLATE, Some("1.0.0")).unwrap().hash());
+        assert!(matches!(registry.render(EMOTION_ANALYSIS_TEMPLATE, &vars[..3]), Err(OmegaError::ConfigError(m)) if m.starts_with("PROMPT_VARIABLE_MISSING")));
+    }
+
+    #[test]
+    fn test_versions_immutable_and_contract() {
+        let mut registry = PromptRegistry::builtin();
+        let mut changed = registry.get(EMOTION_ANALYSIS_TEMPLATE, None).unwrap().clone();
+        changed.user.push_str(" Sois bref.");
+        assert!(matches!(registry.insert(changed), Err(OmegaError::HashMismatch(m)) if m.starts_with("PROMPT_TEMPLATE_CONFLICT")));
+
+        let undeclared = v2("{{label}} {{text}} {{baseline}} {{emotions}} {{genre}}");
+        assert!(matches!(registry.insert(undeclared), Err(OmegaError::ConfigError(_))));
+
+        registry.insert(v2("{{label}}:\n{{text}}\nBaseline:\n{{baseline}}\nÉmotions: {{emotions}}.")).unwrap();
+        assert_eq!(registry.versions(EMOTION_ANALYSIS_TEMPLATE), vec!["1.0.0", "1.1.0"]);
+        assert_eq!(registry.get(EMOTION_ANALYSIS_TEMPLATE, None).unwrap().version, "1.1.0");
+    }
+
+    #[test]
+    fn test_load_dir_and_diff() {
+        let dir = std::env::temp_dir().join(format!("omega-prompts-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("bridge.toml"), r#"
+id = "voice_hybrid.bridge"
+version = "1.1.0"
+variables = ["guidance"]
+system = """Tu es un assistant d'ecriture.
+Respecte strictement la voix."""
+user = "{{guidance}}"
+"#).unwrap();
+        std::fs::write(dir.join("notes.txt"), "ignoré").unwrap();
+        let registry = PromptRegistry::with_dir(&dir).unwrap();
+        std::fs::remove_dir_all(&dir).ok();
+
+        let diff = registry.diff(VOICE_HYBRID_BRIDGE_TEMPLATE, "1.0.0", "1.1.0").unwrap();
+        assert!(!diff.is_identical());
+        let ops: Vec<DiffOp> = diff.system.iter().map(|l| l.op).collect();
+        assert_eq!(ops, vec![DiffOp::Removed, DiffOp::Added, DiffOp::Added]);
+        assert!(diff.user.iter().all(|l| l.op == DiffOp::Equal));
+        assert!(diff.to_unified().contains("+Respecte strictement la voix."));
+        assert!(registry.diff(VOICE_HYBRID_BRIDGE_TEMPLATE, "1.0.0", "9.0.0").is_err());
+    }
+}
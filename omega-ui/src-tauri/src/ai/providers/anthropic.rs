@@ -1,47 +1,107 @@
 ﻿use crate::ai::models::*;
+use crate::ai::async_provider::{block_on, generate_blocking, open_stream, AsyncLLMProvider, BoxFuture, CallControl};
+use crate::ai::resilience::{send_with_retry, PROBE_TIMEOUT};
+use crate::ai::schema::json_instruction;
+use crate::ai::stream::{drive_sse, CancelToken, SseEvent, StreamAccumulator, StreamChunk, StreamDelta};
 use crate::error::{OmegaError, OmegaResult};
 use super::config::ProviderConfig;
 use sha2::{Sha256, Digest};
 use std::time::{Duration, Instant};
 
-pub struct AnthropicProvider { api_key: String, model: String, endpoint: String, timeout: Duration, max_retries: u32 }
+pub struct AnthropicProvider { api_key: String, model: String, endpoint: String, timeout: Duration, max_retries: u32, health_probe: bool, temperature_override: Option<f32> }
 
 impl AnthropicProvider {
     pub fn try_new(config: &ProviderConfig) -> Result<Self, OmegaError> {
         let api_key = config.api_key.clone().ok_or_else(|| OmegaError::ProviderError("ANTHROPIC_MISSING_KEY".into()))?;
         if api_key.is_empty() { return Err(OmegaError::ProviderError("ANTHROPIC_EMPTY_KEY".into())); }
-        Ok(Self { api_key, model: config.model.clone().unwrap_or_else(|| "claude-3-5-sonnet-20241022".into()), endpoint: config.endpoint.clone().unwrap_or_else(|| "https://api.anthropic.com/v1/messages".into()), timeout: Duration::from_millis(config.timeout_ms), max_retries: config.max_retries })
+        Ok(Self { api_key, model: config.model.clone().unwrap_or_else(|| "claude-3-5-sonnet-20241022".into()), endpoint: config.endpoint.clone().unwrap_or_else(|| "https://api.anthropic.com/v1/messages".into()), timeout: Duration::from_millis(config.timeout_ms), max_retries: config.max_retries, health_probe: config.health_probe, temperature_override: config.temperature_override })
     }
+    /// Outil demandé: tool use forcé (input_schema = json_schema); schéma sans outil: consigne JSON dans le prompt système
     fn build_body(&self, req: &CompletionRequest) -> serde_json::Value {
-        serde_json::json!({"model": self.model, "max_tokens": req.max_tokens, "system": req.system_prompt, "messages": [{"role": "user", "content": req.user_prompt}]})
-    }
-    fn execute(&self, body: serde_json::Value) -> Result<serde_json::Value, OmegaError> {
-        let client = reqwest::blocking::Client::builder().timeout(self.timeout).build().map_err(|e| OmegaError::ProviderError(format!("CLIENT_ERR: {}", e)))?;
-        for attempt in 0..=self.max_retries {
-            if attempt > 0 { std::thread::sleep(Duration::from_millis(1000 * 2u64.pow(attempt - 1))); }
-            match client.post(&self.endpoint).header("x-api-key", &self.api_key).header("anthropic-version", "2023-06-01").header("Content-Type", "application/json").json(&body).send() {
-                Ok(resp) if resp.status().is_success() => return resp.json().map_err(|e| OmegaError::InvalidResponse(e.to_string())),
-                Ok(resp) if resp.status().as_u16() == 429 => continue,
-                Ok(resp) => return Err(OmegaError::ProviderError(format!("HTTP_{}: {}", resp.status().as_u16(), resp.text().unwrap_or_default()))),
-                Err(e) if e.is_timeout() => continue,
-                Err(e) => return Err(OmegaError::ProviderError(format!("NETWORK: {}", e))),
-            }
+        let system = match (&req.tool, &req.json_schema) {
+            (None, Some(schema)) => format!("{}\n\n{}", req.system_prompt, json_instruction(schema)),
+            _ => req.system_prompt.clone(),
+        };
+        let mut body = serde_json::json!({"model": self.model, "max_tokens": req.max_tokens, "temperature": self.temperature_override.unwrap_or(req.temperature), "system": system, "messages": [{"role": "user", "content": req.user_prompt}]});
+        if let (Some(spec), Some(schema)) = (&req.tool, &req.json_schema) {
+            body["tools"] = serde_json::json!([{"name": spec.name, "description": spec.description, "input_schema": schema}]);
+            body["tool_choice"] = serde_json::json!({"type": "tool", "name": spec.name});
         }
-        Err(OmegaError::ProviderError("MAX_RETRIES_EXCEEDED".into()))
+        body
+    }
+    /// POST Messages API avec la boucle de retries commune; abandonné avec le futur (annulation, échéance)
+    async fn send_async(&self, body: &serde_json::Value) -> Result<reqwest::Response, OmegaError> {
+        let client = reqwest::Client::builder().timeout(self.timeout).build().map_err(|e| OmegaError::ProviderError(format!("CLIENT_ERR: {}", e)))?;
+        send_with_retry(self.max_retries, || {
+            client.post(&self.endpoint).header("x-api-key", &self.api_key).header("anthropic-version", "2023-06-01").header("Content-Type", "application/json").json(body)
+        }).await
+    }
+    /// Sonde légère: GET /v1/models (aucun token consommé), async + block_on comme OpenAIProvider::probe
+    pub fn probe(&self) -> OmegaResult<()> {
+        let url = format!("{}/models", self.endpoint.trim_end_matches("/messages"));
+        let client = reqwest::Client::builder().timeout(self.timeout.min(PROBE_TIMEOUT)).build().map_err(|e| OmegaError::ProviderError(format!("CLIENT_ERR: {}", e)))?;
+        block_on(CallControl::current().run(async {
+            match client.get(&url).header("x-api-key", &self.api_key).header("anthropic-version", "2023-06-01").send().await {
+                Ok(resp) if resp.status().is_success() => Ok(()),
+                Ok(resp) => Err(OmegaError::ProviderError(format!("PROBE_HTTP_{}", resp.status().as_u16()))),
+                Err(e) => Err(OmegaError::ProviderError(format!("PROBE: {}", e))),
+            }
+        }))
+    }
+    /// Décode un événement SSE Messages API (message_start, content_block_delta, message_delta, message_stop)
+    pub fn decode_sse(ev: &SseEvent) -> OmegaResult<Vec<StreamChunk>> {
+        let json: serde_json::Value = serde_json::from_str(&ev.data).map_err(|e| OmegaError::invalid_response(format!("SSE_JSON: {}", e)))?;
+        let kind = json["type"].as_str().or(ev.event.as_deref()).unwrap_or_default();
+        Ok(match kind {
+            "message_start" => json["message"]["usage"]["input_tokens"].as_u64().map(|n| vec![StreamChunk::PromptTokens(n as u32)]).unwrap_or_default(),
+            // text_delta, ou input_json_delta (fragments des arguments d'un tool_use, gardés à part)
+            "content_block_delta" => match (json["delta"]["text"].as_str(), json["delta"]["partial_json"].as_str()) {
+                (Some(text), _) => vec![StreamChunk::Delta(text.to_string())],
+                (None, Some(args)) => vec![StreamChunk::ToolDelta(args.to_string())],
+                (None, None) => Vec::new(),
+            },
+            "message_delta" => json["usage"]["output_tokens"].as_u64().map(|n| vec![StreamChunk::CompletionTokens(n as u32)]).unwrap_or_default(),
+            "message_stop" => vec![StreamChunk::Done],
+            "error" => return Err(OmegaError::ProviderError(format!("STREAM_ERROR: {}", json["error"]["message"].as_str().unwrap_or("unknown")))),
+            _ => vec![StreamChunk::Ignore],
+        })
     }
     fn parse(&self, json: serde_json::Value, req: &CompletionRequest, latency: u64) -> OmegaResult<CompletionResponse> {
-        let content = json["content"][0]["text"].as_str().ok_or_else(|| OmegaError::InvalidResponse("NO_CONTENT".into()))?.to_string();
+        let blocks = json["content"].as_array().map(Vec::as_slice).unwrap_or_default();
+        // tool_use: contenu = input sérialisé (bloc texte si le modèle n'a pas appelé l'outil)
+        let tool_input = blocks.iter().find(|b| b["type"] == "tool_use" && req.tool.is_some()).map(|b| b["input"].to_string());
+        let content = tool_input.or_else(|| blocks.iter().find_map(|b| b["text"].as_str().map(str::to_string))).ok_or_else(|| OmegaError::invalid_response("NO_CONTENT"))?;
         let u = &json["usage"];
         let pt = u["input_tokens"].as_u64().unwrap_or(0) as u32;
         let ct = u["output_tokens"].as_u64().unwrap_or(0) as u32;
-        Ok(CompletionResponse { provider_id: "anthropic".into(), content: content.clone(), response_hash: format!("{:x}", Sha256::digest(format!("anthropic|{}|{}", req.seed, content).as_bytes())), usage: Usage { prompt_tokens: pt, completion_tokens: ct, total_tokens: pt + ct }, parsed: req.json_schema.as_ref().and_then(|_| serde_json::from_str(&content).ok()), latency_ms: latency })
+        Ok(CompletionResponse { provider_id: "anthropic".into(), content: content.clone(), response_hash: format!("{:x}", Sha256::digest(format!("anthropic|{}|{}", req.seed, content).as_bytes())), usage: Usage { prompt_tokens: pt, completion_tokens: ct, total_tokens: pt + ct }, parsed: req.json_schema.as_ref().and_then(|_| serde_json::from_str(&content).ok()), latency_ms: latency, cache: None, fallback: None })
     }
 }
 
 impl crate::ai::LLMProvider for AnthropicProvider {
     fn id(&self) -> ProviderId { "anthropic".into() }
+    fn model(&self) -> Option<String> { Some(self.model.clone()) }
     fn capabilities(&self) -> ProviderCapabilities { ProviderCapabilities { id: "anthropic".into(), max_context_window: 200000, supports_json_mode: true, supports_streaming: true, supports_tool_calling: true, supports_embeddings: false } }
-    fn generate(&self, req: CompletionRequest) -> OmegaResult<CompletionResponse> { let start = Instant::now(); let json = self.execute(self.build_body(&req))?; self.parse(json, &req, start.elapsed().as_millis() as u64) }
+    fn generate(&self, req: CompletionRequest) -> OmegaResult<CompletionResponse> { generate_blocking(self, req) }
     fn embed(&self, _req: EmbeddingRequest) -> OmegaResult<EmbeddingResponse> { Err(OmegaError::NotSupported("Embeddings not implemented".into())) }
-    fn health(&self) -> bool { true }
+    fn health(&self) -> bool { !self.health_probe || self.probe().is_ok() }
+    fn generate_stream(&self, req: CompletionRequest, on_delta: &mut dyn FnMut(StreamDelta), cancel: &CancelToken) -> OmegaResult<CompletionResponse> {
+        let start = Instant::now();
+        let mut body = self.build_body(&req);
+        body["stream"] = serde_json::json!(true);
+        let reader = open_stream(cancel, self.send_async(&body))?;
+        let mut acc = StreamAccumulator::new();
+        drive_sse(reader, Self::decode_sse, &mut acc, on_delta, cancel)?;
+        Ok(acc.finish("anthropic", "anthropic", &req, start.elapsed().as_millis() as u64))
+    }
+}
+
+impl AsyncLLMProvider for AnthropicProvider {
+    fn generate_async<'a>(&'a self, req: CompletionRequest, ctl: &'a CallControl) -> BoxFuture<'a, OmegaResult<CompletionResponse>> {
+        Box::pin(ctl.run(async move {
+            let start = Instant::now();
+            let json = self.send_async(&self.build_body(&req)).await?.json().await.map_err(|e| OmegaError::invalid_response(e.to_string()))?;
+            self.parse(json, &req, start.elapsed().as_millis() as u64)
+        }))
+    }
 }
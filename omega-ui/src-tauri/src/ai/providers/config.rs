@@ -2,16 +2,54 @@
 use std::env;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-pub enum ProviderType { Mock, OpenAI, Anthropic }
+pub enum ProviderType { Mock, OpenAI, Anthropic, OpenAICompatible, MockScript }
 
 impl Default for ProviderType { fn default() -> Self { ProviderType::Mock } }
 
+impl ProviderType {
+    /// Nom de configuration (OMEGA_PROVIDER, `type` d'un profil); "local" = openai-compatible; "mock-script" = scénario de pannes
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "mock" => Some(ProviderType::Mock), "openai" => Some(ProviderType::OpenAI), "anthropic" => Some(ProviderType::Anthropic),
+            "openai-compatible" | "local" => Some(ProviderType::OpenAICompatible), "mock-script" => Some(ProviderType::MockScript), _ => None,
+        }
+    }
+}
+
 impl std::fmt::Display for ProviderType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             ProviderType::Mock => write!(f, "mock"),
             ProviderType::OpenAI => write!(f, "openai"),
             ProviderType::Anthropic => write!(f, "anthropic"),
+            ProviderType::OpenAICompatible => write!(f, "openai-compatible"),
+            ProviderType::MockScript => write!(f, "mock-script"),
+        }
+    }
+}
+
+/// Surcharges de capacités par modèle (serveurs OpenAI-compatibles: Ollama, llama.cpp, vLLM)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CapabilityOverrides {
+    pub max_context_window: Option<u32>,
+    pub supports_json_mode: Option<bool>,
+    pub supports_streaming: Option<bool>,
+    pub supports_tool_calling: Option<bool>,
+}
+
+impl CapabilityOverrides {
+    pub fn apply(&self, caps: &mut crate::ai::ProviderCapabilities) {
+        if let Some(v) = self.max_context_window { caps.max_context_window = v; }
+        if let Some(v) = self.supports_json_mode { caps.supports_json_mode = v; }
+        if let Some(v) = self.supports_streaming { caps.supports_streaming = v; }
+        if let Some(v) = self.supports_tool_calling { caps.supports_tool_calling = v; }
+    }
+    /// OMEGA_LOCAL_CONTEXT_WINDOW / OMEGA_LOCAL_JSON_MODE
+    pub fn from_env() -> Self {
+        Self {
+            max_context_window: env::var("OMEGA_LOCAL_CONTEXT_WINDOW").ok().and_then(|s| s.parse().ok()),
+            supports_json_mode: env::var("OMEGA_LOCAL_JSON_MODE").ok().and_then(|s| s.parse().ok()),
+            ..Default::default()
         }
     }
 }
@@ -25,32 +63,69 @@ pub struct ProviderConfig {
     pub timeout_ms: u64,
     pub max_retries: u32,
     pub temperature: f32,
+    #[serde(default)]
+    pub capabilities: CapabilityOverrides,
+    /// health() interroge réellement l'API (GET /models) au lieu de supposer le provider disponible
+    #[serde(default)]
+    pub health_probe: bool,
+    /// Modèle d'embedding (OpenAI: text-embedding-3-small par défaut; serveur compatible: embeddings désactivés si absent)
+    #[serde(default)]
+    pub embedding_model: Option<String>,
+    /// Température imposée à toutes les requêtes (profil `temperature`); None = celle de la requête
+    #[serde(default)]
+    pub temperature_override: Option<f32>,
+    /// Script TOML du provider mock-script (voir `ai::mock_script`)
+    #[serde(default)]
+    pub script: Option<String>,
 }
 
 impl Default for ProviderConfig {
     fn default() -> Self {
-        Self { provider: ProviderType::Mock, api_key: None, model: None, endpoint: None, timeout_ms: 30000, max_retries: 3, temperature: 0.7 }
+        Self { provider: ProviderType::Mock, api_key: None, model: None, endpoint: None, timeout_ms: 30000, max_retries: 3, temperature: 0.7, capabilities: CapabilityOverrides::default(), health_probe: false, embedding_model: None, temperature_override: None, script: None }
     }
 }
 
 impl ProviderConfig {
     pub fn from_env() -> Self {
-        let provider = match env::var("OMEGA_PROVIDER").unwrap_or_default().to_lowercase().as_str() {
-            "openai" => ProviderType::OpenAI, "anthropic" => ProviderType::Anthropic, _ => ProviderType::Mock,
-        };
+        let provider = ProviderType::parse(&env::var("OMEGA_PROVIDER").unwrap_or_default()).unwrap_or(ProviderType::Mock);
+        if provider == ProviderType::OpenAICompatible {
+            let mut config = Self::local_from_env().unwrap_or_else(|| Self { provider, ..Default::default() });
+            if let Some(t) = env::var("OMEGA_TIMEOUT_MS").ok().and_then(|s| s.parse().ok()) { config.timeout_ms = t; }
+            return config;
+        }
+        if provider == ProviderType::MockScript {
+            return Self::mock_script(&env::var("OMEGA_MOCK_SCRIPT").unwrap_or_default());
+        }
         let api_key = match provider {
             ProviderType::OpenAI => env::var("OPENAI_API_KEY").ok(),
             ProviderType::Anthropic => env::var("ANTHROPIC_API_KEY").ok(),
-            ProviderType::Mock => None,
+            ProviderType::Mock | ProviderType::OpenAICompatible | ProviderType::MockScript => None,
         };
         Self { provider, api_key, timeout_ms: env::var("OMEGA_TIMEOUT_MS").ok().and_then(|s| s.parse().ok()).unwrap_or(30000), ..Default::default() }
     }
     pub fn openai(api_key: String) -> Self { Self { provider: ProviderType::OpenAI, api_key: Some(api_key), model: Some("gpt-4".into()), endpoint: Some("https://api.openai.com/v1/chat/completions".into()), ..Default::default() } }
     pub fn anthropic(api_key: String) -> Self { Self { provider: ProviderType::Anthropic, api_key: Some(api_key), model: Some("claude-3-5-sonnet-20241022".into()), endpoint: Some("https://api.anthropic.com/v1/messages".into()), ..Default::default() } }
+    /// Serveur OpenAI-compatible: base URL (ex: http://localhost:11434/v1), modèle, clé optionnelle
+    pub fn openai_compatible(base_url: &str, model: &str, api_key: Option<String>) -> Self { Self { provider: ProviderType::OpenAICompatible, api_key: api_key.filter(|k| !k.is_empty()), model: Some(model.into()), endpoint: Some(base_url.into()), timeout_ms: 120000, ..Default::default() } }
+    /// OMEGA_LOCAL_BASE_URL + OMEGA_LOCAL_MODEL (+ OMEGA_LOCAL_API_KEY, OMEGA_LOCAL_EMBEDDING_MODEL optionnelles); None si non configuré
+    pub fn local_from_env() -> Option<Self> {
+        let base_url = env::var("OMEGA_LOCAL_BASE_URL").ok().filter(|s| !s.is_empty())?;
+        let model = env::var("OMEGA_LOCAL_MODEL").ok().filter(|s| !s.is_empty())?;
+        Some(Self { capabilities: CapabilityOverrides::from_env(), embedding_model: env::var("OMEGA_LOCAL_EMBEDDING_MODEL").ok().filter(|s| !s.is_empty()), ..Self::openai_compatible(&base_url, &model, env::var("OMEGA_LOCAL_API_KEY").ok()) })
+    }
     pub fn mock() -> Self { Self { provider: ProviderType::Mock, temperature: 0.0, ..Default::default() } }
+    /// Mock piloté par un script TOML (pannes reproductibles); maillon de chaîne comme un provider réel
+    pub fn mock_script(path: &str) -> Self { Self { provider: ProviderType::MockScript, script: Some(path.into()), temperature: 0.0, ..Default::default() } }
     pub fn validate(&self) -> Result<(), String> {
         match self.provider {
             ProviderType::Mock => Ok(()),
+            ProviderType::MockScript => if self.script.as_deref().unwrap_or_default().is_empty() { Err(format!("{} requires a script path", self.provider)) } else { Ok(()) },
+            ProviderType::OpenAICompatible => {
+                let url = self.endpoint.as_deref().unwrap_or("");
+                if !(url.starts_with("http://") || url.starts_with("https://")) { return Err(format!("{} requires an http(s) base URL", self.provider)); }
+                if self.model.as_ref().map(|m| m.is_empty()).unwrap_or(true) { return Err(format!("{} requires a model name", self.provider)); }
+                Ok(())
+            }
             _ => if self.api_key.as_ref().map(|k| k.is_empty()).unwrap_or(true) { Err(format!("{} requires API key", self.provider)) } else { Ok(()) }
         }
     }
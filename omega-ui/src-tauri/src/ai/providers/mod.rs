@@ -1,6 +1,7 @@
 ﻿pub mod config;
 pub mod openai;
 pub mod anthropic;
+pub mod profiles;
 
 #[cfg(test)]
 mod tests;
@@ -15,7 +16,9 @@ pub fn get_provider(config: &ProviderConfig) -> Result<Arc<dyn LLMProvider>, Ome
     match config.provider {
         ProviderType::Mock => Ok(Arc::new(MockDeterministicProvider::new())),
         ProviderType::OpenAI => openai::OpenAIProvider::try_new(config).map(|p| Arc::new(p) as Arc<dyn LLMProvider>).or_else(|_| Ok(Arc::new(MockDeterministicProvider::new()))),
+        ProviderType::OpenAICompatible => openai::OpenAIProvider::try_new_compatible(config).map(|p| Arc::new(p) as Arc<dyn LLMProvider>).or_else(|_| Ok(Arc::new(MockDeterministicProvider::new()))),
         ProviderType::Anthropic => anthropic::AnthropicProvider::try_new(config).map(|p| Arc::new(p) as Arc<dyn LLMProvider>).or_else(|_| Ok(Arc::new(MockDeterministicProvider::new()))),
+        ProviderType::MockScript => profiles::build_provider(config).or_else(|_| Ok(Arc::new(MockDeterministicProvider::new()))),
     }
 }
 
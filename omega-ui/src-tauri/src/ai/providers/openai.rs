@@ -1,49 +1,171 @@
 ﻿use crate::ai::models::*;
+use crate::ai::async_provider::{block_on, generate_blocking, open_stream, AsyncLLMProvider, BoxFuture, CallControl};
+use crate::ai::resilience::{send_with_retry, PROBE_TIMEOUT};
+use crate::ai::schema::json_instruction;
+use crate::ai::stream::{drive_sse, CancelToken, SseEvent, StreamAccumulator, StreamChunk, StreamDelta};
 use crate::error::{OmegaError, OmegaResult};
 use super::config::ProviderConfig;
 use sha2::{Sha256, Digest};
 use std::time::{Duration, Instant};
 
-pub struct OpenAIProvider { api_key: String, model: String, endpoint: String, timeout: Duration, max_retries: u32 }
+/// Provider chat.completions: api.openai.com ou serveur OpenAI-compatible (Ollama, llama.cpp, vLLM)
+pub struct OpenAIProvider { id: ProviderId, api_key: Option<String>, model: String, endpoint: String, timeout: Duration, max_retries: u32, caps: ProviderCapabilities, health_probe: bool, embedding_model: Option<String>, temperature_override: Option<f32> }
+
+/// Entrées max par requête /embeddings
+pub const MAX_EMBED_BATCH: usize = 64;
+
+/// URL chat.completions à partir d'une base (ex: http://localhost:11434/v1)
+pub fn chat_completions_url(base: &str) -> String {
+    let base = base.trim_end_matches('/');
+    if base.ends_with("/chat/completions") { base.to_string() } else { format!("{}/chat/completions", base) }
+}
+
+/// Endpoint sur la machine (localhost, 127.0.0.0/8, ::1): le texte ne quitte pas le poste
+pub fn is_loopback_url(url: &str) -> bool {
+    let Ok(url) = reqwest::Url::parse(url) else { return false };
+    match url.host_str() {
+        Some(host) if host.eq_ignore_ascii_case("localhost") || host.to_ascii_lowercase().ends_with(".localhost") => true,
+        Some(host) => host.trim_matches(|c| c == '[' || c == ']').parse::<std::net::IpAddr>().is_ok_and(|ip| ip.is_loopback()),
+        None => false,
+    }
+}
 
 impl OpenAIProvider {
     pub fn try_new(config: &ProviderConfig) -> Result<Self, OmegaError> {
         let api_key = config.api_key.clone().ok_or_else(|| OmegaError::ProviderError("OPENAI_MISSING_KEY".into()))?;
         if api_key.is_empty() { return Err(OmegaError::ProviderError("OPENAI_EMPTY_KEY".into())); }
-        Ok(Self { api_key, model: config.model.clone().unwrap_or_else(|| "gpt-4".into()), endpoint: config.endpoint.clone().unwrap_or_else(|| "https://api.openai.com/v1/chat/completions".into()), timeout: Duration::from_millis(config.timeout_ms), max_retries: config.max_retries })
+        let mut caps = ProviderCapabilities { id: "openai".into(), max_context_window: 128000, supports_json_mode: true, supports_streaming: true, supports_tool_calling: true, supports_embeddings: true };
+        config.capabilities.apply(&mut caps);
+        Ok(Self { id: "openai".into(), api_key: Some(api_key), model: config.model.clone().unwrap_or_else(|| "gpt-4".into()), endpoint: config.endpoint.clone().unwrap_or_else(|| "https://api.openai.com/v1/chat/completions".into()), timeout: Duration::from_millis(config.timeout_ms), max_retries: config.max_retries, caps, health_probe: config.health_probe, embedding_model: Some(config.embedding_model.clone().unwrap_or_else(|| "text-embedding-3-small".into())), temperature_override: config.temperature_override })
+    }
+    /// Serveur OpenAI-compatible: base URL + modèle obligatoires, clé optionnelle, capacités prudentes par défaut
+    pub fn try_new_compatible(config: &ProviderConfig) -> Result<Self, OmegaError> {
+        config.validate().map_err(OmegaError::ConfigError)?;
+        let model = config.model.clone().unwrap_or_default();
+        let mut caps = ProviderCapabilities { id: format!("openai-compatible:{}", model), max_context_window: 8192, supports_json_mode: false, supports_streaming: true, supports_tool_calling: false, supports_embeddings: config.embedding_model.is_some() };
+        config.capabilities.apply(&mut caps);
+        Ok(Self { id: "openai-compatible".into(), api_key: config.api_key.clone().filter(|k| !k.is_empty()), endpoint: chat_completions_url(config.endpoint.as_deref().unwrap_or_default()), model, timeout: Duration::from_millis(config.timeout_ms), max_retries: config.max_retries, caps, health_probe: config.health_probe, embedding_model: config.embedding_model.clone(), temperature_override: config.temperature_override })
     }
+    /// Base de l'API (endpoint sans /chat/completions)
+    fn base_url(&self) -> &str { self.endpoint.trim_end_matches("/chat/completions") }
+    /// Outil demandé et supporté: function calling forcé (arguments = json_schema); sinon response_format / consigne JSON
     fn build_body(&self, req: &CompletionRequest) -> serde_json::Value {
-        let mut body = serde_json::json!({"model": self.model, "messages": [{"role": "system", "content": req.system_prompt}, {"role": "user", "content": req.user_prompt}], "max_tokens": req.max_tokens, "temperature": req.temperature});
-        if req.json_schema.is_some() { body["response_format"] = serde_json::json!({"type": "json_object"}); }
+        let tool = req.tool.as_ref().zip(req.json_schema.as_ref());
+        let system = match tool {
+            Some((_, schema)) if !self.caps.supports_tool_calling => format!("{}\n\n{}", req.system_prompt, json_instruction(schema)),
+            _ => req.system_prompt.clone(),
+        };
+        let mut body = serde_json::json!({"model": self.model, "messages": [{"role": "system", "content": system}, {"role": "user", "content": req.user_prompt}], "max_tokens": req.max_tokens, "temperature": self.temperature_override.unwrap_or(req.temperature)});
+        match tool {
+            Some((spec, schema)) if self.caps.supports_tool_calling => {
+                body["tools"] = serde_json::json!([{"type": "function", "function": {"name": spec.name, "description": spec.description, "parameters": schema}}]);
+                body["tool_choice"] = serde_json::json!({"type": "function", "function": {"name": spec.name}});
+            }
+            _ if req.json_schema.is_some() && self.caps.supports_json_mode => body["response_format"] = serde_json::json!({"type": "json_object"}),
+            _ => {}
+        }
         body
     }
-    fn execute(&self, body: serde_json::Value) -> Result<serde_json::Value, OmegaError> {
-        let client = reqwest::blocking::Client::builder().timeout(self.timeout).build().map_err(|e| OmegaError::ProviderError(format!("CLIENT_ERR: {}", e)))?;
-        for attempt in 0..=self.max_retries {
-            if attempt > 0 { std::thread::sleep(Duration::from_millis(1000 * 2u64.pow(attempt - 1))); }
-            match client.post(&self.endpoint).header("Authorization", format!("Bearer {}", self.api_key)).header("Content-Type", "application/json").json(&body).send() {
-                Ok(resp) if resp.status().is_success() => return resp.json().map_err(|e| OmegaError::InvalidResponse(e.to_string())),
-                Ok(resp) if resp.status().as_u16() == 429 => continue,
-                Ok(resp) => return Err(OmegaError::ProviderError(format!("HTTP_{}: {}", resp.status().as_u16(), resp.text().unwrap_or_default()))),
-                Err(e) if e.is_timeout() => continue,
-                Err(e) => return Err(OmegaError::ProviderError(format!("NETWORK: {}", e))),
+    /// POST JSON (bearer si clé) avec la boucle de retries commune; abandonné avec le futur (annulation, échéance)
+    async fn send_async(&self, url: &str, body: &serde_json::Value) -> Result<reqwest::Response, OmegaError> {
+        let client = reqwest::Client::builder().timeout(self.timeout).build().map_err(|e| OmegaError::ProviderError(format!("CLIENT_ERR: {}", e)))?;
+        send_with_retry(self.max_retries, || {
+            let request = client.post(url).header("Content-Type", "application/json").json(body);
+            match &self.api_key { Some(key) => request.bearer_auth(key), None => request }
+        }).await
+    }
+    /// Sonde légère: GET <base>/models (aucun token consommé).
+    /// Client async + block_on: appelable depuis un thread du runtime partagé (la chaîne de fallback y tourne)
+    pub fn probe(&self) -> OmegaResult<()> {
+        let url = format!("{}/models", self.base_url());
+        let client = reqwest::Client::builder().timeout(self.timeout.min(PROBE_TIMEOUT)).build().map_err(|e| OmegaError::ProviderError(format!("CLIENT_ERR: {}", e)))?;
+        block_on(CallControl::current().run(async {
+            let mut request = client.get(&url);
+            if let Some(key) = &self.api_key { request = request.bearer_auth(key); }
+            match request.send().await {
+                Ok(resp) if resp.status().is_success() => Ok(()),
+                Ok(resp) => Err(OmegaError::ProviderError(format!("PROBE_HTTP_{}", resp.status().as_u16()))),
+                Err(e) => Err(OmegaError::ProviderError(format!("PROBE: {}", e))),
             }
+        }))
+    }
+    /// POST <base>/embeddings pour un lot d'entrées (≤ MAX_EMBED_BATCH), vecteurs remis dans l'ordre des entrées
+    fn embed_inputs(&self, inputs: &[String]) -> OmegaResult<Vec<Vec<f32>>> {
+        let model = self.embedding_model.as_ref().ok_or_else(|| OmegaError::NotSupported(format!("{}: no embedding model configured", self.id)))?;
+        let (url, body) = (format!("{}/embeddings", self.base_url()), serde_json::json!({"model": model, "input": inputs}));
+        let json: serde_json::Value = block_on(CallControl::current().run(async {
+            self.send_async(&url, &body).await?.json().await.map_err(|e| OmegaError::invalid_response(e.to_string()))
+        }))?;
+        let data = json["data"].as_array().ok_or_else(|| OmegaError::invalid_response("EMBEDDINGS_NO_DATA"))?;
+        let mut vectors = vec![None; inputs.len()];
+        for (pos, item) in data.iter().enumerate() {
+            let index = item["index"].as_u64().map_or(pos, |i| i as usize);
+            let vector = item["embedding"].as_array().ok_or_else(|| OmegaError::invalid_response("EMBEDDINGS_NO_VECTOR"))?
+                .iter().map(|v| v.as_f64().map(|f| f as f32)).collect::<Option<Vec<f32>>>().ok_or_else(|| OmegaError::invalid_response("EMBEDDINGS_NOT_NUMERIC"))?;
+            if let Some(slot) = vectors.get_mut(index) { *slot = Some(vector); }
         }
-        Err(OmegaError::ProviderError("MAX_RETRIES_EXCEEDED".into()))
+        vectors.into_iter().collect::<Option<Vec<_>>>().ok_or_else(|| OmegaError::invalid_response(format!("EMBEDDINGS_COUNT: expected {}", inputs.len())))
+    }
+    /// Décode un événement SSE chat.completions (delta.content, usage final, [DONE])
+    pub fn decode_sse(ev: &SseEvent) -> OmegaResult<Vec<StreamChunk>> {
+        if ev.data.trim() == "[DONE]" { return Ok(vec![StreamChunk::Done]); }
+        let json: serde_json::Value = serde_json::from_str(&ev.data).map_err(|e| OmegaError::invalid_response(format!("SSE_JSON: {}", e)))?;
+        if let Some(msg) = json["error"]["message"].as_str() { return Err(OmegaError::ProviderError(format!("STREAM_ERROR: {}", msg))); }
+        let mut out = Vec::new();
+        if let Some(text) = json["choices"][0]["delta"]["content"].as_str() { out.push(StreamChunk::Delta(text.to_string())); }
+        // Appel d'outil: les arguments JSON arrivent par fragments
+        if let Some(args) = json["choices"][0]["delta"]["tool_calls"][0]["function"]["arguments"].as_str() { out.push(StreamChunk::ToolDelta(args.to_string())); }
+        if let Some(pt) = json["usage"]["prompt_tokens"].as_u64() { out.push(StreamChunk::PromptTokens(pt as u32)); }
+        if let Some(ct) = json["usage"]["completion_tokens"].as_u64() { out.push(StreamChunk::CompletionTokens(ct as u32)); }
+        Ok(out)
     }
     fn parse(&self, json: serde_json::Value, req: &CompletionRequest, latency: u64) -> OmegaResult<CompletionResponse> {
-        let content = json["choices"][0]["message"]["content"].as_str().ok_or_else(|| OmegaError::InvalidResponse("NO_CONTENT".into()))?.to_string();
+        let message = &json["choices"][0]["message"];
+        // Appel d'outil: contenu = arguments JSON (texte libre si le modèle n'a pas appelé l'outil)
+        let content = message["tool_calls"][0]["function"]["arguments"].as_str().filter(|_| req.tool.is_some())
+            .or_else(|| message["content"].as_str())
+            .ok_or_else(|| OmegaError::invalid_response("NO_CONTENT"))?.to_string();
         let u = &json["usage"];
         let pt = u["prompt_tokens"].as_u64().unwrap_or(0) as u32;
         let ct = u["completion_tokens"].as_u64().unwrap_or(0) as u32;
-        Ok(CompletionResponse { provider_id: "openai".into(), content: content.clone(), response_hash: format!("{:x}", Sha256::digest(format!("openai|{}|{}", req.seed, content).as_bytes())), usage: Usage { prompt_tokens: pt, completion_tokens: ct, total_tokens: pt + ct }, parsed: req.json_schema.as_ref().and_then(|_| serde_json::from_str(&content).ok()), latency_ms: latency })
+        Ok(CompletionResponse { provider_id: self.id.clone(), content: content.clone(), response_hash: format!("{:x}", Sha256::digest(format!("{}|{}|{}", self.id, req.seed, content).as_bytes())), usage: Usage { prompt_tokens: pt, completion_tokens: ct, total_tokens: pt + ct }, parsed: req.json_schema.as_ref().and_then(|_| serde_json::from_str(&content).ok()), latency_ms: latency, cache: None, fallback: None })
     }
 }
 
 impl crate::ai::LLMProvider for OpenAIProvider {
-    fn id(&self) -> ProviderId { "openai".into() }
-    fn capabilities(&self) -> ProviderCapabilities { ProviderCapabilities { id: "openai".into(), max_context_window: 128000, supports_json_mode: true, supports_streaming: true, supports_tool_calling: true, supports_embeddings: true } }
-    fn generate(&self, req: CompletionRequest) -> OmegaResult<CompletionResponse> { let start = Instant::now(); let json = self.execute(self.build_body(&req))?; self.parse(json, &req, start.elapsed().as_millis() as u64) }
-    fn embed(&self, _req: EmbeddingRequest) -> OmegaResult<EmbeddingResponse> { Err(OmegaError::NotSupported("Embeddings not implemented".into())) }
-    fn health(&self) -> bool { true }
+    fn id(&self) -> ProviderId { self.id.clone() }
+    fn model(&self) -> Option<String> { Some(self.model.clone()) }
+    fn capabilities(&self) -> ProviderCapabilities { self.caps.clone() }
+    fn generate(&self, req: CompletionRequest) -> OmegaResult<CompletionResponse> { generate_blocking(self, req) }
+    fn embed(&self, req: EmbeddingRequest) -> OmegaResult<EmbeddingResponse> { self.embed_batch(vec![req])?.pop().ok_or_else(|| OmegaError::invalid_response("EMBEDDINGS_EMPTY")) }
+    fn embed_batch(&self, reqs: Vec<EmbeddingRequest>) -> OmegaResult<Vec<EmbeddingResponse>> {
+        let inputs: Vec<String> = reqs.into_iter().map(|r| r.input).collect();
+        let mut out = Vec::with_capacity(inputs.len());
+        for batch in inputs.chunks(MAX_EMBED_BATCH) {
+            out.extend(self.embed_inputs(batch)?.into_iter().map(|vectors| EmbeddingResponse { provider_id: self.id.clone(), model: self.embedding_model.clone(), vectors, fallback: None }));
+        }
+        Ok(out)
+    }
+    fn health(&self) -> bool { !self.health_probe || self.probe().is_ok() }
+    fn is_local(&self) -> bool { is_loopback_url(&self.endpoint) }
+    fn generate_stream(&self, req: CompletionRequest, on_delta: &mut dyn FnMut(StreamDelta), cancel: &CancelToken) -> OmegaResult<CompletionResponse> {
+        let start = Instant::now();
+        let mut body = self.build_body(&req);
+        body["stream"] = serde_json::json!(true);
+        body["stream_options"] = serde_json::json!({"include_usage": true});
+        let reader = open_stream(cancel, self.send_async(&self.endpoint, &body))?;
+        let mut acc = StreamAccumulator::new();
+        drive_sse(reader, Self::decode_sse, &mut acc, on_delta, cancel)?;
+        Ok(acc.finish(&self.id, &self.id, &req, start.elapsed().as_millis() as u64))
+    }
+}
+
+impl AsyncLLMProvider for OpenAIProvider {
+    fn generate_async<'a>(&'a self, req: CompletionRequest, ctl: &'a CallControl) -> BoxFuture<'a, OmegaResult<CompletionResponse>> {
+        Box::pin(ctl.run(async move {
+            let start = Instant::now();
+            let json = self.send_async(&self.endpoint, &self.build_body(&req)).await?.json().await.map_err(|e| OmegaError::invalid_response(e.to_string()))?;
+            self.parse(json, &req, start.elapsed().as_millis() as u64)
+        }))
+    }
 }
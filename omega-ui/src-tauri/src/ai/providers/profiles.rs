@@ -0,0 +1,357 @@
+//! OMEGA Provider Profiles — profils nommés (omega.toml) + chaîne de fallback ordonnée
+//! NASA-Grade: un fichier invalide est refusé avec une erreur explicite (jamais de profil partiellement appliqué);
+//! les clés API ne sont jamais écrites dans le fichier, seulement le nom de la variable qui les porte.
+//!
+//! ```toml
+//! [profiles.local]
+//! type = "openai-compatible"
+//! endpoint = "http://localhost:11434/v1"
+//! model = "llama3"
+//! timeout_ms = 120000
+//!
+//! [profiles.openai]
+//! type = "openai"
+//! model = "gpt-4o"
+//! temperature = 0.0
+//! api_key_env = "OPENAI_API_KEY"
+//!
+//! [profiles.faults]
+//! type = "mock-script"
+//! script = "faults.toml"
+//!
+//! [fallback]
+//! chain = ["local", "openai"]
+//!
+//! [privacy.manuscrit-nda]
+//! redact = true
+//! local_only = true
+//! ```
+//!
+//! Surcharges: OMEGA_CONFIG (chemin du fichier), OMEGA_FALLBACK_CHAIN ("local,openai"),
+//! OMEGA_PROFILE_<NOM>_{MODEL,ENDPOINT,TIMEOUT_MS,MAX_RETRIES,TEMPERATURE,API_KEY_ENV}.
+//! Section `[privacy.<projet>]`: voir `ai::redaction`.
+
+use super::anthropic::AnthropicProvider;
+use super::config::{CapabilityOverrides, ProviderConfig, ProviderType};
+use super::openai::OpenAIProvider;
+use crate::ai::interface::LLMProvider;
+use crate::ai::mock::MockDeterministicProvider;
+use crate::ai::mock_script::ScriptedProvider;
+use crate::ai::redaction::PrivacyPolicy;
+use crate::error::{OmegaError, OmegaResult};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::env;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Fichier lu par défaut (répertoire courant) si OMEGA_CONFIG n'est pas défini
+pub const DEFAULT_CONFIG_FILE: &str = "omega.toml";
+
+/// Bornes acceptées pour `temperature`
+pub const MAX_TEMPERATURE: f32 = 2.0;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProviderProfile {
+    /// mock, mock-script, openai, anthropic, openai-compatible (alias: local)
+    #[serde(rename = "type")]
+    pub kind: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// URL complète (openai, anthropic) ou base URL (openai-compatible)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_retries: Option<u32>,
+    /// Imposée à toutes les requêtes du profil
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    /// Variable d'environnement portant la clé (défaut: OPENAI_API_KEY / ANTHROPIC_API_KEY)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key_env: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub embedding_model: Option<String>,
+    #[serde(default)]
+    pub health_probe: bool,
+    #[serde(default)]
+    pub capabilities: CapabilityOverrides,
+    /// Script TOML (type mock-script), relatif au répertoire courant
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub script: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FallbackChain {
+    /// Profils tentés dans l'ordre (le mock reste le dernier recours)
+    #[serde(default)]
+    pub chain: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProviderProfiles {
+    #[serde(default)]
+    pub profiles: BTreeMap<String, ProviderProfile>,
+    #[serde(default)]
+    pub fallback: FallbackChain,
+    /// Politiques de confidentialité par projet (`default` pour les autres)
+    #[serde(default)]
+    pub privacy: BTreeMap<String, PrivacyPolicy>,
+}
+
+impl ProviderProfile {
+    pub fn provider_type(&self) -> Option<ProviderType> { ProviderType::parse(&self.kind) }
+
+    /// Variable de la clé: explicite, sinon celle du type
+    pub fn key_env(&self) -> Option<String> {
+        self.api_key_env.clone().or_else(|| match self.provider_type()? {
+            ProviderType::OpenAI => Some("OPENAI_API_KEY".into()),
+            ProviderType::Anthropic => Some("ANTHROPIC_API_KEY".into()),
+            ProviderType::Mock | ProviderType::OpenAICompatible | ProviderType::MockScript => None,
+        })
+    }
+
+    fn validate(&self, name: &str) -> Result<(), String> {
+        let kind = self.provider_type().ok_or_else(|| format!("profile '{}': unknown type '{}' (use: mock, mock-script, openai, anthropic, openai-compatible)", name, self.kind))?;
+        if let Some(url) = &self.endpoint {
+            if !(url.starts_with("http://") || url.starts_with("https://")) { return Err(format!("profile '{}': endpoint '{}' must be an http(s) URL", name, url)); }
+        }
+        if kind == ProviderType::OpenAICompatible {
+            if self.endpoint.is_none() { return Err(format!("profile '{}': openai-compatible requires endpoint", name)); }
+            if self.model.as_deref().unwrap_or_default().is_empty() { return Err(format!("profile '{}': openai-compatible requires model", name)); }
+        }
+        match (kind, self.script.as_deref()) {
+            (ProviderType::MockScript, None | Some("")) => return Err(format!("profile '{}': mock-script requires script", name)),
+            (ProviderType::MockScript, _) | (_, None) => {}
+            (_, Some(_)) => return Err(format!("profile '{}': script only applies to mock-script", name)),
+        }
+        if self.model.as_deref() == Some("") { return Err(format!("profile '{}': model is empty", name)); }
+        if self.timeout_ms == Some(0) { return Err(format!("profile '{}': timeout_ms must be > 0", name)); }
+        if let Some(t) = self.temperature.filter(|t| !(0.0..=MAX_TEMPERATURE).contains(t)) {
+            return Err(format!("profile '{}': temperature {} out of [0, {}]", name, t, MAX_TEMPERATURE));
+        }
+        if let Some(var) = &self.api_key_env {
+            if var.is_empty() || !var.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                return Err(format!("profile '{}': api_key_env '{}' is not an environment variable name", name, var));
+            }
+        }
+        Ok(())
+    }
+
+    /// ProviderConfig du profil; la clé est lue via `var` (l'environnement en production)
+    pub fn to_config(&self, name: &str, var: &dyn Fn(&str) -> Option<String>) -> OmegaResult<ProviderConfig> {
+        self.validate(name).map_err(|e| OmegaError::ConfigError(format!("PROFILE_INVALID: {}", e)))?;
+        let kind = self.provider_type().unwrap_or_default();
+        let api_key = match self.key_env() {
+            Some(key_var) => match var(&key_var).filter(|k| !k.is_empty()) {
+                Some(key) => Some(key),
+                None if kind == ProviderType::OpenAICompatible => None,
+                None => return Err(OmegaError::ConfigError(format!("PROFILE_KEY_MISSING: profile '{}': {} not set", name, key_var))),
+            },
+            None => None,
+        };
+        let base = match kind {
+            ProviderType::Mock => ProviderConfig::mock(),
+            ProviderType::OpenAI => ProviderConfig::openai(String::new()),
+            ProviderType::Anthropic => ProviderConfig::anthropic(String::new()),
+            ProviderType::OpenAICompatible => ProviderConfig::openai_compatible(self.endpoint.as_deref().unwrap_or_default(), self.model.as_deref().unwrap_or_default(), None),
+            ProviderType::MockScript => ProviderConfig::mock_script(self.script.as_deref().unwrap_or_default()),
+        };
+        Ok(ProviderConfig {
+            api_key,
+            model: self.model.clone().or(base.model),
+            endpoint: self.endpoint.clone().or(base.endpoint),
+            timeout_ms: self.timeout_ms.unwrap_or(base.timeout_ms),
+            max_retries: self.max_retries.unwrap_or(base.max_retries),
+            temperature: self.temperature.unwrap_or(base.temperature),
+            temperature_override: self.temperature,
+            capabilities: self.capabilities.clone(),
+            health_probe: self.health_probe,
+            embedding_model: self.embedding_model.clone(),
+            ..base
+        })
+    }
+}
+
+impl ProviderProfiles {
+    /// Parse + validation (`source`: chemin ou libellé repris dans les erreurs)
+    pub fn parse(content: &str, source: &str) -> OmegaResult<Self> {
+        let profiles: Self = toml::from_str(content).map_err(|e| OmegaError::ConfigError(format!("PROFILES_PARSE: {}: {}", source, e)))?;
+        profiles.validate()?;
+        Ok(profiles)
+    }
+
+    pub fn load(path: &Path) -> OmegaResult<Self> {
+        let content = std::fs::read_to_string(path).map_err(|e| OmegaError::ReadError(format!("{}: {}", path.display(), e)))?;
+        Self::parse(&content, &path.display().to_string())
+    }
+
+    /// OMEGA_CONFIG (doit exister), sinon ./omega.toml s'il existe; None = pas de fichier (configuration par variables)
+    pub fn discover() -> OmegaResult<Option<PathBuf>> {
+        match env::var("OMEGA_CONFIG").ok().filter(|p| !p.is_empty()) {
+            Some(path) if Path::new(&path).is_file() => Ok(Some(PathBuf::from(path))),
+            Some(path) => Err(OmegaError::ConfigError(format!("PROFILES_NOT_FOUND: OMEGA_CONFIG={}", path))),
+            None => Ok(Some(PathBuf::from(DEFAULT_CONFIG_FILE)).filter(|p| p.is_file())),
+        }
+    }
+
+    /// Fichier découvert + surcharges d'environnement
+    pub fn from_env() -> OmegaResult<Option<Self>> {
+        let Some(path) = Self::discover()? else { return Ok(None) };
+        let mut profiles = Self::load(&path)?;
+        profiles.apply_overrides(&|k| env::var(k).ok())?;
+        Ok(Some(profiles))
+    }
+
+    /// OMEGA_FALLBACK_CHAIN et OMEGA_PROFILE_<NOM>_*; revalide le résultat
+    pub fn apply_overrides(&mut self, var: &dyn Fn(&str) -> Option<String>) -> OmegaResult<()> {
+        let invalid = |k: &str, v: &str| OmegaError::ConfigError(format!("PROFILE_OVERRIDE_INVALID: {}={}", k, v));
+        if let Some(chain) = var("OMEGA_FALLBACK_CHAIN") {
+            self.fallback.chain = chain.split(',').map(str::trim).filter(|s| !s.is_empty()).map(String::from).collect();
+        }
+        for (name, profile) in self.profiles.iter_mut() {
+            let prefix = format!("OMEGA_PROFILE_{}_", name.to_uppercase().replace('-', "_"));
+            let get = |field: &str| var(&format!("{}{}", prefix, field)).filter(|v| !v.is_empty()).map(|v| (format!("{}{}", prefix, field), v));
+            if let Some((_, v)) = get("MODEL") { profile.model = Some(v); }
+            if let Some((_, v)) = get("ENDPOINT") { profile.endpoint = Some(v); }
+            if let Some((_, v)) = get("API_KEY_ENV") { profile.api_key_env = Some(v); }
+            if let Some((k, v)) = get("TIMEOUT_MS") { profile.timeout_ms = Some(v.parse().map_err(|_| invalid(&k, &v))?); }
+            if let Some((k, v)) = get("MAX_RETRIES") { profile.max_retries = Some(v.parse().map_err(|_| invalid(&k, &v))?); }
+            if let Some((k, v)) = get("TEMPERATURE") { profile.temperature = Some(v.parse().map_err(|_| invalid(&k, &v))?); }
+        }
+        self.validate()
+    }
+
+    /// Tous les profils + la chaîne; première erreur rapportée avec le profil en cause
+    pub fn validate(&self) -> OmegaResult<()> {
+        for (name, profile) in &self.profiles {
+            if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+                return Err(OmegaError::ConfigError(format!("PROFILE_INVALID: profile name '{}' (use [A-Za-z0-9_-])", name)));
+            }
+            profile.validate(name).map_err(|e| OmegaError::ConfigError(format!("PROFILE_INVALID: {}", e)))?;
+        }
+        for (i, name) in self.fallback.chain.iter().enumerate() {
+            if !self.profiles.contains_key(name) {
+                return Err(OmegaError::ConfigError(format!("PROFILE_UNKNOWN: fallback.chain[{}] = '{}' (profiles: {})", i, name, self.names().join(", "))));
+            }
+            if self.fallback.chain[..i].contains(name) {
+                return Err(OmegaError::ConfigError(format!("PROFILE_CHAIN_DUPLICATE: '{}'", name)));
+            }
+        }
+        for (name, policy) in &self.privacy {
+            policy.validate(name).map_err(|e| OmegaError::ConfigError(format!("PRIVACY_INVALID: {}", e)))?;
+        }
+        Ok(())
+    }
+
+    pub fn names(&self) -> Vec<String> { self.profiles.keys().cloned().collect() }
+
+    pub fn get(&self, name: &str) -> OmegaResult<&ProviderProfile> {
+        self.profiles.get(name).ok_or_else(|| OmegaError::ConfigError(format!("PROFILE_UNKNOWN: '{}' (profiles: {})", name, self.names().join(", "))))
+    }
+
+    pub fn config(&self, name: &str) -> OmegaResult<ProviderConfig> {
+        self.get(name)?.to_config(name, &|k| env::var(k).ok())
+    }
+
+    /// Provider d'un profil, sans repli silencieux sur le mock
+    pub fn provider(&self, name: &str) -> OmegaResult<Arc<dyn LLMProvider>> {
+        build_provider(&self.config(name)?)
+    }
+}
+
+/// Construction stricte (erreurs de configuration remontées)
+pub fn build_provider(config: &ProviderConfig) -> OmegaResult<Arc<dyn LLMProvider>> {
+    Ok(match config.provider {
+        ProviderType::Mock => Arc::new(MockDeterministicProvider::default()),
+        ProviderType::OpenAI => Arc::new(OpenAIProvider::try_new(config)?),
+        ProviderType::Anthropic => Arc::new(AnthropicProvider::try_new(config)?),
+        ProviderType::OpenAICompatible => Arc::new(OpenAIProvider::try_new_compatible(config)?),
+        ProviderType::MockScript => {
+            config.validate().map_err(OmegaError::ConfigError)?;
+            Arc::new(ScriptedProvider::load(Path::new(config.script.as_deref().unwrap_or_default()))?)
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = r#"
+[profiles.local]
+type = "openai-compatible"
+endpoint = "http://localhost:11434/v1"
+model = "llama3"
+timeout_ms = 90000
+
+[profiles.local.capabilities]
+max_context_window = 32768
+
+[profiles.openai]
+type = "openai"
+model = "gpt-4o"
+temperature = 0.0
+max_retries = 1
+
+[profiles.claude]
+type = "anthropic"
+api_key_env = "OMEGA_TEST_CLAUDE_KEY"
+
+[fallback]
+chain = ["local", "openai", "claude"]
+"#;
+
+    fn vars(pairs: &'static [(&'static str, &'static str)]) -> impl Fn(&str) -> Option<String> {
+        move |k| pairs.iter().find(|(name, _)| *name == k).map(|(_, v)| v.to_string())
+    }
+
+    #[test]
+    fn test_profiles_to_config() {
+        let profiles = ProviderProfiles::parse(SAMPLE, "omega.toml").unwrap();
+        assert_eq!(profiles.fallback.chain, vec!["local", "openai", "claude"]);
+
+        let local = profiles.get("local").unwrap().to_config("local", &vars(&[])).unwrap();
+        assert_eq!(local.provider, ProviderType::OpenAICompatible);
+        assert_eq!((local.timeout_ms, local.api_key.clone()), (90000, None));
+        assert_eq!(local.capabilities.max_context_window, Some(32768));
+
+        let openai = profiles.get("openai").unwrap().to_config("openai", &vars(&[("OPENAI_API_KEY", "sk-test")])).unwrap();
+        assert_eq!((openai.model.as_deref(), openai.max_retries, openai.temperature_override), (Some("gpt-4o"), 1, Some(0.0)));
+        assert_eq!(openai.endpoint.as_deref(), Some("https://api.openai.com/v1/chat/completions"));
+        assert_eq!(openai.api_key.as_deref(), Some("sk-test"));
+
+        let claude = profiles.get("claude").unwrap().to_config("claude", &vars(&[("ANTHROPIC_API_KEY", "wrong-var")]));
+        assert!(matches!(claude, Err(OmegaError::ConfigError(m)) if m == "PROFILE_KEY_MISSING: profile 'claude': OMEGA_TEST_CLAUDE_KEY not set"));
+    }
+
+    #[test]
+    fn test_env_overrides() {
+        let mut profiles = ProviderProfiles::parse(SAMPLE, "omega.toml").unwrap();
+        profiles.apply_overrides(&vars(&[("OMEGA_FALLBACK_CHAIN", "openai, local"), ("OMEGA_PROFILE_LOCAL_MODEL", "qwen2"), ("OMEGA_PROFILE_OPENAI_TEMPERATURE", "0.3")])).unwrap();
+        assert_eq!(profiles.fallback.chain, vec!["openai", "local"]);
+        assert_eq!(profiles.profiles["local"].model.as_deref(), Some("qwen2"));
+        assert_eq!(profiles.profiles["openai"].temperature, Some(0.3));
+
+        let bad = profiles.apply_overrides(&vars(&[("OMEGA_PROFILE_LOCAL_TIMEOUT_MS", "soon")]));
+        assert!(matches!(bad, Err(OmegaError::ConfigError(m)) if m == "PROFILE_OVERRIDE_INVALID: OMEGA_PROFILE_LOCAL_TIMEOUT_MS=soon"));
+        let unknown = profiles.apply_overrides(&vars(&[("OMEGA_FALLBACK_CHAIN", "openai,gemini")]));
+        assert!(matches!(unknown, Err(OmegaError::ConfigError(m)) if m.starts_with("PROFILE_UNKNOWN: fallback.chain[1] = 'gemini'")));
+    }
+
+    #[test]
+    fn test_validation_errors_are_explicit() {
+        let err = |toml: &str| match ProviderProfiles::parse(toml, "omega.toml") { Err(OmegaError::ConfigError(m)) => m, other => panic!("expected ConfigError, got {:?}", other) };
+        assert!(err("[profiles.a]\ntype = \"gemini\"").contains("profile 'a': unknown type 'gemini'"));
+        assert!(err("[profiles.a]\ntype = \"openai\"\ntemperature = 3.5").contains("temperature 3.5 out of [0, 2]"));
+        assert!(err("[profiles.a]\ntype = \"local\"\nmodel = \"llama3\"").contains("openai-compatible requires endpoint"));
+        assert!(err("[profiles.a]\ntype = \"openai\"\napi_key = \"sk-leak\"").starts_with("PROFILES_PARSE: omega.toml:"), "keys are never accepted in the file");
+        assert!(err("[profiles.a]\ntype = \"mock\"\n[fallback]\nchain = [\"a\", \"a\"]").contains("PROFILE_CHAIN_DUPLICATE"));
+        assert!(err("[profiles.a]\ntype = \"mock-script\"").contains("mock-script requires script"));
+        assert!(err("[profiles.a]\ntype = \"openai\"\nscript = \"faults.toml\"").contains("script only applies to mock-script"));
+    }
+}
@@ -1,6 +1,8 @@
 ﻿use super::config::{ProviderConfig, ProviderType};
 use super::{get_provider, get_provider_with_fallback};
 use crate::ai::LLMProvider;
+use crate::ai::stream::{SseParser, StreamAccumulator, StreamChunk};
+use super::{anthropic::AnthropicProvider, openai::OpenAIProvider};
 
 #[test] fn l2_b001_default_is_mock() { assert!(matches!(ProviderConfig::default().provider, ProviderType::Mock)); println!("OK L2-B001"); }
 #[test] fn l2_b002_mock_valid() { assert!(ProviderConfig::mock().validate().is_ok()); println!("OK L2-B002"); }
@@ -14,4 +16,169 @@ use crate::ai::LLMProvider;
 #[test] fn l3_b001_fallback_mock() { let p = get_provider_with_fallback(&ProviderConfig { provider: ProviderType::OpenAI, api_key: None, ..Default::default() }); assert!(p.id().contains("mock")); println!("OK L3-B001"); }
 #[test] fn l3_b002_mock_works() { assert!(get_provider(&ProviderConfig::mock()).is_ok()); println!("OK L3-B002"); }
 #[test] fn l3_b003_display() { assert_eq!(format!("{}", ProviderType::Mock), "mock"); println!("OK L3-B003"); }
-#[test] fn z_report() { println!("SPRINT B: 12 tests OK"); }
+#[test] fn l2_b010_openai_sse_decode() { let mut p = SseParser::new(); let mut acc = StreamAccumulator::new(); let mut deltas = Vec::new(); for ev in p.push("data: {\"choices\":[{\"delta\":{\"role\":\"assistant\"}}]}\n\ndata: {\"choices\":[{\"delta\":{\"content\":\"Bon\"}}]}\n\ndata: {\"choices\":[{\"delta\":{\"content\":\"jour\"}}]}\n\ndata: {\"choices\":[],\"usage\":{\"prompt_tokens\":5,\"completion_tokens\":2}}\n\ndata: [DONE]\n\n") { for c in OpenAIProvider::decode_sse(&ev).unwrap() { acc.apply(c, &mut |d| deltas.push(d.text)); } } assert!(acc.is_done()); assert_eq!(deltas, vec!["Bon", "jour"]); let r = acc.finish("openai", "openai", &crate::ai::CompletionRequest { run_id: "t".into(), seed: 1, system_prompt: String::new(), user_prompt: String::new(), temperature: 0.0, max_tokens: 10, schema_name: None, json_schema: None, constraints: Default::default(), template: None, tool: None }, 0); assert_eq!(r.content, "Bonjour"); assert_eq!(r.usage.total_tokens, 7); println!("OK L2-B010"); }
+#[test] fn l2_b011_anthropic_sse_decode() { let mut p = SseParser::new(); let mut acc = StreamAccumulator::new(); let mut text = String::new(); for ev in p.push("event: message_start\ndata: {\"type\":\"message_start\",\"message\":{\"usage\":{\"input_tokens\":12}}}\n\nevent: ping\ndata: {\"type\":\"ping\"}\n\nevent: content_block_delta\ndata: {\"type\":\"content_block_delta\",\"delta\":{\"type\":\"text_delta\",\"text\":\"Salut\"}}\n\nevent: message_delta\ndata: {\"type\":\"message_delta\",\"usage\":{\"output_tokens\":3}}\n\nevent: message_stop\ndata: {\"type\":\"message_stop\"}\n\n") { for c in AnthropicProvider::decode_sse(&ev).unwrap() { acc.apply(c, &mut |d| text.push_str(&d.text)); } } assert!(acc.is_done()); assert_eq!(text, "Salut"); println!("OK L2-B011"); }
+#[test] fn l2_b012_sse_stream_errors() { let mut p = SseParser::new(); let evs = p.push("data: {\"error\":{\"message\":\"overloaded\"}}\n\nevent: error\ndata: {\"type\":\"error\",\"error\":{\"message\":\"overloaded\"}}\n\ndata: {oops\n\n"); assert!(OpenAIProvider::decode_sse(&evs[0]).is_err()); assert!(AnthropicProvider::decode_sse(&evs[1]).is_err()); assert!(OpenAIProvider::decode_sse(&evs[2]).is_err()); assert_eq!(AnthropicProvider::decode_sse(&crate::ai::stream::SseEvent { event: None, data: "{\"type\":\"content_block_stop\"}".into() }).unwrap(), vec![StreamChunk::Ignore]); println!("OK L2-B012"); }
+#[test] fn l2_b015_anthropic_tool_stream_keeps_text_apart() { let mut p = SseParser::new(); let mut acc = StreamAccumulator::new(); let mut deltas = Vec::new(); for ev in p.push("event: content_block_delta\ndata: {\"type\":\"content_block_delta\",\"delta\":{\"type\":\"text_delta\",\"text\":\"Voici: \"}}\n\nevent: content_block_delta\ndata: {\"type\":\"content_block_delta\",\"delta\":{\"type\":\"input_json_delta\",\"partial_json\":\"{\\\"subject\\\":\"}}\n\nevent: content_block_delta\ndata: {\"type\":\"content_block_delta\",\"delta\":{\"type\":\"input_json_delta\",\"partial_json\":\"\\\"Marie\\\"}\"}}\n\nevent: message_stop\ndata: {\"type\":\"message_stop\"}\n\n") { for c in AnthropicProvider::decode_sse(&ev).unwrap() { acc.apply(c, &mut |d| deltas.push(d.text)); } } assert_eq!(deltas.len(), 3); let req = crate::ai::CompletionRequest { tool: Some(crate::ai::ToolSpec { name: "canon_fact".into(), description: String::new() }), json_schema: Some(serde_json::json!({"type": "object"})), ..extract_req() }; let r = acc.finish("anthropic", "anthropic", &req, 0); assert_eq!(r.content, "{\"subject\":\"Marie\"}", "arguments seuls, comme parse()"); assert_eq!(r.parsed.unwrap()["subject"], "Marie"); println!("OK L2-B015"); }
+#[test] fn l2_b013_compatible_key_optional() { assert!(ProviderConfig::openai_compatible("http://localhost:11434/v1", "llama3", None).validate().is_ok()); assert!(ProviderConfig::openai_compatible("localhost:11434", "llama3", None).validate().is_err()); assert!(ProviderConfig::openai_compatible("http://localhost:8080", "", None).validate().is_err()); println!("OK L2-B013"); }
+#[test] fn l2_b014_compatible_url() { assert_eq!(super::openai::chat_completions_url("http://h:1/v1/"), "http://h:1/v1/chat/completions"); assert_eq!(super::openai::chat_completions_url("http://h:1/v1/chat/completions"), "http://h:1/v1/chat/completions"); println!("OK L2-B014"); }
+
+/// Serveur HTTP local à une requête: renvoie `body` en JSON et retourne la requête reçue
+fn stub_server(body: &'static str) -> (String, std::thread::JoinHandle<String>) { stub_server_with("200 OK", "", body) }
+
+/// Idem avec statut et en-têtes supplémentaires (ex: "429 Too Many Requests", "Retry-After: 120\r\n")
+fn stub_server_with(status: &'static str, headers: &'static str, body: &'static str) -> (String, std::thread::JoinHandle<String>) {
+    use std::io::{Read, Write};
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let base = format!("http://{}/v1", listener.local_addr().unwrap());
+    let handle = std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut raw = Vec::new();
+        let mut buf = [0u8; 4096];
+        loop {
+            let n = stream.read(&mut buf).unwrap();
+            raw.extend_from_slice(&buf[..n]);
+            let text = String::from_utf8_lossy(&raw).to_string();
+            if let Some(end) = text.find("\r\n\r\n") {
+                let len = text.lines().find_map(|l| l.to_lowercase().strip_prefix("content-length:").map(|v| v.trim().parse::<usize>().unwrap_or(0))).unwrap_or(0);
+                if raw.len() >= end + 4 + len || n == 0 { break; }
+            }
+        }
+        write!(stream, "HTTP/1.1 {}\r\nContent-Type: application/json\r\n{}Content-Length: {}\r\nConnection: close\r\n\r\n{}", status, headers, body.len(), body).unwrap();
+        String::from_utf8_lossy(&raw).to_string()
+    });
+    (base, handle)
+}
+
+#[test] fn l3_b004_openai_compatible_stub_server() {
+    let (base, server) = stub_server(r#"{"choices":[{"message":{"role":"assistant","content":"Bonjour local"}}],"usage":{"prompt_tokens":9,"completion_tokens":2}}"#);
+    let config = ProviderConfig { max_retries: 0, ..ProviderConfig::openai_compatible(&base, "llama3", None) };
+    let p = get_provider(&config).unwrap();
+    assert_eq!(p.id(), "openai-compatible");
+    let req = crate::ai::CompletionRequest { run_id: "t".into(), seed: 42, system_prompt: "S".into(), user_prompt: "U".into(), temperature: 0.0, max_tokens: 16, schema_name: Some("x".into()), json_schema: Some(serde_json::json!({})), constraints: Default::default(), template: None, tool: None };
+    let r = p.generate(req).unwrap();
+    assert_eq!(r.content, "Bonjour local");
+    assert_eq!(r.usage.total_tokens, 11);
+    assert_eq!(r.provider_id, "openai-compatible");
+    let request = server.join().unwrap();
+    assert!(request.starts_with("POST /v1/chat/completions"));
+    assert!(!request.to_lowercase().contains("authorization:"), "No key = no auth header");
+    assert!(request.contains("\"model\":\"llama3\""));
+    assert!(!request.contains("response_format"), "JSON mode off by default for local models");
+    println!("OK L3-B004");
+}
+#[test] fn l3_b005_compatible_capability_overrides() { let config = ProviderConfig { capabilities: super::config::CapabilityOverrides { max_context_window: Some(32768), supports_json_mode: Some(true), ..Default::default() }, ..ProviderConfig::openai_compatible("http://127.0.0.1:9/v1", "qwen", Some("k".into())) }; let c = get_provider(&config).unwrap().capabilities(); assert_eq!(c.max_context_window, 32768); assert!(c.supports_json_mode); assert_eq!(c.id, "openai-compatible:qwen"); println!("OK L3-B005"); }
+#[test] fn l3_b006_retry_after_too_long_is_rate_limit() { let (base, server) = stub_server_with("429 Too Many Requests", "Retry-After: 120\r\n", "{}"); let p = get_provider(&ProviderConfig { max_retries: 3, ..ProviderConfig::openai_compatible(&base, "llama3", None) }).unwrap(); let req = crate::ai::CompletionRequest { run_id: "t".into(), seed: 1, system_prompt: "S".into(), user_prompt: "U".into(), temperature: 0.0, max_tokens: 8, schema_name: None, json_schema: None, constraints: Default::default(), template: None, tool: None }; let start = std::time::Instant::now(); let err = p.generate(req).unwrap_err(); assert!(matches!(&err, crate::error::OmegaError::RateLimit(m) if m.contains("retry_after=120s")), "{}", err); assert!(start.elapsed() < std::time::Duration::from_secs(5), "no sleep beyond MAX_RETRY_AFTER"); server.join().unwrap(); println!("OK L3-B006"); }
+#[test] fn l3_b007_health_probe() { let (base, server) = stub_server(r#"{"data":[]}"#); let p = get_provider(&ProviderConfig { health_probe: true, ..ProviderConfig::openai_compatible(&base, "llama3", None) }).unwrap(); assert!(p.health()); assert!(server.join().unwrap().starts_with("GET /v1/models")); let dead = get_provider(&ProviderConfig { health_probe: true, ..ProviderConfig::openai_compatible("http://127.0.0.1:9/v1", "llama3", None) }).unwrap(); assert!(!dead.health()); assert!(get_provider(&ProviderConfig::openai_compatible("http://127.0.0.1:9/v1", "llama3", None)).unwrap().health(), "probe off by default"); println!("OK L3-B007"); }
+#[test] fn l3_b008_compatible_embeddings_batch() { let (base, server) = stub_server(r#"{"data":[{"index":1,"embedding":[0.0,1.0]},{"index":0,"embedding":[1.0,0.0]}]}"#); let config = ProviderConfig { max_retries: 0, embedding_model: Some("nomic-embed-text".into()), ..ProviderConfig::openai_compatible(&base, "llama3", None) }; let p = get_provider(&config).unwrap(); assert!(p.capabilities().supports_embeddings); let reqs = ["un", "deux"].iter().map(|s| crate::ai::EmbeddingRequest { run_id: "t".into(), seed: 0, input: s.to_string() }).collect(); let out = p.embed_batch(reqs).unwrap(); assert_eq!(out[0].vectors, vec![1.0, 0.0]); assert_eq!(out[1].vectors, vec![0.0, 1.0]); assert_eq!(out[0].model.as_deref(), Some("nomic-embed-text")); let request = server.join().unwrap(); assert!(request.starts_with("POST /v1/embeddings")); assert!(request.contains("\"input\":[\"un\",\"deux\"]")); let no_model = get_provider(&ProviderConfig::openai_compatible("http://127.0.0.1:9/v1", "llama3", None)).unwrap(); assert!(!no_model.capabilities().supports_embeddings); assert!(matches!(no_model.embed(crate::ai::EmbeddingRequest { run_id: "t".into(), seed: 0, input: "x".into() }), Err(crate::error::OmegaError::NotSupported(_)))); println!("OK L3-B008"); }
+
+#[derive(Debug, serde::Deserialize)]
+struct CanonFact { subject: String, fact: String }
+
+impl crate::ai::Extraction for CanonFact {
+    const NAME: &'static str = "canon_fact";
+    const DESCRIPTION: &'static str = "Extrait un fait CANON du passage.";
+    fn json_schema() -> serde_json::Value { serde_json::json!({"type": "object", "required": ["subject", "fact"], "properties": {"subject": {"type": "string"}, "fact": {"type": "string"}}}) }
+}
+
+fn extract_req() -> crate::ai::CompletionRequest { crate::ai::CompletionRequest { run_id: "t".into(), seed: 1, system_prompt: "S".into(), user_prompt: "Marie est née à Lyon.".into(), temperature: 0.5, max_tokens: 64, schema_name: None, json_schema: None, constraints: Default::default(), template: None, tool: None } }
+
+#[test] fn l3_b009_openai_function_calling() {
+    let (base, server) = stub_server(r#"{"choices":[{"message":{"role":"assistant","content":null,"tool_calls":[{"id":"c1","type":"function","function":{"name":"canon_fact","arguments":"{\"subject\":\"Marie\",\"fact\":\"née à Lyon\"}"}}]}}],"usage":{"prompt_tokens":30,"completion_tokens":12}}"#);
+    let config = ProviderConfig { max_retries: 0, capabilities: super::config::CapabilityOverrides { supports_tool_calling: Some(true), ..Default::default() }, ..ProviderConfig::openai_compatible(&base, "qwen", None) };
+    let fact = crate::ai::extract::<CanonFact>(get_provider(&config).unwrap().as_ref(), extract_req(), 0).unwrap();
+    assert_eq!(fact.mode, crate::ai::ExtractionMode::ToolCall);
+    assert_eq!((fact.value.subject.as_str(), fact.value.fact.as_str()), ("Marie", "née à Lyon"));
+    let request = server.join().unwrap();
+    assert!(request.contains("\"tool_choice\":{\"function\":{\"name\":\"canon_fact\"},\"type\":\"function\"}"), "{}", request);
+    assert!(request.contains("\"parameters\":{") && !request.contains("response_format"));
+    println!("OK L3-B009");
+}
+#[test] fn l3_b010_anthropic_tool_use() {
+    let (base, server) = stub_server(r#"{"content":[{"type":"text","text":"Voici."},{"type":"tool_use","id":"t1","name":"canon_fact","input":{"subject":"Marie","fact":"née à Lyon"}}],"usage":{"input_tokens":40,"output_tokens":15}}"#);
+    let config = ProviderConfig { max_retries: 0, endpoint: Some(format!("{}/messages", base)), ..ProviderConfig::anthropic("k".into()) };
+    let fact = crate::ai::extract::<CanonFact>(get_provider(&config).unwrap().as_ref(), extract_req(), 0).unwrap();
+    assert_eq!(fact.value.fact, "née à Lyon");
+    assert_eq!(fact.usage.total_tokens, 55);
+    let request = server.join().unwrap();
+    assert!(request.contains("\"tool_choice\":{\"name\":\"canon_fact\",\"type\":\"tool\"}") && request.contains("\"input_schema\":{"), "{}", request);
+    assert!(request.contains("\"temperature\":0.5"), "temperature is sent");
+    println!("OK L3-B010");
+}
+/// Serveur qui accepte la connexion TCP mais ne répond jamais (provider bloqué)
+fn hung_server() -> (String, std::net::TcpListener) { let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap(); (format!("http://{}/v1", listener.local_addr().unwrap()), listener) }
+
+#[test] fn l3_b011_cancel_in_flight_request() {
+    use crate::ai::CallControl;
+    let (base, _listener) = hung_server();
+    let p = super::openai::OpenAIProvider::try_new_compatible(&ProviderConfig { max_retries: 0, timeout_ms: 30000, ..ProviderConfig::openai_compatible(&base, "llama3", None) }).unwrap();
+    let req = crate::ai::CompletionRequest { run_id: "t".into(), seed: 1, system_prompt: "S".into(), user_prompt: "U".into(), temperature: 0.0, max_tokens: 8, schema_name: None, json_schema: None, constraints: Default::default(), template: None, tool: None };
+    let ctl = CallControl::new();
+    let cancel = ctl.cancel.clone();
+    std::thread::spawn(move || { std::thread::sleep(std::time::Duration::from_millis(100)); cancel.cancel(); });
+    let start = std::time::Instant::now();
+    assert!(matches!(ctl.scope(|| p.generate(req.clone())), Err(crate::error::OmegaError::Cancelled(m)) if m == "CALL_CANCELLED"));
+    assert!(start.elapsed() < std::time::Duration::from_secs(5), "request abandoned, not awaited until timeout");
+    let ctl = CallControl::new().with_timeout(std::time::Duration::from_millis(100));
+    assert!(matches!(crate::ai::async_provider::block_on(crate::ai::AsyncLLMProvider::generate_async(&p, req, &ctl)), Err(crate::error::OmegaError::Cancelled(m)) if m == "DEADLINE_EXCEEDED"));
+    println!("OK L3-B011");
+}
+#[test] fn l3_b012_call_timeout_falls_back() {
+    use crate::ai::{CallControl, FallbackProvider};
+    let (base, _listener) = hung_server();
+    let hung = get_provider(&ProviderConfig { max_retries: 0, timeout_ms: 30000, ..ProviderConfig::openai_compatible(&base, "llama3", None) }).unwrap();
+    let fallback = FallbackProvider::new(Some(hung), None);
+    let req = crate::ai::CompletionRequest { run_id: "t".into(), seed: 1, system_prompt: "S".into(), user_prompt: "U".into(), temperature: 0.0, max_tokens: 8, schema_name: None, json_schema: None, constraints: Default::default(), template: None, tool: None };
+    let r = CallControl::new().with_call_timeout(std::time::Duration::from_millis(100)).scope(|| fallback.generate(req)).unwrap();
+    let trace = r.fallback.unwrap();
+    assert!(trace.fallback_used);
+    assert!(trace.attempts[0].error.as_deref().is_some_and(|e| e.contains("CALL_TIMEOUT: 100ms")), "{:?}", trace.attempts);
+    println!("OK L3-B012");
+}
+
+/// Serveur qui envoie les en-têtes et un premier événement SSE, puis se tait (stream bloqué en cours de lecture)
+fn stalled_stream_server() -> String {
+    use std::io::{Read, Write};
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let base = format!("http://{}/v1", listener.local_addr().unwrap());
+    std::thread::spawn(move || {
+        for stream in listener.incoming().take(2) {
+            let mut stream = stream.unwrap();
+            let _ = stream.read(&mut [0u8; 4096]);
+            let _ = stream.write_all(b"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\n\r\ndata: {\"choices\":[{\"delta\":{\"content\":\"Il \"}}]}\n\n");
+            std::thread::spawn(move || { std::thread::sleep(std::time::Duration::from_secs(30)); drop(stream); });
+        }
+    });
+    base
+}
+
+#[test] fn l3_b013_stream_honours_call_control() {
+    use crate::ai::{CallControl, CancelToken};
+    let p = get_provider(&ProviderConfig { max_retries: 0, timeout_ms: 30000, ..ProviderConfig::openai_compatible(&stalled_stream_server(), "llama3", None) }).unwrap();
+    let req = crate::ai::CompletionRequest { run_id: "t".into(), seed: 1, system_prompt: "S".into(), user_prompt: "U".into(), temperature: 0.0, max_tokens: 8, schema_name: None, json_schema: None, constraints: Default::default(), template: None, tool: None };
+    let start = std::time::Instant::now();
+    let mut text = String::new();
+    let r = CallControl::new().with_timeout(std::time::Duration::from_millis(200)).scope(|| p.generate_stream(req.clone(), &mut |d| text.push_str(&d.text), &CancelToken::new()));
+    assert!(matches!(r, Err(crate::error::OmegaError::Cancelled(ref m)) if m == "DEADLINE_EXCEEDED"), "{:?}", r.map(|r| r.content));
+    assert_eq!(text, "Il ", "delta reçu avant l'échéance");
+    let cancel = CancelToken::new();
+    let stop = cancel.clone();
+    std::thread::spawn(move || { std::thread::sleep(std::time::Duration::from_millis(100)); stop.cancel(); });
+    assert!(matches!(p.generate_stream(req, &mut |_| {}, &cancel), Err(crate::error::OmegaError::Cancelled(_))));
+    assert!(start.elapsed() < std::time::Duration::from_secs(5), "stream abandoned, not awaited until timeout");
+    println!("OK L3-B013");
+}
+#[test] fn l3_b014_health_probe_inside_runtime() {
+    let (base, server) = stub_server(r#"{"data":[]}"#);
+    let p = get_provider(&ProviderConfig { health_probe: true, ..ProviderConfig::openai_compatible(&base, "llama3", None) }).unwrap();
+    let rt = tokio::runtime::Builder::new_multi_thread().worker_threads(1).enable_all().build().unwrap();
+    assert!(rt.block_on(async { tokio::spawn(async move { p.health() }).await.unwrap() }), "sonde depuis un worker tokio sans panique");
+    assert!(server.join().unwrap().starts_with("GET /v1/models"));
+    let (base, server) = stub_server(r#"{"data":[]}"#);
+    let a = get_provider(&ProviderConfig { health_probe: true, endpoint: Some(format!("{}/messages", base)), ..ProviderConfig::anthropic("k".into()) }).unwrap();
+    assert!(rt.block_on(async { tokio::spawn(async move { a.health() }).await.unwrap() }));
+    assert!(server.join().unwrap().starts_with("GET /v1/models"));
+    println!("OK L3-B014");
+}
+
+#[test] fn z_report() { println!("SPRINT B: 30 tests OK"); }
@@ -0,0 +1,789 @@
+//! OMEGA Redaction — pseudonymisation des manuscrits avant tout appel provider
+//! NASA-Grade: les noms de la Bible et du CANON, les e-mails, les téléphones et les motifs du projet sont remplacés
+//! par des marqueurs stables ([CHAR_1]) avant envoi; la réponse est ré-identifiée et la table de correspondance
+//! est conservée chiffrée avec le run. Un projet peut interdire tout provider distant.
+//!
+//! ```toml
+//! [privacy.default]
+//! redact = true
+//!
+//! [privacy.manuscrit-nda]
+//! redact = true
+//! local_only = true
+//! canon = "canon_snapshot.json"
+//! patterns = [{ label = "DOSSIER", regex = "DOS-\\d{4}" }]
+//! ```
+//!
+//! Surcharges (ne peuvent que durcir la politique): OMEGA_PRIVACY_REDACT=1, OMEGA_PRIVACY_LOCAL_ONLY=1.
+//! Clé de la table: OMEGA_REDACTION_KEY (phrase de passe), sinon fichier de clé généré à la racine des runs.
+
+use crate::ai::interface::LLMProvider;
+use crate::ai::models::*;
+use crate::ai::providers::profiles::ProviderProfiles;
+use crate::ai::stream::{CancelToken, StreamDelta};
+use crate::error::{OmegaError, OmegaResult};
+use crate::interfaces::canon::CanonSnapshot;
+use crate::lexicon_fr_gold::{normalize_fr, UserOverrides};
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::env;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, Weak};
+
+/// Clé des `constraints` d'une requête pseudonymisée
+pub const REDACTION_CONSTRAINT: &str = "redaction";
+
+/// Table chiffrée écrite dans le répertoire du run
+pub const REDACTION_MAP_FILE: &str = "redaction.map.enc";
+
+/// Fichier de clé généré si OMEGA_REDACTION_KEY n'est pas défini
+pub const REDACTION_KEY_FILE: &str = ".redaction.key";
+
+/// Bible utilisateur lue par défaut
+pub const DEFAULT_BIBLE_FILE: &str = "user_overrides.json";
+
+/// Étiquettes réservées aux détecteurs intégrés
+const RESERVED_LABELS: [&str; 5] = ["CHAR", "PLACE", "NAME", "EMAIL", "PHONE"];
+
+/// Longueur max d'un marqueur retenu en fin de delta (streaming)
+const MAX_PLACEHOLDER_LEN: usize = 32;
+
+const SEAL_FORMAT: &str = "OMEGA_REDACTION_MAP_V2";
+const SEAL_CIPHER: &str = "xchacha20poly1305+pbkdf2-hmac-sha256";
+/// Itérations PBKDF2-HMAC-SHA256 (recommandation OWASP); réduites en test, l'enveloppe porte sa valeur
+const KDF_ROUNDS: u32 = if cfg!(test) { 1_000 } else { 600_000 };
+
+fn email_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}").unwrap())
+}
+
+/// Numéros français (0X XX XX XX XX, +33 X ...) et internationaux (+CC ...)
+fn phone_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"(?:(?:\+33\s?|\b0)[1-9](?:[\s.-]?\d{2}){4}|\+\d{1,3}(?:[\s.-]?\d{2,4}){2,5})\b").unwrap())
+}
+
+fn placeholder_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"\[([A-Z][A-Z0-9]*(?:_[A-Z0-9]+)*_\d+)\]").unwrap())
+}
+
+fn word_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"[\p{L}\p{M}]+").unwrap())
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// POLITIQUE
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Motif propre au projet: chaque correspondance devient [LABEL_n]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CustomPattern {
+    pub label: String,
+    pub regex: String,
+}
+
+/// Politique de confidentialité d'un projet (`[privacy.<projet>]`, sinon `[privacy.default]`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PrivacyPolicy {
+    /// Pseudonymise prompts et entrées d'embedding avant envoi
+    #[serde(default)]
+    pub redact: bool,
+    /// Aucun provider distant: seuls les maillons locaux (mock, serveur sur la machine) sont appelés
+    #[serde(default)]
+    pub local_only: bool,
+    #[serde(default = "enabled")]
+    pub emails: bool,
+    #[serde(default = "enabled")]
+    pub phones: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub patterns: Vec<CustomPattern>,
+    /// Bible utilisateur (défaut: user_overrides.json)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bible: Option<PathBuf>,
+    /// Snapshot CANON: entités CHAR:<Nom> et PLACE:<Nom>
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub canon: Option<PathBuf>,
+}
+
+fn enabled() -> bool { true }
+
+impl Default for PrivacyPolicy {
+    fn default() -> Self {
+        Self { redact: false, local_only: false, emails: true, phones: true, patterns: Vec::new(), bible: None, canon: None }
+    }
+}
+
+impl PrivacyPolicy {
+    /// Politique du projet, sinon `default`, sinon aucune restriction; surcharges d'environnement appliquées
+    pub fn resolve(profiles: Option<&ProviderProfiles>, project: &str, var: &dyn Fn(&str) -> Option<String>) -> Self {
+        let mut policy = profiles
+            .and_then(|p| p.privacy.get(project).or_else(|| p.privacy.get("default")))
+            .cloned()
+            .unwrap_or_default();
+        let on = |k: &str| var(k).is_some_and(|v| v == "1" || v.eq_ignore_ascii_case("true"));
+        policy.redact |= on("OMEGA_PRIVACY_REDACT");
+        policy.local_only |= on("OMEGA_PRIVACY_LOCAL_ONLY");
+        policy
+    }
+
+    /// omega.toml (OMEGA_CONFIG) + surcharges d'environnement
+    pub fn from_env(project: &str) -> OmegaResult<Self> {
+        let profiles = ProviderProfiles::from_env()?;
+        Ok(Self::resolve(profiles.as_ref(), project, &|k| env::var(k).ok()))
+    }
+
+    /// Étiquettes [A-Z0-9_] non réservées, expressions compilables
+    pub fn validate(&self, name: &str) -> Result<(), String> {
+        for p in &self.patterns {
+            if p.label.is_empty() || !p.label.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_') {
+                return Err(format!("privacy '{}': label '{}' (use [A-Z0-9_])", name, p.label));
+            }
+            if RESERVED_LABELS.contains(&p.label.as_str()) {
+                return Err(format!("privacy '{}': label '{}' is reserved", name, p.label));
+            }
+            Regex::new(&p.regex).map_err(|e| format!("privacy '{}': pattern {}: {}", name, p.label, e))?;
+        }
+        Ok(())
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// PSEUDONYMISATION
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum NameKind {
+    Char,
+    Place,
+    Name,
+}
+
+impl NameKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            NameKind::Char => "CHAR",
+            NameKind::Place => "PLACE",
+            NameKind::Name => "NAME",
+        }
+    }
+
+    /// Type d'un nom propre de la Bible (`type` de user_overrides.json)
+    pub fn from_bible(type_: &str) -> Self {
+        match type_.to_lowercase().as_str() {
+            "character" | "personnage" => NameKind::Char,
+            "place" | "lieu" | "location" => NameKind::Place,
+            _ => NameKind::Name,
+        }
+    }
+
+    /// Entité CANON CHAR:<Nom> / PLACE:<Nom> (LIEU: accepté)
+    pub fn from_entity(entity_id: &str) -> Option<(Self, &str)> {
+        let (prefix, name) = entity_id.split_once(':')?;
+        let kind = match prefix {
+            "CHAR" => NameKind::Char,
+            "PLACE" | "LIEU" => NameKind::Place,
+            _ => return None,
+        };
+        Some((kind, name))
+    }
+}
+
+/// Détecteurs d'un projet. Les noms connus sont numérotés par ordre alphabétique dans leur catégorie:
+/// un nom garde le même marqueur d'un appel et d'un run à l'autre (cache et index sémantique restent cohérents)
+#[derive(Debug, Clone, Default)]
+pub struct Redactor {
+    /// Nom normalisé → marqueur (sans crochets)
+    names: HashMap<String, String>,
+    /// Longueur max d'un nom connu, en mots
+    max_words: usize,
+    /// (étiquette, motif), dans l'ordre de priorité
+    patterns: Vec<(String, Regex)>,
+}
+
+impl Redactor {
+    pub fn new(policy: &PrivacyPolicy, names: impl IntoIterator<Item = (NameKind, String)>) -> OmegaResult<Self> {
+        policy.validate("policy").map_err(|e| OmegaError::ConfigError(format!("PRIVACY_INVALID: {}", e)))?;
+        let known: BTreeSet<(NameKind, String)> = names.into_iter()
+            .map(|(kind, name)| (kind, normalize_fr(&name)))
+            .filter(|(_, n)| n.chars().count() >= 2)
+            .collect();
+        let mut counters: BTreeMap<NameKind, usize> = BTreeMap::new();
+        let mut redactor = Self::default();
+        for (kind, name) in known {
+            if redactor.names.contains_key(&name) { continue; }
+            let n = counters.entry(kind).or_default();
+            *n += 1;
+            redactor.max_words = redactor.max_words.max(name.split(' ').count());
+            redactor.names.insert(name, format!("{}_{}", kind.label(), n));
+        }
+        if policy.emails { redactor.patterns.push(("EMAIL".into(), email_re().clone())); }
+        if policy.phones { redactor.patterns.push(("PHONE".into(), phone_re().clone())); }
+        for p in &policy.patterns {
+            redactor.patterns.push((p.label.clone(), Regex::new(&p.regex).expect("validated")));
+        }
+        Ok(redactor)
+    }
+
+    /// Noms de la Bible (défaut: user_overrides.json, absente = vide) et du snapshot CANON (explicite: doit être lisible)
+    pub fn from_policy(policy: &PrivacyPolicy) -> OmegaResult<Self> {
+        let bible = UserOverrides::load(policy.bible.as_deref().unwrap_or(Path::new(DEFAULT_BIBLE_FILE)));
+        let mut names: Vec<(NameKind, String)> = bible.proper_nouns.iter().map(|r| (NameKind::from_bible(&r.type_), r.token.clone())).collect();
+        if let Some(path) = &policy.canon {
+            let content = std::fs::read_to_string(path).map_err(|e| OmegaError::ReadError(format!("PRIVACY_CANON: {}: {}", path.display(), e)))?;
+            let snapshot: CanonSnapshot = serde_json::from_str(&content)?;
+            names.extend(snapshot.facts.iter().filter_map(|f| NameKind::from_entity(&f.entity_id)).map(|(k, n)| (k, n.to_string())));
+        }
+        Self::new(policy, names)
+    }
+
+    pub fn known_names(&self) -> usize { self.names.len() }
+
+    /// Texte pseudonymisé; les correspondances sont ajoutées à `map`. Retourne aussi le nombre de substitutions
+    pub fn redact(&self, text: &str, map: &mut RedactionMap) -> (String, usize) {
+        let mut spans: Vec<(usize, usize, String)> = Vec::new();
+        for (label, re) in &self.patterns {
+            for m in re.find_iter(text) {
+                if spans.iter().any(|(s, e, _)| m.start() < *e && *s < m.end()) { continue; }
+                let placeholder = map.placeholder_for(label, m.as_str());
+                spans.push((m.start(), m.end(), placeholder));
+            }
+        }
+        for (start, end, placeholder) in self.find_names(text) {
+            if spans.iter().any(|(s, e, _)| start < *e && *s < end) { continue; }
+            map.entries.entry(placeholder.clone()).or_insert_with(|| text[start..end].to_string());
+            spans.push((start, end, placeholder));
+        }
+        spans.sort_by_key(|(s, _, _)| *s);
+        let mut out = String::with_capacity(text.len());
+        let mut last = 0;
+        for (start, end, placeholder) in &spans {
+            out.push_str(&text[last..*start]);
+            out.push('[');
+            out.push_str(placeholder);
+            out.push(']');
+            last = *end;
+        }
+        out.push_str(&text[last..]);
+        (out, spans.len())
+    }
+
+    /// Noms connus commençant par une majuscule (le nom commun homonyme, « rose », n'est pas touché);
+    /// la plus longue séquence de mots l'emporte (Jean-Pierre avant Jean)
+    fn find_names(&self, text: &str) -> Vec<(usize, usize, String)> {
+        if self.names.is_empty() { return Vec::new(); }
+        let words: Vec<(usize, usize)> = word_re().find_iter(text).map(|m| (m.start(), m.end())).collect();
+        let mut found = Vec::new();
+        let mut i = 0;
+        while i < words.len() {
+            let (start, _) = words[i];
+            if !text[start..].chars().next().is_some_and(char::is_uppercase) { i += 1; continue; }
+            let longest = (1..=self.max_words.min(words.len() - i)).rev().find_map(|n| {
+                let end = words[i + n - 1].1;
+                let joined = words[i..i + n].windows(2).all(|w| is_name_separator(&text[w[0].1..w[1].0]));
+                let placeholder = self.names.get(&normalize_fr(&text[start..end])).filter(|_| joined)?;
+                Some((n, end, placeholder.clone()))
+            });
+            match longest {
+                Some((n, end, placeholder)) => { found.push((start, end, placeholder)); i += n; }
+                None => i += 1,
+            }
+        }
+        found
+    }
+}
+
+fn is_name_separator(s: &str) -> bool {
+    !s.is_empty() && s.chars().count() <= 2 && s.chars().all(|c| c == ' ' || c == '-' || c == '\'' || c == '’')
+}
+
+/// Table marqueur → texte d'origine d'un run
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RedactionMap {
+    pub entries: BTreeMap<String, String>,
+}
+
+impl RedactionMap {
+    pub fn len(&self) -> usize { self.entries.len() }
+    pub fn is_empty(&self) -> bool { self.entries.is_empty() }
+
+    /// Marqueur d'une valeur détectée par motif: réutilisé si déjà vu, sinon LABEL_<n+1>
+    fn placeholder_for(&mut self, label: &str, value: &str) -> String {
+        let prefix = format!("{}_", label);
+        let same_label = self.entries.iter().filter(|(k, _)| k.strip_prefix(&prefix).is_some_and(|n| n.chars().all(|c| c.is_ascii_digit())));
+        let mut count = 0;
+        for (key, original) in same_label {
+            if original == value { return key.clone(); }
+            count += 1;
+        }
+        let placeholder = format!("{}{}", prefix, count + 1);
+        self.entries.insert(placeholder.clone(), value.to_string());
+        placeholder
+    }
+
+    /// Remplace les marqueurs connus par le texte d'origine (les inconnus sont laissés tels quels)
+    pub fn restore(&self, text: &str) -> String {
+        placeholder_re().replace_all(text, |c: &regex::Captures| {
+            self.entries.get(&c[1]).cloned().unwrap_or_else(|| c[0].to_string())
+        }).into_owned()
+    }
+
+    /// Chaînes d'une valeur JSON (réponse structurée)
+    pub fn restore_json(&self, value: &mut serde_json::Value) {
+        match value {
+            serde_json::Value::String(s) => *s = self.restore(s),
+            serde_json::Value::Array(items) => items.iter_mut().for_each(|v| self.restore_json(v)),
+            serde_json::Value::Object(fields) => fields.values_mut().for_each(|v| self.restore_json(v)),
+            _ => {}
+        }
+    }
+
+    /// Enveloppe chiffrée: XChaCha20-Poly1305, clé dérivée par PBKDF2; l'en-tête est authentifié avec le contenu
+    pub fn seal(&self, key: &RedactionKey) -> OmegaResult<Vec<u8>> {
+        let salt: [u8; 16] = random_bytes()?;
+        let nonce: [u8; 24] = random_bytes()?;
+        let cipher = key.cipher(&salt, KDF_ROUNDS);
+        let plaintext = serde_json::to_vec(&self.entries)?;
+        let aad = sealed_header(SEAL_FORMAT, SEAL_CIPHER, KDF_ROUNDS);
+        let ciphertext = cipher.encrypt(XNonce::from_slice(&nonce), Payload { msg: &plaintext, aad: aad.as_bytes() })
+            .map_err(|_| OmegaError::WriteError("REDACTION_MAP_SEAL: encryption failed".into()))?;
+        let sealed = SealedMap {
+            format: SEAL_FORMAT.into(),
+            cipher: SEAL_CIPHER.into(),
+            kdf_rounds: KDF_ROUNDS,
+            salt: hex::encode(salt),
+            nonce: hex::encode(nonce),
+            ciphertext: hex::encode(ciphertext),
+        };
+        Ok(serde_json::to_vec_pretty(&sealed)?)
+    }
+
+    /// Déchiffre une enveloppe; clé incorrecte ou contenu altéré (en-tête compris) → HashMismatch
+    pub fn open(bytes: &[u8], key: &RedactionKey) -> OmegaResult<Self> {
+        let sealed: SealedMap = serde_json::from_slice(bytes)?;
+        if sealed.format != SEAL_FORMAT || sealed.cipher != SEAL_CIPHER {
+            return Err(OmegaError::ConfigError(format!("REDACTION_MAP_FORMAT: {} / {}", sealed.format, sealed.cipher)));
+        }
+        let decode = |field: &str, v: &str| hex::decode(v).map_err(|e| OmegaError::JsonError(format!("REDACTION_MAP_{}: {}", field, e)));
+        let (salt, nonce, ciphertext) = (decode("SALT", &sealed.salt)?, decode("NONCE", &sealed.nonce)?, decode("CIPHERTEXT", &sealed.ciphertext)?);
+        if nonce.len() != 24 {
+            return Err(OmegaError::JsonError(format!("REDACTION_MAP_NONCE: {} bytes (expected 24)", nonce.len())));
+        }
+        let cipher = key.cipher(&salt, sealed.kdf_rounds);
+        let aad = sealed_header(&sealed.format, &sealed.cipher, sealed.kdf_rounds);
+        let plaintext = cipher.decrypt(XNonce::from_slice(&nonce), Payload { msg: &ciphertext, aad: aad.as_bytes() })
+            .map_err(|_| OmegaError::HashMismatch("REDACTION_MAP_AEAD: wrong key or altered file".into()))?;
+        Ok(Self { entries: serde_json::from_slice(&plaintext)? })
+    }
+
+    /// Ajoute les correspondances absentes de `other` (les marqueurs déjà connus sont conservés)
+    pub fn merge(&mut self, other: RedactionMap) {
+        for (placeholder, original) in other.entries {
+            self.entries.entry(placeholder).or_insert(original);
+        }
+    }
+
+    /// Écrit `redaction.map.enc` dans le run, fusionné avec la table déjà présente (rien si aucune substitution)
+    pub fn save_sealed(&self, run_dir: &Path, key: &RedactionKey) -> OmegaResult<Option<PathBuf>> {
+        if self.is_empty() { return Ok(None); }
+        let path = run_dir.join(REDACTION_MAP_FILE);
+        let mut merged = self.clone();
+        if path.exists() { merged.merge(Self::load_sealed(run_dir, key)?); }
+        std::fs::write(&path, merged.seal(key)?).map_err(|e| OmegaError::WriteError(format!("{}: {}", path.display(), e)))?;
+        Ok(Some(path))
+    }
+
+    pub fn load_sealed(run_dir: &Path, key: &RedactionKey) -> OmegaResult<Self> {
+        let path = run_dir.join(REDACTION_MAP_FILE);
+        let bytes = std::fs::read(&path).map_err(|e| OmegaError::ReadError(format!("{}: {}", path.display(), e)))?;
+        Self::open(&bytes, key)
+    }
+
+    /// Table partagée par tous les providers d'un même run (analyse, index sémantique, recherche, stream),
+    /// initialisée depuis `redaction.map.enc`: les marqueurs restent stables d'un appel et d'une session à l'autre
+    pub fn shared(run_dir: &Path, key: &RedactionKey) -> OmegaResult<Arc<Mutex<Self>>> {
+        static RUN_MAPS: OnceLock<Mutex<HashMap<PathBuf, Weak<Mutex<RedactionMap>>>>> = OnceLock::new();
+        let mut maps = RUN_MAPS.get_or_init(Default::default).lock().unwrap();
+        if let Some(map) = maps.get(run_dir).and_then(Weak::upgrade) {
+            return Ok(map);
+        }
+        let initial = if run_dir.join(REDACTION_MAP_FILE).exists() { Self::load_sealed(run_dir, key)? } else { Self::default() };
+        let map = Arc::new(Mutex::new(initial));
+        maps.retain(|_, m| m.strong_count() > 0);
+        maps.insert(run_dir.to_path_buf(), Arc::downgrade(&map));
+        Ok(map)
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// CHIFFREMENT DE LA TABLE
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SealedMap {
+    format: String,
+    cipher: String,
+    kdf_rounds: u32,
+    salt: String,
+    nonce: String,
+    /// Chiffré suivi du tag Poly1305
+    ciphertext: String,
+}
+
+/// Données associées: un en-tête modifié (format, nombre d'itérations) fait échouer le déchiffrement
+fn sealed_header(format: &str, cipher: &str, kdf_rounds: u32) -> String {
+    format!("{}|{}|{}", format, cipher, kdf_rounds)
+}
+
+/// Secret de chiffrement des tables (phrase de passe ou contenu du fichier de clé)
+#[derive(Clone)]
+pub struct RedactionKey(Vec<u8>);
+
+impl std::fmt::Debug for RedactionKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { f.write_str("RedactionKey(***)") }
+}
+
+impl RedactionKey {
+    pub fn from_passphrase(passphrase: &str) -> Self { Self(passphrase.as_bytes().to_vec()) }
+
+    /// Clé de 256 bits dérivée du secret par PBKDF2-HMAC-SHA256
+    fn cipher(&self, salt: &[u8], rounds: u32) -> XChaCha20Poly1305 {
+        let mut derived = [0u8; 32];
+        pbkdf2::pbkdf2_hmac::<Sha256>(&self.0, salt, rounds, &mut derived);
+        XChaCha20Poly1305::new(&derived.into())
+    }
+
+    /// OMEGA_REDACTION_KEY, sinon `<root>/.redaction.key` (créé au premier usage, lisible par le seul propriétaire)
+    pub fn load(root: &Path) -> OmegaResult<Self> {
+        if let Some(passphrase) = env::var("OMEGA_REDACTION_KEY").ok().filter(|k| !k.is_empty()) {
+            return Ok(Self::from_passphrase(&passphrase));
+        }
+        let path = root.join(REDACTION_KEY_FILE);
+        if path.exists() {
+            let key = std::fs::read_to_string(&path).map_err(|e| OmegaError::ReadError(format!("{}: {}", path.display(), e)))?;
+            return Ok(Self(key.trim().as_bytes().to_vec()));
+        }
+        std::fs::create_dir_all(root).map_err(|e| OmegaError::WriteError(format!("{}: {}", root.display(), e)))?;
+        let key = hex::encode(random_bytes::<32>()?);
+        std::fs::write(&path, &key).map_err(|e| OmegaError::WriteError(format!("{}: {}", path.display(), e)))?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))
+                .map_err(|e| OmegaError::WriteError(format!("{}: {}", path.display(), e)))?;
+        }
+        Ok(Self(key.into_bytes()))
+    }
+}
+
+/// Octets aléatoires du système (getrandom)
+fn random_bytes<const N: usize>() -> OmegaResult<[u8; N]> {
+    let mut bytes = [0u8; N];
+    getrandom::getrandom(&mut bytes).map_err(|e| OmegaError::WriteError(format!("REDACTION_RANDOM: {}", e)))?;
+    Ok(bytes)
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// PROVIDER
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Décorateur le plus externe: politique appliquée avant cache, ledger et fallback (rien de nominatif n'est
+/// mis en cache ni envoyé). La requête porte `constraints.redaction` quand la pseudonymisation est appliquée
+pub struct RedactingProvider {
+    inner: Arc<dyn LLMProvider>,
+    policy: PrivacyPolicy,
+    project: String,
+    redactor: Redactor,
+    map: Arc<Mutex<RedactionMap>>,
+    substitutions: AtomicUsize,
+}
+
+impl RedactingProvider {
+    pub fn new(inner: Arc<dyn LLMProvider>, policy: PrivacyPolicy, project: &str, redactor: Redactor) -> Self {
+        Self { inner, policy, project: project.to_string(), redactor, map: Arc::new(Mutex::new(RedactionMap::default())), substitutions: AtomicUsize::new(0) }
+    }
+
+    /// Table commune à plusieurs providers (voir `RedactionMap::shared`)
+    pub fn with_map(mut self, map: Arc<Mutex<RedactionMap>>) -> Self {
+        self.map = map;
+        self
+    }
+
+    /// Détecteurs construits depuis la Bible et le CANON de la politique
+    pub fn from_policy(inner: Arc<dyn LLMProvider>, policy: PrivacyPolicy, project: &str) -> OmegaResult<Self> {
+        let redactor = if policy.redact { Redactor::from_policy(&policy)? } else { Redactor::default() };
+        Ok(Self::new(inner, policy, project, redactor))
+    }
+
+    pub fn policy(&self) -> &PrivacyPolicy { &self.policy }
+
+    /// Correspondances accumulées (depuis la création, ou celles de la table partagée du run)
+    pub fn map(&self) -> RedactionMap { self.map.lock().unwrap().clone() }
+
+    /// Substitutions faites par ce provider (la table partagée peut venir d'autres appels du run)
+    pub fn substitutions(&self) -> usize { self.substitutions.load(Ordering::Relaxed) }
+
+    /// Table chiffrée dans le run (rien si aucune substitution)
+    pub fn save_map(&self, run_dir: &Path, key: &RedactionKey) -> OmegaResult<Option<PathBuf>> {
+        self.map().save_sealed(run_dir, key)
+    }
+
+    /// local_only: tout provider distant est refusé avant envoi
+    fn guard(&self) -> OmegaResult<()> {
+        if self.policy.local_only && !self.inner.is_local() {
+            return Err(OmegaError::ConfigError(format!("PRIVACY_REMOTE_FORBIDDEN: project '{}' allows local providers only ({})", self.project, self.inner.id())));
+        }
+        Ok(())
+    }
+
+    fn redact_text(&self, text: &str) -> (String, usize) {
+        let (redacted, n) = self.redactor.redact(text, &mut self.map.lock().unwrap());
+        self.substitutions.fetch_add(n, Ordering::Relaxed);
+        (redacted, n)
+    }
+
+    fn redact_request(&self, mut req: CompletionRequest) -> CompletionRequest {
+        let (system, n_system) = self.redact_text(&req.system_prompt);
+        let (user, n_user) = self.redact_text(&req.user_prompt);
+        req.system_prompt = system;
+        req.user_prompt = user;
+        req.constraints.insert(REDACTION_CONSTRAINT.into(), serde_json::json!({
+            "applied": true,
+            "project": self.project,
+            "substitutions": n_system + n_user,
+        }));
+        req
+    }
+
+    fn restore_response(&self, mut resp: CompletionResponse) -> CompletionResponse {
+        let map = self.map.lock().unwrap();
+        resp.content = map.restore(&resp.content);
+        if let Some(parsed) = resp.parsed.as_mut() { map.restore_json(parsed); }
+        resp
+    }
+}
+
+/// Fin du texte sûre à émettre: un marqueur coupé entre deux deltas est retenu
+fn stream_cut(pending: &str) -> usize {
+    match pending.rfind('[') {
+        Some(open) if !pending[open..].contains(']') && pending.len() - open <= MAX_PLACEHOLDER_LEN => open,
+        _ => pending.len(),
+    }
+}
+
+impl LLMProvider for RedactingProvider {
+    fn id(&self) -> ProviderId { self.inner.id() }
+    fn model(&self) -> Option<String> { self.inner.model() }
+    fn capabilities(&self) -> ProviderCapabilities { self.inner.capabilities() }
+    fn health(&self) -> bool { self.inner.health() }
+    fn is_local(&self) -> bool { self.inner.is_local() }
+    fn reachable_models(&self) -> Vec<(ProviderId, Option<String>)> { self.inner.reachable_models() }
+
+    fn generate(&self, req: CompletionRequest) -> OmegaResult<CompletionResponse> {
+        self.guard()?;
+        if !self.policy.redact { return self.inner.generate(req); }
+        let resp = self.inner.generate(self.redact_request(req))?;
+        Ok(self.restore_response(resp))
+    }
+
+    fn generate_stream(&self, req: CompletionRequest, on_delta: &mut dyn FnMut(StreamDelta), cancel: &CancelToken) -> OmegaResult<CompletionResponse> {
+        self.guard()?;
+        if !self.policy.redact { return self.inner.generate_stream(req, on_delta, cancel); }
+        let req = self.redact_request(req);
+        let mut pending = String::new();
+        let mut index = 0;
+        let resp = {
+            let mut forward = |delta: StreamDelta| {
+                pending.push_str(&delta.text);
+                let cut = stream_cut(&pending);
+                if cut == 0 { return; }
+                let ready: String = pending.drain(..cut).collect();
+                on_delta(StreamDelta { index, text: self.map.lock().unwrap().restore(&ready) });
+                index += 1;
+            };
+            self.inner.generate_stream(req, &mut forward, cancel)?
+        };
+        if !pending.is_empty() {
+            on_delta(StreamDelta { index, text: self.map.lock().unwrap().restore(&pending) });
+        }
+        Ok(self.restore_response(resp))
+    }
+
+    /// Les vecteurs ne sont pas ré-identifiés: seuls les textes envoyés sont pseudonymisés
+    fn embed(&self, mut req: EmbeddingRequest) -> OmegaResult<EmbeddingResponse> {
+        self.guard()?;
+        if self.policy.redact { req.input = self.redact_text(&req.input).0; }
+        self.inner.embed(req)
+    }
+
+    fn embed_batch(&self, mut reqs: Vec<EmbeddingRequest>) -> OmegaResult<Vec<EmbeddingResponse>> {
+        self.guard()?;
+        if self.policy.redact {
+            for req in reqs.iter_mut() { req.input = self.redact_text(&req.input).0; }
+        }
+        self.inner.embed_batch(reqs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::MockDeterministicProvider;
+
+    fn redactor() -> Redactor {
+        let policy = PrivacyPolicy { redact: true, patterns: vec![CustomPattern { label: "DOSSIER".into(), regex: r"DOS-\d{4}".into() }], ..Default::default() };
+        Redactor::new(&policy, [
+            (NameKind::Char, "Mathilde".to_string()),
+            (NameKind::Char, "Jean-Pierre".to_string()),
+            (NameKind::Char, "Jean".to_string()),
+            (NameKind::Char, "Rose".to_string()),
+            (NameKind::Place, "Brocéliande".to_string()),
+        ]).unwrap()
+    }
+
+    /// Provider distant qui renvoie le prompt reçu (écho)
+    struct Echo { seen: Mutex<Vec<CompletionRequest>> }
+
+    impl LLMProvider for Echo {
+        fn id(&self) -> ProviderId { "echo".into() }
+        fn capabilities(&self) -> ProviderCapabilities { MockDeterministicProvider::default().capabilities() }
+        fn health(&self) -> bool { true }
+        fn embed(&self, _req: EmbeddingRequest) -> OmegaResult<EmbeddingResponse> { Err(OmegaError::NotSupported("embed".into())) }
+        fn generate(&self, req: CompletionRequest) -> OmegaResult<CompletionResponse> {
+            let mut resp = MockDeterministicProvider::default().generate(req.clone())?;
+            resp.content = req.user_prompt.clone();
+            resp.parsed = Some(serde_json::json!({"who": req.user_prompt.split(' ').next()}));
+            self.seen.lock().unwrap().push(req);
+            Ok(resp)
+        }
+    }
+
+    fn req(text: &str) -> CompletionRequest {
+        CompletionRequest { run_id: "t".into(), seed: 1, system_prompt: "S".into(), user_prompt: text.into(), temperature: 0.0, max_tokens: 10, schema_name: None, json_schema: None, constraints: Default::default(), template: None, tool: None }
+    }
+
+    #[test]
+    fn test_redact_restore_roundtrip() {
+        let r = redactor();
+        let mut map = RedactionMap::default();
+        let text = "Jean-Pierre écrit à Mathilde (mathilde@editions.fr, 06 12 34 56 78) depuis Brocéliande; Jean lit DOS-2024. Une rose fanée.";
+        let (redacted, n) = r.redact(text, &mut map);
+        assert_eq!(redacted, "[CHAR_2] écrit à [CHAR_3] ([EMAIL_1], [PHONE_1]) depuis [PLACE_1]; [CHAR_1] lit [DOSSIER_1]. Une rose fanée.");
+        assert_eq!(n, 7);
+        assert_eq!(map.restore(&redacted), text);
+
+        // Marqueurs stables: même nom, même marqueur; nouvel e-mail → EMAIL_2
+        let (again, _) = r.redact("MATHILDE écrit à paul@exemple.org et mathilde@editions.fr", &mut map);
+        assert_eq!(again, "[CHAR_3] écrit à [EMAIL_2] et [EMAIL_1]");
+        assert_eq!(map.restore("[CHAR_3] [UNKNOWN_9]"), "Mathilde [UNKNOWN_9]", "première graphie restituée, marqueur inconnu intact");
+    }
+
+    #[test]
+    fn test_provider_redacts_and_records() {
+        let echo = Arc::new(Echo { seen: Mutex::new(Vec::new()) });
+        let policy = PrivacyPolicy { redact: true, ..Default::default() };
+        let provider = RedactingProvider::new(echo.clone(), policy.clone(), "nda", redactor());
+        let resp = provider.generate(req("Mathilde fuit Brocéliande")).unwrap();
+        assert_eq!(resp.content, "Mathilde fuit Brocéliande");
+        assert_eq!(resp.parsed.unwrap()["who"], "Mathilde");
+
+        let sent = echo.seen.lock().unwrap()[0].clone();
+        assert_eq!(sent.user_prompt, "[CHAR_3] fuit [PLACE_1]");
+        assert_eq!(sent.constraints[REDACTION_CONSTRAINT]["applied"], true);
+        assert_eq!(sent.constraints[REDACTION_CONSTRAINT]["substitutions"], 2);
+        assert_eq!(provider.map().len(), 2);
+        assert_eq!(provider.substitutions(), 2);
+
+        let mut deltas = Vec::new();
+        let streamed = provider.generate_stream(req("Rose et Jean"), &mut |d| deltas.push(d.text), &CancelToken::new()).unwrap();
+        assert_eq!(streamed.content, "Rose et Jean");
+        assert_eq!(deltas.concat(), "Rose et Jean");
+
+        let plain = RedactingProvider::new(echo, policy, "nda", redactor());
+        plain.generate(req("Une rose fanée")).unwrap();
+        assert_eq!(plain.substitutions(), 0, "politique active, rien à remplacer");
+    }
+
+    #[test]
+    fn test_local_only_refuses_remote() {
+        let policy = PrivacyPolicy { local_only: true, ..Default::default() };
+        let remote = RedactingProvider::from_policy(Arc::new(Echo { seen: Mutex::new(Vec::new()) }), policy.clone(), "nda").unwrap();
+        assert!(matches!(remote.generate(req("x")), Err(OmegaError::ConfigError(m)) if m.starts_with("PRIVACY_REMOTE_FORBIDDEN")));
+        let local = RedactingProvider::from_policy(Arc::new(MockDeterministicProvider::default()), policy, "nda").unwrap();
+        assert!(local.generate(req("x")).is_ok());
+
+        let profiles: ProviderProfiles = toml::from_str("[privacy.default]\nredact = true\n[privacy.nda]\nlocal_only = true\n").unwrap();
+        let none = |_: &str| None;
+        assert!(PrivacyPolicy::resolve(Some(&profiles), "nda", &none).local_only);
+        assert!(PrivacyPolicy::resolve(Some(&profiles), "other", &none).redact);
+        let forced = PrivacyPolicy::resolve(None, "other", &|k| (k == "OMEGA_PRIVACY_LOCAL_ONLY").then(|| "1".to_string()));
+        assert!(forced.local_only && !forced.redact);
+    }
+
+    #[test]
+    fn test_sealed_map_roundtrip_and_tamper() {
+        let mut map = RedactionMap::default();
+        redactor().redact("Mathilde, mathilde@editions.fr", &mut map);
+        let key = RedactionKey::from_passphrase("secret");
+        let sealed = map.seal(&key).unwrap();
+        assert!(!String::from_utf8_lossy(&sealed).contains("Mathilde"), "aucun nom en clair");
+        assert_eq!(RedactionMap::open(&sealed, &key).unwrap(), map);
+        assert!(matches!(RedactionMap::open(&sealed, &RedactionKey::from_passphrase("autre")), Err(OmegaError::HashMismatch(_))));
+
+        let original: serde_json::Value = serde_json::from_slice(&sealed).unwrap();
+        for field in ["ciphertext", "nonce", "salt"] {
+            let mut altered = original.clone();
+            let v = altered[field].as_str().unwrap().to_string();
+            altered[field] = format!("{}{}", if v.starts_with('0') { "1" } else { "0" }, &v[1..]).into();
+            assert!(matches!(RedactionMap::open(&serde_json::to_vec(&altered).unwrap(), &key), Err(OmegaError::HashMismatch(_))), "{} altéré", field);
+        }
+        let mut weakened = original.clone();
+        weakened["kdf_rounds"] = 1.into();
+        assert!(matches!(RedactionMap::open(&serde_json::to_vec(&weakened).unwrap(), &key), Err(OmegaError::HashMismatch(_))), "en-tête authentifié");
+        let mut truncated = original;
+        let ct = truncated["ciphertext"].as_str().unwrap().to_string();
+        truncated["ciphertext"] = ct[..ct.len() - 2].into();
+        assert!(matches!(RedactionMap::open(&serde_json::to_vec(&truncated).unwrap(), &key), Err(OmegaError::HashMismatch(_))), "tag tronqué");
+    }
+
+    #[test]
+    fn test_shared_map_per_run_merges_sealed_file() {
+        let run_dir = std::env::temp_dir().join(format!("omega-redaction-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&run_dir).unwrap();
+        let key = RedactionKey::from_passphrase("secret");
+        let policy = PrivacyPolicy { redact: true, ..Default::default() };
+        let provider = |map| RedactingProvider::new(Arc::new(Echo { seen: Mutex::new(Vec::new()) }), policy.clone(), "p", redactor()).with_map(map);
+
+        let analysis = provider(RedactionMap::shared(&run_dir, &key).unwrap());
+        let index = provider(RedactionMap::shared(&run_dir, &key).unwrap());
+        analysis.generate(req("Mathilde écrit à m@editions.fr")).unwrap();
+        index.generate(req("Jean écrit à j@editions.fr")).unwrap();
+        assert_eq!(analysis.map(), index.map(), "une table par run");
+        assert_eq!((analysis.substitutions(), index.substitutions()), (2, 2));
+        assert_eq!(index.map().entries["EMAIL_2"], "j@editions.fr");
+        analysis.save_map(&run_dir, &key).unwrap();
+        drop((analysis, index));
+
+        // Nouvelle session: la table est relue, puis fusionnée avec le fichier à l'écriture
+        let search = provider(RedactionMap::shared(&run_dir, &key).unwrap());
+        search.generate(req("Rose écrit à r@editions.fr")).unwrap();
+        assert_eq!(search.map().entries["EMAIL_3"], "r@editions.fr");
+        let mut other = RedactionMap::default();
+        redactor().redact("DOS-1234", &mut other);
+        other.save_sealed(&run_dir, &key).unwrap();
+        search.save_map(&run_dir, &key).unwrap();
+        let saved = RedactionMap::load_sealed(&run_dir, &key).unwrap();
+        assert_eq!(saved.len(), 7);
+        assert_eq!(saved.entries["DOSSIER_1"], "DOS-1234");
+        std::fs::remove_dir_all(&run_dir).ok();
+    }
+}
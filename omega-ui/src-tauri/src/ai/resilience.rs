@@ -0,0 +1,257 @@
+//! OMEGA Resilience — circuit breaker, token bucket, Retry-After
+//! NASA-Grade: un provider en échec n'est plus sollicité à chaque appel; chaque décision de bascule est tracée
+
+use crate::error::{OmegaError, OmegaResult};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Attente Retry-After au-delà de laquelle on bascule au lieu d'attendre
+pub const MAX_RETRY_AFTER: Duration = Duration::from_secs(60);
+
+/// Timeout maximal d'une sonde de santé
+pub const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// CIRCUIT BREAKER
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CircuitState {
+    /// Appels autorisés
+    Closed,
+    /// Appels refusés jusqu'à la fin du cool-down
+    Open,
+    /// Cool-down écoulé: un seul appel d'essai autorisé
+    HalfOpen,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BreakerPolicy {
+    /// Échecs consécutifs avant ouverture
+    pub failure_threshold: u32,
+    pub cool_down: Duration,
+}
+
+impl Default for BreakerPolicy {
+    fn default() -> Self { Self { failure_threshold: 3, cool_down: Duration::from_secs(30) } }
+}
+
+#[derive(Debug)]
+struct BreakerInner {
+    state: CircuitState,
+    failures: u32,
+    opened_at: Option<Instant>,
+    trial_in_flight: bool,
+}
+
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    policy: BreakerPolicy,
+    inner: Mutex<BreakerInner>,
+}
+
+impl CircuitBreaker {
+    pub fn new(policy: BreakerPolicy) -> Self {
+        Self { policy, inner: Mutex::new(BreakerInner { state: CircuitState::Closed, failures: 0, opened_at: None, trial_in_flight: false }) }
+    }
+
+    /// État courant (Open → HalfOpen si le cool-down est écoulé)
+    pub fn state(&self) -> CircuitState {
+        let mut inner = self.inner.lock().unwrap();
+        self.refresh(&mut inner);
+        inner.state
+    }
+
+    /// true si un appel peut partir (en HalfOpen: un seul essai à la fois)
+    pub fn allow(&self) -> bool {
+        let mut inner = self.inner.lock().unwrap();
+        self.refresh(&mut inner);
+        match inner.state {
+            CircuitState::Closed => true,
+            CircuitState::Open => false,
+            CircuitState::HalfOpen if inner.trial_in_flight => false,
+            CircuitState::HalfOpen => { inner.trial_in_flight = true; true }
+        }
+    }
+
+    pub fn on_success(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        *inner = BreakerInner { state: CircuitState::Closed, failures: 0, opened_at: None, trial_in_flight: false };
+    }
+
+    /// Appel abandonné (annulation): ni succès ni échec, l'essai HalfOpen est libéré
+    pub fn on_abandoned(&self) {
+        self.inner.lock().unwrap().trial_in_flight = false;
+    }
+
+    pub fn on_failure(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.failures += 1;
+        inner.trial_in_flight = false;
+        if inner.state == CircuitState::HalfOpen || inner.failures >= self.policy.failure_threshold {
+            inner.state = CircuitState::Open;
+            inner.opened_at = Some(Instant::now());
+        }
+    }
+
+    fn refresh(&self, inner: &mut BreakerInner) {
+        if inner.state == CircuitState::Open && inner.opened_at.is_some_and(|t| t.elapsed() >= self.policy.cool_down) {
+            inner.state = CircuitState::HalfOpen;
+            inner.trial_in_flight = false;
+        }
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// TOKEN BUCKET
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RateLimit {
+    /// Rafale maximale
+    pub capacity: u32,
+    /// Requêtes par minute (recharge continue)
+    pub per_minute: u32,
+    /// Attente maximale d'un jeton avant RateLimit
+    pub max_wait: Duration,
+}
+
+#[derive(Debug)]
+pub struct TokenBucket {
+    limit: RateLimit,
+    state: Mutex<(f64, Instant)>,
+}
+
+impl TokenBucket {
+    pub fn new(limit: RateLimit) -> Self {
+        Self { limit, state: Mutex::new((limit.capacity as f64, Instant::now())) }
+    }
+
+    /// Prend un jeton ou retourne l'attente nécessaire
+    pub fn try_acquire(&self) -> Result<(), Duration> {
+        let mut state = self.state.lock().unwrap();
+        let rate = self.limit.per_minute as f64 / 60.0;
+        let (tokens, last) = *state;
+        let tokens = (tokens + last.elapsed().as_secs_f64() * rate).min(self.limit.capacity as f64);
+        *state = (tokens, Instant::now());
+        if tokens >= 1.0 {
+            state.0 -= 1.0;
+            Ok(())
+        } else if rate <= 0.0 {
+            Err(Duration::MAX)
+        } else {
+            Err(Duration::from_secs_f64((1.0 - tokens) / rate))
+        }
+    }
+
+    /// Attend un jeton (au plus max_wait), sinon RateLimit typé
+    pub fn acquire(&self) -> OmegaResult<()> {
+        loop {
+            match self.try_acquire() {
+                Ok(()) => return Ok(()),
+                Err(wait) if wait <= self.limit.max_wait => std::thread::sleep(wait),
+                Err(wait) => return Err(OmegaError::RateLimit(format!("LOCAL_BUCKET: wait {}ms > max {}ms", wait.as_millis(), self.limit.max_wait.as_millis()))),
+            }
+        }
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// RETRY-AFTER
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Valeur Retry-After: secondes ou date HTTP (RFC 7231)
+pub fn parse_retry_after(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse::<u64>() { return Some(Duration::from_secs(secs)); }
+    let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    let delta = date.signed_duration_since(chrono::Utc::now());
+    Some(delta.to_std().unwrap_or(Duration::ZERO))
+}
+
+/// Retry-After d'une réponse HTTP (429/503)
+pub fn retry_after(headers: &reqwest::header::HeaderMap) -> Option<Duration> {
+    headers.get(reqwest::header::RETRY_AFTER).and_then(|v| v.to_str().ok()).and_then(parse_retry_after)
+}
+
+/// Attente avant la tentative `attempt` (≥ 1): Retry-After du serveur sinon backoff exponentiel
+pub fn retry_delay(attempt: u32, retry_after: Option<Duration>) -> Duration {
+    retry_after.unwrap_or_else(|| Duration::from_millis(1000 * 2u64.pow(attempt.saturating_sub(1).min(16))))
+}
+
+/// HTTP 429: attente avant nouvel essai (Retry-After honoré); trop long ou dernier essai → RateLimit typé (le FallbackProvider bascule)
+pub fn rate_limited(headers: &reqwest::header::HeaderMap, attempt: u32, max_retries: u32) -> OmegaResult<Option<Duration>> {
+    let after = retry_after(headers);
+    if attempt == max_retries || after.is_some_and(|d| d > MAX_RETRY_AFTER) {
+        return Err(OmegaError::RateLimit(format!("HTTP_429: retry_after={}", after.map_or("none".into(), |d| format!("{}s", d.as_secs())))));
+    }
+    Ok(after)
+}
+
+/// Boucle d'envoi commune aux providers HTTP: 429 → attente (Retry-After ou backoff), timeout → nouvel essai,
+/// autre statut → ProviderError. `request` reconstruit la requête à chaque tentative (en-têtes propres au provider)
+pub async fn send_with_retry(max_retries: u32, request: impl Fn() -> reqwest::RequestBuilder) -> OmegaResult<reqwest::Response> {
+    let mut wait = None;
+    for attempt in 0..=max_retries {
+        if attempt > 0 { tokio::time::sleep(retry_delay(attempt, wait.take())).await; }
+        match request().send().await {
+            Ok(resp) if resp.status().is_success() => return Ok(resp),
+            Ok(resp) if resp.status().as_u16() == 429 => wait = rate_limited(resp.headers(), attempt, max_retries)?,
+            Ok(resp) => return Err(OmegaError::ProviderError(format!("HTTP_{}: {}", resp.status().as_u16(), resp.text().await.unwrap_or_default()))),
+            Err(e) if e.is_timeout() => continue,
+            Err(e) => return Err(OmegaError::ProviderError(format!("NETWORK: {}", e))),
+        }
+    }
+    Err(OmegaError::ProviderError("MAX_RETRIES_EXCEEDED".into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_breaker_open_half_open_closed() {
+        let b = CircuitBreaker::new(BreakerPolicy { failure_threshold: 2, cool_down: Duration::from_millis(30) });
+        assert!(b.allow());
+        b.on_failure();
+        assert_eq!(b.state(), CircuitState::Closed);
+        b.on_failure();
+        assert_eq!(b.state(), CircuitState::Open);
+        assert!(!b.allow());
+
+        std::thread::sleep(Duration::from_millis(40));
+        assert_eq!(b.state(), CircuitState::HalfOpen);
+        assert!(b.allow(), "un essai");
+        assert!(!b.allow(), "un seul essai à la fois");
+        b.on_failure();
+        assert_eq!(b.state(), CircuitState::Open, "essai raté → réouverture");
+
+        std::thread::sleep(Duration::from_millis(40));
+        assert!(b.allow());
+        b.on_success();
+        assert_eq!(b.state(), CircuitState::Closed);
+    }
+
+    #[test]
+    fn test_token_bucket_burst_and_refill() {
+        let bucket = TokenBucket::new(RateLimit { capacity: 2, per_minute: 60, max_wait: Duration::ZERO });
+        assert!(bucket.try_acquire().is_ok());
+        assert!(bucket.try_acquire().is_ok());
+        let wait = bucket.try_acquire().unwrap_err();
+        assert!(wait > Duration::from_millis(900) && wait <= Duration::from_secs(1));
+        assert!(matches!(bucket.acquire(), Err(OmegaError::RateLimit(_))));
+    }
+
+    #[test]
+    fn test_retry_after_parsing() {
+        assert_eq!(parse_retry_after("7"), Some(Duration::from_secs(7)));
+        assert_eq!(parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"), Some(Duration::ZERO), "date passée");
+        let future = (chrono::Utc::now() + chrono::Duration::seconds(120)).to_rfc2822();
+        assert!(parse_retry_after(&future).unwrap() > Duration::from_secs(100));
+        assert_eq!(parse_retry_after("soon"), None);
+        assert_eq!(retry_delay(1, None), Duration::from_millis(1000));
+        assert_eq!(retry_delay(3, Some(Duration::from_secs(2))), Duration::from_secs(2));
+    }
+}
@@ -0,0 +1,328 @@
+//! OMEGA Schema — validation JSON Schema des réponses structurées + boucle de réparation
+//! NASA-Grade: une réponse hors schéma n'est jamais acceptée en silence (réparée ou erreur typée)
+//!
+//! Sous-ensemble JSON Schema supporté: type, enum, const, properties, required,
+//! additionalProperties, items, minItems, maxItems, minimum, maximum,
+//! exclusiveMinimum, exclusiveMaximum, minLength, maxLength. Les autres mots-clés sont ignorés.
+
+use crate::ai::interface::LLMProvider;
+use crate::ai::models::*;
+use crate::error::{OmegaError, OmegaResult};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Réparations par défaut après la première réponse
+pub const DEFAULT_MAX_REPAIRS: u32 = 2;
+
+/// Longueur max de la réponse fautive renvoyée au modèle / conservée dans l'erreur
+const MAX_ECHO_CHARS: usize = 4000;
+
+/// Violation: chemin JSON ($.emotions[0].score) + message
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SchemaError {
+    pub path: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { write!(f, "{}: {}", self.path, self.message) }
+}
+
+/// Détail d'un échec définitif (porté par OmegaError::InvalidResponse)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ValidationFailure {
+    pub schema_name: Option<String>,
+    /// Réponses obtenues (1 + réparations)
+    pub attempts: u32,
+    /// Erreurs de la dernière réponse
+    pub errors: Vec<SchemaError>,
+    /// Dernière réponse brute (tronquée)
+    pub content: String,
+}
+
+impl std::fmt::Display for ValidationFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let errors: Vec<String> = self.errors.iter().map(|e| e.to_string()).collect();
+        write!(f, "SCHEMA_VIOLATION schema={} attempts={}: {}", self.schema_name.as_deref().unwrap_or("-"), self.attempts, errors.join("; "))
+    }
+}
+
+/// Réponse conforme au schéma
+#[derive(Debug, Clone)]
+pub struct ValidatedResponse {
+    pub response: CompletionResponse,
+    pub value: Value,
+    /// Re-prompts nécessaires (0 = conforme du premier coup)
+    pub repairs: u32,
+    /// Usage cumulé de toutes les tentatives
+    pub usage: Usage,
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// VALIDATION
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Toutes les violations de `instance` (vide = conforme)
+pub fn validate(schema: &Value, instance: &Value) -> Vec<SchemaError> {
+    let mut errors = Vec::new();
+    check(schema, instance, "$", &mut errors);
+    errors
+}
+
+fn check(schema: &Value, v: &Value, path: &str, errors: &mut Vec<SchemaError>) {
+    let mut fail = |message: String| errors.push(SchemaError { path: path.to_string(), message });
+
+    if let Some(expected) = schema.get("type") {
+        let types: Vec<&str> = match expected {
+            Value::String(t) => vec![t.as_str()],
+            Value::Array(ts) => ts.iter().filter_map(Value::as_str).collect(),
+            _ => vec![],
+        };
+        if !types.is_empty() && !types.iter().any(|t| has_type(v, t)) {
+            fail(format!("expected {}, got {}", types.join("|"), type_name(v)));
+            return;
+        }
+    }
+    if let Some(allowed) = schema.get("enum").and_then(Value::as_array) {
+        if !allowed.contains(v) {
+            fail(format!("{} not in enum {}", v, Value::Array(allowed.clone())));
+        }
+    }
+    if let Some(c) = schema.get("const") {
+        if c != v {
+            fail(format!("expected const {}", c));
+        }
+    }
+    if let Some(n) = v.as_f64() {
+        let bound = |k: &str| schema.get(k).and_then(Value::as_f64);
+        if let Some(min) = bound("minimum").filter(|m| n < *m) { fail(format!("{} < minimum {}", n, min)); }
+        if let Some(max) = bound("maximum").filter(|m| n > *m) { fail(format!("{} > maximum {}", n, max)); }
+        if let Some(min) = bound("exclusiveMinimum").filter(|m| n <= *m) { fail(format!("{} <= exclusiveMinimum {}", n, min)); }
+        if let Some(max) = bound("exclusiveMaximum").filter(|m| n >= *m) { fail(format!("{} >= exclusiveMaximum {}", n, max)); }
+    }
+    if let Some(s) = v.as_str() {
+        let len = s.chars().count() as u64;
+        if let Some(min) = schema.get("minLength").and_then(Value::as_u64).filter(|m| len < *m) { fail(format!("length {} < minLength {}", len, min)); }
+        if let Some(max) = schema.get("maxLength").and_then(Value::as_u64).filter(|m| len > *m) { fail(format!("length {} > maxLength {}", len, max)); }
+    }
+    if let Some(items) = v.as_array() {
+        let len = items.len() as u64;
+        if let Some(min) = schema.get("minItems").and_then(Value::as_u64).filter(|m| len < *m) { fail(format!("{} items < minItems {}", len, min)); }
+        if let Some(max) = schema.get("maxItems").and_then(Value::as_u64).filter(|m| len > *m) { fail(format!("{} items > maxItems {}", len, max)); }
+        if let Some(item_schema) = schema.get("items").filter(|s| s.is_object()) {
+            for (i, item) in items.iter().enumerate() {
+                check(item_schema, item, &format!("{}[{}]", path, i), errors);
+            }
+        }
+    }
+    if let Some(obj) = v.as_object() {
+        let properties = schema.get("properties").and_then(Value::as_object);
+        for name in schema.get("required").and_then(Value::as_array).into_iter().flatten().filter_map(Value::as_str) {
+            if !obj.contains_key(name) {
+                errors.push(SchemaError { path: path.to_string(), message: format!("missing required property '{}'", name) });
+            }
+        }
+        for (key, value) in obj {
+            let child = format!("{}.{}", path, key);
+            match (properties.and_then(|p| p.get(key)), schema.get("additionalProperties")) {
+                (Some(prop_schema), _) => check(prop_schema, value, &child, errors),
+                (None, Some(Value::Bool(false))) => errors.push(SchemaError { path: child, message: "additional property not allowed".into() }),
+                (None, Some(extra @ Value::Object(_))) => check(extra, value, &child, errors),
+                _ => {}
+            }
+        }
+    }
+}
+
+fn has_type(v: &Value, t: &str) -> bool {
+    match t {
+        "object" => v.is_object(),
+        "array" => v.is_array(),
+        "string" => v.is_string(),
+        "boolean" => v.is_boolean(),
+        "null" => v.is_null(),
+        "number" => v.is_number(),
+        "integer" => v.is_i64() || v.is_u64() || v.as_f64().is_some_and(|f| f.fract() == 0.0),
+        _ => true,
+    }
+}
+
+fn type_name(v: &Value) -> &'static str {
+    match v {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// Instance minimale conforme (champs requis uniquement): réponses du mock
+pub fn minimal_instance(schema: &Value) -> Value {
+    if let Some(c) = schema.get("const") { return c.clone(); }
+    if let Some(first) = schema.get("enum").and_then(Value::as_array).and_then(|e| e.first()) { return first.clone(); }
+    let t = match schema.get("type") {
+        Some(Value::String(t)) => t.as_str(),
+        Some(Value::Array(ts)) => ts.first().and_then(Value::as_str).unwrap_or("object"),
+        _ => "object",
+    };
+    match t {
+        "array" => {
+            let item = schema.get("items").map(minimal_instance).unwrap_or(Value::Null);
+            Value::Array(vec![item; schema.get("minItems").and_then(Value::as_u64).unwrap_or(0) as usize])
+        }
+        "string" => Value::String("x".repeat(schema.get("minLength").and_then(Value::as_u64).unwrap_or(0) as usize)),
+        "number" | "integer" => serde_json::json!(schema.get("minimum").and_then(Value::as_f64).map_or(0, |m| m.ceil() as i64)),
+        "boolean" => Value::Bool(false),
+        "null" => Value::Null,
+        _ => {
+            let properties = schema.get("properties").and_then(Value::as_object);
+            let required = schema.get("required").and_then(Value::as_array).into_iter().flatten().filter_map(Value::as_str);
+            Value::Object(required.map(|name| {
+                let prop = properties.and_then(|p| p.get(name)).map(minimal_instance).unwrap_or(Value::Null);
+                (name.to_string(), prop)
+            }).collect())
+        }
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// RÉPARATION
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// JSON de la réponse (tolère un bloc ```json … ```)
+pub fn extract_json(content: &str) -> Result<Value, SchemaError> {
+    let trimmed = content.trim();
+    let body = match trimmed.strip_prefix("```") {
+        Some(fenced) => fenced.split_once('\n').map_or("", |(_, rest)| rest).trim_end().trim_end_matches("```"),
+        None => trimmed,
+    };
+    serde_json::from_str(body).map_err(|e| SchemaError { path: "$".into(), message: format!("invalid JSON: {}", e) })
+}
+
+/// Consigne JSON ajoutée au prompt système quand le provider n'impose pas le format (pas d'outil)
+pub fn json_instruction(schema: &Value) -> String {
+    format!("Réponds uniquement avec un objet JSON conforme à ce JSON Schema, sans texte autour:\n{}", schema)
+}
+
+/// Génère puis valide contre `req.json_schema`; en cas d'échec, re-prompt avec les erreurs (au plus `max_repairs` fois)
+pub fn generate_validated(provider: &dyn LLMProvider, req: CompletionRequest, max_repairs: u32) -> OmegaResult<ValidatedResponse> {
+    let schema = req.json_schema.clone().ok_or_else(|| OmegaError::ConfigError("SCHEMA_REQUIRED: json_schema absent".into()))?;
+    let mut attempt_req = req.clone();
+    let mut repairs = 0;
+    let mut usage = Usage { prompt_tokens: 0, completion_tokens: 0, total_tokens: 0 };
+    loop {
+        let response = provider.generate(attempt_req)?;
+        usage.prompt_tokens += response.usage.prompt_tokens;
+        usage.completion_tokens += response.usage.completion_tokens;
+        usage.total_tokens += response.usage.total_tokens;
+        let errors = match response.parsed.clone().map_or_else(|| extract_json(&response.content), Ok) {
+            Ok(value) => match validate(&schema, &value) {
+                errors if errors.is_empty() => return Ok(ValidatedResponse { response, value, repairs, usage }),
+                errors => errors,
+            },
+            Err(e) => vec![e],
+        };
+        eprintln!("[SCHEMA] {} violation(s) from {} (repair {}/{})", errors.len(), response.provider_id, repairs, max_repairs);
+        if repairs >= max_repairs {
+            return Err(OmegaError::schema_violation(ValidationFailure {
+                schema_name: req.schema_name.clone(),
+                attempts: repairs + 1,
+                errors,
+                content: response.content.chars().take(MAX_ECHO_CHARS).collect(),
+            }));
+        }
+        repairs += 1;
+        attempt_req = repair_request(&req, &schema, &response.content, &errors);
+    }
+}
+
+/// Requête de réparation: prompt d'origine + réponse fautive + erreurs de validation
+fn repair_request(req: &CompletionRequest, schema: &Value, content: &str, errors: &[SchemaError]) -> CompletionRequest {
+    let errors: Vec<String> = errors.iter().map(|e| format!("- {}", e)).collect();
+    CompletionRequest {
+        user_prompt: format!(
+            "{}\n\nTa réponse précédente:\n{}\n\nElle ne respecte pas le JSON Schema:\n{}\n\nErreurs:\n{}\n\nRetourne uniquement le JSON corrigé.",
+            req.user_prompt,
+            content.chars().take(MAX_ECHO_CHARS).collect::<String>(),
+            schema,
+            errors.join("\n"),
+        ),
+        ..req.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    fn emotions_schema() -> Value {
+        serde_json::json!({
+            "type": "object",
+            "required": ["emotions"],
+            "properties": {"emotions": {"type": "array", "items": {
+                "type": "object",
+                "required": ["emotion", "score"],
+                "additionalProperties": false,
+                "properties": {"emotion": {"type": "string", "enum": ["joy", "fear"]}, "score": {"type": "number", "minimum": 0, "maximum": 1}}
+            }}}
+        })
+    }
+
+    #[test]
+    fn test_validate_reports_paths() {
+        let schema = emotions_schema();
+        assert!(validate(&schema, &serde_json::json!({"emotions": [{"emotion": "joy", "score": 0.5}]})).is_empty());
+        let errors = validate(&schema, &serde_json::json!({"emotions": [{"emotion": "peur", "score": 1.4, "x": 1}, "oops"]}));
+        let paths: Vec<&str> = errors.iter().map(|e| e.path.as_str()).collect();
+        assert_eq!(paths, vec!["$.emotions[0].emotion", "$.emotions[0].score", "$.emotions[0].x", "$.emotions[1]"]);
+        assert_eq!(validate(&schema, &serde_json::json!({}))[0].message, "missing required property 'emotions'");
+        assert!(validate(&schema, &minimal_instance(&schema)).is_empty());
+    }
+
+    /// Répond successivement les contenus donnés et mémorise les prompts
+    struct Scripted { replies: Mutex<Vec<&'static str>>, prompts: Mutex<Vec<String>> }
+
+    impl LLMProvider for Scripted {
+        fn id(&self) -> ProviderId { "scripted".into() }
+        fn capabilities(&self) -> ProviderCapabilities { crate::ai::MockDeterministicProvider::default().capabilities() }
+        fn health(&self) -> bool { true }
+        fn embed(&self, _req: EmbeddingRequest) -> OmegaResult<EmbeddingResponse> { Err(OmegaError::NotSupported("embed".into())) }
+        fn generate(&self, req: CompletionRequest) -> OmegaResult<CompletionResponse> {
+            self.prompts.lock().unwrap().push(req.user_prompt);
+            let content = self.replies.lock().unwrap().remove(0).to_string();
+            Ok(CompletionResponse { provider_id: self.id(), content, parsed: None, usage: Usage { prompt_tokens: 1, completion_tokens: 1, total_tokens: 2 }, latency_ms: 0, response_hash: String::new(), cache: None, fallback: None })
+        }
+    }
+
+    fn req() -> CompletionRequest {
+        CompletionRequest { run_id: "t".into(), seed: 1, system_prompt: "S".into(), user_prompt: "U".into(), temperature: 0.0, max_tokens: 100, schema_name: Some("emotions".into()), json_schema: Some(emotions_schema()), constraints: Default::default(), template: None, tool: None }
+    }
+
+    #[test]
+    fn test_repair_loop_fixes_response() {
+        let p = Scripted { replies: Mutex::new(vec!["pas du json", "```json\n{\"emotions\":[{\"emotion\":\"joy\",\"score\":0.7}]}\n```"]), prompts: Mutex::new(vec![]) };
+        let ok = generate_validated(&p, req(), 2).unwrap();
+        assert_eq!(ok.repairs, 1);
+        assert_eq!(ok.usage.total_tokens, 4);
+        assert_eq!(ok.value["emotions"][0]["score"], 0.7);
+        let prompts = p.prompts.lock().unwrap();
+        assert!(prompts[1].starts_with("U\n\nTa réponse précédente:\npas du json") && prompts[1].contains("invalid JSON"));
+    }
+
+    #[test]
+    fn test_repair_loop_bounded() {
+        let p = Scripted { replies: Mutex::new(vec![r#"{"emotions":[{"emotion":"rage","score":2}]}"#; 3]), prompts: Mutex::new(vec![]) };
+        match generate_validated(&p, req(), 1) {
+            Err(OmegaError::InvalidResponse { schema: Some(failure), .. }) => {
+                assert_eq!(failure.attempts, 2);
+                assert_eq!(failure.errors.len(), 2);
+                assert_eq!(failure.schema_name.as_deref(), Some("emotions"));
+            }
+            other => panic!("expected schema InvalidResponse, got {:?}", other.map(|v| v.value)),
+        }
+        assert_eq!(p.prompts.lock().unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,244 @@
+//! OMEGA Streaming — SSE parsing, deltas, annulation
+//! NASA-Grade: la réponse finale d'un stream est identique (contenu + hash) à celle de generate()
+
+use crate::ai::models::*;
+use crate::error::{OmegaError, OmegaResult};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Jeton d'annulation partagé entre l'appelant (UI) et le stream
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn new() -> Self { Self::default() }
+    pub fn cancel(&self) { self.0.store(true, Ordering::SeqCst); }
+    pub fn is_cancelled(&self) -> bool { self.0.load(Ordering::SeqCst) }
+    /// Erreur typée si annulé (à appeler entre deux chunks)
+    pub fn check(&self) -> OmegaResult<()> {
+        if self.is_cancelled() { Err(OmegaError::Cancelled("STREAM_CANCELLED".into())) } else { Ok(()) }
+    }
+}
+
+/// Fragment de texte émis pendant un stream
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StreamDelta {
+    pub index: u32,
+    pub text: String,
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// SSE (text/event-stream)
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Événement SSE complet (lignes `event:` / `data:` terminées par une ligne vide)
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SseEvent {
+    pub event: Option<String>,
+    pub data: String,
+}
+
+/// Parser SSE incrémental: accepte des fragments arbitraires (coupures réseau au milieu d'une ligne)
+#[derive(Debug, Default)]
+pub struct SseParser {
+    buffer: String,
+    event: Option<String>,
+    data: Vec<String>,
+}
+
+impl SseParser {
+    pub fn new() -> Self { Self::default() }
+
+    /// Ajoute un fragment et retourne les événements complets
+    pub fn push(&mut self, chunk: &str) -> Vec<SseEvent> {
+        self.buffer.push_str(chunk);
+        let mut out = Vec::new();
+        while let Some(pos) = self.buffer.find('\n') {
+            let line: String = self.buffer.drain(..=pos).collect();
+            let line = line.trim_end_matches('\n').trim_end_matches('\r');
+            if let Some(ev) = self.line(line) { out.push(ev); }
+        }
+        out
+    }
+
+    /// Vide l'événement en cours (flux terminé sans ligne vide finale)
+    pub fn finish(&mut self) -> Option<SseEvent> {
+        let rest = std::mem::take(&mut self.buffer);
+        if !rest.is_empty() { let _ = self.line(rest.trim_end_matches('\r')); }
+        self.dispatch()
+    }
+
+    fn line(&mut self, line: &str) -> Option<SseEvent> {
+        if line.is_empty() { return self.dispatch(); }
+        if line.starts_with(':') { return None; }
+        let (field, value) = match line.find(':') {
+            Some(i) => (&line[..i], line[i + 1..].strip_prefix(' ').unwrap_or(&line[i + 1..])),
+            None => (line, ""),
+        };
+        match field {
+            "event" => self.event = Some(value.to_string()),
+            "data" => self.data.push(value.to_string()),
+            _ => {}
+        }
+        None
+    }
+
+    fn dispatch(&mut self) -> Option<SseEvent> {
+        if self.data.is_empty() { self.event = None; return None; }
+        let ev = SseEvent { event: self.event.take(), data: self.data.join("\n") };
+        self.data.clear();
+        Some(ev)
+    }
+}
+
+/// Élément décodé d'un flux provider
+#[derive(Debug, Clone, PartialEq)]
+pub enum StreamChunk {
+    Delta(String),
+    /// Fragment des arguments d'un appel d'outil (gardé à part du texte, comme dans generate())
+    ToolDelta(String),
+    PromptTokens(u32),
+    CompletionTokens(u32),
+    Done,
+    Ignore,
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// ACCUMULATEUR
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Reconstruit la CompletionResponse finale à partir des chunks
+#[derive(Debug, Default)]
+pub struct StreamAccumulator {
+    content: String,
+    /// Arguments de l'appel d'outil; None si le modèle n'a pas appelé d'outil
+    tool_args: Option<String>,
+    prompt_tokens: u32,
+    completion_tokens: u32,
+    deltas: u32,
+    done: bool,
+}
+
+impl StreamAccumulator {
+    pub fn new() -> Self { Self::default() }
+
+    /// Applique un chunk; émet le delta éventuel
+    pub fn apply(&mut self, chunk: StreamChunk, on_delta: &mut dyn FnMut(StreamDelta)) {
+        match chunk {
+            StreamChunk::Delta(text) if !text.is_empty() => {
+                self.content.push_str(&text);
+                on_delta(StreamDelta { index: self.deltas, text });
+                self.deltas += 1;
+            }
+            StreamChunk::ToolDelta(args) => {
+                self.tool_args.get_or_insert_with(String::new).push_str(&args);
+                if !args.is_empty() {
+                    on_delta(StreamDelta { index: self.deltas, text: args });
+                    self.deltas += 1;
+                }
+            }
+            StreamChunk::PromptTokens(n) => self.prompt_tokens = n,
+            StreamChunk::CompletionTokens(n) => self.completion_tokens = n,
+            StreamChunk::Done => self.done = true,
+            _ => {}
+        }
+    }
+
+    pub fn is_done(&self) -> bool { self.done }
+    pub fn delta_count(&self) -> u32 { self.deltas }
+
+    /// Réponse finale; hash = sha256("<hash_prefix>|<seed>|<content>") comme generate().
+    /// Outil demandé et appelé: contenu = arguments de l'outil, sinon texte (même règle que generate())
+    pub fn finish(self, provider_id: &str, hash_prefix: &str, req: &CompletionRequest, latency_ms: u64) -> CompletionResponse {
+        let content = match self.tool_args {
+            Some(args) if req.tool.is_some() => args,
+            _ => self.content,
+        };
+        let response_hash = format!("{:x}", Sha256::digest(format!("{}|{}|{}", hash_prefix, req.seed, content).as_bytes()));
+        CompletionResponse {
+            provider_id: provider_id.into(),
+            parsed: req.json_schema.as_ref().and_then(|_| serde_json::from_str(&content).ok()),
+            content,
+            usage: Usage { prompt_tokens: self.prompt_tokens, completion_tokens: self.completion_tokens, total_tokens: self.prompt_tokens + self.completion_tokens },
+            latency_ms,
+            response_hash,
+            cache: None,
+            fallback: None,
+        }
+    }
+}
+
+/// Erreur de lecture: erreur OMEGA transportée telle quelle (annulation, échéance), sinon STREAM_READ
+fn read_error(e: std::io::Error) -> OmegaError {
+    let message = e.to_string();
+    match e.into_inner().map(|inner| inner.downcast::<OmegaError>()) {
+        Some(Ok(err)) => *err,
+        _ => OmegaError::ProviderError(format!("STREAM_READ: {}", message)),
+    }
+}
+
+/// Consomme un flux SSE ligne à ligne (lecture bloquante) jusqu'à la fin ou l'annulation
+pub fn drive_sse<R: std::io::BufRead>(
+    reader: R,
+    decode: impl Fn(&SseEvent) -> OmegaResult<Vec<StreamChunk>>,
+    acc: &mut StreamAccumulator,
+    on_delta: &mut dyn FnMut(StreamDelta),
+    cancel: &CancelToken,
+) -> OmegaResult<()> {
+    let mut parser = SseParser::new();
+    for line in reader.lines() {
+        cancel.check()?;
+        let line = line.map_err(read_error)?;
+        for ev in parser.push(&format!("{}\n", line)) {
+            for chunk in decode(&ev)? { acc.apply(chunk, on_delta); }
+        }
+        if acc.is_done() { return Ok(()); }
+    }
+    if let Some(ev) = parser.finish() {
+        for chunk in decode(&ev)? { acc.apply(chunk, on_delta); }
+    }
+    cancel.check()?;
+    if acc.is_done() { Ok(()) } else { Err(OmegaError::invalid_response("STREAM_TRUNCATED")) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sse_split_across_chunks() {
+        let mut p = SseParser::new();
+        assert!(p.push("event: message\nda").is_empty());
+        assert!(p.push("ta: {\"a\":1}\r\n").is_empty());
+        let evs = p.push("\n: keep-alive\n\ndata: x\ndata: y\n\n");
+        assert_eq!(evs.len(), 2);
+        assert_eq!(evs[0], SseEvent { event: Some("message".into()), data: "{\"a\":1}".into() });
+        assert_eq!(evs[1].data, "x\ny");
+        assert_eq!(evs[1].event, None);
+    }
+
+    #[test]
+    fn test_sse_finish_flushes_pending() {
+        let mut p = SseParser::new();
+        assert!(p.push("data: [DONE]").is_empty());
+        assert_eq!(p.finish().map(|e| e.data), Some("[DONE]".into()));
+    }
+
+    #[test]
+    fn test_drive_truncated_and_cancelled() {
+        let decode = |ev: &SseEvent| Ok(vec![if ev.data == "[DONE]" { StreamChunk::Done } else { StreamChunk::Delta(ev.data.clone()) }]);
+        let mut acc = StreamAccumulator::new();
+        let r = drive_sse("data: a\n\ndata: b\n\n".as_bytes(), decode, &mut acc, &mut |_| {}, &CancelToken::new());
+        assert!(matches!(r, Err(OmegaError::InvalidResponse { .. })));
+        assert_eq!(acc.delta_count(), 2);
+
+        let cancel = CancelToken::new();
+        cancel.cancel();
+        let mut acc = StreamAccumulator::new();
+        let r = drive_sse("data: a\n\ndata: [DONE]\n\n".as_bytes(), decode, &mut acc, &mut |_| {}, &cancel);
+        assert!(matches!(r, Err(OmegaError::Cancelled(_))));
+        assert_eq!(acc.delta_count(), 0);
+    }
+}
@@ -2,12 +2,26 @@
 //! Phase 1 Production — NASA-Grade AS9100D
 //! 
 //! Usage: omega_run --seed 42 --mode deterministic --input-file text.txt
+//!        omega_run --provider openai-compatible --base-url http://localhost:11434/v1 --model llama3 --input-file text.txt
+//!        omega_run --mode boost --profile local [--config omega.toml] --input-file text.txt
+//!        omega_run --mode boost --fallback-chain --input-file text.txt
+//!        omega_run --mode hybrid --provider mock-script:faults.toml --input-file text.txt
+//!        omega_run --mode boost --replay-mode replay --replay-run RUN_<id> --input-file text.txt
+//!        omega_run --usage-report --from 2026-01-01 --to 2026-01-31 [--project <NAME>]
+//!        omega_run --diff-prompt emotion.analysis 1.0.0 1.1.0
 //! Output: Prints run_id to stdout, writes artifacts to runs/<run_id>/
 
 use omega_ui::pipeline::{PipelineRunner, PipelineRun};
 use omega_ui::pipeline::fs_utils::{ensure_dir, write_json, sha256_str};
-use omega_ui::ai::MockDeterministicProvider;
-use omega_ui::error::OmegaResult;
+use omega_ui::ai::{BudgetCaps, FallbackProvider, LLMProvider, ResiliencePolicy, MeteredProvider, MockDeterministicProvider, PriceTable, PrivacyPolicy, PromptRegistry, RedactingProvider, RedactionKey, ScriptedProvider, UsageLedger};
+use omega_ui::ai::ledger::{default_ledger_path, DEFAULT_PROJECT};
+use omega_ui::ai::providers::anthropic::AnthropicProvider;
+use omega_ui::ai::providers::config::{CapabilityOverrides, ProviderConfig};
+use omega_ui::ai::providers::openai::OpenAIProvider;
+use omega_ui::ai::providers::profiles::ProviderProfiles;
+use omega_ui::error::{OmegaError, OmegaResult};
+use omega_ui::interfaces::voice_hybrid::ReplayMode;
+use omega_ui::modules::{AnalyzerMode, EmotionReplayConfig};
 use std::env;
 use std::fs;
 use std::path::PathBuf;
@@ -37,6 +51,19 @@ fn run_cli() -> OmegaResult<String> {
     let mut input_file: Option<String> = None;
     let mut input_text: Option<String> = None;
     let mut output_dir = PathBuf::from("runs");
+    let mut provider_name: Option<String> = None;
+    let mut base_url: Option<String> = None;
+    let mut model: Option<String> = None;
+    let mut profile: Option<String> = None;
+    let mut config_path: Option<PathBuf> = None;
+    let mut fallback_chain = false;
+    let mut replay_mode = ReplayMode::Off;
+    let mut replay_run: Option<String> = None;
+    let mut project = env::var("OMEGA_PROJECT").ok().filter(|p| !p.is_empty());
+    let mut usage_report = false;
+    let mut from: Option<String> = None;
+    let mut to: Option<String> = None;
+    let mut diff_prompt: Option<(String, String, String)> = None;
     
     let mut i = 1;
     while i < args.len() {
@@ -63,6 +90,58 @@ fn run_cli() -> OmegaResult<String> {
                     output_dir = PathBuf::from(dir);
                 }
             }
+            "--provider" => {
+                i += 1;
+                provider_name = args.get(i).cloned();
+            }
+            "--base-url" => {
+                i += 1;
+                base_url = args.get(i).cloned();
+            }
+            "--model" => {
+                i += 1;
+                model = args.get(i).cloned();
+            }
+            "--profile" => {
+                i += 1;
+                profile = args.get(i).cloned();
+            }
+            "--config" => {
+                i += 1;
+                config_path = args.get(i).map(PathBuf::from);
+            }
+            "--fallback-chain" => fallback_chain = true,
+            "--replay-mode" => {
+                i += 1;
+                let value = args.get(i).map(String::as_str).unwrap_or("");
+                replay_mode = ReplayMode::parse(value).ok_or_else(|| OmegaError::ConfigError(
+                    format!("Invalid replay mode '{}'. Use: off, record, replay", value)
+                ))?;
+            }
+            "--replay-run" => {
+                i += 1;
+                replay_run = args.get(i).cloned();
+            }
+            "--project" => {
+                i += 1;
+                project = args.get(i).cloned();
+            }
+            "--usage-report" => usage_report = true,
+            "--from" => {
+                i += 1;
+                from = args.get(i).cloned();
+            }
+            "--to" => {
+                i += 1;
+                to = args.get(i).cloned();
+            }
+            "--diff-prompt" => {
+                let id = args.get(i + 1).cloned().unwrap_or_default();
+                let from_version = args.get(i + 2).cloned().unwrap_or_default();
+                let to_version = args.get(i + 3).cloned().unwrap_or_default();
+                diff_prompt = Some((id, from_version, to_version));
+                i += 3;
+            }
             "--help" | "-h" => {
                 print_help();
                 std::process::exit(0);
@@ -72,6 +151,20 @@ fn run_cli() -> OmegaResult<String> {
         i += 1;
     }
     
+    // Prompt template diff: prints a unified diff instead of a run_id
+    if let Some((id, from_version, to_version)) = diff_prompt {
+        let diff = PromptRegistry::from_env().diff(&id, &from_version, &to_version)?;
+        return Ok(diff.to_unified().trim_end().to_string());
+    }
+    
+    let ledger = Arc::new(UsageLedger::new(default_ledger_path(&output_dir)));
+    
+    // Usage report: prints JSON instead of a run_id
+    if usage_report {
+        let report = ledger.report(from.as_deref(), to.as_deref(), project.as_deref())?;
+        return Ok(serde_json::to_string_pretty(&report)?);
+    }
+    
     // Get input text
     let input = if let Some(file) = input_file {
         fs::read_to_string(&file)
@@ -91,20 +184,59 @@ fn run_cli() -> OmegaResult<String> {
         ));
     }
     
-    // Create provider based on mode
-    let provider: Arc<dyn omega_ui::ai::LLMProvider> = match mode.as_str() {
-        "deterministic" => Arc::new(MockDeterministicProvider::default()),
-        "hybrid" => Arc::new(MockDeterministicProvider::default()), // BACKLOG: real hybrid
-        "boost" => Arc::new(MockDeterministicProvider::default()),  // BACKLOG: real boost
-        _ => Arc::new(MockDeterministicProvider::default()),
+    // Create provider: explicit --provider or --profile / --fallback-chain (omega.toml), otherwise based on mode
+    if [provider_name.is_some(), profile.is_some(), fallback_chain].iter().filter(|set| **set).count() > 1 {
+        return Err(OmegaError::ConfigError("--provider, --profile and --fallback-chain are mutually exclusive".into()));
+    }
+    // Confidentiality policy of the project: local_only keeps remote providers out, redact pseudonymizes prompts
+    let project = project.unwrap_or_else(|| DEFAULT_PROJECT.to_string());
+    let privacy = privacy_policy(config_path.as_deref(), &project)?;
+    let provider: Arc<dyn LLMProvider> = match (provider_name, profile) {
+        (Some(name), _) => build_provider(&name, base_url, model)?,
+        (None, Some(name)) => load_profiles(config_path.as_deref())?.provider(&name)?,
+        (None, None) if fallback_chain => {
+            let chain = FallbackProvider::from_profiles(&load_profiles(config_path.as_deref())?, ResiliencePolicy::from_env())?;
+            Arc::new(if privacy.local_only { chain.retain_local() } else { chain })
+        }
+        (None, None) => match mode.as_str() {
+            "deterministic" => Arc::new(MockDeterministicProvider::default()),
+            "hybrid" => Arc::new(MockDeterministicProvider::default()), // BACKLOG: real hybrid
+            "boost" => Arc::new(MockDeterministicProvider::default()),  // BACKLOG: real boost
+            _ => Arc::new(MockDeterministicProvider::default()),
+        },
     };
     
+    if privacy.local_only && !provider.is_local() {
+        return Err(OmegaError::ConfigError(format!("PRIVACY_REMOTE_FORBIDDEN: project '{}' allows local providers only ({})", project, provider.id())));
+    }
+    
     // Generate UUID v4 for run_id (NASA-grade unique identifier)
     let run_uuid = Uuid::new_v4();
     let run_id = format!("RUN_{}", run_uuid.to_string().to_uppercase().replace("-", ""));
     
+    // Every provider call is recorded in the ledger; budget caps apply before sending
+    let metered: Arc<dyn LLMProvider> = Arc::new(
+        MeteredProvider::new(provider, ledger, PriceTable::from_env()?, BudgetCaps::from_env())
+            .with_project(&project)
+            .with_run_id(&run_id),
+    );
+    let provider = Arc::new(RedactingProvider::from_policy(metered, privacy, &project)?);
+    
+    // Record/Replay of the AI calls made by the emotion pass
+    let replay = match replay_mode {
+        ReplayMode::Off => None,
+        ReplayMode::Record => Some(EmotionReplayConfig::in_output_dir(ReplayMode::Record, &run_id, &output_dir)?),
+        ReplayMode::Replay => {
+            let source = replay_run.ok_or_else(|| OmegaError::ConfigError("--replay-run <RUN_ID> required with --replay-mode replay".into()))?;
+            Some(EmotionReplayConfig::in_output_dir(ReplayMode::Replay, &source, &output_dir)?)
+        }
+    };
+    
     // Run pipeline with custom run_id
-    let runner = PipelineRunner::new(provider);
+    let mut runner = PipelineRunner::new(provider.clone()).with_analyzer_mode(AnalyzerMode::from_str(&mode));
+    if let Some(replay) = replay {
+        runner = runner.with_replay(replay);
+    }
     let mut result = runner.run(&input, seed)?;
     
     // Override run_id with UUID-based one
@@ -117,9 +249,75 @@ fn run_cli() -> OmegaResult<String> {
     // Write artifacts
     write_run_artifacts(&run_dir, &result, &input, &mode)?;
     
+    // Pseudonymization map, encrypted next to the run artifacts
+    if !provider.map().is_empty() {
+        provider.save_map(&run_dir, &RedactionKey::load(&output_dir)?)?;
+    }
+    
     Ok(run_id)
 }
 
+/// Provider profiles: --config, otherwise OMEGA_CONFIG or ./omega.toml (env overrides applied)
+fn load_profiles(path: Option<&std::path::Path>) -> OmegaResult<ProviderProfiles> {
+    let profiles = match path {
+        Some(path) => {
+            let mut profiles = ProviderProfiles::load(path)?;
+            profiles.apply_overrides(&|k| env::var(k).ok())?;
+            Some(profiles)
+        }
+        None => ProviderProfiles::from_env()?,
+    };
+    profiles.ok_or_else(|| OmegaError::ConfigError("PROFILES_NOT_FOUND: no omega.toml (use --config <PATH> or OMEGA_CONFIG)".into()))
+}
+
+/// Privacy policy of the project: --config, otherwise OMEGA_CONFIG or ./omega.toml (no file = no restriction)
+fn privacy_policy(path: Option<&std::path::Path>, project: &str) -> OmegaResult<PrivacyPolicy> {
+    let profiles = match path {
+        Some(_) => Some(load_profiles(path)?),
+        None => ProviderProfiles::from_env()?,
+    };
+    Ok(PrivacyPolicy::resolve(profiles.as_ref(), project, &|k| env::var(k).ok()))
+}
+
+/// Explicit provider: no silent mock fallback, configuration errors are reported
+fn build_provider(name: &str, base_url: Option<String>, model: Option<String>) -> OmegaResult<Arc<dyn LLMProvider>> {
+    let key = |var: &str| env::var(var).ok().filter(|k| !k.is_empty());
+    let with_model = |mut config: ProviderConfig| {
+        if model.is_some() { config.model = model.clone(); }
+        config
+    };
+    match name {
+        "mock" => Ok(Arc::new(MockDeterministicProvider::default())),
+        "openai" => {
+            let api_key = key("OPENAI_API_KEY").ok_or_else(|| OmegaError::ConfigError("OPENAI_API_KEY not set".into()))?;
+            Ok(Arc::new(OpenAIProvider::try_new(&with_model(ProviderConfig::openai(api_key)))?))
+        }
+        "anthropic" => {
+            let api_key = key("ANTHROPIC_API_KEY").ok_or_else(|| OmegaError::ConfigError("ANTHROPIC_API_KEY not set".into()))?;
+            Ok(Arc::new(AnthropicProvider::try_new(&with_model(ProviderConfig::anthropic(api_key)))?))
+        }
+        "openai-compatible" | "local" => {
+            let base_url = base_url.or_else(|| key("OMEGA_LOCAL_BASE_URL"))
+                .ok_or_else(|| OmegaError::ConfigError("--base-url or OMEGA_LOCAL_BASE_URL required".into()))?;
+            let model = model.clone().or_else(|| key("OMEGA_LOCAL_MODEL"))
+                .ok_or_else(|| OmegaError::ConfigError("--model or OMEGA_LOCAL_MODEL required".into()))?;
+            let config = ProviderConfig {
+                capabilities: CapabilityOverrides::from_env(),
+                embedding_model: key("OMEGA_LOCAL_EMBEDDING_MODEL"),
+                ..ProviderConfig::openai_compatible(&base_url, &model, key("OMEGA_LOCAL_API_KEY"))
+            };
+            Ok(Arc::new(OpenAIProvider::try_new_compatible(&config)?))
+        }
+        // Script behind breaker/rate limit/fallback like a real provider: a scripted 429 falls back to the mock (traced)
+        other => match ScriptedProvider::from_spec(other) {
+            Some(script) => Ok(Arc::new(FallbackProvider::with_policy(Some(Arc::new(script?)), None, ResiliencePolicy::from_env()))),
+            None => Err(OmegaError::ConfigError(format!(
+                "Unknown provider '{}'. Use: mock, mock-script:<file>, openai, anthropic, openai-compatible", other
+            ))),
+        },
+    }
+}
+
 fn write_run_artifacts(
     run_dir: &PathBuf,
     result: &PipelineRun,
@@ -208,6 +406,19 @@ fn print_help() {
     eprintln!("    --input-file <FILE>  Path to input text file");
     eprintln!("    --input <TEXT>       Direct input text");
     eprintln!("    --output-dir <DIR>   Output directory (default: runs)");
+    eprintln!("    --provider <NAME>    mock|mock-script:<file>|openai|anthropic|openai-compatible (overrides --mode)");
+    eprintln!("    --base-url <URL>     OpenAI-compatible base URL (default: $OMEGA_LOCAL_BASE_URL)");
+    eprintln!("    --model <NAME>       Model name (default: $OMEGA_LOCAL_MODEL for openai-compatible)");
+    eprintln!("    --profile <NAME>     Provider profile from omega.toml (overrides --mode)");
+    eprintln!("    --fallback-chain     Use the omega.toml [fallback] chain (mock as last resort)");
+    eprintln!("    --config <PATH>      Profiles file (default: $OMEGA_CONFIG or ./omega.toml)");
+    eprintln!("    --replay-mode <M>    AI emotion analysis: off|record|replay (default: off)");
+    eprintln!("    --replay-run <ID>    Run whose emotion.replay.json is replayed (replay mode)");
+    eprintln!("    --project <NAME>     Project charged in the usage ledger, privacy policy [privacy.<NAME>] (default: $OMEGA_PROJECT or default)");
+    eprintln!("    --usage-report       Print a JSON usage report instead of running (see --from/--to/--project)");
+    eprintln!("    --from <YYYY-MM-DD>  Report period start (UTC, inclusive)");
+    eprintln!("    --to <YYYY-MM-DD>    Report period end (UTC, inclusive)");
+    eprintln!("    --diff-prompt <ID> <FROM> <TO>  Print a diff between two prompt template versions ($OMEGA_PROMPTS_DIR)");
     eprintln!("    -h, --help           Show this help");
     eprintln!("");
     eprintln!("OUTPUT:");
@@ -217,6 +428,11 @@ fn print_help() {
     eprintln!("      - manifest.sha256 Hash manifest (MANDATORY)");
     eprintln!("      - logs.txt        Execution logs (MANDATORY)");
     eprintln!("      - input.txt       Original input (OPTIONAL/traceability)");
+    eprintln!("      - emotion.replay.json  AI call record (--replay-mode record)");
+    eprintln!("      - redaction.map.enc    Encrypted pseudonymization map (privacy policy with redact = true)");
+    eprintln!("    Appends every provider call to <output-dir>/usage-ledger.jsonl");
+    eprintln!("    Budget caps: OMEGA_BUDGET_{{RUN,DAY,PROJECT}}_{{TOKENS,USD}}, prices: OMEGA_PRICE_TABLE");
+    eprintln!("    Privacy: OMEGA_PRIVACY_REDACT=1, OMEGA_PRIVACY_LOCAL_ONLY=1, map key: OMEGA_REDACTION_KEY");
     eprintln!("");
     eprintln!("EXIT CODES:");
     eprintln!("    0  SUCCESS");
@@ -5,12 +5,20 @@ use thiserror::Error;
 pub enum OmegaError {
     #[error("OMEGA_AI:PROVIDER_ERROR: {0}")]
     ProviderError(String),
-    #[error("OMEGA_AI:INVALID_RESPONSE: {0}")]
-    InvalidResponse(String),
+    #[error("OMEGA_AI:INVALID_RESPONSE: {message}")]
+    InvalidResponse {
+        message: String,
+        /// Réponse structurée toujours hors schéma après la boucle de réparation
+        schema: Option<Box<crate::ai::schema::ValidationFailure>>,
+    },
     #[error("OMEGA_AI:NOT_SUPPORTED: {0}")]
     NotSupported(String),
     #[error("OMEGA_AI:RATE_LIMIT: {0}")]
     RateLimit(String),
+    #[error("OMEGA_AI:CANCELLED: {0}")]
+    Cancelled(String),
+    #[error("OMEGA_AI:BUDGET_EXCEEDED: {0}")]
+    BudgetExceeded(String),
     #[error("OMEGA_PIPELINE:CANON_FAIL: {0}")]
     CanonFail(String),
     #[error("OMEGA_PIPELINE:PASS_FAILED: {0}")]
@@ -23,6 +31,18 @@ pub enum OmegaError {
     HashMismatch(String),
     #[error("OMEGA_SERDE:JSON_ERROR: {0}")]
     JsonError(String),
+    #[error("OMEGA_CONFIG:INVALID: {0}")]
+    ConfigError(String),
+}
+
+impl OmegaError {
+    pub fn invalid_response(message: impl Into<String>) -> Self {
+        OmegaError::InvalidResponse { message: message.into(), schema: None }
+    }
+    /// Échec définitif de validation: message = résumé, détail structuré conservé
+    pub fn schema_violation(failure: crate::ai::schema::ValidationFailure) -> Self {
+        OmegaError::InvalidResponse { message: failure.to_string(), schema: Some(Box::new(failure)) }
+    }
 }
 
 impl From<std::io::Error> for OmegaError {
@@ -0,0 +1,394 @@
+//! OMEGA HOLOGRAPH — Attribute Binding
+//! LOGIC: rattache un attribut à une entité par proximité syntaxique
+//!
+//! Constructions reconnues:
+//! - attribut du sujet: "Pierre était grand", "Pierre n'était pas grand"
+//! - possession: "Marie avait les cheveux blonds"
+//! - apposition: "Pierre, ce petit homme, entra"
+//! - possessif: "Marie ... ses cheveux blonds" (entité précédente de la phrase)
+//!
+//! "Pierre regarda le grand mur" ne rattache rien à Pierre.
+//! NASA-Grade AS9100D
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use super::{split_sentences, CategoryRule, TextSpan, ValueGroup};
+use crate::lexicon_fr_gold::normalize_fr;
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// TYPES
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Attribut affirmé (ou nié) pour une entité
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttributeAssertion {
+    pub entity: String,
+    pub category: String,
+    /// Trait physique qualifié ("yeux", "peau"...), vide si l'entité elle-même
+    pub facet: String,
+    pub keyword: String,
+    /// Groupe de valeurs (exclusif) auquel appartient le mot-clé
+    pub value: String,
+    /// Attribut nié: "n'était pas grand"
+    pub negated: bool,
+    pub span: TextSpan,
+    pub raw: String,
+}
+
+impl AttributeAssertion {
+    /// Libellé court: "grand", "non petit"
+    pub fn label(&self) -> String {
+        format!("{}{}", if self.negated { "non " } else { "" }, self.keyword)
+    }
+
+    /// Même attribut (entité, catégorie, trait) avec valeurs incompatibles:
+    /// deux groupes exclusifs affirmés, ou un groupe affirmé puis nié
+    pub fn contradicts(&self, other: &AttributeAssertion) -> bool {
+        if self.entity != other.entity || self.category != other.category || self.facet != other.facet {
+            return false;
+        }
+        match (self.negated, other.negated) {
+            (false, false) => self.value != other.value,
+            (true, false) | (false, true) => self.value == other.value,
+            (true, true) => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    word: String,
+    original: String,
+    span: TextSpan,
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// LEXIQUE
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Verbes attributifs (formes normalisées)
+const COPULAS: &[&str] = &[
+    "est", "etait", "fut", "sera", "serait", "semblait", "semble", "paraissait", "parait",
+    "restait", "reste", "demeurait", "devint", "devenait", "devient", "etaient", "sont",
+];
+
+const HAVE: &[&str] = &["a", "avait", "eut", "aura", "aurait", "avaient", "ont"];
+
+const NEGATIONS: &[&str] = &["pas", "plus", "jamais", "point", "guere", "nullement"];
+
+const ADVERBS: &[&str] = &[
+    "tres", "si", "trop", "moins", "bien", "assez", "vraiment", "encore", "deja", "toujours",
+    "tout", "toute", "fort", "peu", "un", "alors", "aussi", "particulierement",
+];
+
+const DETERMINERS: &[&str] = &[
+    "le", "la", "les", "l", "un", "une", "des", "de", "d", "ce", "cet", "cette", "du",
+];
+
+const POSSESSIVES: &[&str] = &["son", "sa", "ses"];
+
+/// Mots qui terminent le groupe attribut
+const STOPS: &[&str] = &[
+    "de", "du", "des", "dans", "sur", "sous", "avec", "pour", "par", "a", "au", "aux", "qui",
+    "que", "quand", "lorsque", "le", "la", "les", "un", "une", "ce", "cette", "mais", "car",
+    "pres", "vers", "chez", "comme", "depuis", "pendant", "il", "elle", "en",
+];
+
+/// Traits physiques: l'attribut qualifie le trait, pas l'entité entière
+const FEATURE_NOUNS: &[&str] = &[
+    "cheveux", "chevelure", "yeux", "regard", "peau", "teint", "visage", "mains", "main",
+    "voix", "barbe", "front", "levres", "corps",
+];
+
+/// Le mot (normalisé) est-il une forme fléchie du mot-clé? (grand → grande, grands)
+fn inflects(word: &str, keyword: &str) -> bool {
+    word == keyword
+        || ["e", "s", "es", "x"].iter().any(|suffix| {
+            word.strip_suffix(suffix).map(|stem| stem == keyword).unwrap_or(false)
+        })
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// BINDER
+// ═══════════════════════════════════════════════════════════════════════════════
+
+pub struct AttributeBinder<'a> {
+    categories: &'a [CategoryRule],
+    word_re: Regex,
+}
+
+impl<'a> AttributeBinder<'a> {
+    pub fn new(categories: &'a [CategoryRule]) -> Self {
+        Self {
+            categories,
+            word_re: Regex::new(r"\p{L}+(?:-\p{L}+)*").unwrap(),
+        }
+    }
+
+    /// Rattache les attributs des catégories aux entités connues
+    pub fn bind(&self, text: &str, entities: &[String]) -> Vec<AttributeAssertion> {
+        let mut assertions = Vec::new();
+        let mut previous_entities: Vec<String> = Vec::new();
+
+        for sentence in split_sentences(text) {
+            let tokens: Vec<Token> = self.word_re.find_iter(&text[sentence.start..sentence.end])
+                .map(|m| Token {
+                    word: normalize_fr(m.as_str()).replace(' ', "-"),
+                    original: m.as_str().to_string(),
+                    span: TextSpan::new(sentence.start + m.start(), sentence.start + m.end()),
+                })
+                .collect();
+            let is_entity = |t: &Token| entities.iter().any(|e| e == &t.original);
+            let mut sentence_entities: Vec<String> = Vec::new();
+
+            for (i, token) in tokens.iter().enumerate() {
+                if is_entity(token) {
+                    sentence_entities.push(token.original.clone());
+                    self.bind_predicate(text, &tokens, i, &mut assertions);
+                    self.bind_apposition(text, &tokens, i, &mut assertions);
+                } else if POSSESSIVES.contains(&token.word.as_str()) {
+                    // Possesseur: dernière entité de la phrase, sinon unique entité de la précédente
+                    let owner = sentence_entities.last().cloned().or_else(|| {
+                        (previous_entities.len() == 1).then(|| previous_entities[0].clone())
+                    });
+                    if let Some(owner) = owner {
+                        self.bind_group(text, &owner, &tokens, i + 1, token.span.start, false, true, &mut assertions);
+                    }
+                }
+            }
+
+            if !sentence_entities.is_empty() {
+                sentence_entities.dedup();
+                previous_entities = sentence_entities;
+            }
+        }
+
+        assertions
+    }
+
+    /// "X (ne) était (pas) ADJ", "X (n')avait (pas) les cheveux ADJ"
+    fn bind_predicate(&self, text: &str, tokens: &[Token], at: usize, out: &mut Vec<AttributeAssertion>) {
+        let entity = &tokens[at].original;
+        let mut j = at + 1;
+        let mut negated = false;
+        let mut reflexive = false;
+        while let Some(t) = tokens.get(j) {
+            match t.word.as_str() {
+                "ne" | "n" => negated = true,
+                "s" | "se" => reflexive = true,
+                _ => break,
+            }
+            j += 1;
+        }
+        let Some(verb) = tokens.get(j) else { return };
+        if has_break(text, &tokens[at], verb) {
+            return;
+        }
+        let copula = COPULAS.contains(&verb.word.as_str());
+        let have = !reflexive && HAVE.contains(&verb.word.as_str());
+        if !copula && !have {
+            return;
+        }
+        j += 1;
+        while let Some(t) = tokens.get(j) {
+            if NEGATIONS.contains(&t.word.as_str()) {
+                negated = true;
+                j += 1;
+            } else {
+                break;
+            }
+        }
+        if have {
+            // La possession passe par un déterminant + trait: "avait les cheveux blonds"
+            match tokens.get(j) {
+                Some(t) if DETERMINERS.contains(&t.word.as_str()) => j += 1,
+                _ => return,
+            }
+        }
+        self.bind_group(text, entity, tokens, j, tokens[at].span.start, negated, have, out);
+    }
+
+    /// "X, ce petit homme, ..." / "X, grand et maigre, ..."
+    fn bind_apposition(&self, text: &str, tokens: &[Token], at: usize, out: &mut Vec<AttributeAssertion>) {
+        let Some(next) = tokens.get(at + 1) else { return };
+        let between = &text[tokens[at].span.end..next.span.start];
+        if between.trim() != "," {
+            return;
+        }
+        let mut j = at + 1;
+        if DETERMINERS.contains(&next.word.as_str()) {
+            j += 1;
+        } else if !self.is_keyword(&next.word) {
+            return;
+        }
+        self.bind_group(text, &tokens[at].original, tokens, j, tokens[at].span.start, false, false, out);
+    }
+
+    /// Lit un groupe adjectival à partir de `from` et produit les assertions.
+    /// `feature_first`: possession, le groupe doit nommer un trait physique
+    #[allow(clippy::too_many_arguments)]
+    fn bind_group(
+        &self,
+        text: &str,
+        entity: &str,
+        tokens: &[Token],
+        from: usize,
+        anchor: usize,
+        negated: bool,
+        feature_first: bool,
+        out: &mut Vec<AttributeAssertion>,
+    ) {
+        let mut facet_noun: Option<String> = None;
+        if feature_first {
+            // Possession: seul un trait physique est qualifié ("ses longs cheveux", pas "sa valise")
+            let feature = tokens[from.min(tokens.len())..].iter().take(3)
+                .find(|t| FEATURE_NOUNS.contains(&t.word.as_str()));
+            match feature {
+                Some(t) => facet_noun = Some(t.word.clone()),
+                None => return,
+            }
+        }
+        let mut j = from;
+        let mut read = 0;
+        while let Some(t) = tokens.get(j) {
+            if j > from && has_break(text, &tokens[j - 1], t) {
+                break;
+            }
+            let w = t.word.as_str();
+            if read >= 6 || (STOPS.contains(&w) && !self.is_keyword(w)) {
+                break;
+            }
+            read += 1;
+            j += 1;
+            if w == "et" || w == "ou" || ADVERBS.contains(&w) {
+                continue;
+            }
+            if FEATURE_NOUNS.contains(&w) {
+                facet_noun = Some(w.to_string());
+                continue;
+            }
+            for (category, group, keyword) in self.matches(w, facet_noun.as_deref()) {
+                let facet = if !category.nouns.is_empty() {
+                    category.name.clone()
+                } else {
+                    facet_noun.clone().unwrap_or_default()
+                };
+                out.push(AttributeAssertion {
+                    entity: entity.to_string(),
+                    category: category.name.clone(),
+                    facet,
+                    keyword: keyword.to_string(),
+                    value: group.name.clone(),
+                    negated,
+                    span: TextSpan::new(anchor, t.span.end),
+                    raw: text[anchor..t.span.end].to_string(),
+                });
+            }
+        }
+    }
+
+    fn is_keyword(&self, word: &str) -> bool {
+        self.categories.iter()
+            .flat_map(|c| c.groups.iter())
+            .any(|g| g.keywords.iter().any(|k| inflects(word, k)))
+    }
+
+    /// Catégories compatibles avec le mot et le trait qualifié.
+    /// Une catégorie restreinte à des noms (cheveux) n'accepte un attribut hors de ces noms
+    /// que si le mot-clé lui est propre (blond, brun).
+    fn matches(&self, word: &str, facet_noun: Option<&str>) -> Vec<(&'a CategoryRule, &'a ValueGroup, &'a str)> {
+        let candidates: Vec<(&CategoryRule, &ValueGroup, &str)> = self.categories.iter()
+            .flat_map(|c| c.groups.iter().map(move |g| (c, g)))
+            .filter_map(|(c, g)| g.keywords.iter().find(|k| inflects(word, k)).map(|k| (c, g, k.as_str())))
+            .collect();
+        let ambiguous = candidates.len() > 1;
+        let restricted = |noun: &str| self.categories.iter().any(|c| c.nouns.iter().any(|n| n == noun));
+
+        candidates.into_iter()
+            .filter(|(c, _, _)| {
+                if c.nouns.is_empty() {
+                    return facet_noun.map(|n| !restricted(n)).unwrap_or(true);
+                }
+                match facet_noun {
+                    Some(n) => c.nouns.iter().any(|x| x == n),
+                    None => !ambiguous,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Ponctuation forte ou virgule entre deux tokens: fin du groupe
+fn has_break(text: &str, a: &Token, b: &Token) -> bool {
+    text[a.span.end..b.span.start].chars().any(|c| matches!(c, ',' | ';' | ':' | '(' | ')' | '—' | '«' | '»' | '"'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::holograph::HolographScanner;
+
+    fn bind(text: &str, entities: &[&str]) -> Vec<AttributeAssertion> {
+        let scanner = HolographScanner::new();
+        let entities: Vec<String> = entities.iter().map(|e| e.to_string()).collect();
+        AttributeBinder::new(scanner.categories()).bind(text, &entities)
+    }
+
+    #[test]
+    fn test_inflects() {
+        assert!(inflects("grande", "grand"));
+        assert!(inflects("grands", "grand"));
+        assert!(!inflects("visage", "age"));
+    }
+
+    #[test]
+    fn test_object_attribute_not_bound() {
+        assert!(bind("Pierre regarda le grand mur.", &["Pierre"]).is_empty());
+    }
+
+    #[test]
+    fn test_predicative() {
+        let a = bind("Pierre etait grand et imposant.", &["Pierre"]);
+        assert_eq!(a.len(), 1);
+        assert_eq!(a[0].label(), "grand");
+        assert_eq!(a[0].value, "grand");
+        assert_eq!(a[0].raw, "Pierre etait grand");
+    }
+
+    #[test]
+    fn test_negated_predicative() {
+        let a = bind("Pierre n'était pas très grand.", &["Pierre"]);
+        assert_eq!(a.len(), 1);
+        assert!(a[0].negated);
+    }
+
+    #[test]
+    fn test_apposition() {
+        let a = bind("Plus tard, Pierre, ce petit homme, entra.", &["Pierre"]);
+        assert_eq!(a.len(), 1);
+        assert_eq!(a[0].label(), "petit");
+        assert_eq!(a[0].value, "petit");
+    }
+
+    #[test]
+    fn test_possessive_feature() {
+        let a = bind("Marie souriait. Ses cheveux blonds brillaient.", &["Marie"]);
+        assert_eq!(a.len(), 1);
+        assert_eq!(a[0].entity, "Marie");
+        assert_eq!(a[0].category, "cheveux");
+    }
+
+    #[test]
+    fn test_feature_facet() {
+        let a = bind("Marie avait les yeux noirs.", &["Marie"]);
+        assert_eq!(a.len(), 1);
+        assert_eq!(a[0].category, "lumiere");
+        assert_eq!(a[0].facet, "yeux");
+    }
+
+    #[test]
+    fn test_possessive_object_ignored() {
+        assert!(bind("Marie posa sa grande valise.", &["Marie"]).is_empty());
+    }
+}
@@ -0,0 +1,874 @@
+//! OMEGA HOLOGRAPH — Character Analyzer
+//! DYNAMICS: cohérence des personnages (genre, titre/rôle, relations, graphie du nom)
+//!
+//! Référence (si disponible): noms propres de la Bible + faits CANON
+//! (entity CHAR:<Nom>, keys character.gender / character.role / character.relation.<Autre>).
+//! Sans référence, la forme majoritaire dans le texte fait foi.
+//! NASA-Grade AS9100D
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+use super::{split_sentences, CoherenceIssue, IssueType, Severity, TextSpan};
+use crate::interfaces::canon::CanonFact;
+use crate::lexicon_fr_gold::{normalize_fr, UserOverrides};
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// CANON NOMENCLATURE
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Entity ID CANON d'un personnage
+pub fn character_entity(name: &str) -> String {
+    format!("CHAR:{}", name)
+}
+
+/// Key: genre grammatical ("F" / "M")
+pub fn k_gender() -> &'static str {
+    "character.gender"
+}
+
+/// Key: titre ou rôle (capitaine, docteur, comtesse...)
+pub fn k_role() -> &'static str {
+    "character.role"
+}
+
+/// Key: relation envers un autre personnage (valeur: "soeur", "pere"...)
+pub fn k_relation(other: &str) -> String {
+    format!("character.relation.{}", other)
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// TYPES
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Gender {
+    Feminine,
+    Masculine,
+}
+
+impl Gender {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Gender::Feminine => "feminin",
+            Gender::Masculine => "masculin",
+        }
+    }
+
+    /// Valeur CANON ("F", "feminin", "female"...) → Gender
+    pub fn parse(value: &str) -> Option<Gender> {
+        match normalize_fr(value).as_str() {
+            "f" | "feminin" | "female" | "femme" => Some(Gender::Feminine),
+            "m" | "masculin" | "male" | "homme" => Some(Gender::Masculine),
+            _ => None,
+        }
+    }
+}
+
+/// Faits de référence d'un personnage (CANON)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CharacterFacts {
+    pub name: String,
+    pub gender: Option<Gender>,
+    pub role: Option<String>,
+    /// Autre personnage → relation ("Pierre" → "soeur")
+    pub relations: BTreeMap<String, String>,
+}
+
+/// Référentiel des personnages: Bible (noms propres) + CANON
+#[derive(Debug, Clone, Default)]
+pub struct CharacterReference {
+    /// Noms propres de la Bible (normalisés)
+    pub proper_nouns: Vec<String>,
+    /// Faits par personnage (clé: nom normalisé)
+    pub facts: BTreeMap<String, CharacterFacts>,
+}
+
+impl CharacterReference {
+    /// Noms propres déclarés dans la Bible utilisateur
+    pub fn from_overrides(overrides: &UserOverrides) -> Self {
+        Self {
+            proper_nouns: overrides.proper_nouns.iter().map(|r| normalize_fr(&r.token)).collect(),
+            facts: BTreeMap::new(),
+        }
+    }
+
+    /// Ajoute les faits CANON des entités CHAR:<Nom>
+    pub fn with_canon_facts(mut self, facts: &[CanonFact]) -> Self {
+        for fact in facts {
+            let Some(name) = fact.entity_id.strip_prefix("CHAR:") else { continue };
+            let Some(value) = fact.value.as_str() else { continue };
+            let entry = self.facts.entry(normalize_fr(name)).or_insert_with(|| CharacterFacts {
+                name: name.to_string(),
+                ..Default::default()
+            });
+            if fact.key == k_gender() {
+                entry.gender = Gender::parse(value);
+            } else if fact.key == k_role() {
+                entry.role = Some(role_base(&normalize_fr(value)));
+            } else if let Some(other) = fact.key.strip_prefix("character.relation.") {
+                entry.relations.insert(normalize_fr(other), relation_kind(&normalize_fr(value)).to_string());
+            }
+        }
+        self
+    }
+
+    fn knows(&self, normalized: &str) -> bool {
+        self.proper_nouns.iter().any(|n| n == normalized) || self.facts.contains_key(normalized)
+    }
+}
+
+/// Profil consolidé d'un personnage après lecture du texte
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CharacterProfile {
+    pub name: String,
+    pub variants: Vec<String>,
+    pub mentions: usize,
+    pub gender: Option<Gender>,
+    pub role: Option<String>,
+    pub relations: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone)]
+struct Evidence<T> {
+    value: T,
+    raw: String,
+    span: TextSpan,
+    /// Forte: civilité, déterminant + titre, accord, nom de parenté.
+    /// Faible: pronom de la phrase suivante.
+    strong: bool,
+}
+
+#[derive(Debug, Default)]
+struct Tracking {
+    gender: Vec<Evidence<Gender>>,
+    roles: Vec<Evidence<String>>,
+    relations: BTreeMap<String, Vec<Evidence<String>>>,
+}
+
+#[derive(Debug, Clone)]
+struct NameOccurrence {
+    name: String,
+    span: TextSpan,
+    sentence: usize,
+    begin: bool,
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// LEXIQUE
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Mots capitalisés courants en tête de phrase (jamais des personnages)
+pub(crate) const STOPWORDS: &[&str] = &[
+    "Le", "La", "Les", "Un", "Une", "Des", "Du", "De", "Au", "Aux", "Il", "Elle", "Ils", "Elles",
+    "Ce", "Cette", "Ces", "Cet", "Son", "Sa", "Ses", "Mon", "Ma", "Mes", "Leur", "Leurs", "Je",
+    "Tu", "On", "Nous", "Vous", "Et", "Mais", "Puis", "Alors", "Quand", "Lorsque", "Si", "Comme",
+    "Dans", "Sur", "Sous", "Avec", "Sans", "Pour", "Par", "Chez", "Depuis", "Pendant", "Plus",
+    "Tout", "Enfin", "Soudain", "Ensuite", "Monsieur", "Madame", "Mademoiselle", "Oui", "Non",
+    "Hier", "Demain", "Aujourd", "Ici", "Parfois", "Toujours", "Jamais", "Pourquoi", "Comment",
+    "Quoi", "Qui", "Que", "Lui", "Eux", "Cependant", "Pourtant", "Ainsi", "Donc", "Tandis",
+    "Apres", "Après", "Avant", "Encore", "Deja", "Déjà", "Maintenant", "Certes", "Bien",
+];
+
+/// Verbes impersonnels après "Il" (pas un pronom de personnage)
+const IMPERSONAL: [&str; 12] = [
+    "y", "faisait", "fait", "fallait", "faut", "pleuvait", "pleut", "neigeait", "s'agissait",
+    "semblait", "restait", "etait",
+];
+
+pub(crate) const NAME: &str = r"[A-ZÀÂÄÉÈÊËÎÏÔÖÙÛÜÇ][a-zàâäéèêëîïôöùûüç]+(?:-[A-ZÀÂÄÉÈÊËÎÏÔÖÙÛÜÇ][a-zàâäéèêëîïôöùûüç]+)?";
+
+const ROLES: &str = r"capitaine|lieutenante?|commandante?|colonel|g[eé]n[eé]rale?|sergent|caporal|amiral|commissaire|inspect(?:eur|rice)|doct(?:eure?|oresse)|professeure?|ma[iî]tre|juge|ministre|pr[eé]sidente?|vicomtesse|vicomte|comtesse|comte|duchesse|duc|baronne|baron|marquise|marquis|reine|roi|princesse|prince";
+
+const CIVILITIES: &str = r"Monsieur|Madame|Mademoiselle|Mme|Mlle|M\.";
+
+const RELATIONS: &str = r"grand-m[eè]re|grand-p[eè]re|s[oœ]eur|fr[eè]re|m[eè]re|p[eè]re|fille|fils|tante|oncle|cousine|cousin|ni[eè]ce|neveu|[eé]pouse|[eé]poux|femme|mari|fianc[eé]e|fianc[eé]|veuve|veuf";
+
+/// Forme de base d'un titre (comtesse → comte, inspectrice → inspecteur)
+fn role_base(role: &str) -> String {
+    let r = normalize_fr(role);
+    let base = match r.as_str() {
+        "lieutenante" => "lieutenant",
+        "commandante" => "commandant",
+        "generale" => "general",
+        "inspectrice" => "inspecteur",
+        "docteure" | "doctoresse" => "docteur",
+        "professeure" => "professeur",
+        "presidente" => "president",
+        "vicomtesse" => "vicomte",
+        "comtesse" => "comte",
+        "duchesse" => "duc",
+        "baronne" => "baron",
+        "marquise" => "marquis",
+        "reine" => "roi",
+        "princesse" => "prince",
+        _ => r.as_str(),
+    };
+    base.to_string()
+}
+
+/// Genre porté par le titre lui-même (None = épicène)
+fn role_gender(role: &str) -> Option<Gender> {
+    match normalize_fr(role).as_str() {
+        "lieutenante" | "commandante" | "generale" | "inspectrice" | "docteure" | "doctoresse"
+        | "professeure" | "presidente" | "vicomtesse" | "comtesse" | "duchesse" | "baronne"
+        | "marquise" | "reine" | "princesse" => Some(Gender::Feminine),
+        "lieutenant" | "commandant" | "colonel" | "general" | "sergent" | "caporal" | "amiral"
+        | "inspecteur" | "president" | "vicomte" | "comte" | "duc" | "baron" | "marquis" | "roi"
+        | "prince" => Some(Gender::Masculine),
+        _ => None,
+    }
+}
+
+/// Type de relation, indépendant du genre (soeur/frere → fratrie)
+fn relation_kind(relation: &str) -> &'static str {
+    match normalize_fr(relation).as_str() {
+        "soeur" | "frere" => "fratrie",
+        "mere" | "pere" => "parent",
+        "fille" | "fils" => "enfant",
+        "tante" | "oncle" => "oncle/tante",
+        "cousine" | "cousin" => "cousin",
+        "niece" | "neveu" => "neveu/niece",
+        "epouse" | "epoux" | "femme" | "mari" => "conjoint",
+        "fiancee" | "fiance" => "fiance",
+        "grand mere" | "grand pere" => "grand-parent",
+        "veuve" | "veuf" => "veuvage",
+        _ => "autre",
+    }
+}
+
+fn relation_gender(relation: &str) -> Gender {
+    match normalize_fr(relation).as_str() {
+        "soeur" | "mere" | "fille" | "tante" | "cousine" | "niece" | "epouse" | "femme"
+        | "fiancee" | "grand mere" | "veuve" => Gender::Feminine,
+        _ => Gender::Masculine,
+    }
+}
+
+/// Genre d'un adjectif ou participe en position d'attribut
+fn adjective_gender(word: &str) -> Option<Gender> {
+    let w = word.to_lowercase();
+    let feminine = ["ée", "euse", "ive", "ienne", "trice", "elle", "ette", "onne", "ière"];
+    let masculine = ["é", "eux", "if", "ien", "teur", "el", "ier"];
+    if feminine.iter().any(|s| w.ends_with(s)) || (w.ends_with("ee") && w.chars().count() > 4) {
+        Some(Gender::Feminine)
+    } else if masculine.iter().any(|s| w.ends_with(s)) {
+        Some(Gender::Masculine)
+    } else {
+        None
+    }
+}
+
+/// Distance d'édition (Levenshtein) sur les caractères
+pub(crate) fn levenshtein(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut curr = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = if ca == cb { 0 } else { 1 };
+            curr[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(curr[j] + 1);
+        }
+        prev = curr;
+    }
+    prev[b.len()]
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// ANALYZER
+// ═══════════════════════════════════════════════════════════════════════════════
+
+pub struct CharacterAnalyzer {
+    reference: CharacterReference,
+    name_re: Regex,
+    title_re: Regex,
+    agreement_re: Regex,
+    pronoun_re: Regex,
+    possessive_re: Regex,
+    apposition_re: Regex,
+}
+
+impl Default for CharacterAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CharacterAnalyzer {
+    pub fn new() -> Self {
+        Self::with_reference(CharacterReference::default())
+    }
+
+    pub fn with_reference(reference: CharacterReference) -> Self {
+        Self {
+            reference,
+            name_re: Regex::new(&format!(r"\b{}\b", NAME)).unwrap(),
+            title_re: Regex::new(&format!(
+                r"(?:\b(?i:(?P<det>le|la|l['’]|du|au)\s*))?\b(?P<title>(?i:{})|{})\s+(?P<name>{})\b",
+                ROLES, CIVILITIES, NAME
+            )).unwrap(),
+            agreement_re: Regex::new(&format!(
+                r"\b(?P<name>{})\s+(?:[eé]tait|est|fut|semblait|paraissait|restait|demeurait|devint|devenait|s['’][eé]tait|s['’]est|s['’][eé]taient)\s+(?:(?:tr[eè]s|si|trop|plus|moins|bien|toute?|d[eé]j[aà]|encore)\s+)?(?P<adj>[a-zàâäéèêëîïôöùûüç]+)\b",
+                NAME
+            )).unwrap(),
+            pronoun_re: Regex::new(r#"^[\s«"“—–-]*(?P<pron>Il|Elle)\s+(?P<next>[\w'’]+)"#).unwrap(),
+            possessive_re: Regex::new(&format!(
+                r"\b(?i:sa|son)\s+(?P<rel>(?i:{}))\s*,?\s+(?P<name>{})\b",
+                RELATIONS, NAME
+            )).unwrap(),
+            apposition_re: Regex::new(&format!(
+                r"\b(?P<name>{})\s*,\s*(?i:la|le|l['’])\s*(?P<rel>(?i:{}))\s+(?i:de|du|d['’])\s*(?P<owner>{})\b",
+                NAME, RELATIONS, NAME
+            )).unwrap(),
+        }
+    }
+
+    /// Analyse complète: retourne les incohérences de personnages
+    pub fn analyze(&self, text: &str) -> Vec<CoherenceIssue> {
+        let (aliases, mut issues) = self.resolve_names(text);
+        let tracking = self.track(text, &aliases);
+
+        let mut names: Vec<&String> = tracking.keys().collect();
+        names.sort();
+        for name in names {
+            let t = &tracking[name];
+            let facts = self.reference.facts.get(&normalize_fr(name));
+            issues.extend(self.check_gender(name, &t.gender, facts.and_then(|f| f.gender)));
+            issues.extend(self.check_role(name, &t.roles, facts.and_then(|f| f.role.clone())));
+            for (owner, evidences) in &t.relations {
+                let expected = facts.and_then(|f| f.relations.get(&normalize_fr(owner)).cloned());
+                issues.extend(self.check_relation(name, owner, evidences, expected));
+            }
+        }
+
+        issues
+    }
+
+    /// Profils consolidés (forme majoritaire ou CANON)
+    pub fn profiles(&self, text: &str) -> Vec<CharacterProfile> {
+        let (aliases, _) = self.resolve_names(text);
+        let tracking = self.track(text, &aliases);
+        let occurrences = self.occurrences(text, &split_sentences(text));
+
+        let mut profiles: Vec<CharacterProfile> = self.candidates(&occurrences).into_iter()
+            .filter(|(name, _)| !aliases.contains_key(name))
+            .map(|(name, mentions)| {
+                let variants: Vec<String> = aliases.iter()
+                    .filter(|(_, canonical)| **canonical == name)
+                    .map(|(variant, _)| variant.clone())
+                    .collect();
+                let mentions = mentions + variants.iter()
+                    .map(|v| occurrences.iter().filter(|o| &o.name == v).count())
+                    .sum::<usize>();
+                let t = tracking.get(&name);
+                CharacterProfile {
+                    gender: t.and_then(|t| majority(&t.gender)),
+                    role: t.and_then(|t| t.roles.first().map(|r| r.value.clone())),
+                    relations: t.map(|t| t.relations.iter()
+                        .filter_map(|(owner, ev)| ev.first().map(|e| (owner.clone(), e.value.clone())))
+                        .collect()).unwrap_or_default(),
+                    name,
+                    variants,
+                    mentions,
+                }
+            })
+            .collect();
+        profiles.sort_by(|a, b| b.mentions.cmp(&a.mentions).then_with(|| a.name.cmp(&b.name)));
+        profiles
+    }
+
+    fn occurrences(&self, text: &str, sentences: &[TextSpan]) -> Vec<NameOccurrence> {
+        let mut occurrences = Vec::new();
+        for (idx, sentence) in sentences.iter().enumerate() {
+            let s = &text[sentence.start..sentence.end];
+            let first_word = s.find(|c: char| c.is_alphabetic()).unwrap_or(0);
+            for m in self.name_re.find_iter(s) {
+                occurrences.push(NameOccurrence {
+                    name: m.as_str().to_string(),
+                    span: TextSpan::new(sentence.start + m.start(), sentence.start + m.end()),
+                    sentence: idx,
+                    begin: m.start() == first_word,
+                });
+            }
+        }
+        occurrences
+    }
+
+    /// Noms retenus comme personnages: au moins une occurrence hors tête de phrase,
+    /// répétés, ou déclarés dans la Bible / le CANON
+    fn candidates(&self, occurrences: &[NameOccurrence]) -> Vec<(String, usize)> {
+        let mut counts: BTreeMap<String, (usize, bool)> = BTreeMap::new();
+        for o in occurrences {
+            if STOPWORDS.contains(&o.name.as_str()) {
+                continue;
+            }
+            let entry = counts.entry(o.name.clone()).or_insert((0, false));
+            entry.0 += 1;
+            entry.1 |= !o.begin || self.reference.knows(&normalize_fr(&o.name));
+        }
+        counts.into_iter()
+            .filter(|(_, (count, accepted))| *accepted || *count >= 2)
+            .map(|(name, (count, _))| (name, count))
+            .collect()
+    }
+
+    /// Détecte les variantes de graphie et construit la table variante → forme canonique
+    fn resolve_names(&self, text: &str) -> (HashMap<String, String>, Vec<CoherenceIssue>) {
+        let occurrences = self.occurrences(text, &split_sentences(text));
+        let candidates = self.candidates(&occurrences);
+        let mut aliases: HashMap<String, String> = HashMap::new();
+        let mut issues = Vec::new();
+
+        for (variant, variant_count) in &candidates {
+            let variant_norm = normalize_fr(variant);
+            let variant_known = self.reference.knows(&variant_norm)
+                && !self.reference.facts.get(&variant_norm).map(|f| &f.name != variant).unwrap_or(false);
+            if variant_known {
+                continue;
+            }
+
+            let best = candidates.iter()
+                .filter(|(other, _)| other != variant)
+                .filter_map(|(other, other_count)| {
+                    let other_norm = normalize_fr(other);
+                    let len = variant_norm.chars().count().max(other_norm.chars().count());
+                    let distance = levenshtein(&variant.to_lowercase(), &other.to_lowercase());
+                    let threshold = if len <= 6 { 1 } else { 2 };
+                    if len < 4 || distance == 0 || distance > threshold {
+                        return None;
+                    }
+                    // Nom de référence connu, sinon la variante doit être nettement plus rare
+                    let rarer = *variant_count * 3 <= *other_count;
+                    (self.reference.knows(&other_norm) || rarer)
+                        .then_some((other.clone(), distance, *other_count))
+                })
+                .max_by(|a, b| a.2.cmp(&b.2).then_with(|| b.1.cmp(&a.1)));
+
+            let canonical = self.reference.facts.get(&variant_norm)
+                .filter(|f| &f.name != variant)
+                .map(|f| (f.name.clone(), 0, 0))
+                .or(best);
+
+            if let Some((canonical, _, _)) = canonical {
+                let first_canonical = occurrences.iter().find(|o| o.name == canonical);
+                let variant_occ: Vec<&NameOccurrence> = occurrences.iter().filter(|o| &o.name == variant).collect();
+                let accent_only = normalize_fr(&canonical) == variant_norm;
+
+                let mut spans: Vec<TextSpan> = first_canonical.map(|o| vec![o.span]).unwrap_or_default();
+                spans.extend(variant_occ.iter().map(|o| o.span));
+                let mut evidence: Vec<String> = first_canonical
+                    .map(|o| vec![format!("{} @{}", o.name, o.span.start)])
+                    .unwrap_or_default();
+                evidence.extend(variant_occ.iter().map(|o| format!("{} @{}", o.name, o.span.start)));
+
+                issues.push(CoherenceIssue {
+                    issue_type: IssueType::CharacterInconsistency,
+                    severity: if accent_only { Severity::Low } else { Severity::Medium },
+                    description: format!(
+                        "Graphie du nom incoherente: '{}' ({} occurrence(s)) au lieu de '{}'",
+                        variant, variant_occ.len(), canonical
+                    ),
+                    evidence,
+                    location: Some(format!("Personnage: {}", canonical)),
+                    suggestion: Some(format!("Uniformiser l'orthographe: {}", canonical)),
+                    spans,
+                    entity: Some(canonical.clone()),
+                    category: Some("graphie".to_string()),
+                    fingerprint: String::new(),
+                });
+                aliases.insert(variant.clone(), canonical);
+            }
+        }
+
+        (aliases, issues)
+    }
+
+    /// Collecte les indices (genre, titre, relations) par personnage canonique
+    fn track(&self, text: &str, aliases: &HashMap<String, String>) -> HashMap<String, Tracking> {
+        let sentences = split_sentences(text);
+        let occurrences = self.occurrences(text, &sentences);
+        let characters: Vec<String> = self.candidates(&occurrences).into_iter().map(|(n, _)| n).collect();
+        let canonical = |name: &str| -> Option<String> {
+            if !characters.iter().any(|c| c == name) {
+                return None;
+            }
+            Some(aliases.get(name).cloned().unwrap_or_else(|| name.to_string()))
+        };
+        let mut tracking: HashMap<String, Tracking> = HashMap::new();
+
+        for (idx, sentence) in sentences.iter().enumerate() {
+            let s = &text[sentence.start..sentence.end];
+            let span_of = |start: usize, end: usize| TextSpan::new(sentence.start + start, sentence.start + end);
+
+            // Titres et civilités: "la capitaine Mathilde", "Madame Roux"
+            for cap in self.title_re.captures_iter(s) {
+                let Some(name) = canonical(&cap["name"]) else { continue };
+                let m = cap.get(0).unwrap();
+                let title = cap["title"].to_string();
+                let t = tracking.entry(name).or_default();
+                let civility_gender = match title.as_str() {
+                    "Monsieur" | "M." => Some(Gender::Masculine),
+                    "Madame" | "Mademoiselle" | "Mme" | "Mlle" => Some(Gender::Feminine),
+                    _ => None,
+                };
+                let det_gender = cap.name("det").and_then(|d| match d.as_str().to_lowercase().as_str() {
+                    "la" => Some(Gender::Feminine),
+                    "le" | "du" | "au" => Some(Gender::Masculine),
+                    _ => None,
+                });
+                if let Some(g) = civility_gender.or(role_gender(&title)).or(det_gender) {
+                    t.gender.push(Evidence { value: g, raw: m.as_str().to_string(), span: span_of(m.start(), m.end()), strong: true });
+                }
+                if civility_gender.is_none() {
+                    t.roles.push(Evidence { value: role_base(&title), raw: m.as_str().to_string(), span: span_of(m.start(), m.end()), strong: true });
+                }
+            }
+
+            // Accords: "Mathilde était fatiguée", "Pierre s'est levé"
+            for cap in self.agreement_re.captures_iter(s) {
+                let Some(name) = canonical(&cap["name"]) else { continue };
+                let Some(g) = adjective_gender(&cap["adj"]) else { continue };
+                let m = cap.get(0).unwrap();
+                tracking.entry(name).or_default().gender.push(Evidence {
+                    value: g, raw: m.as_str().to_string(), span: span_of(m.start(), m.end()), strong: true,
+                });
+            }
+
+            // Relations: "sa soeur Mathilde", "Mathilde, la soeur de Pierre"
+            let sentence_occ: Vec<&NameOccurrence> = occurrences.iter().filter(|o| o.sentence == idx).collect();
+            for cap in self.possessive_re.captures_iter(s) {
+                let Some(name) = canonical(&cap["name"]) else { continue };
+                let m = cap.get(0).unwrap();
+                let owner = sentence_occ.iter()
+                    .filter(|o| o.span.start < sentence.start + m.start())
+                    .filter_map(|o| canonical(&o.name))
+                    .rfind(|o| *o != name);
+                self.push_relation(&mut tracking, name, owner, &cap["rel"], m.as_str(), span_of(m.start(), m.end()));
+            }
+            for cap in self.apposition_re.captures_iter(s) {
+                let (Some(name), Some(owner)) = (canonical(&cap["name"]), canonical(&cap["owner"])) else { continue };
+                let m = cap.get(0).unwrap();
+                self.push_relation(&mut tracking, name, Some(owner), &cap["rel"], m.as_str(), span_of(m.start(), m.end()));
+            }
+
+            // Pronom sujet de la phrase suivante, si un seul personnage est le sujet ici
+            let distinct: Vec<String> = {
+                let mut v: Vec<String> = sentence_occ.iter().filter_map(|o| canonical(&o.name)).collect();
+                v.dedup();
+                v
+            };
+            let subject_first = sentence_occ.iter()
+                .find(|o| canonical(&o.name).is_some())
+                .map(|o| s[..o.span.start - sentence.start].split_whitespace().count() <= 2)
+                .unwrap_or(false);
+            if distinct.len() == 1 && subject_first {
+                if let Some(next) = sentences.get(idx + 1) {
+                    let ns = &text[next.start..next.end];
+                    if let Some(cap) = self.pronoun_re.captures(ns) {
+                        let verb = normalize_fr(&cap["next"].replace('’', "'"));
+                        if !IMPERSONAL.contains(&verb.as_str()) && !IMPERSONAL.contains(&cap["next"].to_lowercase().as_str()) {
+                            let pron = cap.name("pron").unwrap();
+                            let g = if pron.as_str() == "Elle" { Gender::Feminine } else { Gender::Masculine };
+                            tracking.entry(distinct[0].clone()).or_default().gender.push(Evidence {
+                                value: g,
+                                raw: ns.trim().chars().take(60).collect(),
+                                span: TextSpan::new(next.start + pron.start(), next.start + pron.end()),
+                                strong: false,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+
+        for t in tracking.values_mut() {
+            t.gender.sort_by_key(|e| e.span.start);
+            t.roles.sort_by_key(|e| e.span.start);
+        }
+        tracking
+    }
+
+    fn push_relation(
+        &self,
+        tracking: &mut HashMap<String, Tracking>,
+        name: String,
+        owner: Option<String>,
+        relation: &str,
+        raw: &str,
+        span: TextSpan,
+    ) {
+        let t = tracking.entry(name).or_default();
+        t.gender.push(Evidence { value: relation_gender(relation), raw: raw.to_string(), span, strong: true });
+        if let Some(owner) = owner {
+            t.relations.entry(owner).or_default().push(Evidence {
+                value: normalize_fr(relation),
+                raw: raw.to_string(),
+                span,
+                strong: true,
+            });
+        }
+    }
+
+    fn check_gender(&self, name: &str, evidences: &[Evidence<Gender>], reference: Option<Gender>) -> Option<CoherenceIssue> {
+        let (expected, source) = match reference {
+            Some(g) => (g, "CANON"),
+            None => (majority(evidences)?, "majoritaire"),
+        };
+        let conflicting: Vec<&Evidence<Gender>> = evidences.iter().filter(|e| e.value != expected).collect();
+        let strong = conflicting.iter().any(|e| e.strong);
+        if conflicting.is_empty() || (!strong && conflicting.len() < 2) {
+            return None;
+        }
+
+        let support = evidences.iter().find(|e| e.value == expected);
+        let mut spans: Vec<TextSpan> = support.map(|e| vec![e.span]).unwrap_or_default();
+        spans.extend(conflicting.iter().map(|e| e.span));
+        let mut evidence: Vec<String> = support
+            .map(|e| vec![format!("{}: {} @{}", expected.label(), e.raw, e.span.start)])
+            .unwrap_or_default();
+        evidence.extend(conflicting.iter().map(|e| format!("{}: {} @{}", e.value.label(), e.raw, e.span.start)));
+
+        Some(CoherenceIssue {
+            issue_type: IssueType::CharacterInconsistency,
+            severity: if reference.is_some() { Severity::High } else { Severity::Medium },
+            description: format!(
+                "Genre incoherent pour '{}': {} ({}) mais {} indice(s) {}",
+                name, expected.label(), source, conflicting.len(), conflicting[0].value.label()
+            ),
+            evidence,
+            location: Some(format!("Personnage: {}", name)),
+            suggestion: Some(format!("Verifier les accords et pronoms de {}", name)),
+            spans,
+            entity: Some(name.to_string()),
+            category: Some("genre".to_string()),
+            fingerprint: String::new(),
+        })
+    }
+
+    fn check_role(&self, name: &str, evidences: &[Evidence<String>], reference: Option<String>) -> Option<CoherenceIssue> {
+        let (expected, source) = match reference {
+            Some(r) => (r, "CANON"),
+            None => (evidences.first()?.value.clone(), "premiere mention"),
+        };
+        let conflicting: Vec<&Evidence<String>> = evidences.iter().filter(|e| e.value != expected).collect();
+        if conflicting.is_empty() {
+            return None;
+        }
+
+        let support = evidences.iter().find(|e| e.value == expected);
+        let mut spans: Vec<TextSpan> = support.map(|e| vec![e.span]).unwrap_or_default();
+        spans.extend(conflicting.iter().map(|e| e.span));
+        let mut evidence: Vec<String> = support.map(|e| vec![format!("{} @{}", e.raw, e.span.start)]).unwrap_or_default();
+        evidence.extend(conflicting.iter().map(|e| format!("{} @{}", e.raw, e.span.start)));
+
+        Some(CoherenceIssue {
+            issue_type: IssueType::CharacterInconsistency,
+            severity: Severity::Medium,
+            description: format!(
+                "Titre incoherent pour '{}': {} ({}) puis {}",
+                name, expected, source,
+                conflicting.iter().map(|e| e.value.as_str()).collect::<Vec<_>>().join(", ")
+            ),
+            evidence,
+            location: Some(format!("Personnage: {}", name)),
+            suggestion: Some(format!("Verifier le titre de {} (promotion non racontee?)", name)),
+            spans,
+            entity: Some(name.to_string()),
+            category: Some("titre".to_string()),
+            fingerprint: String::new(),
+        })
+    }
+
+    fn check_relation(
+        &self,
+        name: &str,
+        owner: &str,
+        evidences: &[Evidence<String>],
+        reference: Option<String>,
+    ) -> Option<CoherenceIssue> {
+        let (expected, source) = match reference {
+            Some(kind) => (kind, "CANON"),
+            None => (relation_kind(&evidences.first()?.value).to_string(), "premiere mention"),
+        };
+        let conflicting: Vec<&Evidence<String>> = evidences.iter()
+            .filter(|e| relation_kind(&e.value) != expected)
+            .collect();
+        if conflicting.is_empty() {
+            return None;
+        }
+
+        let support = evidences.iter().find(|e| relation_kind(&e.value) == expected);
+        let mut spans: Vec<TextSpan> = support.map(|e| vec![e.span]).unwrap_or_default();
+        spans.extend(conflicting.iter().map(|e| e.span));
+        let mut evidence: Vec<String> = support.map(|e| vec![format!("{} @{}", e.raw, e.span.start)]).unwrap_or_default();
+        evidence.extend(conflicting.iter().map(|e| format!("{} @{}", e.raw, e.span.start)));
+
+        Some(CoherenceIssue {
+            issue_type: IssueType::CharacterInconsistency,
+            severity: Severity::High,
+            description: format!(
+                "Relation incoherente: '{}' envers '{}': {} ({}) puis {}",
+                name, owner, expected, source,
+                conflicting.iter().map(|e| e.value.as_str()).collect::<Vec<_>>().join(", ")
+            ),
+            evidence,
+            location: Some(format!("Personnage: {}", name)),
+            suggestion: Some(format!("Verifier le lien de parente entre {} et {}", name, owner)),
+            spans,
+            entity: Some(name.to_string()),
+            category: Some("relation".to_string()),
+            fingerprint: String::new(),
+        })
+    }
+}
+
+/// Genre majoritaire pondéré (fort = 2, faible = 1), None si indécis
+fn majority(evidences: &[Evidence<Gender>]) -> Option<Gender> {
+    let weight = |g: Gender| -> usize {
+        evidences.iter().filter(|e| e.value == g).map(|e| if e.strong { 2 } else { 1 }).sum()
+    };
+    let (f, m) = (weight(Gender::Feminine), weight(Gender::Masculine));
+    if f >= 2 && f > m {
+        Some(Gender::Feminine)
+    } else if m >= 2 && m > f {
+        Some(Gender::Masculine)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interfaces::canon::{FactSource, LockLevel};
+    use crate::modules::canon::CanonJsonStore;
+
+    fn canon(entity: &str, key: &str, value: &str) -> CanonFact {
+        CanonJsonStore::create_fact(entity, key, serde_json::json!(value), FactSource::User, LockLevel::Hard)
+    }
+
+    #[test]
+    fn test_levenshtein() {
+        assert_eq!(levenshtein("mathilde", "matilde"), 1);
+        assert_eq!(levenshtein("pierre", "pierre"), 0);
+        assert_eq!(levenshtein("", "abc"), 3);
+    }
+
+    #[test]
+    fn test_name_spelling_variant() {
+        let text = "Mathilde ouvrit la porte. Pierre regarda Mathilde. Puis Mathilde sourit. Enfin Matilde partit.";
+        let issues = CharacterAnalyzer::new().analyze(text);
+        let variant = issues.iter().find(|i| i.description.contains("Graphie")).expect("variant expected");
+        assert!(variant.description.contains("'Matilde'"));
+        assert!(variant.spans.iter().any(|s| &text[s.start..s.end] == "Matilde"));
+    }
+
+    #[test]
+    fn test_distinct_names_not_variants() {
+        let text = "Marie parla a Maria. Ensuite Maria repondit a Marie. Puis Marie et Maria partirent.";
+        let issues = CharacterAnalyzer::new().analyze(text);
+        assert!(issues.iter().all(|i| !i.description.contains("Graphie")), "{:?}", issues);
+    }
+
+    #[test]
+    fn test_bible_name_is_reference() {
+        let mut overrides = UserOverrides::default();
+        overrides.proper_nouns.push(crate::lexicon_fr_gold::ProperNounRule {
+            token: "Matilde".into(),
+            type_: "character".into(),
+            decision: String::new(),
+            source: String::new(),
+            date: String::new(),
+            example: String::new(),
+            rationale: String::new(),
+        });
+        let analyzer = CharacterAnalyzer::with_reference(CharacterReference::from_overrides(&overrides));
+        let text = "Mathilde ouvrit la porte. Pierre regarda Mathilde. Puis Mathilde sourit. Enfin Matilde partit.";
+        let issues = analyzer.analyze(text);
+        let variant = issues.iter().find(|i| i.description.contains("Graphie")).expect("variant expected");
+        assert!(variant.description.contains("'Mathilde'"), "{}", variant.description);
+    }
+
+    #[test]
+    fn test_gender_drift_agreement() {
+        let text = "Puis Mathilde etait fatiguee. Ensuite Mathilde s'etait levee. Enfin Mathilde etait arrivé.";
+        let issues = CharacterAnalyzer::new().analyze(text);
+        let gender = issues.iter().find(|i| i.description.contains("Genre")).expect("gender issue expected");
+        assert!(gender.description.contains("feminin"));
+        assert!(gender.spans.len() >= 2);
+    }
+
+    #[test]
+    fn test_gender_drift_against_canon() {
+        let reference = CharacterReference::default()
+            .with_canon_facts(&[canon("CHAR:Mathilde", k_gender(), "F")]);
+        let analyzer = CharacterAnalyzer::with_reference(reference);
+        let text = "Le docteur regarda Mathilde. Puis Mathilde etait fatigué.";
+        let issues = analyzer.analyze(text);
+        let gender = issues.iter().find(|i| i.description.contains("Genre")).expect("gender issue expected");
+        assert_eq!(gender.severity, Severity::High);
+        assert!(gender.description.contains("CANON"));
+    }
+
+    #[test]
+    fn test_pronoun_evidence() {
+        let text = "Puis Mathilde entra. Elle posa son sac. Ensuite Mathilde sortit. Il referma la porte. Enfin Mathilde revint. Il s'assit.";
+        let analyzer = CharacterAnalyzer::with_reference(
+            CharacterReference::default().with_canon_facts(&[canon("CHAR:Mathilde", k_gender(), "feminin")]),
+        );
+        let issues = analyzer.analyze(text);
+        let gender = issues.iter().find(|i| i.description.contains("Genre")).expect("gender issue expected");
+        assert!(gender.description.contains("2 indice(s) masculin"), "{}", gender.description);
+    }
+
+    #[test]
+    fn test_role_drift() {
+        let text = "Le capitaine Morel donna l'ordre. Plus tard, le lieutenant Morel hesita.";
+        let issues = CharacterAnalyzer::new().analyze(text);
+        let role = issues.iter().find(|i| i.description.contains("Titre")).expect("role issue expected");
+        assert!(role.description.contains("capitaine"));
+        assert!(role.description.contains("lieutenant"));
+        assert_eq!(role.spans.len(), 2);
+    }
+
+    #[test]
+    fn test_role_feminine_form_consistent() {
+        let text = "La comtesse Irene arriva. Plus tard, le comte Irene? Non: la comtesse Irene repartit.";
+        let issues = CharacterAnalyzer::new().analyze(text);
+        assert!(issues.iter().all(|i| !i.description.contains("Titre")), "{:?}", issues);
+        assert!(issues.iter().any(|i| i.description.contains("Genre")), "{:?}", issues);
+    }
+
+    #[test]
+    fn test_relation_drift() {
+        let text = "Pierre embrassa sa soeur Lucie. Plus tard, Pierre salua sa cousine Lucie.";
+        let issues = CharacterAnalyzer::new().analyze(text);
+        let relation = issues.iter().find(|i| i.description.contains("Relation")).expect("relation issue expected");
+        assert!(relation.description.contains("'Lucie' envers 'Pierre'"));
+    }
+
+    #[test]
+    fn test_relation_gender_conflict() {
+        let text = "Pierre embrassa sa soeur Lucie. Ensuite Lucie etait heureuse. Plus tard, Pierre appela son frere Lucie.";
+        let issues = CharacterAnalyzer::new().analyze(text);
+        assert!(issues.iter().any(|i| i.description.contains("Genre")), "{:?}", issues);
+    }
+
+    #[test]
+    fn test_consistent_character_no_issue() {
+        let text = "Puis Mathilde entra. Elle posa son sac. Ensuite Mathilde etait fatiguee. La capitaine Mathilde dormit.";
+        let issues = CharacterAnalyzer::new().analyze(text);
+        assert!(issues.is_empty(), "{:?}", issues);
+    }
+
+    #[test]
+    fn test_profiles() {
+        let text = "Pierre embrassa sa soeur Lucie. Ensuite Lucie etait heureuse. Pierre partit.";
+        let profiles = CharacterAnalyzer::new().profiles(text);
+        let lucie = profiles.iter().find(|p| p.name == "Lucie").unwrap();
+        assert_eq!(lucie.gender, Some(Gender::Feminine));
+        assert_eq!(lucie.relations.get("Pierre"), Some(&"soeur".to_string()));
+    }
+}
@@ -0,0 +1,579 @@
+//! OMEGA HOLOGRAPH — Emotional Dynamics
+//! Trajectoire émotionnelle par paragraphe (ou scène) sur le lexique GOLD
+//!
+//! - émotions et intensités du lexique FR_LEXICON_V1_GOLD (+ mots-clés du rule pack)
+//! - coût de transition entre émotions: roue de Plutchik, surchargeable par le pack
+//! - seuil proportionnel au nombre de mots séparant les deux points
+//! - phrase de transition / rupture de scène: pénalité atténuée
+//! - courbe de "jerk" émotionnel (vitesse, accélération, à-coup par 100 mots)
+//!
+//! NASA-Grade AS9100D
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use super::states::SCENE_BREAK;
+use super::{split_segments, split_sentences, CoherenceIssue, IssueType, RulePack, Severity, TextSpan};
+use crate::lexicon_fr_gold::{
+    analyze_gold, build_lexicon_gold, normalize_fr, tokenize, AnalyzerConfig, LexEntry, MatchKind, ProperNounGuard,
+};
+
+/// Positions sur la roue de Plutchik (8 secteurs); dyades entre leurs composantes
+/// (amour = joie + confiance, fierté = colère + joie)
+const PLUTCHIK: &[(&str, f32)] = &[
+    ("joy", 0.0),
+    ("love", 0.5),
+    ("trust", 1.0),
+    ("fear", 2.0),
+    ("surprise", 3.0),
+    ("sadness", 4.0),
+    ("disgust", 5.0),
+    ("anger", 6.0),
+    ("anticipation", 7.0),
+    ("pride", 7.5),
+];
+
+/// Marqueurs de transition émotionnelle (forme normalisée)
+const TRANSITION_MARKERS: &[&str] = &[
+    "peu a peu", "petit a petit", "progressivement", "lentement", "au fil", "avec le temps",
+    "finalement", "apres un long moment", "apres un moment", "plus tard", "le lendemain",
+    "quelques jours", "quelques heures", "des semaines", "des mois",
+];
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// CONFIGURATION
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Unité de la trajectoire émotionnelle
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DynamicsUnit {
+    #[default]
+    Paragraph,
+    Scene,
+}
+
+/// Coût de transition surchargé (symétrique), dans [0, 1]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TransitionCost {
+    pub from: String,
+    pub to: String,
+    pub cost: f32,
+}
+
+/// Réglages DYNAMICS du rule pack
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DynamicsRules {
+    #[serde(default)]
+    pub unit: DynamicsUnit,
+    /// Ampleur tolérée entre deux points adjacents (0 = aucune, 1 = opposition totale)
+    #[serde(default = "default_threshold")]
+    pub threshold: f32,
+    /// Nombre de mots qui double le seuil
+    #[serde(default = "default_reference_words")]
+    pub reference_words: usize,
+    /// Multiplicateur d'ampleur quand une phrase de transition précède le changement
+    #[serde(default = "default_transition_discount")]
+    pub transition_discount: f32,
+    /// Multiplicateur d'ampleur à travers une rupture de scène
+    #[serde(default = "default_scene_break_discount")]
+    pub scene_break_discount: f32,
+    #[serde(default = "default_transition_markers")]
+    pub transition_markers: Vec<String>,
+    /// Surcharges de la matrice de Plutchik
+    #[serde(default)]
+    pub transition_costs: Vec<TransitionCost>,
+}
+
+fn default_threshold() -> f32 {
+    0.45
+}
+
+fn default_reference_words() -> usize {
+    150
+}
+
+fn default_transition_discount() -> f32 {
+    0.5
+}
+
+fn default_scene_break_discount() -> f32 {
+    0.25
+}
+
+fn default_transition_markers() -> Vec<String> {
+    TRANSITION_MARKERS.iter().map(|m| m.to_string()).collect()
+}
+
+impl Default for DynamicsRules {
+    fn default() -> Self {
+        Self {
+            unit: DynamicsUnit::default(),
+            threshold: default_threshold(),
+            reference_words: default_reference_words(),
+            transition_discount: default_transition_discount(),
+            scene_break_discount: default_scene_break_discount(),
+            transition_markers: default_transition_markers(),
+            transition_costs: Vec::new(),
+        }
+    }
+}
+
+impl DynamicsRules {
+    /// Marqueurs en forme normalisée (minuscules, sans accents)
+    pub(crate) fn normalize(&mut self) {
+        self.transition_markers = self.transition_markers.iter().map(|m| normalize_fr(m)).collect();
+    }
+
+    /// Vérifie les bornes; `known` = émotions reconnues (GOLD + pack)
+    pub(crate) fn validate(&self, known: &HashSet<String>) -> Result<(), String> {
+        if self.threshold.is_nan() || self.threshold <= 0.0 {
+            return Err(format!("dynamics: seuil {} invalide (> 0 attendu)", self.threshold));
+        }
+        if self.reference_words == 0 {
+            return Err("dynamics: reference_words doit etre > 0".to_string());
+        }
+        for (name, value) in [("transition_discount", self.transition_discount), ("scene_break_discount", self.scene_break_discount)] {
+            if !(0.0..=1.0).contains(&value) {
+                return Err(format!("dynamics: {} {} hors de [0, 1]", name, value));
+            }
+        }
+        if self.transition_markers.iter().any(|m| m.trim().is_empty()) {
+            return Err("dynamics: marqueur de transition vide".to_string());
+        }
+        for t in &self.transition_costs {
+            for emotion in [&t.from, &t.to] {
+                if !known.contains(emotion) {
+                    return Err(format!("dynamics: emotion inconnue dans la matrice: {}", emotion));
+                }
+            }
+            if !(0.0..=1.0).contains(&t.cost) {
+                return Err(format!("dynamics: cout {} -> {} hors de [0, 1]: {}", t.from, t.to, t.cost));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Émotions reconnues: lexique GOLD + émotions déclarées par le pack
+pub(crate) fn known_emotions(pack: &RulePack) -> HashSet<String> {
+    build_lexicon_gold().keys()
+        .map(|e| e.to_string())
+        .chain(pack.emotions.keys().cloned())
+        .collect()
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// TYPES
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Point de la trajectoire émotionnelle
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmotionCurvePoint {
+    /// Rang du paragraphe / de la scène (1 = premier)
+    pub unit: usize,
+    pub span: TextSpan,
+    /// Mots précédant le point dans le texte
+    pub word_offset: usize,
+    pub dominant: Option<String>,
+    /// Intensités (part des occurrences) par émotion
+    pub emotions: BTreeMap<String, f32>,
+    /// Ampleur du changement depuis le point précédent (après atténuation)
+    pub shift: f32,
+    /// Dérivées par 100 mots
+    pub velocity: f32,
+    pub acceleration: f32,
+    pub jerk: f32,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DynamicsAnalysis {
+    pub issues: Vec<CoherenceIssue>,
+    pub curve: Vec<EmotionCurvePoint>,
+}
+
+/// Unité en cours de lecture (paragraphe ou scène)
+struct Unit<'a> {
+    span: TextSpan,
+    text: &'a str,
+    word_offset: usize,
+    words: usize,
+    after_break: bool,
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// ANALYZER
+// ═══════════════════════════════════════════════════════════════════════════════
+
+pub struct DynamicsAnalyzer {
+    rules: DynamicsRules,
+    /// Mots-clés du pack (préfixes normalisés), en complément du lexique GOLD
+    keywords: BTreeMap<String, Vec<String>>,
+    costs: HashMap<(String, String), f32>,
+    lexicon: HashMap<&'static str, Vec<LexEntry>>,
+    scene_break_re: Regex,
+}
+
+impl Default for DynamicsAnalyzer {
+    fn default() -> Self {
+        Self::new(&RulePack::builtin())
+    }
+}
+
+impl DynamicsAnalyzer {
+    pub fn new(pack: &RulePack) -> Self {
+        let mut costs = HashMap::new();
+        for (a, b) in &pack.emotion_opposites {
+            costs.insert((a.clone(), b.clone()), 1.0);
+            costs.insert((b.clone(), a.clone()), 1.0);
+        }
+        for t in &pack.dynamics.transition_costs {
+            costs.insert((t.from.clone(), t.to.clone()), t.cost);
+            costs.insert((t.to.clone(), t.from.clone()), t.cost);
+        }
+        Self {
+            rules: pack.dynamics.clone(),
+            keywords: pack.emotions.clone(),
+            costs,
+            lexicon: build_lexicon_gold(),
+            scene_break_re: Regex::new(SCENE_BREAK).unwrap(),
+        }
+    }
+
+    /// Coût de transition entre deux émotions, dans [0, 1]
+    pub fn cost(&self, from: &str, to: &str) -> f32 {
+        if from == to {
+            return 0.0;
+        }
+        if let Some(cost) = self.costs.get(&(from.to_string(), to.to_string())) {
+            return *cost;
+        }
+        let position = |e: &str| PLUTCHIK.iter().find(|(name, _)| *name == e).map(|(_, p)| *p);
+        match (position(from), position(to)) {
+            (Some(a), Some(b)) => {
+                let d = (a - b).abs();
+                d.min(8.0 - d) / 4.0
+            }
+            _ => 1.0,
+        }
+    }
+
+    /// Intensités par émotion: lexique GOLD, puis mots-clés du pack sur les tokens restants
+    pub fn emotions(&self, text: &str) -> BTreeMap<String, f32> {
+        let gold = analyze_gold(text, None, &AnalyzerConfig::default());
+        let mut counts: BTreeMap<String, usize> = gold.emotions.iter()
+            .filter(|e| e.occurrences > 0)
+            .map(|e| (e.emotion.clone(), e.occurrences))
+            .collect();
+
+        if !self.keywords.is_empty() {
+            let png = ProperNounGuard::new(true);
+            for token in tokenize(text) {
+                if png.should_block(&token.original, token.is_begin_sentence) || self.gold_match(&token.normalized) {
+                    continue;
+                }
+                let extra = self.keywords.iter()
+                    .find(|(_, kws)| kws.iter().any(|k| token.normalized.starts_with(k.as_str())));
+                if let Some((emotion, _)) = extra {
+                    *counts.entry(emotion.clone()).or_insert(0) += 1;
+                }
+            }
+        }
+
+        let total: usize = counts.values().sum();
+        counts.into_iter()
+            .map(|(e, c)| (e, c as f32 / total as f32))
+            .collect()
+    }
+
+    fn gold_match(&self, token: &str) -> bool {
+        self.lexicon.values().flatten().any(|entry| match entry.kind {
+            MatchKind::Exact => token == entry.pattern,
+            MatchKind::Stem => entry.pattern.len() >= 4 && token.starts_with(entry.pattern),
+        })
+    }
+
+    /// Ampleur d'un changement: masse émotionnelle perdue → gagnée, pondérée par le coût
+    /// (0 = même état, 1 = bascule complète vers l'émotion opposée)
+    pub fn shift(&self, from: &BTreeMap<String, f32>, to: &BTreeMap<String, f32>) -> f32 {
+        let delta = |a: &BTreeMap<String, f32>, b: &BTreeMap<String, f32>| -> Vec<(String, f32)> {
+            a.iter()
+                .map(|(e, p)| (e.clone(), p - b.get(e).copied().unwrap_or(0.0)))
+                .filter(|(_, d)| *d > 0.0)
+                .collect()
+        };
+        let lost = delta(from, to);
+        let gained = delta(to, from);
+        let moved: f32 = lost.iter().map(|(_, d)| d).sum();
+        if moved <= f32::EPSILON {
+            return 0.0;
+        }
+        let weighted: f32 = lost.iter()
+            .flat_map(|(a, la)| gained.iter().map(move |(b, gb)| (a, la, b, gb)))
+            .map(|(a, la, b, gb)| la * gb * self.cost(a, b))
+            .sum();
+        weighted / moved
+    }
+
+    /// Seuil toléré pour deux points séparés de `words` mots
+    pub fn allowed(&self, words: usize) -> f32 {
+        self.rules.threshold * (1.0 + words as f32 / self.rules.reference_words as f32)
+    }
+
+    fn has_transition(&self, text: &str) -> bool {
+        let normalized = format!(" {} ", normalize_fr(text));
+        self.rules.transition_markers.iter().any(|m| normalized.contains(&format!(" {} ", m)))
+    }
+
+    /// Début d'une unité: première phrase
+    fn opening(text: &str) -> &str {
+        split_sentences(text).first().map(|s| &text[s.start..s.end]).unwrap_or(text)
+    }
+
+    /// Paragraphes (ou scènes), sans les lignes de rupture
+    fn units<'a>(&self, text: &'a str) -> Vec<Unit<'a>> {
+        let mut units: Vec<Unit<'a>> = Vec::new();
+        let mut words = 0;
+        let mut pending_break = false;
+
+        for (segment, seg_text) in split_segments(text) {
+            let first_line = seg_text.lines().next().unwrap_or("");
+            let (span, body) = if self.scene_break_re.is_match(first_line) {
+                pending_break = true;
+                match seg_text.find('\n') {
+                    Some(nl) => (TextSpan::new(segment.start + nl + 1, segment.end), &seg_text[nl + 1..]),
+                    None => continue,
+                }
+            } else {
+                (segment, seg_text)
+            };
+            if body.trim().is_empty() {
+                continue;
+            }
+
+            let count = body.split_whitespace().count();
+            let merge = self.rules.unit == DynamicsUnit::Scene && !pending_break;
+            match units.last_mut() {
+                Some(last) if merge => {
+                    last.span.end = span.end;
+                    last.text = &text[last.span.start..last.span.end];
+                    last.words += count;
+                }
+                _ => units.push(Unit { span, text: body, word_offset: words, words: count, after_break: pending_break }),
+            }
+            words += count;
+            pending_break = false;
+        }
+
+        units
+    }
+
+    pub fn analyze(&self, text: &str) -> DynamicsAnalysis {
+        let mut analysis = DynamicsAnalysis::default();
+        let label = match self.rules.unit {
+            DynamicsUnit::Paragraph => "Paragraphe",
+            DynamicsUnit::Scene => "Scene",
+        };
+
+        let mut crossed_break = false;
+        let mut bridged = false;
+        let mut previous: Option<(usize, (f32, f32))> = None;
+
+        for (idx, unit) in self.units(text).into_iter().enumerate() {
+            crossed_break |= unit.after_break;
+            let emotions = self.emotions(unit.text);
+            if emotions.is_empty() {
+                // Paragraphe neutre: peut porter la transition
+                bridged |= self.has_transition(unit.text);
+                continue;
+            }
+            bridged |= self.has_transition(Self::opening(unit.text));
+
+            let dominant = emotions.iter()
+                .max_by(|a, b| a.1.partial_cmp(b.1).unwrap().then_with(|| b.0.cmp(a.0)))
+                .map(|(e, _)| e.clone());
+            let mut point = EmotionCurvePoint {
+                unit: idx + 1,
+                span: unit.span,
+                word_offset: unit.word_offset,
+                dominant,
+                emotions,
+                shift: 0.0,
+                velocity: 0.0,
+                acceleration: 0.0,
+                jerk: 0.0,
+            };
+
+            if let Some((prev_idx, (prev_velocity, prev_acceleration))) = previous {
+                let prev = &analysis.curve[prev_idx];
+                let distance = point.word_offset.saturating_sub(prev.word_offset).max(1);
+                let magnitude = self.shift(&prev.emotions, &point.emotions);
+
+                let mut discount = 1.0;
+                let mut mitigation = Vec::new();
+                if bridged {
+                    discount *= self.rules.transition_discount;
+                    mitigation.push(format!("transition (x{:.2})", self.rules.transition_discount));
+                }
+                if crossed_break && self.rules.unit == DynamicsUnit::Paragraph {
+                    discount *= self.rules.scene_break_discount;
+                    mitigation.push(format!("rupture de scene (x{:.2})", self.rules.scene_break_discount));
+                }
+                let effective = magnitude * discount;
+                let allowed = self.allowed(distance);
+
+                let per100 = distance as f32 / 100.0;
+                point.shift = effective;
+                point.velocity = effective / per100;
+                point.acceleration = (point.velocity - prev_velocity) / per100;
+                point.jerk = (point.acceleration - prev_acceleration) / per100;
+
+                if effective > allowed {
+                    let mut evidence = vec![
+                        format!("{} {}: {}", label, prev.unit, describe(&prev.emotions)),
+                        format!("{} {}: {}", label, point.unit, describe(&point.emotions)),
+                    ];
+                    if !mitigation.is_empty() {
+                        evidence.push(format!("Attenuation: {}", mitigation.join(", ")));
+                    }
+                    analysis.issues.push(CoherenceIssue {
+                        issue_type: IssueType::EmotionShift,
+                        severity: if effective >= 2.0 * allowed { Severity::High } else { Severity::Medium },
+                        description: format!(
+                            "Changement emotionnel brusque: {} -> {} (ampleur {:.2} > seuil {:.2} sur {} mots)",
+                            prev.dominant.as_deref().unwrap_or("?"),
+                            point.dominant.as_deref().unwrap_or("?"),
+                            effective, allowed, distance
+                        ),
+                        evidence,
+                        location: Some(format!("{} {}-{}", label, prev.unit, point.unit)),
+                        suggestion: Some("Ajouter une transition emotionnelle".to_string()),
+                        spans: vec![prev.span, point.span],
+                        entity: None,
+                        category: Some("emotion".to_string()),
+                        fingerprint: String::new(),
+                    });
+                }
+            }
+
+            previous = Some((analysis.curve.len(), (point.velocity, point.acceleration)));
+            analysis.curve.push(point);
+            crossed_break = false;
+            bridged = false;
+        }
+
+        analysis
+    }
+}
+
+/// "joy 0.67, trust 0.33" (intensités décroissantes)
+fn describe(emotions: &BTreeMap<String, f32>) -> String {
+    let mut sorted: Vec<(&String, &f32)> = emotions.iter().collect();
+    sorted.sort_by(|a, b| b.1.partial_cmp(a.1).unwrap().then_with(|| a.0.cmp(b.0)));
+    sorted.iter().map(|(e, p)| format!("{} {:.2}", e, p)).collect::<Vec<_>>().join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const JOY: &str = "Il etait heureux ce matin-la. Le sourire illuminait son visage.";
+    const SADNESS: &str = "Soudain, le chagrin l'envahit. Les larmes coulerent sur ses joues.";
+
+    #[test]
+    fn test_plutchik_costs() {
+        let analyzer = DynamicsAnalyzer::default();
+        assert_eq!(analyzer.cost("joy", "sadness"), 1.0);
+        assert_eq!(analyzer.cost("anger", "fear"), 1.0);
+        assert_eq!(analyzer.cost("joy", "trust"), 0.25);
+        assert_eq!(analyzer.cost("joy", "joy"), 0.0);
+        assert!(analyzer.cost("joy", "love") < analyzer.cost("joy", "trust"));
+    }
+
+    #[test]
+    fn test_cost_override() {
+        let mut pack = RulePack::builtin();
+        pack.dynamics.transition_costs.push(TransitionCost { from: "joy".into(), to: "surprise".into(), cost: 0.1 });
+        let analyzer = DynamicsAnalyzer::new(&pack);
+        assert_eq!(analyzer.cost("surprise", "joy"), 0.1);
+    }
+
+    #[test]
+    fn test_gold_emotions_and_intensities() {
+        let analyzer = DynamicsAnalyzer::default();
+        let emotions = analyzer.emotions("Quelle joie! Elle etait heureuse, mais la peur restait.");
+        assert!((emotions["joy"] - 2.0 / 3.0).abs() < 1e-3);
+        assert!((emotions["fear"] - 1.0 / 3.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_abrupt_shift_detected() {
+        let analyzer = DynamicsAnalyzer::default();
+        let analysis = analyzer.analyze(&format!("{}\n\n{}", JOY, SADNESS));
+
+        assert_eq!(analysis.issues.len(), 1);
+        let issue = &analysis.issues[0];
+        assert_eq!(issue.issue_type, IssueType::EmotionShift);
+        assert!(issue.description.contains("joy -> sadness"));
+        assert_eq!(issue.spans.len(), 2);
+        assert_eq!(analysis.curve.len(), 2);
+        assert!((analysis.curve[1].shift - 1.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_adjacent_emotions_tolerated() {
+        let analyzer = DynamicsAnalyzer::default();
+        let text = format!("{}\n\nElle lui faisait confiance, une confiance totale et sereine.", JOY);
+        assert!(analyzer.analyze(&text).issues.is_empty());
+    }
+
+    #[test]
+    fn test_distance_scales_threshold() {
+        let analyzer = DynamicsAnalyzer::default();
+        let filler = "Le train roulait dans la plaine et les champs defilaient sous un ciel uniforme. ".repeat(20);
+        let text = format!("{}\n\n{}\n\n{}", JOY, filler, SADNESS);
+        let analysis = analyzer.analyze(&text);
+
+        assert!(analysis.issues.is_empty(), "{:?}", analysis.issues);
+        assert!(analyzer.allowed(300) > analyzer.allowed(10));
+    }
+
+    #[test]
+    fn test_transition_and_scene_break_lower_penalty() {
+        let analyzer = DynamicsAnalyzer::default();
+        let direct = analyzer.analyze(&format!("{}\n\n{}", JOY, SADNESS));
+        let bridged = analyzer.analyze(&format!("{}\n\nPeu a peu, le ciel se couvrit.\n\n{}", JOY, SADNESS));
+        let scene = analyzer.analyze(&format!("{}\n\n***\n\n{}", JOY, SADNESS));
+
+        assert!(bridged.curve[1].shift < direct.curve[1].shift);
+        assert!(scene.curve[1].shift < bridged.curve[1].shift);
+        assert!(scene.issues.is_empty());
+    }
+
+    #[test]
+    fn test_jerk_curve() {
+        let analyzer = DynamicsAnalyzer::default();
+        let text = format!("{}\n\n{}\n\n{}\n\n{}", JOY, JOY, SADNESS, JOY);
+        let curve = analyzer.analyze(&text).curve;
+
+        assert_eq!(curve.len(), 4);
+        assert_eq!(curve[1].velocity, 0.0);
+        assert!(curve[2].velocity > 0.0);
+        assert!(curve[2].jerk > 0.0);
+        assert_eq!(curve[2].dominant.as_deref(), Some("sadness"));
+    }
+
+    #[test]
+    fn test_scene_unit() {
+        let mut pack = RulePack::builtin();
+        pack.dynamics.unit = DynamicsUnit::Scene;
+        let analyzer = DynamicsAnalyzer::new(&pack);
+        let text = format!("{}\n\n{}\n\n***\n\n{}", JOY, JOY, SADNESS);
+        let analysis = analyzer.analyze(&text);
+
+        assert_eq!(analysis.curve.len(), 2);
+        assert_eq!(analysis.curve[0].unit, 1);
+        assert_eq!(analysis.issues.len(), 1);
+        assert!(analysis.issues[0].evidence[0].starts_with("Scene 1"));
+    }
+}
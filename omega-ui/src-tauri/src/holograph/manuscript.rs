@@ -0,0 +1,679 @@
+//! OMEGA HOLOGRAPH — Manuscript Scanner
+//! Scan multi-chapitres avec registre d'entités persistant
+//!
+//! - chapitres ordonnés (ou run segmenté) → registre: historique des attributs,
+//!   première / dernière apparition de chaque entité
+//! - contradictions inter-chapitres localisées (chapitre, segment)
+//! - états des personnages (vie, présence, lieu) portés d'un chapitre à l'autre: un personnage
+//!   mort au chapitre 2 qui agit au chapitre 19 est signalé (présence et lieu repartent à chaque chapitre)
+//! - registre sauvegardé: un nouveau chapitre ne relit pas tout le livre
+//!   (les chapitres inchangés — même id, même hash — sont réutilisés)
+//! - dérogations et comparaison au scan précédent, par empreinte d'issue
+//! - score du livre normalisé (mots, entités), chapitres et entités les plus pénalisés
+//!
+//! NASA-Grade AS9100D
+
+use chrono::NaiveDate;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use super::{
+    attribute_label, fingerprint, split_segments, AttributeAssertion, CoherenceIssue, HolographReport,
+    HolographScanner, IssueType, RulePackInfo, ScanHistory, ScoreBreakdown, StateTransition, TextSpan, Waiver,
+    WaiverFile,
+};
+use crate::error::OmegaResult;
+use crate::pipeline::fs_utils::{ensure_dir, read_json, sha256_str, write_json};
+
+/// Version du schéma du registre persistant
+pub const REGISTRY_SCHEMA_VERSION: u32 = 3;
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// TYPES
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Chapter {
+    pub id: String,
+    #[serde(default)]
+    pub title: String,
+    pub text: String,
+}
+
+impl Chapter {
+    pub fn new(id: &str, text: &str) -> Self {
+        Self { id: id.to_string(), title: String::new(), text: text.to_string() }
+    }
+
+    /// Chapitres à partir d'un texte segmenté (titre, char_start, char_end en octets)
+    pub fn from_segments(text: &str, segments: &[(String, usize, usize)]) -> Vec<Chapter> {
+        segments.iter()
+            .enumerate()
+            .filter(|(_, (_, start, end))| start <= end && *end <= text.len())
+            .filter(|(_, (_, start, end))| text.is_char_boundary(*start) && text.is_char_boundary(*end))
+            .map(|(idx, (title, start, end))| Chapter {
+                id: format!("seg_{:04}", idx),
+                title: title.clone(),
+                text: text[*start..*end].to_string(),
+            })
+            .collect()
+    }
+}
+
+/// Position dans le manuscrit: chapitre, segment (paragraphe), offsets dans le chapitre
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChapterLocation {
+    pub chapter_index: usize,
+    pub chapter_id: String,
+    pub segment_index: usize,
+    pub span: TextSpan,
+}
+
+impl ChapterLocation {
+    pub fn label(&self) -> String {
+        format!("Chapitre {} ({}), segment {}", self.chapter_index + 1, self.chapter_id, self.segment_index + 1)
+    }
+}
+
+/// Attribut observé pour une entité, avec sa position
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttributeObservation {
+    pub assertion: AttributeAssertion,
+    pub location: ChapterLocation,
+}
+
+/// Transition d'état (vie, présence, lieu) d'une entité, avec sa position
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StateObservation {
+    pub transition: StateTransition,
+    pub location: ChapterLocation,
+}
+
+/// Présence d'une entité dans un chapitre
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Appearance {
+    pub chapter_index: usize,
+    pub mentions: usize,
+    pub first: ChapterLocation,
+    pub last: ChapterLocation,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntityRecord {
+    pub name: String,
+    pub mentions: usize,
+    pub first_seen: ChapterLocation,
+    pub last_seen: ChapterLocation,
+    pub appearances: Vec<Appearance>,
+    pub attributes: Vec<AttributeObservation>,
+    #[serde(default)]
+    pub states: Vec<StateObservation>,
+}
+
+/// Empreinte d'un chapitre déjà intégré au registre
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChapterDigest {
+    pub index: usize,
+    pub id: String,
+    pub hash: String,
+    pub words: usize,
+}
+
+/// Issue localisée dans le manuscrit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManuscriptIssue {
+    /// Chapitre où l'issue est détectée
+    pub chapter_index: usize,
+    pub issue: CoherenceIssue,
+    pub locations: Vec<ChapterLocation>,
+}
+
+/// Registre persistant des entités d'un manuscrit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntityRegistry {
+    pub schema_version: u32,
+    /// Hash du rule pack ayant produit le registre (changement → rescan complet)
+    pub rule_pack_hash: String,
+    pub chapters: Vec<ChapterDigest>,
+    pub entities: BTreeMap<String, EntityRecord>,
+    pub issues: Vec<ManuscriptIssue>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManuscriptReport {
+    pub rule_pack: RulePackInfo,
+    #[serde(default)]
+    pub logic_score: f32,
+    #[serde(default)]
+    pub dynamics_score: f32,
+    #[serde(default)]
+    pub overall_score: f32,
+    /// Détail du score (top offenders: entités et chapitres)
+    #[serde(default)]
+    pub score: ScoreBreakdown,
+    /// Chapitres lus lors de ce scan
+    pub scanned: Vec<String>,
+    /// Chapitres réutilisés depuis le registre
+    pub reused: Vec<String>,
+    pub entities: usize,
+    pub issues: Vec<ManuscriptIssue>,
+    pub scan_duration_ms: u64,
+    /// Issues couvertes par une dérogation active
+    #[serde(default)]
+    pub waived: Vec<WaivedManuscriptIssue>,
+    /// Issues absentes du scan précédent (None: premier scan)
+    #[serde(default)]
+    pub new_issues: Option<usize>,
+    #[serde(default)]
+    pub resolved_issues: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WaivedManuscriptIssue {
+    pub issue: ManuscriptIssue,
+    pub waiver: Waiver,
+}
+
+impl ManuscriptReport {
+    /// Retire les issues couvertes par une dérogation active
+    pub fn apply_waivers(&mut self, waivers: &WaiverFile, today: NaiveDate) {
+        let (kept, waived) = waivers.partition(std::mem::take(&mut self.issues), today, |i| i.issue.fingerprint.as_str());
+        self.issues = kept;
+        self.waived.extend(waived.into_iter().map(|(issue, waiver)| WaivedManuscriptIssue { issue, waiver }));
+        self.score = self.score.weights.breakdown(&scored(&self.issues), self.score.words, self.score.entities);
+        (self.logic_score, self.dynamics_score, self.overall_score) = self.score.scores();
+    }
+
+    /// Compte les issues nouvelles / résolues depuis le scan précédent
+    pub fn compare_with(&mut self, history: &ScanHistory) {
+        let active: Vec<&str> = self.issues.iter().map(|i| i.issue.fingerprint.as_str()).collect();
+        let waived: Vec<&str> = self.waived.iter().map(|w| w.issue.issue.fingerprint.as_str()).collect();
+        let delta = history.compare(&active, &waived);
+        self.new_issues = delta.map(|d| d.new_issues);
+        self.resolved_issues = delta.map(|d| d.resolved_issues);
+    }
+
+    /// Empreintes de toutes les issues (actives et dérogées)
+    pub fn fingerprints(&self) -> impl Iterator<Item = &str> {
+        self.issues.iter()
+            .map(|i| i.issue.fingerprint.as_str())
+            .chain(self.waived.iter().map(|w| w.issue.issue.fingerprint.as_str()))
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// REGISTRY
+// ═══════════════════════════════════════════════════════════════════════════════
+
+impl Default for EntityRegistry {
+    fn default() -> Self {
+        Self {
+            schema_version: REGISTRY_SCHEMA_VERSION,
+            rule_pack_hash: String::new(),
+            chapters: Vec::new(),
+            entities: BTreeMap::new(),
+            issues: Vec::new(),
+        }
+    }
+}
+
+impl EntityRegistry {
+    /// Charge le registre (vide si le fichier n'existe pas)
+    pub fn load<P: AsRef<Path>>(path: P) -> OmegaResult<Self> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        read_json(path)
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> OmegaResult<()> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            ensure_dir(parent)?;
+        }
+        write_json(path, self)
+    }
+
+    /// Oublie tout ce qui provient des chapitres >= `index`
+    pub fn truncate(&mut self, index: usize) {
+        self.chapters.retain(|c| c.index < index);
+        self.issues.retain(|i| i.chapter_index < index);
+        for record in self.entities.values_mut() {
+            record.appearances.retain(|a| a.chapter_index < index);
+            record.attributes.retain(|a| a.location.chapter_index < index);
+            record.states.retain(|s| s.location.chapter_index < index);
+        }
+        self.entities.retain(|_, r| !r.appearances.is_empty());
+        for record in self.entities.values_mut() {
+            record.refresh();
+        }
+    }
+
+    /// Historique d'un attribut (catégorie) pour une entité
+    pub fn history(&self, entity: &str, category: &str) -> Vec<&AttributeObservation> {
+        self.entities.get(entity)
+            .map(|r| r.attributes.iter().filter(|a| a.assertion.category == category).collect())
+            .unwrap_or_default()
+    }
+}
+
+impl EntityRecord {
+    /// Mort constatée avant le chapitre `index` (la vie ne se réinitialise pas entre chapitres)
+    pub fn death_before(&self, index: usize) -> Option<&StateObservation> {
+        self.states.iter().find(|s| s.location.chapter_index < index && s.transition.to == "mort")
+    }
+
+    fn refresh(&mut self) {
+        self.mentions = self.appearances.iter().map(|a| a.mentions).sum();
+        if let (Some(first), Some(last)) = (self.appearances.first(), self.appearances.last()) {
+            self.first_seen = first.first.clone();
+            self.last_seen = last.last.clone();
+        }
+    }
+}
+
+/// Issues à noter, rattachées à leur chapitre ("Chapitre 3 (ch03)")
+fn scored(issues: &[ManuscriptIssue]) -> Vec<(&CoherenceIssue, Option<String>)> {
+    issues.iter()
+        .map(|i| {
+            let chapter = match i.locations.iter().rev().find(|l| l.chapter_index == i.chapter_index) {
+                Some(location) => format!("Chapitre {} ({})", i.chapter_index + 1, location.chapter_id),
+                None => format!("Chapitre {}", i.chapter_index + 1),
+            };
+            (&i.issue, Some(chapter))
+        })
+        .collect()
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// SCANNER
+// ═══════════════════════════════════════════════════════════════════════════════
+
+pub struct ManuscriptScanner {
+    scanner: HolographScanner,
+    name_re: Regex,
+}
+
+impl ManuscriptScanner {
+    pub fn new(scanner: HolographScanner) -> Self {
+        Self {
+            scanner,
+            name_re: Regex::new(r"\b[A-ZÀÂÄÉÈÊËÎÏÔÖÙÛÜÇ][a-zàâäéèêëîïôöùûüç]+\b").unwrap(),
+        }
+    }
+
+    /// Scan incrémental: réutilise les chapitres inchangés du registre,
+    /// relit à partir du premier chapitre nouveau ou modifié
+    pub fn scan(&self, registry: &mut EntityRegistry, chapters: &[Chapter]) -> ManuscriptReport {
+        let start = std::time::Instant::now();
+        let rule_pack = self.scanner.rule_pack().clone();
+
+        if registry.schema_version != REGISTRY_SCHEMA_VERSION || registry.rule_pack_hash != rule_pack.hash {
+            *registry = EntityRegistry {
+                rule_pack_hash: rule_pack.hash.clone(),
+                ..EntityRegistry::default()
+            };
+        }
+
+        let digests: Vec<ChapterDigest> = chapters.iter().enumerate().map(|(index, c)| ChapterDigest {
+            index,
+            id: c.id.clone(),
+            hash: sha256_str(&c.text),
+            words: c.text.split_whitespace().count(),
+        }).collect();
+        let unchanged = digests.iter()
+            .zip(registry.chapters.iter())
+            .take_while(|(new, old)| new == old)
+            .count();
+        registry.truncate(unchanged);
+
+        for (index, chapter) in chapters.iter().enumerate().skip(unchanged) {
+            self.ingest(registry, index, chapter);
+            registry.chapters.push(digests[index].clone());
+        }
+
+        let score = self.scanner.scoring().breakdown(
+            &scored(&registry.issues),
+            registry.chapters.iter().map(|c| c.words).sum(),
+            registry.entities.len(),
+        );
+        let (logic_score, dynamics_score, overall_score) = score.scores();
+
+        ManuscriptReport {
+            rule_pack,
+            logic_score,
+            dynamics_score,
+            overall_score,
+            score,
+            scanned: chapters[unchanged..].iter().map(|c| c.id.clone()).collect(),
+            reused: chapters[..unchanged].iter().map(|c| c.id.clone()).collect(),
+            entities: registry.entities.len(),
+            issues: registry.issues.clone(),
+            scan_duration_ms: start.elapsed().as_millis() as u64,
+            waived: Vec::new(),
+            new_issues: None,
+            resolved_issues: None,
+        }
+    }
+
+    fn ingest(&self, registry: &mut EntityRegistry, index: usize, chapter: &Chapter) {
+        let segments: Vec<TextSpan> = split_segments(&chapter.text).into_iter().map(|(s, _)| s).collect();
+        let locate = |span: TextSpan| ChapterLocation {
+            chapter_index: index,
+            chapter_id: chapter.id.clone(),
+            segment_index: segments.iter().rposition(|s| s.start <= span.start).unwrap_or(0),
+            span,
+        };
+
+        // Issues propres au chapitre
+        let report: HolographReport = self.scanner.scan(&chapter.text);
+        for mut issue in report.issues {
+            let locations: Vec<ChapterLocation> = issue.spans.iter().map(|s| locate(*s)).collect();
+            if let Some(first) = locations.first() {
+                issue.location = Some(match issue.location {
+                    Some(loc) => format!("{} — {}", first.label(), loc),
+                    None => first.label(),
+                });
+            }
+            registry.issues.push(ManuscriptIssue { chapter_index: index, issue, locations });
+        }
+
+        // Apparitions
+        let entities = self.scanner.extract_entities(&chapter.text);
+        let mut appearances: BTreeMap<String, Appearance> = BTreeMap::new();
+        for m in self.name_re.find_iter(&chapter.text) {
+            if !entities.iter().any(|e| e == m.as_str()) {
+                continue;
+            }
+            let location = locate(TextSpan::new(m.start(), m.end()));
+            appearances.entry(m.as_str().to_string())
+                .and_modify(|a| {
+                    a.mentions += 1;
+                    a.last = location.clone();
+                })
+                .or_insert_with(|| Appearance {
+                    chapter_index: index,
+                    mentions: 1,
+                    first: location.clone(),
+                    last: location,
+                });
+        }
+        for (name, appearance) in appearances {
+            let record = registry.entities.entry(name.clone()).or_insert_with(|| EntityRecord {
+                name,
+                mentions: 0,
+                first_seen: appearance.first.clone(),
+                last_seen: appearance.last.clone(),
+                appearances: Vec::new(),
+                attributes: Vec::new(),
+                states: Vec::new(),
+            });
+            record.appearances.push(appearance);
+            record.refresh();
+        }
+
+        // Attributs: confrontation avec l'historique des chapitres précédents
+        let mut reported: Vec<(String, String, String)> = Vec::new();
+        for assertion in self.scanner.attributes(&chapter.text) {
+            let location = locate(assertion.span);
+            let Some(record) = registry.entities.get_mut(&assertion.entity) else { continue };
+            let key = (assertion.entity.clone(), assertion.category.clone(), assertion.facet.clone());
+
+            let prior = record.attributes.iter()
+                .filter(|o| o.location.chapter_index < index)
+                .find(|o| o.assertion.contradicts(&assertion));
+            if let (Some(prior), false) = (prior, reported.contains(&key)) {
+                let label = attribute_label(&assertion.category, &assertion.facet);
+                let mut issue = CoherenceIssue {
+                    issue_type: IssueType::Contradiction,
+                    severity: self.scanner.category_severity(&assertion.category),
+                    description: format!(
+                        "Contradiction sur {} pour '{}': {} (chapitre {}) vs {} (chapitre {})",
+                        label, assertion.entity,
+                        prior.assertion.label(), prior.location.chapter_index + 1,
+                        assertion.label(), index + 1
+                    ),
+                    evidence: vec![
+                        format!("{}: \"{}\"", prior.location.label(), prior.assertion.raw),
+                        format!("{}: \"{}\"", location.label(), assertion.raw),
+                    ],
+                    location: Some(format!("Entite: {} — {}", assertion.entity, location.label())),
+                    suggestion: Some(format!(
+                        "Verifier la coherence de {} pour {} depuis le chapitre {}",
+                        label, assertion.entity, prior.location.chapter_index + 1
+                    )),
+                    spans: vec![prior.assertion.span, assertion.span],
+                    entity: Some(assertion.entity.clone()),
+                    category: Some(assertion.category.clone()),
+                    fingerprint: String::new(),
+                };
+                issue.fingerprint = fingerprint(&issue);
+                registry.issues.push(ManuscriptIssue {
+                    chapter_index: index,
+                    issue,
+                    locations: vec![prior.location.clone(), location.clone()],
+                });
+                reported.push(key);
+            }
+
+            record.attributes.push(AttributeObservation { assertion, location });
+        }
+
+        // États: un mort des chapitres précédents qui agit ici
+        let dead: BTreeSet<String> = registry.entities.iter()
+            .filter(|(_, r)| r.death_before(index).is_some())
+            .map(|(name, _)| name.clone())
+            .collect();
+        let mut reported: BTreeSet<String> = BTreeSet::new();
+        for violation in self.scanner.inherited_state_violations(&chapter.text, &dead) {
+            if !reported.insert(violation.entity.clone()) {
+                continue;
+            }
+            let Some(death) = registry.entities.get(&violation.entity).and_then(|r| r.death_before(index)) else { continue };
+            let location = locate(violation.span);
+            let mut issue = CoherenceIssue {
+                issue_type: IssueType::ContinuityError,
+                severity: self.scanner.category_severity("vie"),
+                description: format!(
+                    "Personnage mort qui parle ou agit: '{}' (mort au chapitre {}, transition vivant → mort violee)",
+                    violation.entity, death.location.chapter_index + 1
+                ),
+                evidence: vec![
+                    format!("{}: \"{}\"", death.location.label(), death.transition.raw),
+                    format!("{}: \"{}\"", location.label(), violation.raw),
+                ],
+                location: Some(format!("Personnage: {} — {}", violation.entity, location.label())),
+                suggestion: Some("Marquer le passage comme flashback/reve, ou revoir la mort".to_string()),
+                spans: vec![death.transition.span, violation.span],
+                entity: Some(violation.entity.clone()),
+                category: Some("vie".to_string()),
+                fingerprint: String::new(),
+            };
+            issue.fingerprint = fingerprint(&issue);
+            let locations = vec![death.location.clone(), location];
+            registry.issues.push(ManuscriptIssue { chapter_index: index, issue, locations });
+        }
+        for transition in self.scanner.state_transitions(&chapter.text) {
+            let location = locate(transition.span);
+            if let Some(record) = registry.entities.get_mut(&transition.entity) {
+                record.states.push(StateObservation { transition, location });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn book() -> Vec<Chapter> {
+        vec![
+            Chapter::new("ch01", "Pierre ouvrit la porte.\n\nPierre etait grand et fort."),
+            Chapter::new("ch02", "Marie arriva au village. Marie avait les cheveux blonds."),
+            Chapter::new("ch03", "Le soir tomba.\n\nPlus tard, Pierre, ce petit homme, entra chez Marie."),
+        ]
+    }
+
+    #[test]
+    fn test_cross_chapter_contradiction() {
+        let scanner = ManuscriptScanner::new(HolographScanner::new());
+        let mut registry = EntityRegistry::default();
+        let report = scanner.scan(&mut registry, &book());
+
+        let issue = report.issues.iter()
+            .find(|i| i.issue.description.contains("chapitre 1") && i.issue.description.contains("chapitre 3"))
+            .expect("cross-chapter contradiction expected");
+        assert_eq!(issue.chapter_index, 2);
+        assert_eq!(issue.locations[0].chapter_id, "ch01");
+        assert_eq!(issue.locations[0].segment_index, 1);
+        assert_eq!(issue.locations[1].chapter_id, "ch03");
+        assert_eq!(issue.locations[1].segment_index, 1);
+    }
+
+    #[test]
+    fn test_registry_first_last_appearance() {
+        let scanner = ManuscriptScanner::new(HolographScanner::new());
+        let mut registry = EntityRegistry::default();
+        scanner.scan(&mut registry, &book());
+
+        let pierre = &registry.entities["Pierre"];
+        assert_eq!(pierre.first_seen.chapter_id, "ch01");
+        assert_eq!(pierre.last_seen.chapter_id, "ch03");
+        assert_eq!(pierre.mentions, 3);
+        assert_eq!(registry.history("Pierre", "taille").len(), 2);
+        assert_eq!(registry.entities["Marie"].first_seen.chapter_id, "ch02");
+    }
+
+    #[test]
+    fn test_incremental_scan_reuses_chapters() {
+        let scanner = ManuscriptScanner::new(HolographScanner::new());
+        let mut registry = EntityRegistry::default();
+        let mut chapters = book();
+        let last = chapters.pop().unwrap();
+        let first = scanner.scan(&mut registry, &chapters);
+        assert_eq!(first.scanned.len(), 2);
+        assert!(first.issues.is_empty());
+
+        chapters.push(last);
+        let second = scanner.scan(&mut registry, &chapters);
+        assert_eq!(second.reused, vec!["ch01", "ch02"]);
+        assert_eq!(second.scanned, vec!["ch03"]);
+        assert!(!second.issues.is_empty());
+        assert!(second.logic_score < first.logic_score);
+        assert!(second.score.top_offenders.iter().any(|o| o.name == "Chapitre 3 (ch03)"));
+    }
+
+    #[test]
+    fn test_modified_chapter_rescans_from_there() {
+        let scanner = ManuscriptScanner::new(HolographScanner::new());
+        let mut registry = EntityRegistry::default();
+        let mut chapters = book();
+        scanner.scan(&mut registry, &chapters);
+
+        chapters[0].text = "Pierre ouvrit la porte.\n\nPierre etait petit.".to_string();
+        let report = scanner.scan(&mut registry, &chapters);
+        assert_eq!(report.scanned.len(), 3);
+        assert!(report.issues.iter().all(|i| i.issue.issue_type != IssueType::Contradiction));
+        assert_eq!(registry.entities["Pierre"].mentions, 3);
+    }
+
+    #[test]
+    fn test_registry_persistence() {
+        let dir = std::env::temp_dir().join(format!("omega_holo_registry_{}", uuid::Uuid::new_v4()));
+        let path = dir.join("registry.json");
+        let scanner = ManuscriptScanner::new(HolographScanner::new());
+
+        let mut registry = EntityRegistry::load(&path).unwrap();
+        scanner.scan(&mut registry, &book()[..2]);
+        registry.save(&path).unwrap();
+
+        let mut reloaded = EntityRegistry::load(&path).unwrap();
+        let report = scanner.scan(&mut reloaded, &book());
+        assert_eq!(report.reused.len(), 2);
+        assert!(report.issues.iter().any(|i| i.issue.issue_type == IssueType::Contradiction));
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_rule_pack_change_resets_registry() {
+        let scanner = ManuscriptScanner::new(HolographScanner::new());
+        let mut registry = EntityRegistry::default();
+        scanner.scan(&mut registry, &book());
+
+        let mut pack = crate::holograph::RulePack::builtin();
+        pack.version = "1.0.1".into();
+        let other = ManuscriptScanner::new(HolographScanner::with_rule_pack(pack).unwrap());
+        let report = other.scan(&mut registry, &book());
+        assert!(report.reused.is_empty());
+    }
+
+    #[test]
+    fn test_waivers_and_new_issues() {
+        let scanner = ManuscriptScanner::new(HolographScanner::new());
+        let mut registry = EntityRegistry::default();
+        let mut history = ScanHistory::default();
+        let today = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+
+        let mut first = scanner.scan(&mut registry, &book());
+        first.compare_with(&history);
+        assert_eq!(first.new_issues, None);
+        history.record(first.fingerprints());
+
+        let cross = first.issues.iter().find(|i| i.chapter_index == 2).unwrap().issue.fingerprint.clone();
+        let waivers = WaiverFile {
+            waivers: vec![Waiver {
+                fingerprint: cross.clone(),
+                justification: "Pierre se tasse avec l'age".into(),
+                author: "auteur".into(),
+                expires: None,
+            }],
+            ..WaiverFile::default()
+        };
+
+        let mut chapters = book();
+        chapters.push(Chapter::new("ch04", "Marie etait grande. Marie etait petite."));
+        let mut second = scanner.scan(&mut registry, &chapters);
+        second.apply_waivers(&waivers, today);
+        second.compare_with(&history);
+        assert!(second.issues.iter().all(|i| i.issue.fingerprint != cross));
+        assert_eq!(second.waived.len(), 1);
+        assert_eq!(second.new_issues, Some(1));
+        assert_eq!(second.resolved_issues, Some(0));
+    }
+
+    #[test]
+    fn test_death_carried_across_chapters() {
+        let scanner = ManuscriptScanner::new(HolographScanner::new());
+        let mut registry = EntityRegistry::default();
+        let mut chapters = vec![
+            Chapter::new("ch02", "Le soir venu, Pierre mourut dans ses bras."),
+            Chapter::new("ch19", "Le lendemain, Marie pleurait. « Courage », dit Pierre. Pierre sourit."),
+        ];
+        let report = scanner.scan(&mut registry, &chapters);
+        let deaths: Vec<&ManuscriptIssue> = report.issues.iter().filter(|i| i.issue.category.as_deref() == Some("vie")).collect();
+        assert_eq!(deaths.len(), 1, "{:?}", report.issues);
+        assert_eq!(deaths[0].chapter_index, 1);
+        assert_eq!(deaths[0].locations.iter().map(|l| l.chapter_id.as_str()).collect::<Vec<_>>(), ["ch02", "ch19"]);
+        assert!(deaths[0].issue.description.contains("mort au chapitre 1"));
+        assert_eq!(registry.entities["Pierre"].states[0].transition.to, "mort");
+
+        // Le registre porte l'état: seul le chapitre modifié est relu, la mort reste connue
+        chapters[1] = Chapter::new("ch19", "Marie se souvenait de lui. Dans son reve, Pierre lui parla.");
+        let report = scanner.scan(&mut registry, &chapters);
+        assert_eq!(report.reused, ["ch02"]);
+        assert!(report.issues.iter().all(|i| i.issue.category.as_deref() != Some("vie")), "{:?}", report.issues);
+    }
+
+    #[test]
+    fn test_from_segments() {
+        let text = "Chapitre 1\nPierre.\nChapitre 2\nMarie.";
+        let chapters = Chapter::from_segments(text, &[("Chapitre 1".into(), 0, 19), ("Chapitre 2".into(), 19, text.len())]);
+        assert_eq!(chapters.len(), 2);
+        assert_eq!(chapters[1].id, "seg_0001");
+        assert!(chapters[1].text.contains("Marie"));
+    }
+}
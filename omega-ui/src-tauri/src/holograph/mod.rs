@@ -0,0 +1,657 @@
+//! OMEGA HOLOGRAPH — Coherence Scanner
+//! MVP v0.1: LOGIC (contradictions) + DYNAMICS (emotion coherence)
+//! v0.2: LOGIC temporelle (timeline, jours, saisons, âges)
+//!       DYNAMICS personnages (genre, titres, relations, graphie des noms)
+//!       LOGIC: attributs rattachés par proximité syntaxique (négation comprise)
+//!       Rule packs configurables (JSON / TOML), hash tamponné dans le rapport
+//!       Scan multi-chapitres avec registre d'entités persistant (incrémental)
+//!       LOGIC: machine à états des personnages (mort, départ, lieu)
+//!       DYNAMICS: lexique GOLD, matrice de transition (Plutchik), courbe de jerk
+//!       Scores pondérés par sévérité, normalisés (mots, entités), top offenders
+//!       Empreintes stables, dérogations (waivers), issues nouvelles vs scan précédent
+//! NASA-Grade AS9100D
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use regex::Regex;
+use std::collections::BTreeSet;
+
+use crate::error::OmegaResult;
+
+pub mod temporal;
+pub mod characters;
+pub mod binding;
+pub mod rules;
+pub mod manuscript;
+pub mod states;
+pub mod waivers;
+pub mod dynamics;
+pub mod scoring;
+
+pub use temporal::{TemporalAnalyzer, TemporalKind, TemporalMarker, TimelineSegment, Season};
+pub use binding::{AttributeAssertion, AttributeBinder};
+pub use rules::{CategoryRule, GenreFilter, RegexRule, RulePack, RulePackInfo, ValueGroup, RULE_PACK_SCHEMA_VERSION};
+pub use manuscript::{
+    Appearance, AttributeObservation, Chapter, ChapterDigest, ChapterLocation, EntityRecord, EntityRegistry,
+    ManuscriptIssue, ManuscriptReport, ManuscriptScanner, StateObservation, WaivedManuscriptIssue,
+    REGISTRY_SCHEMA_VERSION,
+};
+pub use states::{InheritedViolation, LifeState, PresenceState, StateChecks, StateEvent, StateTracker, StateTransition};
+pub use characters::{CharacterAnalyzer, CharacterFacts, CharacterProfile, CharacterReference, Gender};
+pub use dynamics::{DynamicsAnalysis, DynamicsAnalyzer, DynamicsRules, DynamicsUnit, EmotionCurvePoint, TransitionCost};
+pub use scoring::{Offender, OffenderKind, PenaltyShare, ScoreBreakdown, ScoringRules, SeverityWeights, SCORING_FORMULA};
+pub use waivers::{fingerprint, ScanDelta, ScanHistory, WaivedIssue, Waiver, WaiverFile, WAIVER_SCHEMA_VERSION};
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// TYPES
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HolographReport {
+    pub logic_score: f32,
+    pub dynamics_score: f32,
+    pub overall_score: f32,
+    pub issues: Vec<CoherenceIssue>,
+    pub scan_duration_ms: u64,
+    /// Rule pack appliqué (id, version, hash)
+    #[serde(default)]
+    pub rule_pack: RulePackInfo,
+    /// Détail du score: formule, poids, répartition, top offenders
+    #[serde(default)]
+    pub score: ScoreBreakdown,
+    /// Trajectoire émotionnelle (intensités, vitesse, accélération, jerk)
+    #[serde(default)]
+    pub emotion_curve: Vec<EmotionCurvePoint>,
+    /// Issues couvertes par une dérogation active (hors scores)
+    #[serde(default)]
+    pub waived: Vec<WaivedIssue>,
+    /// Issues absentes du scan précédent du même manuscrit (None: premier scan)
+    #[serde(default)]
+    pub new_issues: Option<usize>,
+    /// Issues du scan précédent disparues
+    #[serde(default)]
+    pub resolved_issues: Option<usize>,
+}
+
+impl HolographReport {
+    /// Retire les issues couvertes par une dérogation active et recalcule les scores
+    pub fn apply_waivers(&mut self, waivers: &WaiverFile, today: NaiveDate) {
+        let (kept, waived) = waivers.partition(std::mem::take(&mut self.issues), today, |i| i.fingerprint.as_str());
+        self.issues = kept;
+        self.waived.extend(waived.into_iter().map(|(issue, waiver)| WaivedIssue { issue, waiver }));
+        self.rescore();
+    }
+
+    /// Compte les issues nouvelles / résolues depuis le scan précédent
+    pub fn compare_with(&mut self, history: &ScanHistory) {
+        let active: Vec<&str> = self.issues.iter().map(|i| i.fingerprint.as_str()).collect();
+        let waived: Vec<&str> = self.waived.iter().map(|w| w.issue.fingerprint.as_str()).collect();
+        let delta = history.compare(&active, &waived);
+        self.new_issues = delta.map(|d| d.new_issues);
+        self.resolved_issues = delta.map(|d| d.resolved_issues);
+    }
+
+    /// Empreintes de toutes les issues (actives et dérogées)
+    pub fn fingerprints(&self) -> impl Iterator<Item = &str> {
+        self.issues.iter()
+            .map(|i| i.fingerprint.as_str())
+            .chain(self.waived.iter().map(|w| w.issue.fingerprint.as_str()))
+    }
+
+    fn rescore(&mut self) {
+        let issues: Vec<(&CoherenceIssue, Option<String>)> = self.issues.iter().map(|i| (i, None)).collect();
+        self.score = self.score.weights.breakdown(&issues, self.score.words, self.score.entities);
+        (self.logic_score, self.dynamics_score, self.overall_score) = self.score.scores();
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CoherenceIssue {
+    pub issue_type: IssueType,
+    pub severity: Severity,
+    pub description: String,
+    pub evidence: Vec<String>,
+    pub location: Option<String>,
+    pub suggestion: Option<String>,
+    /// Positions des énoncés en conflit
+    #[serde(default)]
+    pub spans: Vec<TextSpan>,
+    /// Entité visée (personnage, événement), si connue
+    #[serde(default)]
+    pub entity: Option<String>,
+    /// Catégorie de la règle (taille, genre, date, emotion, id de règle regex...)
+    #[serde(default)]
+    pub category: Option<String>,
+    /// Empreinte stable (type, entité, catégorie, preuves normalisées)
+    #[serde(default)]
+    pub fingerprint: String,
+}
+
+/// Position d'un énoncé dans le texte scanné
+/// Offsets en octets (UTF-8), comme char_start/char_end des segments
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TextSpan {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl TextSpan {
+    pub fn new(start: usize, end: usize) -> Self {
+        Self { start, end }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum IssueType {
+    Contradiction,      // LOGIC: fait contradictoire
+    TemporalError,      // LOGIC: erreur chronologique  
+    EmotionShift,       // DYNAMICS: changement emotion brutal
+    CharacterInconsistency, // DYNAMICS: personnage incohérent
+    ContinuityError,    // LOGIC: état de personnage violé (mort, parti, lieu)
+}
+
+impl IssueType {
+    /// Issue comptée dans le score LOGIC (sinon DYNAMICS)
+    pub fn is_logic(&self) -> bool {
+        matches!(self, IssueType::Contradiction | IssueType::TemporalError | IssueType::ContinuityError)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum Severity {
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// HOLOGRAPH SCANNER
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// (entité, catégorie, trait)
+type AttributeKey<'a> = (&'a str, &'a str, &'a str);
+
+pub struct HolographScanner {
+    rules: RulePack,
+    rules_info: RulePackInfo,
+    genre: Option<String>,
+    categories: Vec<CategoryRule>,
+    regex_rules: Vec<(RegexRule, Regex)>,
+    temporal: TemporalAnalyzer,
+    states: StateTracker,
+    characters: CharacterAnalyzer,
+    dynamics: DynamicsAnalyzer,
+}
+
+impl Default for HolographScanner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HolographScanner {
+    /// Scanner avec le rule pack intégré
+    pub fn new() -> Self {
+        Self::with_rule_pack(RulePack::builtin()).expect("builtin rule pack must be valid")
+    }
+
+    /// Scanner avec un rule pack personnalisé (validé)
+    pub fn with_rule_pack(rules: RulePack) -> OmegaResult<Self> {
+        rules.validate()?;
+        let dynamics = DynamicsAnalyzer::new(&rules);
+        let mut scanner = Self {
+            rules,
+            rules_info: RulePackInfo::default(),
+            genre: None,
+            categories: Vec::new(),
+            regex_rules: Vec::new(),
+            temporal: TemporalAnalyzer::new(),
+            states: StateTracker::new(),
+            characters: CharacterAnalyzer::new(),
+            dynamics,
+        };
+        scanner.activate();
+        Ok(scanner)
+    }
+
+    /// Genre du manuscrit: active / désactive les règles selon leur filtre
+    pub fn with_genre(mut self, genre: &str) -> Self {
+        self.genre = Some(genre.to_string());
+        self.activate();
+        self
+    }
+
+    fn activate(&mut self) {
+        let genre = self.genre.as_deref();
+        self.rules_info = self.rules.info(genre);
+        self.categories = self.rules.active_categories(genre);
+        // Regex déjà validées par RulePack::validate
+        self.regex_rules = self.rules.regex_rules.iter()
+            .filter(|r| r.genres.allows(genre))
+            .filter_map(|r| r.compile().ok().map(|re| (r.clone(), re)))
+            .collect();
+    }
+
+    /// Identité du rule pack appliqué
+    pub fn rule_pack(&self) -> &RulePackInfo {
+        &self.rules_info
+    }
+
+    /// Poids du score du rule pack
+    pub fn scoring(&self) -> &ScoringRules {
+        &self.rules.scoring
+    }
+
+    /// Utilise la Bible / le CANON comme référence des personnages
+    pub fn with_character_reference(mut self, reference: CharacterReference) -> Self {
+        self.characters = CharacterAnalyzer::with_reference(reference);
+        self
+    }
+
+    /// Scan complet du texte
+    pub fn scan(&self, text: &str) -> HolographReport {
+        let start = std::time::Instant::now();
+        
+        let mut issues = Vec::new();
+        
+        // Règles regex du pack: réparties selon leur type
+        let (rule_logic, rule_dynamics): (Vec<_>, Vec<_>) = self.scan_regex_rules(text)
+            .into_iter()
+            .partition(|i| i.issue_type.is_logic());
+
+        // LOGIC: Scan contradictions + chronologie
+        let mut logic_issues = self.scan_logic(text);
+        logic_issues.extend(self.temporal.analyze(text));
+        logic_issues.extend(self.states.analyze(text, self.state_checks()));
+        logic_issues.extend(rule_logic);
+        issues.extend(logic_issues);
+        
+        // DYNAMICS: Trajectoire émotionnelle + personnages
+        let dynamics = self.dynamics.analyze(text);
+        let mut dynamics_issues = dynamics.issues;
+        dynamics_issues.extend(self.characters.analyze(text));
+        dynamics_issues.extend(rule_dynamics);
+        issues.extend(dynamics_issues);
+        waivers::assign_fingerprints(&mut issues);
+        
+        // Scores normalisés (mots, entités)
+        let words = text.split_whitespace().count();
+        let entities = self.extract_entities(text).len();
+        let refs: Vec<(&CoherenceIssue, Option<String>)> = issues.iter().map(|i| (i, None)).collect();
+        let score = self.rules.scoring.breakdown(&refs, words, entities);
+        let (logic_score, dynamics_score, overall_score) = score.scores();
+        
+        HolographReport {
+            logic_score,
+            dynamics_score,
+            overall_score,
+            issues,
+            scan_duration_ms: start.elapsed().as_millis() as u64,
+            rule_pack: self.rules_info.clone(),
+            score,
+            emotion_curve: dynamics.curve,
+            waived: Vec::new(),
+            new_issues: None,
+            resolved_issues: None,
+        }
+    }
+
+    /// Catégories d'attributs actives (pack + genre)
+    pub fn categories(&self) -> &[CategoryRule] {
+        &self.categories
+    }
+
+    /// Checks d'état actifs: suivent les catégories "vie" et "presence" du pack
+    /// (un genre où les morts marchent désactive "vie")
+    pub fn state_checks(&self) -> StateChecks {
+        StateChecks {
+            life: self.categories.iter().any(|c| c.name == "vie"),
+            presence: self.categories.iter().any(|c| c.name == "presence"),
+        }
+    }
+
+    /// Transitions d'état du texte (vie, présence, lieu), pour le registre du manuscrit
+    pub fn state_transitions(&self, text: &str) -> Vec<StateTransition> {
+        self.states.transitions(text)
+    }
+
+    /// Personnages morts avant le texte (chapitres précédents) qui y agissent, selon les checks du pack
+    pub fn inherited_state_violations(&self, text: &str, dead: &BTreeSet<String>) -> Vec<InheritedViolation> {
+        self.states.analyze_after(text, self.state_checks(), dead)
+    }
+
+    /// Sévérité configurée pour une catégorie (High par défaut)
+    pub fn category_severity(&self, category: &str) -> Severity {
+        self.categories.iter()
+            .find(|c| c.name == category)
+            .map(|c| c.severity.clone())
+            .unwrap_or(Severity::High)
+    }
+
+    /// LOGIC: Détecte les contradictions factuelles
+    /// Un même attribut (entité, catégorie, trait) affirmé avec des valeurs opposées,
+    /// ou affirmé puis nié
+    fn scan_logic(&self, text: &str) -> Vec<CoherenceIssue> {
+        let mut issues = Vec::new();
+
+        let assertions = self.attributes(text);
+
+        let mut groups: Vec<(AttributeKey, Vec<&AttributeAssertion>)> = Vec::new();
+        for a in &assertions {
+            let key = (a.entity.as_str(), a.category.as_str(), a.facet.as_str());
+            match groups.iter_mut().find(|(k, _)| *k == key) {
+                Some((_, group)) => group.push(a),
+                None => groups.push((key, vec![a])),
+            }
+        }
+
+        for ((entity, category, facet), attrs) in &groups {
+            // Premier énoncé contredit par un énoncé ultérieur
+            let conflict = attrs.iter().enumerate().find_map(|(i, a)| {
+                attrs[i + 1..].iter().find(|b| a.contradicts(b)).map(|b| (*a, *b))
+            });
+            let Some((first, second)) = conflict else { continue };
+
+            let label = attribute_label(category, facet);
+            issues.push(CoherenceIssue {
+                issue_type: IssueType::Contradiction,
+                severity: self.category_severity(category),
+                description: format!(
+                    "Contradiction sur {} pour '{}': {} vs {}",
+                    label, entity, first.label(), second.label()
+                ),
+                // Seule la paire en conflit fait preuve: une mention cohérente ajoutée ailleurs ne change pas l'empreinte
+                evidence: [first, second].iter()
+                    .map(|a| format!("{} [{}]: \"{}\" @{}", a.label(), a.value, a.raw, a.span.start))
+                    .collect(),
+                location: Some(format!("Entite: {}", entity)),
+                suggestion: Some(format!("Verifier la coherence de {} pour {}", label, entity)),
+                spans: vec![first.span, second.span],
+                entity: Some(entity.to_string()),
+                category: Some(category.to_string()),
+                fingerprint: String::new(),
+            });
+        }
+
+        issues
+    }
+
+    /// Règles regex personnalisées du pack
+    fn scan_regex_rules(&self, text: &str) -> Vec<CoherenceIssue> {
+        let mut issues = Vec::new();
+
+        for (rule, re) in &self.regex_rules {
+            for caps in re.captures_iter(text) {
+                let m = caps.get(0).unwrap();
+                let mut description = String::new();
+                caps.expand(&rule.message, &mut description);
+                issues.push(CoherenceIssue {
+                    issue_type: rule.issue_type.clone(),
+                    severity: rule.severity.clone(),
+                    description,
+                    evidence: vec![format!("\"{}\" @{}", m.as_str(), m.start())],
+                    location: Some(format!("Regle: {}", rule.id)),
+                    suggestion: rule.suggestion.clone(),
+                    spans: vec![TextSpan::new(m.start(), m.end())],
+                    entity: None,
+                    category: Some(rule.id.clone()),
+                    fingerprint: String::new(),
+                });
+            }
+        }
+
+        issues
+    }
+
+    /// Attributs rattachés aux entités du texte
+    pub fn attributes(&self, text: &str) -> Vec<AttributeAssertion> {
+        // Extraire les entités (noms propres simplifiés)
+        let entities = self.extract_entities(text);
+        AttributeBinder::new(&self.categories).bind(text, &entities)
+    }
+
+    /// Extrait les entités (noms propres) du texte
+    pub fn extract_entities(&self, text: &str) -> Vec<String> {
+        let mut entities = Vec::new();
+        let re = Regex::new(r"\b([A-Z][a-zàâäéèêëïîôùûüç]+)\b").unwrap();
+        
+        for cap in re.captures_iter(text) {
+            let entity = cap[1].to_string();
+            // Filtrer les mots en début de phrase
+            let common_words = vec!["Le", "La", "Les", "Un", "Une", "Il", "Elle", "Ce", "Cette", "Son", "Sa", "Ses"];
+            if !common_words.contains(&entity.as_str()) && !entities.contains(&entity) {
+                entities.push(entity);
+            }
+        }
+        
+        entities
+    }
+}
+
+/// Libellé d'un attribut: "taille", "lumiere (yeux)"
+pub(crate) fn attribute_label(category: &str, facet: &str) -> String {
+    if facet.is_empty() || facet == category {
+        category.to_string()
+    } else {
+        format!("{} ({})", category, facet)
+    }
+}
+
+/// Découpe le texte en segments (paragraphes séparés par une ligne vide)
+/// Retourne chaque segment non vide avec sa position dans le texte
+pub(crate) fn split_segments(text: &str) -> Vec<(TextSpan, &str)> {
+    let separator = Regex::new(r"\n[ \t\r]*\n").unwrap();
+    let mut segments = Vec::new();
+    let mut start = 0;
+
+    for sep in separator.find_iter(text) {
+        if !text[start..sep.start()].trim().is_empty() {
+            segments.push((TextSpan::new(start, sep.start()), &text[start..sep.start()]));
+        }
+        start = sep.end();
+    }
+    if !text[start..].trim().is_empty() {
+        segments.push((TextSpan::new(start, text.len()), &text[start..]));
+    }
+
+    segments
+}
+
+/// Découpe le texte en phrases (., !, ?, … et retours à la ligne)
+/// Ne coupe pas après les abréviations de civilité (M., Mme., Dr.)
+pub(crate) fn split_sentences(text: &str) -> Vec<TextSpan> {
+    const ABBREVIATIONS: [&str; 5] = ["M", "Mme", "Mlle", "Dr", "St"];
+    let mut spans = Vec::new();
+    let mut start = 0;
+
+    for (idx, c) in text.char_indices() {
+        let end = idx + c.len_utf8();
+        let boundary = match c {
+            '!' | '?' | '…' | '\n' => true,
+            '.' => {
+                let word = text[start..idx].rsplit(|ch: char| !ch.is_alphabetic()).next().unwrap_or("");
+                !ABBREVIATIONS.contains(&word)
+            }
+            _ => false,
+        };
+        if boundary {
+            if !text[start..idx].trim().is_empty() {
+                spans.push(TextSpan::new(start, end));
+            }
+            start = end;
+        }
+    }
+    if !text[start..].trim().is_empty() {
+        spans.push(TextSpan::new(start, text.len()));
+    }
+
+    spans
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_contradiction() {
+        let scanner = HolographScanner::new();
+        let text = "Pierre etait grand et imposant. Plus tard, Pierre, ce petit homme, entra.";
+        let report = scanner.scan(text);
+        
+        assert!(!report.issues.is_empty(), "Should detect contradiction");
+        assert!(report.issues.iter().any(|i| i.issue_type == IssueType::Contradiction));
+    }
+
+    #[test]
+    fn test_no_contradiction() {
+        let scanner = HolographScanner::new();
+        let text = "Marie etait belle. Elle portait une robe rouge.";
+        let report = scanner.scan(text);
+        
+        let contradictions: Vec<_> = report.issues.iter()
+            .filter(|i| i.issue_type == IssueType::Contradiction)
+            .collect();
+        assert!(contradictions.is_empty(), "Should not detect contradiction");
+    }
+
+    #[test]
+    fn test_emotion_shift() {
+        let scanner = HolographScanner::new();
+        let text = "Il etait heureux et joyeux ce matin-la. Le sourire illuminait son visage.\n\nSoudain, la tristesse l'envahit. Les larmes coulerent sur ses joues.";
+        let report = scanner.scan(text);
+        
+        let shifts: Vec<_> = report.issues.iter()
+            .filter(|i| i.issue_type == IssueType::EmotionShift)
+            .collect();
+        assert!(!shifts.is_empty(), "Should detect emotion shift");
+    }
+
+    #[test]
+    fn test_scores() {
+        let scanner = HolographScanner::new();
+        let text = "Un texte simple sans probleme.";
+        let report = scanner.scan(text);
+        
+        assert!(report.logic_score >= 0.9, "Clean text should have high logic score");
+        assert!(report.dynamics_score >= 0.9, "Clean text should have high dynamics score");
+    }
+
+    #[test]
+    fn test_split_sentences_keeps_civility() {
+        let text = "M. Roux entra. Mme Roux sortit!\nFin";
+        let sentences: Vec<&str> = split_sentences(text).iter().map(|s| text[s.start..s.end].trim()).collect();
+        assert_eq!(sentences, vec!["M. Roux entra.", "Mme Roux sortit!", "Fin"]);
+    }
+
+    #[test]
+    fn test_character_issue_in_dynamics() {
+        let scanner = HolographScanner::new();
+        let text = "Le capitaine Morel donna l'ordre. Plus tard, le lieutenant Morel hesita.";
+        let report = scanner.scan(text);
+
+        assert!(report.issues.iter().any(|i| i.issue_type == IssueType::CharacterInconsistency));
+        assert!(report.dynamics_score < 1.0);
+    }
+
+    #[test]
+    fn test_object_attribute_no_contradiction() {
+        let scanner = HolographScanner::new();
+        let text = "Pierre regarda le grand mur. Pierre etait petit. Marie etait la, pres du mur.";
+        let report = scanner.scan(text);
+
+        assert!(report.issues.iter().all(|i| i.issue_type != IssueType::Contradiction), "{:?}", report.issues);
+    }
+
+    #[test]
+    fn test_negation_contradiction_spans() {
+        let scanner = HolographScanner::new();
+        let text = "Pierre etait grand. Plus tard, Pierre n'etait pas grand.";
+        let report = scanner.scan(text);
+
+        let issue = report.issues.iter().find(|i| i.issue_type == IssueType::Contradiction).expect("contradiction expected");
+        assert_eq!(issue.spans.len(), 2);
+        assert_eq!(&text[issue.spans[0].start..issue.spans[0].end], "Pierre etait grand");
+        assert!(issue.description.contains("non grand"));
+
+        let consistent = scanner.scan("Pierre n'etait pas grand. Pierre etait petit.");
+        assert!(consistent.issues.iter().all(|i| i.issue_type != IssueType::Contradiction));
+    }
+
+    #[test]
+    fn test_rule_pack_stamped() {
+        let report = HolographScanner::new().scan("Un texte simple.");
+        assert_eq!(report.rule_pack.id, "omega.builtin");
+        assert_eq!(report.rule_pack.hash, RulePack::builtin().hash());
+    }
+
+    #[test]
+    fn test_genre_disables_category() {
+        let mut pack = RulePack::builtin();
+        pack.categories.iter_mut()
+            .find(|c| c.name == "vie")
+            .unwrap()
+            .genres.disabled.push("fantasy".into());
+        let text = "Pierre etait mort. Plus tard, Pierre etait vivant.";
+
+        let realist = HolographScanner::with_rule_pack(pack.clone()).unwrap().scan(text);
+        assert!(realist.issues.iter().any(|i| i.issue_type == IssueType::Contradiction));
+
+        let fantasy = HolographScanner::with_rule_pack(pack).unwrap().with_genre("fantasy").scan(text);
+        assert!(fantasy.issues.iter().all(|i| i.issue_type != IssueType::Contradiction));
+        assert_eq!(fantasy.rule_pack.genre.as_deref(), Some("fantasy"));
+    }
+
+    #[test]
+    fn test_custom_regex_rule() {
+        let mut pack = RulePack::builtin();
+        pack.regex_rules.push(RegexRule {
+            id: "anachronisme".into(),
+            pattern: r"\b(?P<objet>telephone)\b".into(),
+            message: "Anachronisme: $objet".into(),
+            issue_type: IssueType::TemporalError,
+            severity: Severity::Medium,
+            suggestion: None,
+            case_insensitive: true,
+            genres: GenreFilter::default(),
+        });
+        let text = "En 1450, le chevalier prit son Telephone.";
+        let report = HolographScanner::with_rule_pack(pack).unwrap().scan(text);
+
+        let issue = report.issues.iter().find(|i| i.issue_type == IssueType::TemporalError).unwrap();
+        assert_eq!(issue.description, "Anachronisme: Telephone");
+        assert_eq!(&text[issue.spans[0].start..issue.spans[0].end], "Telephone");
+        assert_ne!(report.rule_pack.hash, RulePack::builtin().hash());
+    }
+
+    #[test]
+    fn test_category_severity() {
+        let mut pack = RulePack::builtin();
+        pack.categories[0].severity = Severity::Critical;
+        let report = HolographScanner::with_rule_pack(pack).unwrap().scan("Pierre etait grand. Pierre etait petit.");
+        assert!(report.issues.iter().any(|i| i.issue_type == IssueType::Contradiction && i.severity == Severity::Critical));
+    }
+
+    #[test]
+    fn test_invalid_pack_rejected() {
+        let mut pack = RulePack::builtin();
+        pack.id.clear();
+        assert!(HolographScanner::with_rule_pack(pack).is_err());
+    }
+
+    #[test]
+    fn test_resurrection_follows_genre() {
+        let mut pack = RulePack::builtin();
+        pack.categories.iter_mut()
+            .find(|c| c.name == "vie")
+            .unwrap()
+            .genres.disabled.push("fantasy".into());
+        let text = "Le bandit tua Pierre.\n\nA minuit, Pierre ouvrit les yeux et marcha vers le village.";
+
+        let realist = HolographScanner::with_rule_pack(pack.clone()).unwrap().scan(text);
+        assert!(realist.issues.iter().any(|i| i.issue_type == IssueType::ContinuityError));
+
+        let fantasy = HolographScanner::with_rule_pack(pack).unwrap().with_genre("fantasy").scan(text);
+        assert!(fantasy.issues.iter().all(|i| i.issue_type != IssueType::ContinuityError));
+    }
+}
@@ -0,0 +1,476 @@
+//! OMEGA HOLOGRAPH — Rule Packs
+//! Règles configurables (JSON / TOML), validées et versionnées
+//!
+//! - catégories d'attributs à groupes de valeurs mutuellement exclusifs
+//! - sévérité par catégorie, activation par genre (fantasy: les morts marchent)
+//! - règles regex personnalisées
+//! - mots-clés d'émotions complémentaires du lexique GOLD, matrice de transition (DYNAMICS)
+//! - poids du score (sévérité, type d'issue, normalisation)
+//!
+//! Le hash du pack (JSON canonique) est tamponné dans le HolographReport.
+//! NASA-Grade AS9100D
+
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+
+use super::dynamics::{known_emotions, DynamicsRules};
+use super::scoring::ScoringRules;
+use super::{IssueType, Severity};
+use crate::error::{OmegaError, OmegaResult};
+use crate::lexicon_fr_gold::normalize_fr;
+use crate::pipeline::fs_utils::{canonicalize_json, sha256_str};
+
+/// Version du schéma des packs supportée
+pub const RULE_PACK_SCHEMA_VERSION: u32 = 1;
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// TYPES
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RulePack {
+    #[serde(default = "default_schema_version")]
+    pub schema_version: u32,
+    pub id: String,
+    /// Version sémantique du pack (x.y.z)
+    pub version: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub categories: Vec<CategoryRule>,
+    /// Émotion → mots-clés (préfixes), en complément du lexique GOLD
+    #[serde(default)]
+    pub emotions: BTreeMap<String, Vec<String>>,
+    /// Paires d'émotions opposées (coût de transition maximal)
+    #[serde(default)]
+    pub emotion_opposites: Vec<(String, String)>,
+    #[serde(default)]
+    pub regex_rules: Vec<RegexRule>,
+    /// Seuils et matrice de transition DYNAMICS
+    #[serde(default)]
+    pub dynamics: DynamicsRules,
+    /// Poids et normalisation des scores
+    #[serde(default)]
+    pub scoring: ScoringRules,
+}
+
+/// Catégorie d'attribut: une entité ne peut relever que d'un seul groupe à la fois
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CategoryRule {
+    pub name: String,
+    pub groups: Vec<ValueGroup>,
+    /// Noms qualifiés (vide = l'entité elle-même): "cheveux", "chevelure"
+    #[serde(default)]
+    pub nouns: Vec<String>,
+    #[serde(default = "default_severity")]
+    pub severity: Severity,
+    #[serde(default)]
+    pub genres: GenreFilter,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValueGroup {
+    pub name: String,
+    pub keywords: Vec<String>,
+}
+
+/// Activation par genre: `only` restreint, `disabled` exclut
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GenreFilter {
+    #[serde(default)]
+    pub only: Vec<String>,
+    #[serde(default)]
+    pub disabled: Vec<String>,
+}
+
+/// Règle regex: chaque correspondance produit une issue.
+/// `message` accepte les groupes capturés ($1, $name).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegexRule {
+    pub id: String,
+    pub pattern: String,
+    pub message: String,
+    #[serde(default = "default_issue_type")]
+    pub issue_type: IssueType,
+    #[serde(default = "default_severity")]
+    pub severity: Severity,
+    #[serde(default)]
+    pub suggestion: Option<String>,
+    #[serde(default)]
+    pub case_insensitive: bool,
+    #[serde(default)]
+    pub genres: GenreFilter,
+}
+
+/// Identité du pack appliqué (tamponnée dans le rapport)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RulePackInfo {
+    pub id: String,
+    pub version: String,
+    pub hash: String,
+    #[serde(default)]
+    pub genre: Option<String>,
+}
+
+fn default_schema_version() -> u32 {
+    RULE_PACK_SCHEMA_VERSION
+}
+
+fn default_severity() -> Severity {
+    Severity::High
+}
+
+fn default_issue_type() -> IssueType {
+    IssueType::Contradiction
+}
+
+impl GenreFilter {
+    pub fn allows(&self, genre: Option<&str>) -> bool {
+        let genre = genre.map(normalize_fr);
+        let matches = |list: &[String]| {
+            genre.as_ref().map(|g| list.iter().any(|x| &normalize_fr(x) == g)).unwrap_or(false)
+        };
+        (self.only.is_empty() || matches(&self.only)) && !matches(&self.disabled)
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// LOAD / VALIDATE
+// ═══════════════════════════════════════════════════════════════════════════════
+
+impl RulePack {
+    /// Charge un pack JSON ou TOML (selon l'extension), normalisé et validé
+    pub fn load<P: AsRef<Path>>(path: P) -> OmegaResult<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| OmegaError::ReadError(format!("{}: {}", path.display(), e)))?;
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("toml") => Self::from_toml_str(&content),
+            Some("json") => Self::from_json_str(&content),
+            other => Err(OmegaError::ConfigError(format!(
+                "Extension de rule pack non supportee: {:?} (json | toml)",
+                other
+            ))),
+        }
+    }
+
+    pub fn from_json_str(content: &str) -> OmegaResult<Self> {
+        let pack: RulePack = serde_json::from_str(content)?;
+        pack.prepared()
+    }
+
+    pub fn from_toml_str(content: &str) -> OmegaResult<Self> {
+        let pack: RulePack = toml::from_str(content)
+            .map_err(|e| OmegaError::ConfigError(format!("TOML invalide: {}", e)))?;
+        pack.prepared()
+    }
+
+    fn prepared(self) -> OmegaResult<Self> {
+        let pack = self.normalized();
+        pack.validate()?;
+        Ok(pack)
+    }
+
+    /// Mots-clés et noms en forme normalisée (minuscules, sans accents)
+    fn normalized(mut self) -> Self {
+        for category in &mut self.categories {
+            for group in &mut category.groups {
+                group.keywords = group.keywords.iter().map(|k| normalize_fr(k)).collect();
+            }
+            category.nouns = category.nouns.iter().map(|n| normalize_fr(n)).collect();
+        }
+        for keywords in self.emotions.values_mut() {
+            *keywords = keywords.iter().map(|k| normalize_fr(k)).collect();
+        }
+        self.dynamics.normalize();
+        self
+    }
+
+    /// Vérifie la structure du pack; retourne la première erreur rencontrée
+    pub fn validate(&self) -> OmegaResult<()> {
+        let fail = |msg: String| Err(OmegaError::ConfigError(format!("Rule pack '{}': {}", self.id, msg)));
+
+        if self.schema_version != RULE_PACK_SCHEMA_VERSION {
+            return fail(format!(
+                "schema_version {} non supporte (attendu {})",
+                self.schema_version, RULE_PACK_SCHEMA_VERSION
+            ));
+        }
+        if self.id.trim().is_empty() {
+            return fail("id vide".to_string());
+        }
+        let semver = self.version.split('.').collect::<Vec<_>>();
+        if semver.len() != 3 || semver.iter().any(|p| p.parse::<u32>().is_err()) {
+            return fail(format!("version '{}' invalide (attendu x.y.z)", self.version));
+        }
+
+        let mut names = HashSet::new();
+        for category in &self.categories {
+            if !names.insert(category.name.as_str()) {
+                return fail(format!("categorie dupliquee: {}", category.name));
+            }
+            if category.groups.len() < 2 {
+                return fail(format!("categorie '{}': au moins 2 groupes exclusifs requis", category.name));
+            }
+            let mut keywords = HashSet::new();
+            let mut groups = HashSet::new();
+            for group in &category.groups {
+                if !groups.insert(group.name.as_str()) {
+                    return fail(format!("categorie '{}': groupe duplique {}", category.name, group.name));
+                }
+                if group.keywords.is_empty() {
+                    return fail(format!("categorie '{}': groupe '{}' sans mot-cle", category.name, group.name));
+                }
+                for keyword in &group.keywords {
+                    if keyword.is_empty() || keyword.contains(' ') {
+                        return fail(format!("categorie '{}': mot-cle invalide '{}'", category.name, keyword));
+                    }
+                    // Un mot-clé dans deux groupes d'une même catégorie rendrait l'exclusion contradictoire
+                    if !keywords.insert(keyword.as_str()) {
+                        return fail(format!(
+                            "categorie '{}': mot-cle '{}' present dans plusieurs groupes",
+                            category.name, keyword
+                        ));
+                    }
+                }
+            }
+        }
+
+        let known = known_emotions(self);
+        for (a, b) in &self.emotion_opposites {
+            for emotion in [a, b] {
+                if !known.contains(emotion) {
+                    return fail(format!("emotion opposee inconnue: {}", emotion));
+                }
+            }
+        }
+        if let Err(msg) = self.dynamics.validate(&known) {
+            return fail(msg);
+        }
+        if let Err(msg) = self.scoring.validate() {
+            return fail(msg);
+        }
+
+        let mut ids = HashSet::new();
+        for rule in &self.regex_rules {
+            if !ids.insert(rule.id.as_str()) {
+                return fail(format!("regle regex dupliquee: {}", rule.id));
+            }
+            if let Err(e) = rule.compile() {
+                return fail(format!("regle '{}': regex invalide: {}", rule.id, e));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Hash SHA-256 du JSON canonique du pack
+    pub fn hash(&self) -> String {
+        let value = serde_json::to_value(self).unwrap_or(serde_json::Value::Null);
+        sha256_str(&canonicalize_json(&value))
+    }
+
+    pub fn info(&self, genre: Option<&str>) -> RulePackInfo {
+        RulePackInfo {
+            id: self.id.clone(),
+            version: self.version.clone(),
+            hash: self.hash(),
+            genre: genre.map(|g| g.to_string()),
+        }
+    }
+
+    /// Catégories actives pour le genre donné
+    pub fn active_categories(&self, genre: Option<&str>) -> Vec<CategoryRule> {
+        self.categories.iter().filter(|c| c.genres.allows(genre)).cloned().collect()
+    }
+}
+
+impl RegexRule {
+    pub fn compile(&self) -> Result<Regex, regex::Error> {
+        RegexBuilder::new(&self.pattern).case_insensitive(self.case_insensitive).build()
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// BUILTIN PACK
+// ═══════════════════════════════════════════════════════════════════════════════
+
+fn category(name: &str, groups: &[(&str, &[&str])], nouns: &[&str]) -> CategoryRule {
+    CategoryRule {
+        name: name.to_string(),
+        groups: groups.iter().map(|(group, keywords)| ValueGroup {
+            name: group.to_string(),
+            keywords: keywords.iter().map(|k| k.to_string()).collect(),
+        }).collect(),
+        nouns: nouns.iter().map(|n| n.to_string()).collect(),
+        severity: Severity::High,
+        genres: GenreFilter::default(),
+    }
+}
+
+impl Default for RulePack {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+impl RulePack {
+    /// Pack par défaut (catégories historiques de HOLOGRAPH v0.1)
+    pub fn builtin() -> Self {
+        let emotions = [
+            ("joy", &["heureux", "joyeux", "ravi", "content", "sourire", "rire"][..]),
+            ("sadness", &["triste", "pleure", "larme", "chagrin", "melancolie", "desespoir"][..]),
+            ("anger", &["colere", "furieux", "rage", "enerve", "agace", "irrite"][..]),
+            ("fear", &["peur", "terreur", "effraye", "angoisse", "panique", "crainte"][..]),
+        ];
+
+        RulePack {
+            schema_version: RULE_PACK_SCHEMA_VERSION,
+            id: "omega.builtin".to_string(),
+            version: "1.0.0".to_string(),
+            description: "Regles par defaut HOLOGRAPH".to_string(),
+            categories: vec![
+                category("taille", &[
+                    ("grand", &["grand", "immense", "enorme", "geant", "haut"]),
+                    ("petit", &["petit", "minuscule", "nain", "bas", "court"]),
+                ], &[]),
+                category("age", &[
+                    ("jeune", &["jeune", "juvenile", "enfant", "adolescent"]),
+                    ("vieux", &["vieux", "age", "ancien", "vieillard"]),
+                ], &[]),
+                category("cheveux", &[
+                    ("blond", &["blond", "blonde", "dore"]),
+                    ("brun", &["brun", "brune", "noir", "sombre"]),
+                ], &["cheveux", "chevelure", "barbe"]),
+                category("temperature", &[
+                    ("chaud", &["chaud", "brulant", "torride", "chaleur"]),
+                    ("froid", &["froid", "glacial", "gele", "glace"]),
+                ], &[]),
+                category("lumiere", &[
+                    ("clair", &["lumineux", "clair", "eclaire", "brillant"]),
+                    ("sombre", &["sombre", "obscur", "noir", "tenebreux"]),
+                ], &[]),
+                category("vie", &[
+                    ("vivant", &["vivant", "vie", "respire", "bouge"]),
+                    ("mort", &["mort", "decede", "cadavre", "tue"]),
+                ], &[]),
+                category("presence", &[
+                    ("present", &["present", "arrive", "entre"]),
+                    ("absent", &["absent", "parti", "disparu", "sorti"]),
+                ], &[]),
+            ],
+            emotions: emotions.iter()
+                .map(|(e, kws)| (e.to_string(), kws.iter().map(|k| k.to_string()).collect()))
+                .collect(),
+            emotion_opposites: vec![
+                ("joy".to_string(), "sadness".to_string()),
+                ("anger".to_string(), "fear".to_string()),
+            ],
+            regex_rules: Vec::new(),
+            dynamics: DynamicsRules::default(),
+            scoring: ScoringRules::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FANTASY_TOML: &str = r#"
+id = "fantasy"
+version = "0.2.0"
+
+[[categories]]
+name = "vie"
+severity = "Critical"
+genres = { disabled = ["fantasy"] }
+groups = [
+    { name = "vivant", keywords = ["vivant"] },
+    { name = "mort", keywords = ["mort", "décédé"] },
+]
+
+[[regex_rules]]
+id = "no-modern-tech"
+pattern = "\\b(?P<objet>telephone|ordinateur)\\b"
+case_insensitive = true
+message = "Anachronisme: $objet"
+severity = "Medium"
+"#;
+
+    #[test]
+    fn test_builtin_valid_and_stable_hash() {
+        let pack = RulePack::builtin();
+        pack.validate().unwrap();
+        assert_eq!(pack.hash(), RulePack::builtin().hash());
+        assert_eq!(pack.hash().len(), 64);
+    }
+
+    #[test]
+    fn test_load_toml_normalizes() {
+        let pack = RulePack::from_toml_str(FANTASY_TOML).unwrap();
+        assert_eq!(pack.categories[0].severity, Severity::Critical);
+        assert!(pack.categories[0].groups[1].keywords.contains(&"decede".to_string()));
+        assert_eq!(pack.schema_version, RULE_PACK_SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn test_json_roundtrip_same_hash() {
+        let pack = RulePack::from_toml_str(FANTASY_TOML).unwrap();
+        let json = serde_json::to_string(&pack).unwrap();
+        assert_eq!(RulePack::from_json_str(&json).unwrap().hash(), pack.hash());
+    }
+
+    #[test]
+    fn test_dynamics_section() {
+        let toml = format!("{}\n[dynamics]\nunit = \"Scene\"\nthreshold = 0.3\ntransition_markers = [\"Peu à peu\"]\n", FANTASY_TOML);
+        let pack = RulePack::from_toml_str(&toml).unwrap();
+        assert_eq!(pack.dynamics.unit, crate::holograph::DynamicsUnit::Scene);
+        assert_eq!(pack.dynamics.threshold, 0.3);
+        assert_eq!(pack.dynamics.transition_markers, vec!["peu a peu"]);
+        assert_eq!(pack.dynamics.reference_words, DynamicsRules::default().reference_words);
+    }
+
+    #[test]
+    fn test_genre_filter() {
+        let pack = RulePack::from_toml_str(FANTASY_TOML).unwrap();
+        assert_eq!(pack.active_categories(None).len(), 1);
+        assert_eq!(pack.active_categories(Some("Fantasy")).len(), 0);
+
+        let only = GenreFilter { only: vec!["polar".into()], disabled: vec![] };
+        assert!(only.allows(Some("polar")));
+        assert!(!only.allows(None));
+    }
+
+    #[test]
+    fn test_validation_errors() {
+        let mut pack = RulePack::builtin();
+        pack.version = "1.0".into();
+        assert!(matches!(pack.validate(), Err(OmegaError::ConfigError(_))));
+
+        let mut pack = RulePack::builtin();
+        pack.categories[0].groups[1].keywords.push("grand".into());
+        assert!(pack.validate().unwrap_err().to_string().contains("plusieurs groupes"));
+
+        let mut pack = RulePack::builtin();
+        pack.categories[0].groups.truncate(1);
+        assert!(pack.validate().is_err());
+
+        let mut pack = RulePack::builtin();
+        pack.schema_version = 99;
+        assert!(pack.validate().is_err());
+
+        let mut pack = RulePack::builtin();
+        pack.emotion_opposites.push(("joy".into(), "nostalgie".into()));
+        assert!(pack.validate().unwrap_err().to_string().contains("nostalgie"));
+
+        let mut pack = RulePack::builtin();
+        pack.dynamics.threshold = 0.0;
+        assert!(pack.validate().is_err());
+
+        let bad_regex = FANTASY_TOML.replace("\\\\b(?P<objet>", "(?P<objet>[");
+        assert!(RulePack::from_toml_str(&bad_regex).is_err());
+    }
+}
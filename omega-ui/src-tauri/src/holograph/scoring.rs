@@ -0,0 +1,343 @@
+//! OMEGA HOLOGRAPH — Scoring
+//! Scores pondérés par la sévérité, normalisés et explicables
+//!
+//! Formule (poids configurables dans le rule pack, section `scoring`):
+//!
+//!   penalite(issue) = poids_severite(severite) × poids_type(type)
+//!   densite         = Σ penalites / max(1, mots / mots_reference)
+//!   score           = max(0, 100 − densite) / 100
+//!
+//! LOGIC (contradiction, temporel, continuité) et DYNAMICS (émotions, personnages)
+//! sont calculés séparément; le score global est leur moyenne.
+//! Un texte plus court que mots_reference n'est pas amplifié: pénalités brutes.
+//! Le détail par type, par catégorie et les "top offenders" (entités, chapitres)
+//! expliquent d'où vient la pénalité.
+//!
+//! NASA-Grade AS9100D
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use super::{CoherenceIssue, IssueType, Severity};
+
+/// Formule appliquée, recopiée dans chaque rapport
+pub const SCORING_FORMULA: &str =
+    "penalite = poids_severite x poids_type; densite = somme(penalites) / max(1, mots / mots_reference); score = max(0, 100 - densite) / 100";
+
+/// Types d'issue reconnus dans `type_weights`
+const ISSUE_TYPES: &[&str] = &["Contradiction", "TemporalError", "EmotionShift", "CharacterInconsistency", "ContinuityError"];
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// CONFIGURATION
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SeverityWeights {
+    pub critical: f32,
+    pub high: f32,
+    pub medium: f32,
+    pub low: f32,
+}
+
+impl Default for SeverityWeights {
+    fn default() -> Self {
+        Self { critical: 30.0, high: 15.0, medium: 5.0, low: 1.0 }
+    }
+}
+
+/// Poids du score (section `scoring` du rule pack)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScoringRules {
+    #[serde(default)]
+    pub severity_weights: SeverityWeights,
+    /// Multiplicateur par type d'issue ("EmotionShift" = 2.0); absent = 1.0
+    #[serde(default = "default_type_weights")]
+    pub type_weights: BTreeMap<String, f32>,
+    /// Taille de référence de la normalisation (mots)
+    #[serde(default = "default_reference_words")]
+    pub reference_words: usize,
+    /// Nombre d'entrées du classement "top offenders"
+    #[serde(default = "default_top_offenders")]
+    pub top_offenders: usize,
+}
+
+fn default_type_weights() -> BTreeMap<String, f32> {
+    // Un changement émotionnel (Medium) pèse 10, comme en v0.1
+    BTreeMap::from([("EmotionShift".to_string(), 2.0)])
+}
+
+fn default_reference_words() -> usize {
+    1000
+}
+
+fn default_top_offenders() -> usize {
+    5
+}
+
+impl Default for ScoringRules {
+    fn default() -> Self {
+        Self {
+            severity_weights: SeverityWeights::default(),
+            type_weights: default_type_weights(),
+            reference_words: default_reference_words(),
+            top_offenders: default_top_offenders(),
+        }
+    }
+}
+
+impl ScoringRules {
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        let w = &self.severity_weights;
+        for (name, value) in [("critical", w.critical), ("high", w.high), ("medium", w.medium), ("low", w.low)] {
+            if !value.is_finite() || value < 0.0 {
+                return Err(format!("scoring: poids de severite {} invalide: {}", name, value));
+            }
+        }
+        for (issue_type, value) in &self.type_weights {
+            if !ISSUE_TYPES.contains(&issue_type.as_str()) {
+                return Err(format!("scoring: type d'issue inconnu: {}", issue_type));
+            }
+            if !value.is_finite() || *value < 0.0 {
+                return Err(format!("scoring: poids invalide pour {}: {}", issue_type, value));
+            }
+        }
+        if self.reference_words == 0 {
+            return Err("scoring: reference_words doit etre > 0".to_string());
+        }
+        Ok(())
+    }
+
+    /// Pénalité d'une issue
+    pub fn penalty(&self, issue: &CoherenceIssue) -> f32 {
+        let w = &self.severity_weights;
+        let severity = match issue.severity {
+            Severity::Critical => w.critical,
+            Severity::High => w.high,
+            Severity::Medium => w.medium,
+            Severity::Low => w.low,
+        };
+        severity * self.type_weights.get(&type_name(&issue.issue_type)).copied().unwrap_or(1.0)
+    }
+
+    /// Diviseur de normalisation: max(1, mots / mots_reference)
+    pub fn normalizer(&self, words: usize) -> f32 {
+        (words as f32 / self.reference_words as f32).max(1.0)
+    }
+
+    /// Score dans [0, 1] pour une pénalité brute
+    pub fn score(&self, penalty: f32, words: usize) -> f32 {
+        (100.0 - penalty / self.normalizer(words)).max(0.0) / 100.0
+    }
+
+    /// Détail du score; `chapter` = chapitre de l'issue (scan de manuscrit)
+    pub fn breakdown(&self, issues: &[(&CoherenceIssue, Option<String>)], words: usize, entities: usize) -> ScoreBreakdown {
+        let mut by_type: BTreeMap<String, (usize, f32)> = BTreeMap::new();
+        let mut by_category: BTreeMap<String, (usize, f32)> = BTreeMap::new();
+        let mut offenders: BTreeMap<(OffenderKind, String), (usize, f32)> = BTreeMap::new();
+        let (mut logic, mut dynamics) = (0.0, 0.0);
+
+        for (issue, chapter) in issues {
+            let penalty = self.penalty(issue);
+            if issue.issue_type.is_logic() {
+                logic += penalty;
+            } else {
+                dynamics += penalty;
+            }
+            tally(&mut by_type, type_name(&issue.issue_type), penalty);
+            tally(&mut by_category, issue.category.clone().unwrap_or_else(|| "(aucune)".to_string()), penalty);
+            if let Some(entity) = &issue.entity {
+                tally(&mut offenders, (OffenderKind::Entity, entity.clone()), penalty);
+            }
+            if let Some(chapter) = chapter {
+                tally(&mut offenders, (OffenderKind::Chapter, chapter.clone()), penalty);
+            }
+        }
+
+        let total = logic + dynamics;
+        let share = |penalty: f32| if total > 0.0 { penalty / total } else { 0.0 };
+        let shares = |map: BTreeMap<String, (usize, f32)>| -> Vec<PenaltyShare> {
+            let mut shares: Vec<PenaltyShare> = map.into_iter()
+                .map(|(key, (issues, penalty))| PenaltyShare { key, issues, penalty, share: share(penalty) })
+                .collect();
+            shares.sort_by(|a, b| b.penalty.partial_cmp(&a.penalty).unwrap().then_with(|| a.key.cmp(&b.key)));
+            shares
+        };
+
+        let mut top_offenders: Vec<Offender> = offenders.into_iter()
+            .map(|((kind, name), (issues, penalty))| Offender { kind, name, issues, penalty, share: share(penalty) })
+            .collect();
+        top_offenders.sort_by(|a, b| b.penalty.partial_cmp(&a.penalty).unwrap().then_with(|| a.name.cmp(&b.name)));
+        top_offenders.truncate(self.top_offenders);
+
+        ScoreBreakdown {
+            formula: SCORING_FORMULA.to_string(),
+            weights: self.clone(),
+            words,
+            entities,
+            logic_penalty: logic,
+            dynamics_penalty: dynamics,
+            density: total / self.normalizer(words),
+            penalty_per_entity: total / entities.max(1) as f32,
+            by_type: shares(by_type),
+            by_category: shares(by_category),
+            top_offenders,
+        }
+    }
+}
+
+/// Compte une issue et sa pénalité sous une clé
+fn tally<K: Ord>(map: &mut BTreeMap<K, (usize, f32)>, key: K, penalty: f32) {
+    let entry = map.entry(key).or_insert((0, 0.0));
+    entry.0 += 1;
+    entry.1 += penalty;
+}
+
+fn type_name(issue_type: &IssueType) -> String {
+    format!("{:?}", issue_type)
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// BREAKDOWN
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Part de la pénalité totale
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PenaltyShare {
+    pub key: String,
+    pub issues: usize,
+    pub penalty: f32,
+    /// Part de la pénalité totale, dans [0, 1]
+    pub share: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum OffenderKind {
+    Entity,
+    Chapter,
+}
+
+/// Entité ou chapitre qui concentre la pénalité
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Offender {
+    pub kind: OffenderKind,
+    pub name: String,
+    pub issues: usize,
+    pub penalty: f32,
+    pub share: f32,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScoreBreakdown {
+    pub formula: String,
+    pub weights: ScoringRules,
+    pub words: usize,
+    pub entities: usize,
+    pub logic_penalty: f32,
+    pub dynamics_penalty: f32,
+    /// Pénalité totale par tranche de `reference_words` mots
+    pub density: f32,
+    pub penalty_per_entity: f32,
+    pub by_type: Vec<PenaltyShare>,
+    pub by_category: Vec<PenaltyShare>,
+    pub top_offenders: Vec<Offender>,
+}
+
+impl ScoreBreakdown {
+    /// (logic, dynamics, global)
+    pub fn scores(&self) -> (f32, f32, f32) {
+        let logic = self.weights.score(self.logic_penalty, self.words);
+        let dynamics = self.weights.score(self.dynamics_penalty, self.words);
+        (logic, dynamics, (logic + dynamics) / 2.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::holograph::HolographScanner;
+
+    fn issue(issue_type: IssueType, severity: Severity, entity: Option<&str>, category: &str) -> CoherenceIssue {
+        CoherenceIssue {
+            issue_type,
+            severity,
+            description: String::new(),
+            evidence: Vec::new(),
+            location: None,
+            suggestion: None,
+            spans: Vec::new(),
+            entity: entity.map(str::to_string),
+            category: Some(category.to_string()),
+            fingerprint: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_penalty_weights() {
+        let rules = ScoringRules::default();
+        assert_eq!(rules.penalty(&issue(IssueType::Contradiction, Severity::High, None, "taille")), 15.0);
+        assert_eq!(rules.penalty(&issue(IssueType::EmotionShift, Severity::Medium, None, "emotion")), 10.0);
+
+        let mut custom = ScoringRules::default();
+        custom.severity_weights.high = 20.0;
+        custom.type_weights.insert("Contradiction".into(), 0.5);
+        assert_eq!(custom.penalty(&issue(IssueType::Contradiction, Severity::High, None, "taille")), 10.0);
+    }
+
+    #[test]
+    fn test_normalized_by_length() {
+        let rules = ScoringRules::default();
+        let issues: Vec<CoherenceIssue> = (0..7).map(|_| issue(IssueType::Contradiction, Severity::Medium, None, "age")).collect();
+        let refs: Vec<(&CoherenceIssue, Option<String>)> = issues.iter().map(|i| (i, None)).collect();
+
+        let page = rules.breakdown(&refs, 300, 2).scores().0;
+        let novel = rules.breakdown(&refs, 90_000, 40).scores().0;
+        assert!((page - 0.65).abs() < 1e-4);
+        assert!(novel > 0.99);
+    }
+
+    #[test]
+    fn test_breakdown_and_offenders() {
+        let rules = ScoringRules::default();
+        let issues = [
+            issue(IssueType::Contradiction, Severity::High, Some("Pierre"), "taille"),
+            issue(IssueType::ContinuityError, Severity::High, Some("Pierre"), "vie"),
+            issue(IssueType::CharacterInconsistency, Severity::Medium, Some("Marie"), "genre"),
+            issue(IssueType::TemporalError, Severity::Medium, None, "date"),
+        ];
+        let refs: Vec<(&CoherenceIssue, Option<String>)> = issues.iter()
+            .enumerate()
+            .map(|(i, issue)| (issue, Some(format!("ch{:02}", i % 2 + 1))))
+            .collect();
+        let breakdown = rules.breakdown(&refs, 500, 2);
+
+        assert_eq!(breakdown.logic_penalty, 35.0);
+        assert_eq!(breakdown.dynamics_penalty, 5.0);
+        assert_eq!(breakdown.penalty_per_entity, 20.0);
+        assert_eq!(breakdown.by_type.len(), 4);
+        assert_eq!(breakdown.by_category[0].penalty, 15.0);
+        let top = &breakdown.top_offenders[0];
+        assert_eq!((top.kind, top.name.as_str(), top.penalty), (OffenderKind::Entity, "Pierre", 30.0));
+        assert!((top.share - 0.75).abs() < 1e-4);
+        assert!(breakdown.top_offenders.iter().any(|o| o.kind == OffenderKind::Chapter && o.name == "ch01"));
+    }
+
+    #[test]
+    fn test_report_carries_breakdown() {
+        let report = HolographScanner::new().scan("Pierre etait grand. Plus tard, Pierre etait petit.");
+        assert_eq!(report.score.formula, SCORING_FORMULA);
+        assert_eq!(report.score.words, 8);
+        assert_eq!(report.score.top_offenders[0].name, "Pierre");
+        assert_eq!(report.score.scores().0, report.logic_score);
+    }
+
+    #[test]
+    fn test_validation() {
+        let mut rules = ScoringRules::default();
+        rules.type_weights.insert("Typo".into(), 1.0);
+        assert!(rules.validate().is_err());
+
+        let mut rules = ScoringRules::default();
+        rules.severity_weights.low = -1.0;
+        assert!(rules.validate().is_err());
+    }
+}
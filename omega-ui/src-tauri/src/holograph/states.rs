@@ -0,0 +1,561 @@
+//! OMEGA HOLOGRAPH — Character State Machine
+//! LOGIC: continuité d'état des personnages, dans l'ordre des segments
+//!
+//! - vie: vivant → mort (mourut, tué, décédé); un mort qui parle ou agit est signalé
+//! - présence: présent → parti (partit, quitta, disparut) jusqu'au retour (revint, entra)
+//! - lieu: un personnage à LOC:A ne peut agir à LOC:B dans la même scène sans déplacement
+//!
+//! Les passages marqués flashback / rêve sont exemptés.
+//! Une rupture de scène (***, #, chapitre) réinitialise présence et lieu, pas la vie:
+//! un personnage mort dans un chapitre précédent reste mort (`analyze_after`).
+//!
+//! NASA-Grade AS9100D
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::characters::{NAME, STOPWORDS};
+use super::{split_segments, split_sentences, CoherenceIssue, IssueType, Severity, TemporalAnalyzer, TextSpan};
+use crate::lexicon_fr_gold::normalize_fr;
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// TYPES
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LifeState {
+    Alive,
+    Dead,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PresenceState {
+    Present,
+    Departed,
+}
+
+/// Événement d'état détecté dans le texte
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum StateEvent {
+    Death,
+    Departure,
+    /// Retour / arrivée, éventuellement vers un lieu
+    Arrival { location: Option<String> },
+    /// Présence constatée dans un lieu ("était dans la cuisine")
+    Located { location: String },
+    /// Parole ou action dont le personnage est le sujet
+    Action,
+}
+
+/// Transition d'état appliquée à un personnage
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StateTransition {
+    pub entity: String,
+    pub from: String,
+    pub to: String,
+    pub scene: usize,
+    pub span: TextSpan,
+    pub raw: String,
+}
+
+/// Personnage mort avant le texte (chapitre précédent) qui y parle ou agit
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InheritedViolation {
+    pub entity: String,
+    pub span: TextSpan,
+    pub raw: String,
+}
+
+#[derive(Debug, Clone)]
+struct Event {
+    entity: String,
+    event: StateEvent,
+    span: TextSpan,
+    /// Position du nom: un même nom ne porte qu'un événement
+    name_at: usize,
+}
+
+#[derive(Debug, Clone)]
+struct Since<T> {
+    state: T,
+    span: TextSpan,
+    raw: String,
+}
+
+#[derive(Debug, Clone)]
+struct CharacterState {
+    life: Since<LifeState>,
+    presence: Since<PresenceState>,
+    location: Option<Since<String>>,
+    /// Mort héritée d'un texte précédent (la preuve n'est pas dans ce texte)
+    inherited: bool,
+}
+
+/// Résultat d'un passage du tracker
+#[derive(Debug, Default)]
+struct Run {
+    issues: Vec<CoherenceIssue>,
+    transitions: Vec<StateTransition>,
+    inherited: Vec<InheritedViolation>,
+}
+
+/// Checks actifs (selon les catégories du rule pack)
+#[derive(Debug, Clone, Copy)]
+pub struct StateChecks {
+    pub life: bool,
+    pub presence: bool,
+}
+
+impl Default for StateChecks {
+    fn default() -> Self {
+        Self { life: true, presence: true }
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// LEXIQUE
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Lieux communs reconnus après une préposition (les noms propres le sont toujours)
+const PLACES: &[&str] = &[
+    "cuisine", "salon", "jardin", "chambre", "bureau", "cave", "grenier", "eglise", "gare",
+    "foret", "village", "ville", "chateau", "maison", "bibliotheque", "hopital", "port", "plage",
+    "rue", "cour", "auberge", "taverne", "hall", "couloir", "garage", "palais", "temple", "ecole",
+    "marche", "salle", "grange", "ecurie", "tour", "crypte", "cimetiere", "parc", "atelier",
+];
+
+/// Verbes d'état: ne comptent pas comme une action du sujet
+const STATIVE: &[&str] = &[
+    "etait", "fut", "avait", "eut", "semblait", "sembla", "paraissait", "restait", "resta",
+    "gisait", "reposait", "demeurait", "devait", "pouvait", "allait", "venait", "etaient",
+];
+
+/// Rupture de scène: "***", "#", "---", "Chapitre 3", "PARTIE II : Le retour" (première ligne d'un segment).
+/// Un titre est une ligne courte numérotée: « Partie de chasse terminée, il rentra. » n'en est pas un
+pub(crate) const SCENE_BREAK: &str = r"(?m)^\s*(?:(?:\*\s*){1,5}|#+.*|~+|-{3,}|—+|(?i:chapitre|chapter|partie)\s+(?:\d+|[IVXLC]+|(?i:premi(?:er|[eè]re)))\b.{0,60})\s*$";
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// TRACKER
+// ═══════════════════════════════════════════════════════════════════════════════
+
+pub struct StateTracker {
+    death_subject_re: Regex,
+    death_object_re: Regex,
+    departure_re: Regex,
+    arrival_re: Regex,
+    located_re: Regex,
+    action_re: Regex,
+    speech_re: Regex,
+    exempt_re: Regex,
+    scene_break_re: Regex,
+    temporal: TemporalAnalyzer,
+}
+
+impl Default for StateTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StateTracker {
+    pub fn new() -> Self {
+        let prep = r"(?i:dans|[àa]|au|aux|vers|jusqu['’][àa]|chez)";
+        let place = r"(?:(?i:le|la|les|l['’])\s*)?(?P<loc>[\p{L}-]+)";
+        Self {
+            death_subject_re: Regex::new(&format!(
+                r"\b(?P<name>{})\s+(?:(?i:mourut|meurt|d[ée]c[ée]da|succomba|expira|p[ée]rit|rendit\s+l['’][aâ]me|rendit\s+son\s+dernier\s+souffle)|(?i:est|fut|a\s+[ée]t[ée]|avait\s+[ée]t[ée]|[ée]tait|venait\s+d['’][êe]tre)\s+(?i:mort|morte|tu[ée]e?|d[ée]c[ée]d[ée]e?|assassin[ée]e?|ex[ée]cut[ée]e?|abattue?))\b",
+                NAME
+            )).unwrap(),
+            death_object_re: Regex::new(&format!(
+                r"(?:(?i:tua|a\s+tu[ée]|avait\s+tu[ée]|assassina|abattit|poignarda|ex[ée]cuta|[ée]trangla|empoisonna)\s+|(?i:le\s+cadavre|le\s+corps\s+sans\s+vie|l['’]enterrement|les\s+fun[ée]railles|la\s+tombe)\s+(?i:de|d['’])\s*)(?P<name>{})\b",
+                NAME
+            )).unwrap(),
+            departure_re: Regex::new(&format!(
+                r"\b(?P<name>{})\s+(?:(?i:partit|s['’]en\s+alla|s['’]enfuit|quitta|disparut|sortit|s['’][ée]loigna)|(?i:est|[ée]tait)\s+(?i:partie?|sortie?))\b",
+                NAME
+            )).unwrap(),
+            arrival_re: Regex::new(&format!(
+                r"\b(?P<name>{})\s+(?:(?i:revint|rentra|r[ée]apparut|arriva|entra|alla|se\s+rendit|rejoignit|gagna|courut|monta|descendit)|(?i:est|[ée]tait)\s+(?i:revenue?|arriv[ée]e?|entr[ée]e?|rentr[ée]e?))(?:\s+{}\s+{})?",
+                NAME, prep, place
+            )).unwrap(),
+            located_re: Regex::new(&format!(
+                r"\b(?P<name>{})\s+(?i:[ée]tait|se\s+trouvait|attendait|restait|dormait|travaillait|demeurait|patientait|se\s+tenait|mangeait|lisait)\s+{}\s+{}",
+                NAME, prep, place
+            )).unwrap(),
+            action_re: Regex::new(&format!(
+                r"\b(?P<name>{})\s+(?:(?i:ne|n['’]|se|s['’]|lui|leur|le|la|les|l['’]|y|en)\s*)*(?P<verb>\p{{Ll}}{{2,}}(?:a|ait|aient|it|ut|èrent|irent|urent|ira|era))\b",
+                NAME
+            )).unwrap(),
+            speech_re: Regex::new(&format!(
+                r"(?i:\bdit|r[ée]pondit|murmura|cria|demanda|souffla|lan[çc]a|ajouta|s['’]exclama|chuchota|hurla|r[ée]pliqua|soupira|reprit)\s+(?P<name>{})\b",
+                NAME
+            )).unwrap(),
+            exempt_re: Regex::new(
+                r"(?i)\b(?:flashback|r[êe]v(?:e|es|ait|a|ant)|en\s+r[êe]ve|songe|cauchemar|hallucination|vision|souvenirs?|se\s+souv\w+|autrefois|jadis)\b"
+            ).unwrap(),
+            scene_break_re: Regex::new(SCENE_BREAK).unwrap(),
+            temporal: TemporalAnalyzer::new(),
+        }
+    }
+
+    /// Transitions d'état appliquées, dans l'ordre du texte
+    pub fn transitions(&self, text: &str) -> Vec<StateTransition> {
+        self.run(text, StateChecks::default(), &BTreeSet::new()).transitions
+    }
+
+    /// Violations des transitions d'état
+    pub fn analyze(&self, text: &str, checks: StateChecks) -> Vec<CoherenceIssue> {
+        self.run(text, checks, &BTreeSet::new()).issues
+    }
+
+    /// Personnages de `dead` (morts dans un texte précédent) qui parlent ou agissent dans ce texte,
+    /// hors flashback / rêve; les violations propres au texte restent dans `analyze`
+    pub fn analyze_after(&self, text: &str, checks: StateChecks, dead: &BTreeSet<String>) -> Vec<InheritedViolation> {
+        self.run(text, checks, dead).inherited
+    }
+
+    fn run(&self, text: &str, checks: StateChecks, dead: &BTreeSet<String>) -> Run {
+        let mut run = Run::default();
+        if !checks.life && !checks.presence {
+            return run;
+        }
+
+        let retrospective: Vec<TextSpan> = self.temporal.timeline(text).into_iter()
+            .filter(|s| s.retrospective)
+            .map(|s| s.span)
+            .collect();
+        let mut states: BTreeMap<String, CharacterState> = dead.iter()
+            .map(|name| (name.clone(), CharacterState {
+                life: Since { state: LifeState::Dead, span: TextSpan::new(0, 0), raw: String::new() },
+                presence: Since { state: PresenceState::Present, span: TextSpan::new(0, 0), raw: String::new() },
+                location: None,
+                inherited: true,
+            }))
+            .collect();
+        let mut scene = 0;
+
+        for (segment, seg_text) in split_segments(text) {
+            if self.scene_break_re.is_match(seg_text.lines().next().unwrap_or("")) {
+                scene += 1;
+                for state in states.values_mut() {
+                    state.presence = Since { state: PresenceState::Present, span: segment, raw: String::new() };
+                    state.location = None;
+                }
+                if seg_text.lines().count() <= 1 {
+                    continue;
+                }
+            }
+            // Segment entier en flashback / rêve: hors continuité
+            let first_sentence = split_sentences(seg_text).first().map(|s| &seg_text[s.start..s.end]).unwrap_or("");
+            if retrospective.contains(&segment) || self.exempt_re.is_match(first_sentence) {
+                continue;
+            }
+
+            for sentence in split_sentences(seg_text) {
+                let s = &seg_text[sentence.start..sentence.end];
+                if self.exempt_re.is_match(s) {
+                    continue;
+                }
+                let base = segment.start + sentence.start;
+                for event in self.events(s, base) {
+                    let raw = text[event.span.start..event.span.end].to_string();
+                    let state = states.entry(event.entity.clone()).or_insert_with(|| CharacterState {
+                        life: Since { state: LifeState::Alive, span: event.span, raw: String::new() },
+                        presence: Since { state: PresenceState::Present, span: event.span, raw: String::new() },
+                        location: None,
+                        inherited: false,
+                    });
+                    let mut transition = |from: &str, to: &str| run.transitions.push(StateTransition {
+                        entity: event.entity.clone(),
+                        from: from.to_string(),
+                        to: to.to_string(),
+                        scene,
+                        span: event.span,
+                        raw: raw.clone(),
+                    });
+
+                    // Un mort n'agit plus, ne part plus, n'arrive plus
+                    if checks.life && state.life.state == LifeState::Dead && event.event != StateEvent::Death {
+                        if state.inherited {
+                            run.inherited.push(InheritedViolation { entity: event.entity.clone(), span: event.span, raw });
+                            continue;
+                        }
+                        run.issues.push(violation(
+                            &event.entity, "vie", Severity::High,
+                            "vivant → mort", &state.life, &raw, event.span,
+                            "Personnage mort qui parle ou agit",
+                            "Marquer le passage comme flashback/reve, ou revoir la mort",
+                        ));
+                        continue;
+                    }
+
+                    // Un personnage parti n'agit plus dans la scène avant son retour
+                    let acting = matches!(event.event, StateEvent::Located { .. } | StateEvent::Action);
+                    if checks.presence && acting && state.presence.state == PresenceState::Departed {
+                        run.issues.push(violation(
+                            &event.entity, "presence", Severity::Medium,
+                            "present → parti", &state.presence, &raw, event.span,
+                            "Personnage parti qui agit dans la meme scene",
+                            "Ajouter un retour du personnage ou une rupture de scene",
+                        ));
+                        continue;
+                    }
+
+                    match &event.event {
+                        StateEvent::Death => {
+                            if state.life.state == LifeState::Alive {
+                                transition("vivant", "mort");
+                                state.life = Since { state: LifeState::Dead, span: event.span, raw: raw.clone() };
+                            }
+                        }
+                        StateEvent::Departure => {
+                            transition("present", "parti");
+                            state.presence = Since { state: PresenceState::Departed, span: event.span, raw: raw.clone() };
+                            state.location = None;
+                        }
+                        StateEvent::Arrival { location } => {
+                            if state.presence.state == PresenceState::Departed {
+                                transition("parti", "present");
+                            }
+                            if let Some(loc) = location {
+                                transition(
+                                    state.location.as_ref().map(|l| l.state.as_str()).unwrap_or("?"),
+                                    loc,
+                                );
+                                state.location = Some(Since { state: loc.clone(), span: event.span, raw: raw.clone() });
+                            }
+                            state.presence = Since { state: PresenceState::Present, span: event.span, raw: raw.clone() };
+                        }
+                        StateEvent::Located { location } => {
+                            match &state.location {
+                                Some(current) if checks.presence && &current.state != location => {
+                                    run.issues.push(violation(
+                                        &event.entity, "lieu", Severity::Medium,
+                                        &format!("{} → {}", current.state, location), current, &raw, event.span,
+                                        "Personnage dans deux lieux de la meme scene",
+                                        "Raconter le deplacement ou separer les scenes",
+                                    ));
+                                }
+                                Some(_) => {}
+                                None => {
+                                    transition("?", location);
+                                }
+                            }
+                            state.location = Some(Since { state: location.clone(), span: event.span, raw: raw.clone() });
+                        }
+                        StateEvent::Action => {}
+                    }
+                }
+            }
+        }
+
+        run
+    }
+
+    /// Événements d'une phrase, dans l'ordre; un seul événement par occurrence de nom
+    fn events(&self, sentence: &str, base: usize) -> Vec<Event> {
+        let mut events: Vec<Event> = Vec::new();
+        let is_name = |name: &str| !STOPWORDS.contains(&name);
+        let location = |cap: &regex::Captures| -> Option<String> {
+            let loc = cap.name("loc")?;
+            let word = normalize_fr(loc.as_str());
+            let proper = loc.as_str().chars().next().map(|c| c.is_uppercase()).unwrap_or(false);
+            (proper || PLACES.contains(&word.as_str())).then(|| format!("LOC:{}", word.replace(' ', "_")))
+        };
+        let mut push = |cap: &regex::Captures, event: StateEvent| {
+            let name = cap.name("name").unwrap();
+            if !is_name(name.as_str()) || events.iter().any(|e| e.name_at == base + name.start()) {
+                return;
+            }
+            let m = cap.get(0).unwrap();
+            events.push(Event {
+                entity: name.as_str().to_string(),
+                event,
+                span: TextSpan::new(base + m.start(), base + m.end()),
+                name_at: base + name.start(),
+            });
+        };
+
+        // Priorité: mort > départ > lieu > arrivée > parole > action
+        for cap in self.death_subject_re.captures_iter(sentence) {
+            push(&cap, StateEvent::Death);
+        }
+        for cap in self.death_object_re.captures_iter(sentence) {
+            push(&cap, StateEvent::Death);
+        }
+        for cap in self.departure_re.captures_iter(sentence) {
+            push(&cap, StateEvent::Departure);
+        }
+        for cap in self.located_re.captures_iter(sentence) {
+            if let Some(location) = location(&cap) {
+                push(&cap, StateEvent::Located { location });
+            }
+        }
+        for cap in self.arrival_re.captures_iter(sentence) {
+            let location = location(&cap);
+            push(&cap, StateEvent::Arrival { location });
+        }
+        for cap in self.speech_re.captures_iter(sentence) {
+            push(&cap, StateEvent::Action);
+        }
+        for cap in self.action_re.captures_iter(sentence) {
+            if !STATIVE.contains(&normalize_fr(&cap["verb"]).as_str()) {
+                push(&cap, StateEvent::Action);
+            }
+        }
+
+        events.sort_by_key(|e| e.span.start);
+        events
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn violation<T>(
+    entity: &str,
+    category: &str,
+    severity: Severity,
+    transition: &str,
+    since: &Since<T>,
+    raw: &str,
+    span: TextSpan,
+    what: &str,
+    suggestion: &str,
+) -> CoherenceIssue {
+    CoherenceIssue {
+        issue_type: IssueType::ContinuityError,
+        severity,
+        description: format!("{}: '{}' (transition {} violee)", what, entity, transition),
+        evidence: vec![
+            format!("Transition {}: \"{}\" @{}", transition, since.raw, since.span.start),
+            format!("Violation: \"{}\" @{}", raw, span.start),
+        ],
+        location: Some(format!("Personnage: {}", entity)),
+        suggestion: Some(suggestion.to_string()),
+        spans: vec![since.span, span],
+        entity: Some(entity.to_string()),
+        category: Some(category.to_string()),
+        fingerprint: String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn analyze(text: &str) -> Vec<CoherenceIssue> {
+        StateTracker::new().analyze(text, StateChecks::default())
+    }
+
+    #[test]
+    fn test_dead_character_speaks() {
+        let text = "Le soir venu, Pierre mourut dans ses bras.\n\nLe lendemain, Marie pleurait. « Courage », dit Pierre.";
+        let issues = analyze(text);
+        assert_eq!(issues.len(), 1, "{:?}", issues);
+        assert_eq!(issues[0].issue_type, IssueType::ContinuityError);
+        assert!(issues[0].description.contains("vivant → mort"));
+        assert_eq!(&text[issues[0].spans[0].start..issues[0].spans[0].end], "Pierre mourut");
+        assert_eq!(&text[issues[0].spans[1].start..issues[0].spans[1].end], "dit Pierre");
+    }
+
+    #[test]
+    fn test_killed_character_acts() {
+        let issues = analyze("Le bandit tua Pierre. Plus tard, Pierre ouvrit la porte.");
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].severity, Severity::High);
+    }
+
+    #[test]
+    fn test_death_inherited_from_previous_text() {
+        let dead: BTreeSet<String> = ["Pierre".to_string()].into();
+        let text = "Marie ouvrit la porte. « Bonjour », dit Pierre. Pierre sourit.\n\nDans son reve, Pierre lui parla.";
+        let inherited = StateTracker::new().analyze_after(text, StateChecks::default(), &dead);
+        assert_eq!(inherited.len(), 2, "{:?}", inherited);
+        assert_eq!(inherited[0].raw, "dit Pierre");
+        assert!(analyze(text).is_empty(), "violations propres au texte seulement");
+        let off = StateChecks { life: false, presence: true };
+        assert!(StateTracker::new().analyze_after(text, off, &dead).is_empty());
+    }
+
+    #[test]
+    fn test_flashback_exempt() {
+        let text = "Pierre mourut en mars.\n\nMarie se souvenait de ce jour. Pierre riait alors.\n\nFlashback. Pierre marcha vers elle.";
+        assert!(analyze(text).is_empty(), "{:?}", analyze(text));
+    }
+
+    #[test]
+    fn test_dream_exempt() {
+        let text = "Pierre mourut en mars. Cette nuit-la, dans son reve, Pierre lui parla.";
+        assert!(analyze(text).is_empty());
+    }
+
+    #[test]
+    fn test_mention_of_dead_is_fine() {
+        let text = "Pierre mourut en mars. Marie pensait a Pierre. Le cadavre de Pierre fut enterre.";
+        assert!(analyze(text).is_empty(), "{:?}", analyze(text));
+    }
+
+    #[test]
+    fn test_departed_acts_same_scene() {
+        let text = "Pierre quitta la maison en claquant la porte. Marie soupira. Pierre sourit.";
+        let issues = analyze(text);
+        assert_eq!(issues.len(), 1, "{:?}", issues);
+        assert!(issues[0].description.contains("present → parti"));
+    }
+
+    #[test]
+    fn test_departure_then_return() {
+        let text = "Pierre quitta la maison. Une heure passa. Pierre revint dans la cuisine. Pierre sourit.";
+        assert!(analyze(text).is_empty(), "{:?}", analyze(text));
+    }
+
+    #[test]
+    fn test_scene_break_resets_presence() {
+        let text = "Pierre quitta la maison.\n\n***\n\nPierre sourit a Marie.";
+        assert!(analyze(text).is_empty(), "{:?}", analyze(text));
+    }
+
+    #[test]
+    fn test_chapter_heading_is_break_but_prose_is_not() {
+        for heading in ["Chapitre 3", "PARTIE II : Le retour", "Chapitre premier"] {
+            let text = format!("Pierre quitta la maison.\n\n{}\nPierre sourit a Marie.", heading);
+            assert!(analyze(&text).is_empty(), "{}: {:?}", heading, analyze(&text));
+        }
+        let text = "Pierre quitta la maison.\n\nPartie de chasse terminee, Pierre sourit a Marie.";
+        assert_eq!(analyze(text).len(), 1, "{:?}", analyze(text));
+    }
+
+    #[test]
+    fn test_two_locations_same_scene() {
+        let text = "Pierre attendait dans la cuisine. Marie entra. Pierre se trouvait au jardin.";
+        let issues = analyze(text);
+        assert_eq!(issues.len(), 1, "{:?}", issues);
+        assert!(issues[0].description.contains("LOC:cuisine → LOC:jardin"));
+    }
+
+    #[test]
+    fn test_movement_allows_new_location() {
+        let text = "Pierre attendait dans la cuisine. Pierre courut vers le jardin. Pierre se trouvait au jardin.";
+        assert!(analyze(text).is_empty(), "{:?}", analyze(text));
+    }
+
+    #[test]
+    fn test_location_across_scenes() {
+        let text = "Pierre attendait dans la cuisine.\n\n* * *\n\nPierre etait a Paris.";
+        assert!(analyze(text).is_empty());
+    }
+
+    #[test]
+    fn test_checks_disabled() {
+        let text = "Pierre mourut. Pierre ouvrit les yeux.";
+        let none = StateTracker::new().analyze(text, StateChecks { life: false, presence: true });
+        assert!(none.is_empty());
+    }
+
+    #[test]
+    fn test_transitions_reported() {
+        let transitions = StateTracker::new().transitions("Pierre partit. Pierre revint dans la cuisine. Pierre mourut.");
+        let labels: Vec<String> = transitions.iter().map(|t| format!("{}→{}", t.from, t.to)).collect();
+        assert_eq!(labels, vec!["present→parti", "parti→present", "?→LOC:cuisine", "vivant→mort"]);
+    }
+}
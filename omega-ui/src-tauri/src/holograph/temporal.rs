@@ -0,0 +1,909 @@
+//! OMEGA HOLOGRAPH — Temporal Analyzer
+//! LOGIC: cohérence chronologique (dates, jours, saisons, marqueurs relatifs, âges)
+//!
+//! Chaque segment (paragraphe) produit une timeline ordonnée de marqueurs.
+//! Les marqueurs relatifs (le lendemain, trois jours plus tard) relient les
+//! ancrages entre eux; seuls les ancrages reliés explicitement sont comparés.
+//! NASA-Grade AS9100D
+
+use chrono::{Datelike, NaiveDate, TimeDelta};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+use super::{split_segments, CoherenceIssue, IssueType, Severity, TextSpan};
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// TYPES
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Season {
+    Spring,
+    Summer,
+    Autumn,
+    Winter,
+}
+
+impl Season {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Season::Spring => "printemps",
+            Season::Summer => "ete",
+            Season::Autumn => "automne",
+            Season::Winter => "hiver",
+        }
+    }
+
+    fn next(&self) -> Season {
+        match self {
+            Season::Spring => Season::Summer,
+            Season::Summer => Season::Autumn,
+            Season::Autumn => Season::Winter,
+            Season::Winter => Season::Spring,
+        }
+    }
+
+    /// Saison astronomique (hémisphère nord)
+    pub fn of_date(date: NaiveDate) -> Season {
+        match date.month() * 100 + date.day() {
+            320..=620 => Season::Spring,
+            621..=922 => Season::Summer,
+            923..=1220 => Season::Autumn,
+            _ => Season::Winter,
+        }
+    }
+
+    /// Tolérance de 10 jours autour des changements de saison
+    fn plausible_for(&self, date: NaiveDate) -> bool {
+        [-10, 0, 10].iter().any(|d| shift(date, *d).is_some_and(|date| Season::of_date(date) == *self))
+    }
+}
+
+/// Nature d'un marqueur temporel extrait du texte
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum TemporalKind {
+    /// Date explicite (jour de semaine accolé éventuel: "lundi 3 mars 2024")
+    Date { day: u32, month: u32, year: Option<i32>, weekday: Option<u32> },
+    /// Jour de semaine isolé (0 = lundi)
+    Weekday(u32),
+    Season(Season),
+    /// Décalage en jours. None = durée vague (continuité perdue).
+    /// moves_clock = false pour les références ponctuelles (hier, la veille)
+    Relative { days: Option<i64>, moves_clock: bool },
+    Age { entity: String, years: u32 },
+    /// Événement évoqué comme passé ("depuis le mariage")
+    EventReference(String),
+    /// Événement raconté ("le mariage eut lieu")
+    EventOccurrence(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemporalMarker {
+    pub kind: TemporalKind,
+    pub span: TextSpan,
+    pub raw: String,
+    /// Index global de phrase
+    pub sentence: usize,
+    /// Phrase rétrospective (souvenir, "à l'époque", etc.)
+    pub retrospective: bool,
+}
+
+/// Timeline d'un segment (paragraphe), marqueurs dans l'ordre du texte
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimelineSegment {
+    pub index: usize,
+    pub span: TextSpan,
+    /// Segment rétrospectif (flashback): hors timeline principale
+    pub retrospective: bool,
+    /// Relié au segment précédent par un marqueur relatif en tête
+    pub linked: bool,
+    pub markers: Vec<TemporalMarker>,
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// LEXIQUE
+// ═══════════════════════════════════════════════════════════════════════════════
+
+const WEEKDAYS: [&str; 7] = ["lundi", "mardi", "mercredi", "jeudi", "vendredi", "samedi", "dimanche"];
+
+const MONTHS: [&str; 12] = [
+    "janvier", "fevrier", "mars", "avril", "mai", "juin",
+    "juillet", "aout", "septembre", "octobre", "novembre", "decembre",
+];
+
+/// Mots capitalisés qui ne sont pas des personnages
+const NON_ENTITIES: [&str; 14] = [
+    "Le", "La", "Les", "Un", "Une", "Il", "Elle", "Ils", "Elles", "Ce", "Cette", "Son", "Sa", "Ses",
+];
+
+fn strip_accents(s: &str) -> String {
+    s.to_lowercase().chars().map(|c| match c {
+        'é' | 'è' | 'ê' | 'ë' => 'e',
+        'à' | 'â' | 'ä' => 'a',
+        'ù' | 'û' | 'ü' => 'u',
+        'ô' | 'ö' => 'o',
+        'î' | 'ï' => 'i',
+        'ç' => 'c',
+        '’' => '\'',
+        _ => c,
+    }).collect()
+}
+
+/// Nombre en chiffres ou en toutes lettres ("trois", "trente-deux", "quatre-vingt-dix")
+pub(crate) fn parse_fr_number(raw: &str) -> Option<u32> {
+    if let Ok(n) = raw.parse() {
+        return Some(n);
+    }
+    const UNITS: [&str; 17] = [
+        "zero", "un", "deux", "trois", "quatre", "cinq", "six", "sept", "huit", "neuf",
+        "dix", "onze", "douze", "treize", "quatorze", "quinze", "seize",
+    ];
+    let word = strip_accents(raw);
+    let parts: Vec<&str> = word.split('-').filter(|p| *p != "et").collect();
+    if parts.is_empty() {
+        return None;
+    }
+
+    let mut total = 0;
+    let mut i = 0;
+    while i < parts.len() {
+        let value = match parts[i] {
+            "quatre" if matches!(parts.get(i + 1), Some(&"vingt") | Some(&"vingts")) => {
+                i += 1;
+                80
+            }
+            "une" => 1,
+            "vingt" | "vingts" => 20,
+            "trente" => 30,
+            "quarante" => 40,
+            "cinquante" => 50,
+            "soixante" => 60,
+            "cent" => 100,
+            p => UNITS.iter().position(|u| *u == p)? as u32,
+        };
+        total += value;
+        i += 1;
+    }
+    Some(total)
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// ANALYZER
+// ═══════════════════════════════════════════════════════════════════════════════
+
+pub struct TemporalAnalyzer {
+    date_re: Regex,
+    weekday_re: Regex,
+    season_re: Regex,
+    relative_count_re: Regex,
+    relative_fixed_re: Regex,
+    age_re: Regex,
+    event_reference_re: Regex,
+    event_occurrence_re: Regex,
+    retrospective_re: Regex,
+    sentence_re: Regex,
+}
+
+impl Default for TemporalAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TemporalAnalyzer {
+    pub fn new() -> Self {
+        let weekdays = WEEKDAYS.join("|");
+        let months = r"janvier|f[eé]vrier|mars|avril|mai|juin|juillet|ao[uû]t|septembre|octobre|novembre|d[eé]cembre";
+        let events = r"mariage|enterrement|fun[eé]railles|bal|proc[eè]s|bataille|duel|incendie|accident|naissance|bapt[eê]me|couronnement|c[eé]r[eé]monie|r[eé]ception|naufrage|attentat";
+        let det = r"(?:le|la|l['’]|du|de\s+la|de\s+l['’]|son|sa|leur)\s*";
+
+        Self {
+            date_re: Regex::new(&format!(
+                r"(?i)\b(?:(?P<wd>{})\s+)?(?P<d>1er|\d{{1,2}})\s+(?P<m>{})(?:\s+(?P<y>\d{{4}}))?\b",
+                weekdays, months
+            )).unwrap(),
+            weekday_re: Regex::new(&format!(r"(?i)\b({})\b", weekdays)).unwrap(),
+            season_re: Regex::new(
+                r"(?i)\b(?:au|le|ce|en|cet|l['’])\s*(printemps|automne|hiver|[eé]t[eé])\b"
+            ).unwrap(),
+            relative_count_re: Regex::new(
+                r"(?i)\b(?P<n>\d+|[a-zéè]+(?:-[a-zéè]+)*)\s+(?P<unit>jours?|nuits?|semaines?|mois|ans|ann[eé]es?)\s+(?P<dir>plus\s+tard|apr[eè]s|ensuite|plus\s+t[oô]t|auparavant|avant)\b"
+            ).unwrap(),
+            relative_fixed_re: Regex::new(
+                r"(?i)\b(le\s+lendemain|le\s+surlendemain|la\s+veille|avant-hier|apr[eè]s-demain|hier|demain|le\s+jour\s+suivant|la\s+nuit\s+suivante|la\s+semaine\s+(?:suivante|d['’]apr[eè]s)|le\s+mois\s+suivant|l['’]ann[eé]e\s+(?:suivante|d['’]apr[eè]s))\b"
+            ).unwrap(),
+            age_re: Regex::new(
+                r"\b(?P<name>[A-ZÀÂÄÉÈÊËÎÏÔÖÙÛÜÇ][a-zàâäéèêëîïôöùûüç]+)(?:\s*,\s*(?:[âa]g[ée]e?\s+de\s+)?|\s+(?:avait|a|aura|aurait|allait\s+avoir|venait\s+d['’]avoir|f[eê]tait\s+ses|f[eê]te\s+ses)\s+)(?P<n>\d{1,3}|[a-zéè]+(?:-[a-zéè]+)*)\s+ans\b"
+            ).unwrap(),
+            event_reference_re: Regex::new(&format!(
+                r"(?i)\b(?:depuis|apr[eè]s|souvenir\s+d[ue]|se\s+souv\w+\s+d[ue])\s+{}(?P<e>{})\b|\b(?:le|la|l['’])\s*(?P<e2>{})\s+de\s+la\s+veille\b",
+                det, events, events
+            )).unwrap(),
+            event_occurrence_re: Regex::new(&format!(
+                r"(?i)\b(?P<e>{})\s+(?:eut\s+lieu|commen[cç]a|d[eé]buta|se\s+d[eé]roula|se\s+tint|fut\s+c[eé]l[eé]br[eé]e?)\b|\b(?:c['’][eé]tait|ce\s+fut|enfin)\s+le\s+jour\s+d[ue]\s+(?:la\s+|l['’])?(?P<e2>{})\b",
+                events, events
+            )).unwrap(),
+            retrospective_re: Regex::new(
+                r"(?i)\b(?:autrefois|jadis|flashback|se\s+souv\w+|souvenirs?|(?:des|quelques)\s+ann[eé]es\s+plus\s+t[oô]t|[àa]\s+l['’][eé]poque|il\s+y\s+avait\s+\w+\s+ans|(?:quand|lorsqu['’])\s*(?:il|elle)\s+[eé]tait\s+(?:enfant|petite?|jeune))"
+            ).unwrap(),
+            sentence_re: Regex::new(r"[.!?…]+").unwrap(),
+        }
+    }
+
+    /// Construit la timeline ordonnée, segment par segment
+    pub fn timeline(&self, text: &str) -> Vec<TimelineSegment> {
+        let mut segments = Vec::new();
+        let mut sentence_base = 0;
+
+        for (index, (span, seg_text)) in split_segments(text).into_iter().enumerate() {
+            // Bornes des phrases (offsets relatifs au segment)
+            let mut bounds: Vec<usize> = self.sentence_re.find_iter(seg_text).map(|m| m.end()).collect();
+            if bounds.last().copied() != Some(seg_text.len()) {
+                bounds.push(seg_text.len());
+            }
+            let sentence_of = |offset: usize| bounds.iter().position(|b| offset < *b).unwrap_or(bounds.len() - 1);
+
+            let mut retro_sentences = vec![false; bounds.len()];
+            for (i, end) in bounds.iter().enumerate() {
+                let start = if i == 0 { 0 } else { bounds[i - 1] };
+                retro_sentences[i] = self.retrospective_re.is_match(&seg_text[start..*end]);
+            }
+
+            let mut markers = self.extract_markers(seg_text);
+            markers.sort_by_key(|(start, _, _, _)| *start);
+
+            let markers: Vec<TemporalMarker> = markers.into_iter().map(|(start, end, raw, kind)| {
+                let local_sentence = sentence_of(start);
+                TemporalMarker {
+                    kind,
+                    span: TextSpan::new(span.start + start, span.start + end),
+                    raw,
+                    sentence: sentence_base + local_sentence,
+                    retrospective: retro_sentences[local_sentence],
+                }
+            }).collect();
+
+            let linked = markers.first().map(|m| {
+                m.sentence == sentence_base
+                    && matches!(m.kind, TemporalKind::Relative { days: Some(d), moves_clock: true } if d > 0)
+            }).unwrap_or(false);
+
+            segments.push(TimelineSegment {
+                index,
+                span,
+                retrospective: retro_sentences[0],
+                linked,
+                markers,
+            });
+            sentence_base += bounds.len();
+        }
+
+        segments
+    }
+
+    /// Extrait les marqueurs bruts d'un segment: (start, end, raw, kind)
+    fn extract_markers(&self, text: &str) -> Vec<(usize, usize, String, TemporalKind)> {
+        let mut found: Vec<(usize, usize, String, TemporalKind)> = Vec::new();
+
+        for cap in self.date_re.captures_iter(text) {
+            let m = cap.get(0).unwrap();
+            let day = if cap["d"].eq_ignore_ascii_case("1er") { 1 } else { cap["d"].parse().unwrap_or(0) };
+            let month_name = strip_accents(&cap["m"]);
+            let month = MONTHS.iter().position(|n| *n == month_name).map(|i| i as u32 + 1).unwrap_or(0);
+            let year = cap.name("y").and_then(|y| y.as_str().parse().ok());
+            let weekday = cap.name("wd").and_then(|w| WEEKDAYS.iter().position(|d| *d == w.as_str().to_lowercase()))
+                .map(|i| i as u32);
+            found.push((m.start(), m.end(), m.as_str().to_string(), TemporalKind::Date { day, month, year, weekday }));
+        }
+
+        for m in self.weekday_re.find_iter(text) {
+            let inside_date = found.iter().any(|(s, e, _, _)| m.start() >= *s && m.end() <= *e);
+            if inside_date {
+                continue;
+            }
+            let idx = WEEKDAYS.iter().position(|d| *d == m.as_str().to_lowercase()).unwrap_or(0) as u32;
+            found.push((m.start(), m.end(), m.as_str().to_string(), TemporalKind::Weekday(idx)));
+        }
+
+        for cap in self.season_re.captures_iter(text) {
+            let m = cap.get(0).unwrap();
+            let season = match strip_accents(&cap[1]).as_str() {
+                "printemps" => Season::Spring,
+                "ete" => Season::Summer,
+                "automne" => Season::Autumn,
+                _ => Season::Winter,
+            };
+            found.push((m.start(), m.end(), m.as_str().to_string(), TemporalKind::Season(season)));
+        }
+
+        for cap in self.relative_count_re.captures_iter(text) {
+            let m = cap.get(0).unwrap();
+            let unit = match strip_accents(&cap["unit"]).trim_end_matches('s') {
+                "jour" | "nuit" => 1,
+                "semaine" => 7,
+                "moi" => 30,
+                _ => 365,
+            };
+            let n = strip_accents(&cap["n"]);
+            let days = match n.as_str() {
+                "quelques" | "plusieurs" | "des" => None,
+                _ => match parse_fr_number(&n) {
+                    Some(v) => Some(v as i64 * unit),
+                    None => continue,
+                },
+            };
+            let backward = matches!(strip_accents(&cap["dir"]).split_whitespace().last(), Some("tot") | Some("auparavant") | Some("avant"));
+            let kind = TemporalKind::Relative { days: days.map(|d| if backward { -d } else { d }), moves_clock: !backward };
+            found.push((m.start(), m.end(), m.as_str().to_string(), kind));
+        }
+
+        for m in self.relative_fixed_re.find_iter(text) {
+            let phrase = strip_accents(m.as_str());
+            let phrase = phrase.split_whitespace().collect::<Vec<_>>().join(" ");
+            // "le mariage de la veille" est une référence, pas un déplacement
+            if phrase == "la veille" && text[..m.start()].trim_end().to_lowercase().ends_with(" de") {
+                continue;
+            }
+            let (days, moves_clock) = match phrase.as_str() {
+                "le lendemain" | "le jour suivant" | "la nuit suivante" => (1, true),
+                "le surlendemain" => (2, true),
+                "la veille" | "hier" => (-1, false),
+                "avant-hier" => (-2, false),
+                "demain" => (1, false),
+                "apres-demain" => (2, false),
+                "le mois suivant" => (30, true),
+                p if p.starts_with("la semaine") => (7, true),
+                _ => (365, true),
+            };
+            found.push((m.start(), m.end(), m.as_str().to_string(), TemporalKind::Relative { days: Some(days), moves_clock }));
+        }
+
+        for cap in self.age_re.captures_iter(text) {
+            let m = cap.get(0).unwrap();
+            let name = cap["name"].to_string();
+            if NON_ENTITIES.contains(&name.as_str()) {
+                continue;
+            }
+            if let Some(years) = parse_fr_number(&cap["n"]) {
+                found.push((m.start(), m.end(), m.as_str().to_string(), TemporalKind::Age { entity: name, years }));
+            }
+        }
+
+        for cap in self.event_reference_re.captures_iter(text) {
+            let m = cap.get(0).unwrap();
+            let event = cap.name("e").or_else(|| cap.name("e2")).map(|e| strip_accents(e.as_str())).unwrap_or_default();
+            found.push((m.start(), m.end(), m.as_str().to_string(), TemporalKind::EventReference(event)));
+        }
+
+        for cap in self.event_occurrence_re.captures_iter(text) {
+            let m = cap.get(0).unwrap();
+            let event = cap.name("e").or_else(|| cap.name("e2")).map(|e| strip_accents(e.as_str())).unwrap_or_default();
+            found.push((m.start(), m.end(), m.as_str().to_string(), TemporalKind::EventOccurrence(event)));
+        }
+
+        found
+    }
+
+    /// Analyse complète: timeline + détection des impossibilités
+    pub fn analyze(&self, text: &str) -> Vec<CoherenceIssue> {
+        let timeline = self.timeline(text);
+        let mut issues = Vec::new();
+        let mut clock = Clock::default();
+
+        for segment in &timeline {
+            if segment.retrospective {
+                clock.epoch += 1;
+                continue;
+            }
+            if segment.index > 0 && !segment.linked {
+                clock.epoch += 1;
+            }
+
+            let mut local: Option<(usize, i64)> = None;
+            for marker in &segment.markers {
+                if marker.retrospective {
+                    continue;
+                }
+                let offset = match local {
+                    Some((sentence, days)) if sentence == marker.sentence => days,
+                    _ => 0,
+                };
+                self.apply(marker, offset, &mut clock, &mut local, &mut issues);
+            }
+        }
+
+        issues.extend(self.check_events(&timeline));
+        issues
+    }
+
+    fn apply(
+        &self,
+        marker: &TemporalMarker,
+        offset: i64,
+        clock: &mut Clock,
+        local: &mut Option<(usize, i64)>,
+        issues: &mut Vec<CoherenceIssue>,
+    ) {
+        let day = clock.day + offset;
+        match &marker.kind {
+            TemporalKind::Relative { days: None, .. } => {
+                clock.epoch += 1;
+            }
+            TemporalKind::Relative { days: Some(d), moves_clock } => {
+                clock.hops += 1;
+                if *moves_clock {
+                    clock.day += d;
+                } else {
+                    *local = Some((marker.sentence, *d));
+                }
+            }
+            TemporalKind::Date { day: d, month, year, weekday } => {
+                self.check_date(marker, *d, *month, *year, *weekday, day, clock, issues);
+            }
+            TemporalKind::Weekday(w) => {
+                if let Some(anchor) = clock.date.as_ref() {
+                    if let Some(date) = clock.linked_elapsed(anchor, day).and_then(|elapsed| shift(anchor.value, elapsed)) {
+                        let expected = date.weekday().num_days_from_monday();
+                        if expected != *w {
+                            issues.push(issue(
+                                Severity::Medium,
+                                format!(
+                                    "Jour de semaine incoherent: '{}' alors que la chronologie depuis '{}' donne un {}",
+                                    marker.raw, anchor.raw, WEEKDAYS[expected as usize]
+                                ),
+                                anchor, marker,
+                                "Verifier le jour de la semaine ou le decalage temporel",
+                            ));
+                        }
+                    }
+                } else if let Some(anchor) = clock.weekday.as_ref() {
+                    if let Some(elapsed) = clock.linked_elapsed(anchor, day) {
+                        let expected = (anchor.value as i64 + elapsed).rem_euclid(7) as u32;
+                        if expected != *w {
+                            issues.push(issue(
+                                Severity::Medium,
+                                format!(
+                                    "Jour de semaine incoherent: '{}' apres '{}' (+{} jours) devrait etre un {}",
+                                    marker.raw, anchor.raw, elapsed, WEEKDAYS[expected as usize]
+                                ),
+                                anchor, marker,
+                                "Verifier le jour de la semaine ou le decalage temporel",
+                            ));
+                        }
+                    }
+                }
+                clock.weekday = Some(clock.anchor(*w, marker, day));
+            }
+            TemporalKind::Season(season) => {
+                if let Some(anchor) = clock.date.as_ref() {
+                    if let Some(date) = clock.elapsed(anchor, marker.sentence, day).and_then(|elapsed| shift(anchor.value, elapsed)) {
+                        if !season.plausible_for(date) {
+                            issues.push(issue(
+                                Severity::High,
+                                format!(
+                                    "Saison incoherente: '{}' alors que la date est le {} ({})",
+                                    marker.raw, date.format("%d/%m/%Y"), Season::of_date(date).label()
+                                ),
+                                anchor, marker,
+                                "Aligner la saison sur la date du recit",
+                            ));
+                        }
+                    }
+                }
+                if let Some(anchor) = clock.season.as_ref() {
+                    if let Some(elapsed) = clock.linked_elapsed(anchor, day) {
+                        let span = elapsed.abs();
+                        let forward = if elapsed >= 0 { anchor.value.next() } else { season.next() };
+                        let allowed = if elapsed >= 0 { *season == forward } else { anchor.value == forward };
+                        let jump = *season != anchor.value && (span <= 7 || (span <= 80 && !allowed));
+                        if jump {
+                            issues.push(issue(
+                                Severity::High,
+                                format!(
+                                    "Saut de saison: '{}' -> '{}' en {} jour(s)",
+                                    anchor.raw, marker.raw, span
+                                ),
+                                anchor, marker,
+                                "Verifier l'ellipse temporelle ou la saison",
+                            ));
+                        }
+                    }
+                }
+                clock.season = Some(clock.anchor(*season, marker, day));
+            }
+            TemporalKind::Age { entity, years } => {
+                if let Some(anchor) = clock.ages.get(entity) {
+                    if *years < anchor.value {
+                        issues.push(issue(
+                            Severity::High,
+                            format!(
+                                "{} vieillit a rebours: {} ans puis {} ans",
+                                entity, anchor.value, years
+                            ),
+                            anchor, marker,
+                            &format!("Verifier l'age de {} ou marquer le passage comme retrospectif", entity),
+                        ));
+                    } else if let Some(elapsed) = clock.linked_elapsed(anchor, day) {
+                        let max_growth = (elapsed.max(0) / 365 + 1) as u32;
+                        if years - anchor.value > max_growth {
+                            issues.push(issue(
+                                Severity::Medium,
+                                format!(
+                                    "{} prend {} ans en {} jour(s)",
+                                    entity, years - anchor.value, elapsed
+                                ),
+                                anchor, marker,
+                                &format!("Verifier l'age de {} ou l'ellipse temporelle", entity),
+                            ));
+                        }
+                    }
+                }
+                let anchor = clock.anchor(*years, marker, day);
+                clock.ages.insert(entity.clone(), anchor);
+            }
+            TemporalKind::EventReference(_) | TemporalKind::EventOccurrence(_) => {}
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn check_date(
+        &self,
+        marker: &TemporalMarker,
+        d: u32,
+        month: u32,
+        year: Option<i32>,
+        weekday: Option<u32>,
+        day: i64,
+        clock: &mut Clock,
+        issues: &mut Vec<CoherenceIssue>,
+    ) {
+        // Année bissextile de référence si l'année est inconnue (29 février accepté)
+        let effective_year = year.or(clock.year);
+        let Some(date) = NaiveDate::from_ymd_opt(effective_year.unwrap_or(2000), month, d) else {
+            issues.push(CoherenceIssue {
+                issue_type: IssueType::TemporalError,
+                severity: Severity::High,
+                description: format!("Date impossible: '{}'", marker.raw),
+                evidence: vec![format!("{} @{}", marker.raw, marker.span.start)],
+                location: Some(format!("Segment: phrase {}", marker.sentence + 1)),
+                suggestion: Some("Corriger le jour ou le mois".to_string()),
+                spans: vec![marker.span],
+                entity: None,
+                category: Some("date".to_string()),
+                fingerprint: String::new(),
+            });
+            return;
+        };
+
+        if effective_year.is_none() {
+            return;
+        }
+
+        if let Some(w) = weekday {
+            let actual = date.weekday().num_days_from_monday();
+            if actual != w {
+                issues.push(CoherenceIssue {
+                    issue_type: IssueType::TemporalError,
+                    severity: Severity::Medium,
+                    description: format!(
+                        "Jour de semaine incoherent: le {} tombe un {}, pas un {}",
+                        date.format("%d/%m/%Y"), WEEKDAYS[actual as usize], WEEKDAYS[w as usize]
+                    ),
+                    evidence: vec![format!("{} @{}", marker.raw, marker.span.start)],
+                    location: Some(format!("Segment: phrase {}", marker.sentence + 1)),
+                    suggestion: Some("Corriger le jour de la semaine ou la date".to_string()),
+                    spans: vec![marker.span],
+                    entity: None,
+                    category: Some("jour".to_string()),
+                    fingerprint: String::new(),
+                });
+            }
+        }
+
+        if let Some(anchor) = clock.date.as_ref() {
+            let linked = clock.linked_elapsed(anchor, day)
+                .and_then(|elapsed| Some((elapsed, shift(anchor.value, elapsed)?)));
+            if let Some((elapsed, expected)) = linked {
+                if expected != date {
+                    issues.push(issue(
+                        Severity::Medium,
+                        format!(
+                            "Date incoherente avec la chronologie: '{}' + {} jour(s) donne le {}, pas le {}",
+                            anchor.raw, elapsed, expected.format("%d/%m/%Y"), date.format("%d/%m/%Y")
+                        ),
+                        anchor, marker,
+                        "Verifier la date ou le marqueur relatif",
+                    ));
+                }
+            }
+        } else if let Some(anchor) = clock.weekday.as_ref() {
+            if let Some(elapsed) = clock.linked_elapsed(anchor, day) {
+                let expected = (anchor.value as i64 + elapsed).rem_euclid(7) as u32;
+                let actual = date.weekday().num_days_from_monday();
+                if expected != actual {
+                    issues.push(issue(
+                        Severity::Medium,
+                        format!(
+                            "Jour de semaine incoherent: le {} tombe un {}, la chronologie depuis '{}' donne un {}",
+                            date.format("%d/%m/%Y"), WEEKDAYS[actual as usize], anchor.raw, WEEKDAYS[expected as usize]
+                        ),
+                        anchor, marker,
+                        "Verifier le jour de la semaine ou la date",
+                    ));
+                }
+            }
+        }
+
+        if let Some(anchor) = clock.season.as_ref() {
+            if let Some(at_season) = clock.elapsed(anchor, marker.sentence, day).and_then(|elapsed| shift(date, elapsed.checked_neg()?)) {
+                if !anchor.value.plausible_for(at_season) {
+                    issues.push(issue(
+                        Severity::High,
+                        format!(
+                            "Saison incoherente: '{}' alors que la date est le {} ({})",
+                            anchor.raw, at_season.format("%d/%m/%Y"), Season::of_date(at_season).label()
+                        ),
+                        anchor, marker,
+                        "Aligner la saison sur la date du recit",
+                    ));
+                }
+            }
+        }
+
+        if year.is_some() {
+            clock.year = year;
+        }
+        clock.date = Some(clock.anchor(date, marker, day));
+    }
+
+    /// Événements évoqués comme passés avant d'être racontés
+    fn check_events(&self, timeline: &[TimelineSegment]) -> Vec<CoherenceIssue> {
+        let mut first_reference: HashMap<String, &TemporalMarker> = HashMap::new();
+        let mut occurred: HashSet<String> = HashSet::new();
+        let mut issues = Vec::new();
+
+        for segment in timeline.iter().filter(|s| !s.retrospective) {
+            for marker in segment.markers.iter().filter(|m| !m.retrospective) {
+                match &marker.kind {
+                    TemporalKind::EventReference(event) if !occurred.contains(event) => {
+                        first_reference.entry(event.clone()).or_insert(marker);
+                    }
+                    TemporalKind::EventOccurrence(event) if occurred.insert(event.clone()) => {
+                        // Une fois raconté, l'événement peut être évoqué librement
+                        if let Some(reference) = first_reference.remove(event) {
+                            issues.push(CoherenceIssue {
+                                issue_type: IssueType::TemporalError,
+                                severity: Severity::Medium,
+                                description: format!(
+                                    "Evenement reference avant d'avoir lieu: '{}' puis '{}'",
+                                    reference.raw, marker.raw
+                                ),
+                                evidence: vec![
+                                    format!("Reference: {} @{}", reference.raw, reference.span.start),
+                                    format!("Evenement: {} @{}", marker.raw, marker.span.start),
+                                ],
+                                location: Some(format!("Evenement: {}", event)),
+                                suggestion: Some("Deplacer la reference apres l'evenement ou marquer une prolepse".to_string()),
+                                spans: vec![reference.span, marker.span],
+                                entity: Some(event.clone()),
+                                category: Some("evenement".to_string()),
+                                fingerprint: String::new(),
+                            });
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        issues
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// CLOCK (état courant de la timeline)
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[derive(Debug, Clone)]
+struct Anchor<T> {
+    value: T,
+    raw: String,
+    span: TextSpan,
+    sentence: usize,
+    epoch: u32,
+    hops: u32,
+    day: i64,
+}
+
+/// epoch: incrémenté quand la continuité est perdue (nouveau segment non relié, ellipse vague)
+/// hops: nombre de marqueurs relatifs franchis (rend un écart explicite)
+/// day: jour narratif courant dans l'epoch
+#[derive(Debug, Default)]
+struct Clock {
+    epoch: u32,
+    hops: u32,
+    day: i64,
+    year: Option<i32>,
+    date: Option<Anchor<NaiveDate>>,
+    weekday: Option<Anchor<u32>>,
+    season: Option<Anchor<Season>>,
+    ages: HashMap<String, Anchor<u32>>,
+}
+
+impl Clock {
+    fn anchor<T>(&self, value: T, marker: &TemporalMarker, day: i64) -> Anchor<T> {
+        Anchor {
+            value,
+            raw: marker.raw.clone(),
+            span: marker.span,
+            sentence: marker.sentence,
+            epoch: self.epoch,
+            hops: self.hops,
+            day,
+        }
+    }
+
+    /// Écart en jours si l'ancrage est relié par au moins un marqueur relatif
+    fn linked_elapsed<T>(&self, anchor: &Anchor<T>, day: i64) -> Option<i64> {
+        (anchor.epoch == self.epoch && anchor.hops != self.hops).then_some(day - anchor.day)
+    }
+
+    /// Écart en jours si relié, ou si dans la même phrase
+    fn elapsed<T>(&self, anchor: &Anchor<T>, sentence: usize, day: i64) -> Option<i64> {
+        if anchor.epoch == self.epoch && anchor.sentence == sentence {
+            return Some(day - anchor.day);
+        }
+        self.linked_elapsed(anchor, day)
+    }
+}
+
+/// Date décalée de `days` jours; None hors de l'intervalle de chrono (ellipse démesurée: vérification ignorée)
+fn shift(date: NaiveDate, days: i64) -> Option<NaiveDate> {
+    date.checked_add_signed(TimeDelta::try_days(days)?)
+}
+
+fn issue<T>(
+    severity: Severity,
+    description: String,
+    anchor: &Anchor<T>,
+    marker: &TemporalMarker,
+    suggestion: &str,
+) -> CoherenceIssue {
+    CoherenceIssue {
+        issue_type: IssueType::TemporalError,
+        severity,
+        description,
+        evidence: vec![
+            format!("{} @{}", anchor.raw, anchor.span.start),
+            format!("{} @{}", marker.raw, marker.span.start),
+        ],
+        location: Some(format!("Phrases {}-{}", anchor.sentence + 1, marker.sentence + 1)),
+        suggestion: Some(suggestion.to_string()),
+        spans: vec![anchor.span, marker.span],
+        entity: None,
+        category: Some("chronologie".to_string()),
+        fingerprint: String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temporal_issues(text: &str) -> Vec<CoherenceIssue> {
+        TemporalAnalyzer::new().analyze(text)
+    }
+
+    #[test]
+    fn test_parse_fr_number() {
+        assert_eq!(parse_fr_number("3"), Some(3));
+        assert_eq!(parse_fr_number("trois"), Some(3));
+        assert_eq!(parse_fr_number("trente-deux"), Some(32));
+        assert_eq!(parse_fr_number("soixante-et-onze"), Some(71));
+        assert_eq!(parse_fr_number("quatre-vingt-dix"), Some(90));
+        assert_eq!(parse_fr_number("les"), None);
+    }
+
+    #[test]
+    fn test_weekday_date_mismatch() {
+        // Le 3 mars 2024 est un dimanche
+        let issues = temporal_issues("Le lundi 3 mars 2024, Pierre partit pour Lyon.");
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].description.contains("dimanche"));
+        assert_eq!(issues[0].spans.len(), 1);
+    }
+
+    #[test]
+    fn test_weekday_consistent() {
+        let issues = temporal_issues("Le dimanche 3 mars 2024, Pierre partit. Le lendemain, lundi, il arriva.");
+        assert!(issues.is_empty(), "{:?}", issues);
+    }
+
+    #[test]
+    fn test_weekday_after_relative_marker() {
+        let text = "Ce lundi-la, Marie attendait.\n\nLe lendemain, jeudi, elle partit.";
+        let issues = temporal_issues(text);
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].description.contains("mardi"));
+        assert_eq!(issues[0].spans.len(), 2);
+        assert!(issues[0].spans[0].start < issues[0].spans[1].start);
+    }
+
+    #[test]
+    fn test_impossible_date() {
+        let issues = temporal_issues("Le 31 avril, la neige tomba.");
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].severity, Severity::High);
+    }
+
+    #[test]
+    fn test_aging_backwards() {
+        let text = "Pierre avait 30 ans quand il arriva au village.\n\nDes mois passerent. Pierre avait 25 ans et travaillait a la forge.";
+        let issues = temporal_issues(text);
+        assert!(issues.iter().any(|i| i.description.contains("rebours")), "{:?}", issues);
+        let aging = issues.iter().find(|i| i.description.contains("rebours")).unwrap();
+        assert_eq!(&text[aging.spans[0].start..aging.spans[0].end], "Pierre avait 30 ans");
+    }
+
+    #[test]
+    fn test_aging_backwards_in_flashback_ignored() {
+        let text = "Pierre avait 30 ans.\n\nAutrefois, Pierre avait 10 ans et courait dans les champs.";
+        assert!(temporal_issues(text).is_empty());
+    }
+
+    #[test]
+    fn test_next_morning_season_jump() {
+        let text = "C'etait l'ete, la chaleur ecrasait la ville.\n\nLe lendemain matin, l'hiver avait couvert les toits de neige.";
+        let issues = temporal_issues(text);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].severity, Severity::High);
+        assert!(issues[0].description.contains("Saut de saison"));
+    }
+
+    #[test]
+    fn test_season_after_long_ellipsis_ok() {
+        let text = "C'etait l'ete, la chaleur ecrasait la ville.\n\nSix mois plus tard, l'hiver avait couvert les toits de neige.";
+        assert!(temporal_issues(text).is_empty());
+    }
+
+    #[test]
+    fn test_relative_marker_contradicts_date() {
+        let text = "Le 3 mars 2024, Jean arriva.\n\nTrois jours plus tard, le 10 mars 2024, il repartit.";
+        let issues = temporal_issues(text);
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].description.contains("06/03/2024"));
+    }
+
+    #[test]
+    fn test_huge_relative_offset_skipped() {
+        let text = "Le 3 mars 2024, Jean arriva.\n\n500000 ans plus tard, le 4 mars 2024, un lundi d'hiver, il repartit.\n\n4000000000 jours auparavant, c'etait l'ete.";
+        let issues = temporal_issues(text);
+        assert!(issues.iter().all(|i| !i.description.contains("Date incoherente")), "{:?}", issues);
+    }
+
+    #[test]
+    fn test_event_referenced_before_it_happens() {
+        let text = "Depuis le mariage, Marie ne parlait plus a sa soeur.\n\nLe mariage eut lieu au printemps.";
+        let issues = temporal_issues(text);
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].description.contains("avant d'avoir lieu"));
+        assert_eq!(issues[0].spans.len(), 2);
+    }
+
+    #[test]
+    fn test_event_referenced_after_it_happens_ok() {
+        let text = "Le mariage eut lieu au printemps.\n\nDepuis le mariage, Marie ne parlait plus a sa soeur.";
+        assert!(temporal_issues(text).is_empty());
+    }
+
+    #[test]
+    fn test_timeline_segments() {
+        let text = "Le lundi 4 mars 2024, tout commenca.\n\nLe lendemain, il pleuvait.";
+        let timeline = TemporalAnalyzer::new().timeline(text);
+        assert_eq!(timeline.len(), 2);
+        assert!(!timeline[0].linked);
+        assert!(timeline[1].linked);
+        assert!(matches!(timeline[0].markers[0].kind, TemporalKind::Date { day: 4, month: 3, year: Some(2024), weekday: Some(0) }));
+    }
+}
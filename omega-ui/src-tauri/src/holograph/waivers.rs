@@ -0,0 +1,452 @@
+//! OMEGA HOLOGRAPH — Fingerprints & Waivers
+//! Empreintes stables des issues, dérogations et suivi entre scans
+//!
+//! - empreinte = hash(type, entité, catégorie, preuves normalisées):
+//!   insensible aux offsets, numéros de paragraphe / chapitre, casse et accents
+//! - fichier de dérogations (JSON / TOML): justification, auteur, expiration
+//! - historique du scan précédent d'un manuscrit: issues nouvelles / résolues
+//!
+//! NASA-Grade AS9100D
+
+use chrono::NaiveDate;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashSet};
+use std::path::Path;
+
+use super::CoherenceIssue;
+use crate::error::{OmegaError, OmegaResult};
+use crate::lexicon_fr_gold::normalize_fr;
+use crate::pipeline::fs_utils::{ensure_dir, read_json, sha256_str, write_json};
+
+/// Version du schéma du fichier de dérogations
+pub const WAIVER_SCHEMA_VERSION: u32 = 1;
+
+/// Version du schéma de l'historique des scans
+pub const HISTORY_SCHEMA_VERSION: u32 = 1;
+
+/// Format des dates d'expiration
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// FINGERPRINTS
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Normalisation des preuves: retire ce qui bouge quand le texte est édité ailleurs
+struct Normalizer {
+    positions: Regex,
+    entity: Regex,
+    spaces: Regex,
+}
+
+impl Normalizer {
+    fn new() -> Self {
+        Self {
+            // "@123", "chapitre 2 (ch02), segment 3", "paragraphe 4-5", "phrase 7", "scene 2"
+            positions: Regex::new(
+                r"(?i)@\d+|\bchapitre \d+(?: \([^)]*\))?(?:, segment \d+)?|\b(?:paragraphe|segment|phrase|scene) \d+(?:-\d+)?"
+            ).unwrap(),
+            entity: Regex::new(r"(?:Entite|Personnage): ([^—]+)").unwrap(),
+            spaces: Regex::new(r"\s+").unwrap(),
+        }
+    }
+
+    fn text(&self, s: &str) -> String {
+        let stripped = self.positions.replace_all(s, " ");
+        let normalized = normalize_fr(&stripped);
+        self.spaces.replace_all(normalized.trim(), " ").into_owned()
+    }
+
+    /// Entité visée; à défaut, localisation "Entite: X" / "Personnage: X"
+    fn entity(&self, issue: &CoherenceIssue) -> String {
+        if let Some(entity) = &issue.entity {
+            return self.text(entity);
+        }
+        issue.location.as_deref()
+            .and_then(|loc| self.entity.captures(loc))
+            .map(|c| self.text(&c[1]))
+            .unwrap_or_default()
+    }
+
+    /// Catégorie; à défaut, tête de la description ("Contradiction sur taille pour 'pierre'")
+    fn category(&self, issue: &CoherenceIssue) -> String {
+        if let Some(category) = &issue.category {
+            return self.text(category);
+        }
+        let head = issue.description.split(':').next().unwrap_or("");
+        self.text(head).chars().filter(|c| !c.is_ascii_digit()).collect::<String>().trim().to_string()
+    }
+
+    fn fingerprint(&self, issue: &CoherenceIssue) -> String {
+        let evidence: BTreeSet<String> = issue.evidence.iter().map(|e| self.text(e)).collect();
+        let material = format!(
+            "{:?}|{}|{}|{}",
+            issue.issue_type,
+            self.entity(issue),
+            self.category(issue),
+            evidence.into_iter().collect::<Vec<_>>().join("\n")
+        );
+        sha256_str(&material)[..16].to_string()
+    }
+}
+
+/// Empreinte stable d'une issue (16 hex)
+pub fn fingerprint(issue: &CoherenceIssue) -> String {
+    Normalizer::new().fingerprint(issue)
+}
+
+/// Calcule l'empreinte de chaque issue
+pub fn assign_fingerprints(issues: &mut [CoherenceIssue]) {
+    let normalizer = Normalizer::new();
+    for issue in issues {
+        issue.fingerprint = normalizer.fingerprint(issue);
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// WAIVERS
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Dérogation: issue connue et acceptée
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Waiver {
+    pub fingerprint: String,
+    pub justification: String,
+    pub author: String,
+    /// Dernier jour de validité (AAAA-MM-JJ), sans limite si absent
+    #[serde(default)]
+    pub expires: Option<String>,
+}
+
+impl Waiver {
+    fn expiry(&self) -> OmegaResult<Option<NaiveDate>> {
+        self.expires.as_deref()
+            .map(|d| NaiveDate::parse_from_str(d, DATE_FORMAT).map_err(|e| OmegaError::ConfigError(format!(
+                "Derogation {}: date d'expiration invalide '{}' ({})", self.fingerprint, d, e
+            ))))
+            .transpose()
+    }
+
+    /// Valide à la date donnée (expiration incluse)
+    pub fn is_active(&self, today: NaiveDate) -> bool {
+        match self.expiry() {
+            Ok(Some(date)) => today <= date,
+            Ok(None) => true,
+            Err(_) => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WaiverFile {
+    #[serde(default = "default_schema_version")]
+    pub schema_version: u32,
+    #[serde(default)]
+    pub waivers: Vec<Waiver>,
+}
+
+fn default_schema_version() -> u32 {
+    WAIVER_SCHEMA_VERSION
+}
+
+impl Default for WaiverFile {
+    fn default() -> Self {
+        Self { schema_version: WAIVER_SCHEMA_VERSION, waivers: Vec::new() }
+    }
+}
+
+impl WaiverFile {
+    /// Charge un fichier JSON ou TOML (vide si le fichier n'existe pas)
+    pub fn load<P: AsRef<Path>>(path: P) -> OmegaResult<Self> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| OmegaError::ReadError(format!("{}: {}", path.display(), e)))?;
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("toml") => Self::from_toml_str(&content),
+            Some("json") => Self::from_json_str(&content),
+            other => Err(OmegaError::ConfigError(format!(
+                "Extension de fichier de derogations non supportee: {:?} (json | toml)",
+                other
+            ))),
+        }
+    }
+
+    pub fn from_json_str(content: &str) -> OmegaResult<Self> {
+        let file: WaiverFile = serde_json::from_str(content)?;
+        file.validate()?;
+        Ok(file)
+    }
+
+    pub fn from_toml_str(content: &str) -> OmegaResult<Self> {
+        let file: WaiverFile = toml::from_str(content)
+            .map_err(|e| OmegaError::ConfigError(format!("TOML invalide: {}", e)))?;
+        file.validate()?;
+        Ok(file)
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> OmegaResult<()> {
+        self.validate()?;
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            ensure_dir(parent)?;
+        }
+        write_json(path, self)
+    }
+
+    /// Schéma, champs obligatoires, dates, empreintes uniques
+    pub fn validate(&self) -> OmegaResult<()> {
+        if self.schema_version != WAIVER_SCHEMA_VERSION {
+            return Err(OmegaError::ConfigError(format!(
+                "Schema de derogations {} non supporte (attendu {})",
+                self.schema_version, WAIVER_SCHEMA_VERSION
+            )));
+        }
+        let mut seen = HashSet::new();
+        for waiver in &self.waivers {
+            if waiver.fingerprint.trim().is_empty() {
+                return Err(OmegaError::ConfigError("Derogation sans empreinte".to_string()));
+            }
+            if !seen.insert(waiver.fingerprint.as_str()) {
+                return Err(OmegaError::ConfigError(format!("Derogation en double: {}", waiver.fingerprint)));
+            }
+            if waiver.justification.trim().is_empty() {
+                return Err(OmegaError::ConfigError(format!("Derogation {}: justification requise", waiver.fingerprint)));
+            }
+            if waiver.author.trim().is_empty() {
+                return Err(OmegaError::ConfigError(format!("Derogation {}: auteur requis", waiver.fingerprint)));
+            }
+            waiver.expiry()?;
+        }
+        Ok(())
+    }
+
+    /// Dérogation active pour une empreinte
+    pub fn find(&self, fingerprint: &str, today: NaiveDate) -> Option<&Waiver> {
+        self.waivers.iter().find(|w| w.fingerprint == fingerprint && w.is_active(today))
+    }
+
+    /// Dérogations expirées (les issues correspondantes réapparaissent)
+    pub fn expired(&self, today: NaiveDate) -> Vec<&Waiver> {
+        self.waivers.iter().filter(|w| !w.is_active(today)).collect()
+    }
+
+    /// Sépare les éléments couverts par une dérogation active
+    pub(crate) fn partition<T>(
+        &self,
+        items: Vec<T>,
+        today: NaiveDate,
+        fingerprint: impl Fn(&T) -> &str,
+    ) -> (Vec<T>, Vec<(T, Waiver)>) {
+        let mut kept = Vec::new();
+        let mut waived = Vec::new();
+        for item in items {
+            match self.find(fingerprint(&item), today) {
+                Some(waiver) => waived.push((item, waiver.clone())),
+                None => kept.push(item),
+            }
+        }
+        (kept, waived)
+    }
+}
+
+/// Issue retirée du rapport par une dérogation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WaivedIssue {
+    pub issue: CoherenceIssue,
+    pub waiver: Waiver,
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// HISTORY
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Empreintes du scan précédent d'un même manuscrit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanHistory {
+    pub schema_version: u32,
+    /// Horodatage du dernier scan (None: jamais scanné)
+    pub scanned_at: Option<String>,
+    pub fingerprints: BTreeSet<String>,
+}
+
+impl Default for ScanHistory {
+    fn default() -> Self {
+        Self { schema_version: HISTORY_SCHEMA_VERSION, scanned_at: None, fingerprints: BTreeSet::new() }
+    }
+}
+
+/// Comparaison avec le scan précédent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScanDelta {
+    /// Issues actives absentes du scan précédent
+    pub new_issues: usize,
+    /// Issues du scan précédent disparues
+    pub resolved_issues: usize,
+}
+
+impl ScanHistory {
+    /// Charge l'historique (vide si absent ou d'un autre schéma)
+    pub fn load<P: AsRef<Path>>(path: P) -> OmegaResult<Self> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let history: ScanHistory = read_json(path)?;
+        if history.schema_version != HISTORY_SCHEMA_VERSION {
+            return Ok(Self::default());
+        }
+        Ok(history)
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> OmegaResult<()> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            ensure_dir(parent)?;
+        }
+        write_json(path, self)
+    }
+
+    /// Compare les issues actives (et dérogées) au scan précédent; None au premier scan
+    pub fn compare(&self, active: &[&str], waived: &[&str]) -> Option<ScanDelta> {
+        self.scanned_at.as_ref()?;
+        let current: BTreeSet<&str> = active.iter().chain(waived).copied().collect();
+        Some(ScanDelta {
+            new_issues: active.iter().filter(|f| !self.fingerprints.contains(**f)).count(),
+            resolved_issues: self.fingerprints.iter().filter(|f| !current.contains(f.as_str())).count(),
+        })
+    }
+
+    /// Enregistre le scan courant comme référence du prochain
+    pub fn record<'a>(&mut self, fingerprints: impl IntoIterator<Item = &'a str>) {
+        self.fingerprints = fingerprints.into_iter().map(str::to_string).collect();
+        self.scanned_at = Some(chrono::Utc::now().to_rfc3339());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::holograph::{HolographScanner, IssueType};
+
+    fn day(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, DATE_FORMAT).unwrap()
+    }
+
+    fn contradiction(text: &str) -> CoherenceIssue {
+        HolographScanner::new().scan(text).issues.into_iter()
+            .find(|i| i.issue_type == IssueType::Contradiction)
+            .expect("contradiction expected")
+    }
+
+    fn waiver(fingerprint: &str, expires: Option<&str>) -> Waiver {
+        Waiver {
+            fingerprint: fingerprint.to_string(),
+            justification: "Voulu: Pierre ment sur sa taille".to_string(),
+            author: "editeur".to_string(),
+            expires: expires.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_fingerprint_stable_across_offsets() {
+        let a = contradiction("Pierre etait grand. Pierre etait petit.");
+        let b = contradiction("Au matin, le vent soufflait fort.\n\nPierre etait grand. Pierre etait petit.");
+        assert!(!a.fingerprint.is_empty());
+        assert_eq!(a.fingerprint, b.fingerprint);
+        assert_eq!(a.fingerprint, fingerprint(&a));
+
+        let other = contradiction("Marie etait grande. Marie etait petite.");
+        assert_ne!(a.fingerprint, other.fingerprint);
+    }
+
+    #[test]
+    fn test_fingerprint_ignores_unrelated_consistent_mention() {
+        let a = contradiction("Pierre etait grand. Pierre etait petit.");
+        let b = contradiction("Pierre etait grand. Pierre etait petit. Plus tard, Pierre etait petit.");
+        assert_eq!(a.evidence.len(), 2);
+        assert_eq!(b.evidence.len(), 2, "{:?}", b.evidence);
+        assert_eq!(a.fingerprint, b.fingerprint);
+    }
+
+    #[test]
+    fn test_waiver_filters_issue() {
+        let text = "Pierre etait grand. Pierre etait petit.";
+        let scanner = HolographScanner::new();
+        let report = scanner.scan(text);
+        let fp = contradiction(text).fingerprint;
+        let waivers = WaiverFile { waivers: vec![waiver(&fp, None)], ..WaiverFile::default() };
+
+        let mut waived = report.clone();
+        waived.apply_waivers(&waivers, day("2026-01-01"));
+        assert!(waived.issues.iter().all(|i| i.fingerprint != fp));
+        assert_eq!(waived.waived.len(), 1);
+        assert_eq!(waived.waived[0].waiver.author, "editeur");
+        assert!(waived.logic_score > report.logic_score);
+    }
+
+    #[test]
+    fn test_expired_waiver_ignored() {
+        let text = "Pierre etait grand. Pierre etait petit.";
+        let fp = contradiction(text).fingerprint;
+        let waivers = WaiverFile { waivers: vec![waiver(&fp, Some("2025-12-31"))], ..WaiverFile::default() };
+
+        assert!(waivers.find(&fp, day("2025-12-31")).is_some());
+        let mut report = HolographScanner::new().scan(text);
+        report.apply_waivers(&waivers, day("2026-01-01"));
+        assert!(report.waived.is_empty());
+        assert!(report.issues.iter().any(|i| i.fingerprint == fp));
+        assert_eq!(waivers.expired(day("2026-01-01")).len(), 1);
+    }
+
+    #[test]
+    fn test_waiver_validation() {
+        let mut missing = waiver("abc", None);
+        missing.justification.clear();
+        assert!(WaiverFile { waivers: vec![missing], ..WaiverFile::default() }.validate().is_err());
+
+        let bad_date = WaiverFile { waivers: vec![waiver("abc", Some("31/12/2025"))], ..WaiverFile::default() };
+        assert!(bad_date.validate().is_err());
+
+        let duplicate = WaiverFile { waivers: vec![waiver("abc", None), waiver("abc", None)], ..WaiverFile::default() };
+        assert!(duplicate.validate().is_err());
+    }
+
+    #[test]
+    fn test_waiver_toml() {
+        let file = WaiverFile::from_toml_str(r#"
+            [[waivers]]
+            fingerprint = "0123456789abcdef"
+            justification = "Surnom volontaire"
+            author = "auteur"
+            expires = "2027-06-30"
+        "#).unwrap();
+        assert_eq!(file.waivers.len(), 1);
+        assert!(file.waivers[0].is_active(day("2027-06-30")));
+        assert!(!file.waivers[0].is_active(day("2027-07-01")));
+    }
+
+    #[test]
+    fn test_history_counts_new_issues() {
+        let dir = std::env::temp_dir().join(format!("omega_holo_history_{}", uuid::Uuid::new_v4()));
+        let path = dir.join("history.json");
+        let scanner = HolographScanner::new();
+
+        let mut history = ScanHistory::load(&path).unwrap();
+        let mut first = scanner.scan("Pierre etait grand. Pierre etait petit.");
+        first.compare_with(&history);
+        assert_eq!(first.new_issues, None);
+        history.record(first.fingerprints());
+        history.save(&path).unwrap();
+
+        let history = ScanHistory::load(&path).unwrap();
+        let mut second = scanner.scan("Pierre etait grand. Pierre etait petit. Marie etait grande. Marie etait petite.");
+        second.compare_with(&history);
+        assert_eq!(second.new_issues, Some(1));
+        assert_eq!(second.resolved_issues, Some(0));
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -12,6 +12,7 @@
 //! @certification VOICE_HYBRID v2.0.0 INDUSTRIAL
 
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 use super::policy::VoiceHybridPolicy;
 use super::replay::{ReplayMode, VoiceHybridReplayRecord};
@@ -181,6 +182,13 @@ pub trait LlmProvider: Send + Sync {
     /// En mode Record: appel réel, résultat potentiellement non-déterministe
     /// En mode Replay: ne devrait pas être appelé (on utilise le record)
     fn complete(&self, prompt: &str) -> Result<String, String>;
+
+    /// Métadonnées du dernier appel (usage, latence, response_hash, seed...)
+    /// Recopiées dans `VoiceHybridReplayRecord.meta` en mode Record
+    /// Par défaut: aucune (providers sans télémétrie)
+    fn call_meta(&self) -> BTreeMap<String, String> {
+        BTreeMap::new()
+    }
 }
 
 // ═══════════════════════════════════════════════════════════════════════════════
@@ -43,6 +43,16 @@ impl ReplayMode {
     pub fn requires_record_file(&self) -> bool {
         matches!(self, ReplayMode::Replay)
     }
+
+    /// Parse "off" | "record" | "replay" (insensible à la casse)
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "off" => Some(ReplayMode::Off),
+            "record" => Some(ReplayMode::Record),
+            "replay" => Some(ReplayMode::Replay),
+            _ => None,
+        }
+    }
 }
 
 // ═══════════════════════════════════════════════════════════════════════════════
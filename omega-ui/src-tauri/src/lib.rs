@@ -45,6 +45,15 @@ pub struct AnalyzeOptions {
     pub segmentation: Option<SegmentationOptions>,
     /// Mode d'analyse: "deterministic" | "hybrid" | "boost"
     pub analyzer_mode: Option<String>,
+    /// Record/Replay des appels IA: "off" | "record" | "replay"
+    #[serde(default)]
+    pub replay_mode: Option<String>,
+    /// Run rejoué (requis en mode "replay")
+    #[serde(default)]
+    pub replay_run_id: Option<String>,
+    /// Construit l'index sémantique du run (embeddings des segments et paragraphes)
+    #[serde(default)]
+    pub semantic_index: Option<bool>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -52,6 +61,9 @@ pub struct AnalyzeInput {
     pub text: String,
     pub source: Option<String>,
     pub options: Option<AnalyzeOptions>,
+    /// Identifiant choisi par l'UI pour cancel_analysis
+    #[serde(default)]
+    pub job_id: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -101,6 +113,25 @@ pub struct AnalysisMeta {
     pub ai_calls: u32,
     pub deterministic: bool,
     pub fallback_used: bool,
+    /// Appels IA servis par le cache / envoyés au provider
+    #[serde(default)]
+    pub cache_hits: u32,
+    #[serde(default)]
+    pub cache_misses: u32,
+    /// Record/Replay de l'analyse IA (record écrit ou relu)
+    #[serde(default)]
+    pub replay: Option<modules::ReplayInfo>,
+    /// Réparations de réponses IA hors schéma (prompts de correction envoyés)
+    #[serde(default)]
+    pub repair_attempts: u32,
+    /// Extraits envoyés à l'IA / ignorés (budget de tokens atteint)
+    #[serde(default)]
+    pub chunks: u32,
+    #[serde(default)]
+    pub chunks_skipped: u32,
+    /// Au moins une substitution faite avant envoi (politique de confidentialité du projet)
+    #[serde(default)]
+    pub redacted: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -176,6 +207,105 @@ fn get_output_dir() -> PathBuf {
         .join("omega-ui-output")
 }
 
+/// Politique du cache de completions IA (omega-ui-output/ai-cache, surcharges OMEGA_CACHE*)
+fn ai_cache_policy() -> ai::CachePolicy {
+    ai::CachePolicy::from_env(ai::cache::default_cache_dir(&get_output_dir()))
+}
+
+/// Ledger d'usage partagé (omega-ui-output/usage-ledger.jsonl)
+fn usage_ledger() -> std::sync::Arc<ai::UsageLedger> {
+    static LEDGER: std::sync::OnceLock<std::sync::Arc<ai::UsageLedger>> = std::sync::OnceLock::new();
+    LEDGER.get_or_init(|| {
+        std::sync::Arc::new(ai::UsageLedger::new(ai::ledger::default_ledger_path(&get_output_dir())))
+    }).clone()
+}
+
+/// Projet imputé dans le ledger (OMEGA_PROJECT, sinon "default")
+fn ai_project() -> String {
+    std::env::var("OMEGA_PROJECT").ok().filter(|p| !p.is_empty())
+        .unwrap_or_else(|| ai::ledger::DEFAULT_PROJECT.to_string())
+}
+
+/// Chaîne de fallback partagée: l'état des disjoncteurs et limiteurs survit entre deux commandes.
+/// Profils omega.toml (OMEGA_CONFIG) s'ils existent, sinon variables d'environnement; une configuration
+/// invalide est remontée à chaque commande jusqu'à correction (rien n'est mis en cache)
+fn fallback_provider() -> Result<std::sync::Arc<ai::FallbackProvider>, String> {
+    static FALLBACK: std::sync::OnceLock<std::sync::Arc<ai::FallbackProvider>> = std::sync::OnceLock::new();
+    if let Some(provider) = FALLBACK.get() {
+        return Ok(provider.clone());
+    }
+    let mut fallback = ai::FallbackProvider::from_config().map_err(|e| e.to_string())?;
+    if privacy_policy()?.local_only {
+        fallback = fallback.retain_local();
+    }
+    Ok(FALLBACK.get_or_init(|| std::sync::Arc::new(fallback)).clone())
+}
+
+/// Politique de confidentialité du projet: omega.toml [privacy.<projet>] + OMEGA_PRIVACY_*
+fn privacy_policy() -> Result<ai::PrivacyPolicy, String> {
+    ai::PrivacyPolicy::from_env(&ai_project()).map_err(|e| e.to_string())
+}
+
+/// Clé des tables de pseudonymisation: OMEGA_REDACTION_KEY, sinon fichier à la racine des runs
+fn redaction_key() -> Result<ai::RedactionKey, String> {
+    ai::RedactionKey::load(&get_output_dir()).map_err(|e| e.to_string())
+}
+
+/// Table de pseudonymisation du run, chiffrée et fusionnée avec celle déjà écrite par les autres commandes du run
+fn save_redaction_map(provider: &ai::RedactingProvider, run_id: &str) -> Result<(), String> {
+    if provider.map().is_empty() {
+        return Ok(());
+    }
+    let run_dir = get_output_dir().join(run_id);
+    fs::create_dir_all(&run_dir).map_err(|e| e.to_string())?;
+    provider.save_map(&run_dir, &redaction_key()?).map(|_| ()).map_err(|e| e.to_string())
+}
+
+/// Provider IA de l'application: fallback (env) journalisé et plafonné, derrière le cache disque,
+/// le tout derrière la politique de confidentialité (pseudonymisation, providers locaux seulement).
+/// Tous les providers d'un même run partagent sa table de pseudonymisation
+fn ai_provider(run_id: &str) -> Result<std::sync::Arc<ai::RedactingProvider>, String> {
+    let prices = ai::PriceTable::from_env().map_err(|e| e.to_string())?;
+    let metered = ai::MeteredProvider::new(
+        fallback_provider()?,
+        usage_ledger(),
+        prices,
+        ai::BudgetCaps::from_env(),
+    )
+    .with_project(&ai_project())
+    .with_run_id(run_id);
+    let cached = ai::CachedProvider::new(std::sync::Arc::new(metered), ai_cache_policy());
+    let mut private = ai::RedactingProvider::from_policy(std::sync::Arc::new(cached), privacy_policy()?, &ai_project())
+        .map_err(|e| e.to_string())?;
+    if private.policy().redact {
+        modules::emotion_replay::validate_run_id(run_id).map_err(|e| e.to_string())?;
+        let map = ai::RedactionMap::shared(&get_output_dir().join(run_id), &redaction_key()?).map_err(|e| e.to_string())?;
+        private = private.with_map(map);
+    }
+    Ok(std::sync::Arc::new(private))
+}
+
+/// Index sémantique du run: segments (si segmentation) + paragraphes, même provider que l'analyse IA
+fn build_semantic_index(text: &str, result: &AnalyzeResult, run_id: &str) -> Result<(), String> {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let mut passages: Vec<modules::semantic_index::Passage> = result.segments.iter().flatten().map(|seg| modules::semantic_index::Passage {
+        id: seg.id.clone(),
+        kind: modules::semantic_index::PassageKind::Segment,
+        title: Some(seg.title.clone()),
+        char_start: seg.char_start,
+        char_end: seg.char_end,
+        text: words[seg.word_start.min(words.len())..seg.word_end.min(words.len())].join(" "),
+    }).collect();
+    passages.extend(modules::semantic_index::paragraphs(text));
+    
+    let provider = ai_provider(run_id)?;
+    let index = modules::SemanticIndex::build(provider.as_ref(), run_id, &compute_sha256(text), passages);
+    save_redaction_map(&provider, run_id)?;
+    let index = index.map_err(|e| e.to_string())?;
+    let path = modules::SemanticIndex::path_in(&get_output_dir(), run_id).map_err(|e| e.to_string())?;
+    index.save(&path).map_err(|e| e.to_string())
+}
+
 fn compute_sha256(text: &str) -> String {
     let mut hasher = Sha256::new();
     hasher.update(text.as_bytes());
@@ -410,7 +540,13 @@ fn segment_fixed_words(text: &str, block_size: usize, min_words: usize, max_segm
 // CORE ANALYZE
 // // =========================================================================
 
-fn analyze_internal(text: &str, source: &str, options: &AnalyzeOptions) -> AnalyzeResult {
+fn analyze_internal(
+    text: &str,
+    source: &str,
+    options: &AnalyzeOptions,
+    run_id: &str,
+    replay: Option<modules::EmotionReplayConfig>,
+) -> Result<AnalyzeResult, String> {
     let start = Instant::now();
     let timestamp = Utc::now().to_rfc3339();
     let normalize = options.normalize.unwrap_or(true);
@@ -424,9 +560,25 @@ fn analyze_internal(text: &str, source: &str, options: &AnalyzeOptions) -> Analy
     let analyzer_mode_enum = modules::AnalyzerMode::from_str(
         options.analyzer_mode.as_deref().unwrap_or("deterministic")
     );
-    let analyzer = modules::create_analyzer(analyzer_mode_enum, None);
+    // Replay: aucun provider, le record fait foi
+    let replaying = replay.as_ref().is_some_and(|r| r.mode == interfaces::voice_hybrid::ReplayMode::Replay);
+    let provider = if analyzer_mode_enum != modules::AnalyzerMode::Deterministic && !replaying {
+        Some(ai_provider(run_id)?)
+    } else {
+        None
+    };
+    let strict = replay.as_ref().is_some_and(modules::EmotionReplayConfig::is_active);
+    let analyzer = modules::create_analyzer_with_replay(
+        analyzer_mode_enum,
+        provider.clone().map(|p| p as std::sync::Arc<dyn ai::LLMProvider>),
+        replay,
+    );
     
-    let (emotions, total_hits, analysis_meta) = match analyzer.analyze(text) {
+    let analyzed = analyzer.analyze(text);
+    if let Some(provider) = &provider {
+        save_redaction_map(provider, run_id)?;
+    }
+    let (emotions, total_hits, analysis_meta) = match analyzed {
         Ok(result) => {
             let emo: Vec<EmotionStat> = result.emotions.iter().map(|e| EmotionStat {
                 emotion: e.emotion.clone(),
@@ -444,9 +596,19 @@ fn analyze_internal(text: &str, source: &str, options: &AnalyzeOptions) -> Analy
                 ai_calls: result.meta.ai_calls,
                 deterministic: result.meta.deterministic,
                 fallback_used: result.meta.fallback_used,
+                cache_hits: result.meta.cache_hits,
+                cache_misses: result.meta.cache_misses,
+                replay: result.meta.replay,
+                repair_attempts: result.meta.repair_attempts,
+                chunks: result.meta.chunks,
+                chunks_skipped: result.meta.chunks_skipped,
+                redacted: provider.as_ref().is_some_and(|p| p.substitutions() > 0),
             };
             (emo, result.total_hits, Some(meta))
         }
+        // Record/Replay: record absent ou divergent → erreur explicite
+        Err(e) if strict => return Err(e.to_string()),
+        Err(e @ error::OmegaError::Cancelled(_)) => return Err(e.to_string()),
         Err(_) => {
             // Fallback to old method
             let (emo, hits) = analyze_segment(text, normalize);
@@ -456,6 +618,7 @@ fn analyze_internal(text: &str, source: &str, options: &AnalyzeOptions) -> Analy
                 ai_calls: 0,
                 deterministic: true,
                 fallback_used: true,
+                ..Default::default()
             }))
         }
     };
@@ -527,7 +690,7 @@ fn analyze_internal(text: &str, source: &str, options: &AnalyzeOptions) -> Analy
         (Some(info), Some(segment_results))
     };
     
-    AnalyzeResult {
+    Ok(AnalyzeResult {
         run_id: None,
         timestamp,
         duration_ms: start.elapsed().as_millis() as u64,
@@ -542,7 +705,7 @@ fn analyze_internal(text: &str, source: &str, options: &AnalyzeOptions) -> Analy
         segmentation: segmentation_info,
         segments,
         analysis_meta,
-    }
+    })
 }
 
 // // =========================================================================
@@ -652,18 +815,46 @@ fn generate_summary(result: &AnalyzeResult) -> String {
 // TAURI COMMANDS
 // // =========================================================================
 
+/// Record dans le dossier du run courant, Replay depuis celui de `replay_run_id`
+fn emotion_replay_config(options: &AnalyzeOptions, run_id: &str) -> Result<Option<modules::EmotionReplayConfig>, String> {
+    use interfaces::voice_hybrid::ReplayMode;
+    let mode = match options.replay_mode.as_deref() {
+        None => return Ok(None),
+        Some(m) => ReplayMode::parse(m).ok_or_else(|| format!("Mode replay invalide: {}", m))?,
+    };
+    let source = match mode {
+        ReplayMode::Off => return Ok(None),
+        ReplayMode::Record => run_id,
+        ReplayMode::Replay => options.replay_run_id.as_deref()
+            .ok_or_else(|| "replay_run_id requis en mode replay".to_string())?,
+    };
+    modules::EmotionReplayConfig::in_output_dir(mode, source, &get_output_dir())
+        .map(Some)
+        .map_err(|e| e.to_string())
+}
+
+/// Analyse hors du thread UI, annulable via cancel_analysis(job_id)
 #[tauri::command]
-fn analyze_text(input: AnalyzeInput) -> Result<AnalyzeResult, String> {
+async fn analyze_text(analyses: tauri::State<'_, AnalysisRegistry>, input: AnalyzeInput) -> Result<AnalyzeResult, String> {
+    let job_id = input.job_id.clone();
+    analyses.run(job_id, move || run_analysis(input)).await
+}
+
+fn run_analysis(input: AnalyzeInput) -> Result<AnalyzeResult, String> {
     let source = input.source.unwrap_or_else(|| "direct_input".to_string());
     let options = input.options.unwrap_or_else(|| AnalyzeOptions {
         language: Some("fr".to_string()),
         normalize: Some(true),
         segmentation: None,
         analyzer_mode: None,
+        replay_mode: None,
+        replay_run_id: None,
+        semantic_index: None,
     });
     
-    let mut result = analyze_internal(&input.text, &source, &options);
     let run_id = Local::now().format("%Y-%m-%d_%H-%M-%S").to_string();
+    let replay = emotion_replay_config(&options, &run_id)?;
+    let mut result = analyze_internal(&input.text, &source, &options, &run_id, replay)?;
     result.run_id = Some(run_id.clone());
     let run_dir = get_output_dir().join(&run_id);
     fs::create_dir_all(&run_dir).ok();
@@ -698,6 +889,10 @@ fn analyze_text(input: AnalyzeInput) -> Result<AnalyzeResult, String> {
     let summary_path = run_dir.join("summary.txt");
     let _ = fs::write(&summary_path, summary);
     
+    if options.semantic_index.unwrap_or(false) {
+        build_semantic_index(&input.text, &result, &run_id)?;
+    }
+    
     let relative_path = format!("omega-ui-output/{}/result.json", run_id);
     let _ = add_to_history(&result, &run_id, &relative_path);
     
@@ -705,7 +900,17 @@ fn analyze_text(input: AnalyzeInput) -> Result<AnalyzeResult, String> {
 }
 
 #[tauri::command]
-fn analyze_file(file_path: String, segmentation_mode: Option<String>, fixed_words: Option<usize>) -> Result<AnalyzeResult, String> {
+async fn analyze_file(
+    analyses: tauri::State<'_, AnalysisRegistry>,
+    file_path: String,
+    segmentation_mode: Option<String>,
+    fixed_words: Option<usize>,
+    job_id: Option<String>,
+) -> Result<AnalyzeResult, String> {
+    analyses.run(job_id, move || run_analysis(file_input(file_path, segmentation_mode, fixed_words)?)).await
+}
+
+fn file_input(file_path: String, segmentation_mode: Option<String>, fixed_words: Option<usize>) -> Result<AnalyzeInput, String> {
     let path = PathBuf::from(&file_path);
     
     if !path.exists() {
@@ -730,10 +935,14 @@ fn analyze_file(file_path: String, segmentation_mode: Option<String>, fixed_word
             normalize: Some(true),
             segmentation: seg_opts,
             analyzer_mode: None,
+            replay_mode: None,
+            replay_run_id: None,
+            semantic_index: None,
         }),
+        job_id: None,
     };
     
-    analyze_text(input)
+    Ok(input)
 }
 
 
@@ -748,15 +957,22 @@ pub struct DumpAnalysisInput {
     pub segmentation_mode: Option<String>,
     pub fixed_words: Option<usize>,
     pub pretty: Option<bool>,
+    #[serde(default)]
+    pub job_id: Option<String>,
 }
 
 #[tauri::command]
-fn dump_analysis(input: DumpAnalysisInput) -> Result<String, String> {
-    let analysis = analyze_file(
+async fn dump_analysis(analyses: tauri::State<'_, AnalysisRegistry>, input: DumpAnalysisInput) -> Result<String, String> {
+    let job_id = input.job_id.clone();
+    analyses.run(job_id, move || write_dump(input)).await
+}
+
+fn write_dump(input: DumpAnalysisInput) -> Result<String, String> {
+    let analysis = run_analysis(file_input(
         input.input_path.clone(),
         input.segmentation_mode.clone(),
         input.fixed_words,
-    )?;
+    )?)?;
 
     let out_path = std::path::Path::new(&input.output_path);
     if let Some(parent) = out_path.parent() {
@@ -801,6 +1017,25 @@ fn load_run(run_id: String) -> Result<AnalyzeResult, String> {
         .map_err(|e| format!("Parse error: {}", e))
 }
 
+#[tauri::command]
+async fn semantic_search(analyses: tauri::State<'_, AnalysisRegistry>, run_id: String, query: String, limit: Option<usize>) -> Result<Vec<modules::SemanticHit>, String> {
+    analyses.run(None, move || {
+        let path = modules::SemanticIndex::path_in(&get_output_dir(), &run_id).map_err(|e| e.to_string())?;
+        let index = modules::SemanticIndex::load(&path).map_err(|e| e.to_string())?;
+        let provider = ai_provider(&run_id)?;
+        let hits = index.search(provider.as_ref(), &query, limit.unwrap_or(10));
+        save_redaction_map(&provider, &run_id)?;
+        hits.map_err(|e| e.to_string())
+    }).await
+}
+
+#[tauri::command]
+fn similar_scenes(run_id: String, entry_id: String, limit: Option<usize>) -> Result<Vec<modules::SemanticHit>, String> {
+    let path = modules::SemanticIndex::path_in(&get_output_dir(), &run_id).map_err(|e| e.to_string())?;
+    let index = modules::SemanticIndex::load(&path).map_err(|e| e.to_string())?;
+    index.similar(&entry_id, limit.unwrap_or(5)).map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 fn open_output_folder() -> Result<(), String> {
     let output_dir = get_output_dir();
@@ -991,10 +1226,13 @@ pub fn run() {
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_fs::init())
+        .manage(StreamRegistry::default())
+        .manage(AnalysisRegistry::default())
         .invoke_handler(tauri::generate_handler![
             analyze_text,
             analyze_file,
             dump_analysis,
+            cancel_analysis,
             read_file,
             get_history,
             load_run,
@@ -1002,7 +1240,19 @@ pub fn run() {
             open_run_folder,
             export_markdown,
             export_docx,
-            scan_holograph
+            scan_holograph,
+            scan_manuscript,
+            start_completion_stream,
+            cancel_completion_stream,
+            list_ai_cache,
+            inspect_ai_cache,
+            purge_ai_cache,
+            usage_report,
+            semantic_search,
+            similar_scenes,
+            provider_chain,
+            list_prompt_templates,
+            diff_prompt_templates
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -1026,8 +1276,378 @@ mod aerospace_tests;
 // =========================================================================
 // HOLOGRAPH COMMAND
 // =========================================================================
+/// Scanner HOLOGRAPH: rule pack JSON/TOML optionnel (sinon pack intégré), genre,
+/// Bible utilisateur comme référence des noms de personnages
+fn build_holograph_scanner(
+    rule_pack: Option<String>,
+    genre: Option<String>,
+    project: Option<&str>,
+) -> Result<holograph::HolographScanner, String> {
+    let mut scanner = match rule_pack {
+        Some(path) => {
+            let pack = holograph::RulePack::load(&path).map_err(|e| e.to_string())?;
+            holograph::HolographScanner::with_rule_pack(pack).map_err(|e| e.to_string())?
+        }
+        None => holograph::HolographScanner::new(),
+    };
+    if let Some(genre) = genre {
+        scanner = scanner.with_genre(&genre);
+    }
+
+    Ok(scanner.with_character_reference(holograph_character_reference(project)?))
+}
+
+/// Référence personnages du projet: Bible <projet>.user_overrides.json (absente = vide)
+/// + faits CANON du snapshot <projet>.canon.json (absent = aucun; illisible = erreur)
+fn holograph_character_reference(project: Option<&str>) -> Result<holograph::CharacterReference, String> {
+    let overrides = lexicon_fr_gold::UserOverrides::load(holograph_project_file(project, "user_overrides.json"));
+    let reference = holograph::CharacterReference::from_overrides(&overrides);
+    let canon_path = holograph_project_file(project, "canon.json");
+    if !canon_path.is_file() {
+        return Ok(reference);
+    }
+    let content = fs::read_to_string(&canon_path).map_err(|e| format!("{}: {}", canon_path.display(), e))?;
+    let snapshot: interfaces::canon::CanonSnapshot = serde_json::from_str(&content)
+        .map_err(|e| format!("{}: {}", canon_path.display(), e))?;
+    Ok(reference.with_canon_facts(&snapshot.facts))
+}
+
+/// Fichier par projet dans omega-ui-output/holograph (nom assaini)
+fn holograph_project_file(project: Option<&str>, suffix: &str) -> std::path::PathBuf {
+    let project: String = project.unwrap_or("default")
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect();
+    get_output_dir().join("holograph").join(format!("{}.{}", project, suffix))
+}
+
+/// Dérogations: fichier explicite, sinon <projet>.waivers.json (absent = aucune)
+fn load_holograph_waivers(path: Option<String>, project: Option<&str>) -> Result<holograph::WaiverFile, String> {
+    let path = path.map(std::path::PathBuf::from)
+        .unwrap_or_else(|| holograph_project_file(project, "waivers.json"));
+    holograph::WaiverFile::load(&path).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn scan_holograph(
+    text: String,
+    rule_pack: Option<String>,
+    genre: Option<String>,
+    project: Option<String>,
+    waivers: Option<String>,
+) -> Result<holograph::HolographReport, String> {
+    let scanner = build_holograph_scanner(rule_pack, genre, project.as_deref())?;
+    let mut report = scanner.scan(&text);
+
+    let waivers = load_holograph_waivers(waivers, project.as_deref())?;
+    report.apply_waivers(&waivers, chrono::Local::now().date_naive());
+
+    // Comparaison au scan précédent: seulement pour un manuscrit identifié
+    if project.is_some() {
+        let history_path = holograph_project_file(project.as_deref(), "history.json");
+        let mut history = holograph::ScanHistory::load(&history_path).map_err(|e| e.to_string())?;
+        report.compare_with(&history);
+        history.record(report.fingerprints());
+        history.save(&history_path).map_err(|e| e.to_string())?;
+    }
+
+    Ok(report)
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ManuscriptScanInput {
+    /// Chapitres ordonnés, ou à défaut `text` découpé en chapitres
+    pub chapters: Option<Vec<holograph::Chapter>>,
+    pub text: Option<String>,
+    /// Projet: nom du registre persistant (défaut: "default")
+    pub project: Option<String>,
+    pub rule_pack: Option<String>,
+    pub genre: Option<String>,
+    /// Fichier de dérogations (défaut: <projet>.waivers.json)
+    #[serde(default)]
+    pub waivers: Option<String>,
+}
+
+#[tauri::command]
+fn scan_manuscript(input: ManuscriptScanInput) -> Result<holograph::ManuscriptReport, String> {
+    let chapters = match (input.chapters, input.text) {
+        (Some(chapters), _) => chapters,
+        (None, Some(text)) => {
+            let segments: Vec<(String, usize, usize)> = detect_chapters(&text, 0)
+                .into_iter()
+                .map(|s| (s.title, s.char_start, s.char_end))
+                .collect();
+            if segments.is_empty() {
+                vec![holograph::Chapter::new("seg_0000", &text)]
+            } else {
+                holograph::Chapter::from_segments(&text, &segments)
+            }
+        }
+        (None, None) => return Err("Aucun chapitre a analyser".to_string()),
+    };
+
+    let project = input.project.as_deref();
+    let registry_path = holograph_project_file(project, "registry.json");
+    let history_path = holograph_project_file(project, "manuscript-history.json");
+
+    let scanner = holograph::ManuscriptScanner::new(build_holograph_scanner(input.rule_pack, input.genre, project)?);
+    let waivers = load_holograph_waivers(input.waivers, project)?;
+    let mut registry = holograph::EntityRegistry::load(&registry_path).map_err(|e| e.to_string())?;
+    let mut history = holograph::ScanHistory::load(&history_path).map_err(|e| e.to_string())?;
+
+    let mut report = scanner.scan(&mut registry, &chapters);
+    report.apply_waivers(&waivers, chrono::Local::now().date_naive());
+    report.compare_with(&history);
+    history.record(report.fingerprints());
+
+    registry.save(&registry_path).map_err(|e| e.to_string())?;
+    history.save(&history_path).map_err(|e| e.to_string())?;
+
+    Ok(report)
+}
+
+// // =========================================================================
+// CACHE IA (list / inspect / purge)
+// // =========================================================================
+
+#[tauri::command]
+fn list_ai_cache() -> Result<Vec<ai::cache::CacheEntrySummary>, String> {
+    ai::CompletionCache::new(ai_cache_policy()).list().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn inspect_ai_cache(key: String) -> Result<ai::cache::CacheEntry, String> {
+    ai::CompletionCache::new(ai_cache_policy()).inspect(&key).map_err(|e| e.to_string())
+}
+
+/// scope: "all" | "expired" | "invalid" | <clé>
+#[tauri::command]
+fn purge_ai_cache(scope: String) -> Result<usize, String> {
+    let scope = match scope.as_str() {
+        "all" => ai::cache::PurgeScope::All,
+        "expired" => ai::cache::PurgeScope::Expired,
+        "invalid" => ai::cache::PurgeScope::Invalid,
+        key => ai::cache::PurgeScope::Key(key.to_string()),
+    };
+    ai::CompletionCache::new(ai_cache_policy()).purge(&scope).map_err(|e| e.to_string())
+}
+
+/// Rapport d'usage IA sur [from, to] (YYYY-MM-DD, UTC), optionnellement par projet
+#[tauri::command]
+fn usage_report(from: Option<String>, to: Option<String>, project: Option<String>) -> Result<ai::UsageReport, String> {
+    usage_ledger()
+        .report(from.as_deref(), to.as_deref(), project.as_deref())
+        .map_err(|e| e.to_string())
+}
+
+// // =========================================================================
+// CHAÎNE DE PROVIDERS (fallback)
+// // =========================================================================
+
+/// Maillons de la chaîne de fallback (noms de profils, ou providers détectés par variables), mock exclu
+#[tauri::command]
+fn provider_chain() -> Result<Vec<String>, String> {
+    Ok(fallback_provider()?.chain_ids())
+}
+
+// // =========================================================================
+// TEMPLATES DE PROMPTS (registre versionné)
+// // =========================================================================
+
+/// Templates du registre (intégrés + OMEGA_PROMPTS_DIR), toutes versions
+#[tauri::command]
+fn list_prompt_templates() -> Vec<ai::PromptTemplateRef> {
+    let registry = ai::PromptRegistry::global();
+    registry.ids().iter()
+        .flat_map(|id| registry.versions(id).into_iter().filter_map(move |v| registry.get(id, Some(&v)).ok().map(|t| t.reference())))
+        .collect()
+}
+
+#[tauri::command]
+fn diff_prompt_templates(id: String, from: String, to: String) -> Result<ai::PromptDiff, String> {
+    ai::PromptRegistry::global().diff(&id, &from, &to).map_err(|e| e.to_string())
+}
+
+// // =========================================================================
+// STREAMING IA (deltas -> événements UI, annulation)
+// // =========================================================================
+
+/// Événements émis vers l'UI pendant un stream
+const STREAM_DELTA_EVENT: &str = "omega://stream-delta";
+const STREAM_DONE_EVENT: &str = "omega://stream-done";
+const STREAM_ERROR_EVENT: &str = "omega://stream-error";
+
+/// Streams en cours: stream_id -> jeton d'annulation
+#[derive(Default)]
+pub struct StreamRegistry(std::sync::Mutex<HashMap<String, ai::CancelToken>>);
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct StreamCompletionInput {
+    /// Identifiant choisi par l'UI (sinon généré)
+    pub stream_id: Option<String>,
+    pub system_prompt: Option<String>,
+    pub user_prompt: String,
+    pub seed: Option<u64>,
+    pub max_tokens: Option<u32>,
+    /// Défaut: OMEGA_STREAM_TEMPERATURE, sinon 0.0 (la `temperature` d'un profil omega.toml reste prioritaire)
+    #[serde(default)]
+    pub temperature: Option<f32>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct StreamDeltaEvent {
+    pub stream_id: String,
+    pub index: u32,
+    pub text: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct StreamDoneEvent {
+    pub stream_id: String,
+    pub response: ai::CompletionResponse,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct StreamErrorEvent {
+    pub stream_id: String,
+    pub error: String,
+    pub cancelled: bool,
+}
+
+/// Température du stream: requête, sinon OMEGA_STREAM_TEMPERATURE, sinon 0.0; bornée à [0, MAX_TEMPERATURE]
+fn stream_temperature(requested: Option<f32>) -> Result<f32, String> {
+    let temperature = match requested {
+        Some(t) => t,
+        None => match std::env::var("OMEGA_STREAM_TEMPERATURE").ok().filter(|v| !v.is_empty()) {
+            Some(v) => v.parse().map_err(|_| format!("OMEGA_STREAM_TEMPERATURE invalide: {}", v))?,
+            None => 0.0,
+        },
+    };
+    if !(0.0..=ai::providers::profiles::MAX_TEMPERATURE).contains(&temperature) {
+        return Err(format!("Temperature hors bornes [0, {}]: {}", ai::providers::profiles::MAX_TEMPERATURE, temperature));
+    }
+    Ok(temperature)
+}
+
+/// Lance un stream en tâche de fond et retourne son identifiant;
+/// deltas, réponse finale et erreurs arrivent par événements
+#[tauri::command]
+fn start_completion_stream(
+    app: tauri::AppHandle,
+    streams: tauri::State<'_, StreamRegistry>,
+    input: StreamCompletionInput,
+) -> Result<String, String> {
+    use tauri::{Emitter, Manager};
+
+    let stream_id = input.stream_id.clone()
+        .unwrap_or_else(|| format!("STREAM_{}", uuid::Uuid::new_v4().simple()));
+    let temperature = stream_temperature(input.temperature)?;
+    let cancel = ai::CancelToken::new();
+    {
+        let mut active = streams.0.lock().map_err(|e| e.to_string())?;
+        if active.contains_key(&stream_id) {
+            return Err(format!("Stream deja actif: {}", stream_id));
+        }
+        active.insert(stream_id.clone(), cancel.clone());
+    }
+
+    let req = ai::CompletionRequest {
+        run_id: stream_id.clone(),
+        seed: input.seed.unwrap_or(42),
+        system_prompt: input.system_prompt.unwrap_or_default(),
+        user_prompt: input.user_prompt,
+        temperature,
+        max_tokens: input.max_tokens.unwrap_or(1024),
+        schema_name: None,
+        json_schema: None,
+        constraints: Default::default(),
+        template: None,
+        tool: None,
+    };
+
+    let provider = match ai_provider(&stream_id) {
+        Ok(provider) => provider,
+        Err(e) => {
+            streams.0.lock().map_err(|e| e.to_string())?.remove(&stream_id);
+            return Err(e);
+        }
+    };
+    let id = stream_id.clone();
+    std::thread::spawn(move || {
+        let result = ai::LLMProvider::generate_stream(&*provider, req, &mut |d| {
+            let _ = app.emit(STREAM_DELTA_EVENT, StreamDeltaEvent { stream_id: id.clone(), index: d.index, text: d.text });
+        }, &cancel);
+        // Table écrite même après annulation: le prompt pseudonymisé a pu partir
+        let saved = save_redaction_map(&provider, &id).map_err(error::OmegaError::WriteError);
+        let result = result.and_then(|response| saved.map(|()| response));
+
+        let _ = match result {
+            Ok(response) => app.emit(STREAM_DONE_EVENT, StreamDoneEvent { stream_id: id.clone(), response }),
+            Err(e) => app.emit(STREAM_ERROR_EVENT, StreamErrorEvent {
+                stream_id: id.clone(),
+                cancelled: matches!(e, error::OmegaError::Cancelled(_)),
+                error: e.to_string(),
+            }),
+        };
+        if let Ok(mut active) = app.state::<StreamRegistry>().0.lock() {
+            active.remove(&id);
+        }
+    });
+
+    Ok(stream_id)
+}
+
+/// Annule un stream en cours (false si inconnu ou déjà terminé)
+#[tauri::command]
+fn cancel_completion_stream(streams: tauri::State<'_, StreamRegistry>, stream_id: String) -> Result<bool, String> {
+    let active = streams.0.lock().map_err(|e| e.to_string())?;
+    Ok(match active.get(&stream_id) {
+        Some(cancel) => { cancel.cancel(); true }
+        None => false,
+    })
+}
+
+// =========================================================================
+// ANALYSES ANNULABLES
+// =========================================================================
+
+/// Analyses en cours: job_id -> jeton d'annulation
+#[derive(Default)]
+pub struct AnalysisRegistry(std::sync::Mutex<HashMap<String, ai::CancelToken>>);
+
+impl AnalysisRegistry {
+    /// Exécute `job` sur le pool bloquant (l'UI reste réactive), sous un CallControl
+    /// (OMEGA_AI_CALL_TIMEOUT_MS, OMEGA_AI_DEADLINE_MS) annulable par job_id
+    async fn run<T: Send + 'static>(
+        &self,
+        job_id: Option<String>,
+        job: impl FnOnce() -> Result<T, String> + Send + 'static,
+    ) -> Result<T, String> {
+        let ctl = ai::CallControl::from_env();
+        if let Some(id) = &job_id {
+            let mut active = self.0.lock().map_err(|e| e.to_string())?;
+            if active.contains_key(id) {
+                return Err(format!("Analyse deja active: {}", id));
+            }
+            active.insert(id.clone(), ctl.cancel.clone());
+        }
+        let scoped = ctl.clone();
+        let result = tauri::async_runtime::spawn_blocking(move || scoped.scope(job)).await;
+        if let Some(id) = &job_id {
+            if let Ok(mut active) = self.0.lock() {
+                active.remove(id);
+            }
+        }
+        result.map_err(|e| format!("Analyse interrompue: {}", e))?
+    }
+}
+
+/// Annule une analyse en cours (false si inconnue ou déjà terminée); l'appel provider en vol est abandonné
 #[tauri::command]
-fn scan_holograph(text: String) -> Result<holograph::HolographReport, String> {
-    let scanner = holograph::HolographScanner::new();
-    Ok(scanner.scan(&text))
+fn cancel_analysis(analyses: tauri::State<'_, AnalysisRegistry>, job_id: String) -> Result<bool, String> {
+    let active = analyses.0.lock().map_err(|e| e.to_string())?;
+    Ok(match active.get(&job_id) {
+        Some(cancel) => { cancel.cancel(); true }
+        None => false,
+    })
 }
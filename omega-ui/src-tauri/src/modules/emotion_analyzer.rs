@@ -3,9 +3,14 @@
 //! v2.0 — Branché sur FR_LEXICON_V1_GOLD (118 keywords)
 
 use serde::{Deserialize, Serialize};
-use crate::error::OmegaResult;
-use crate::ai::LLMProvider;
+use crate::error::{OmegaError, OmegaResult};
+use crate::ai::{CacheOutcome, ChunkConfig, ChunkLimits, ChunkPlan, CompletionRequest, LLMProvider};
+use crate::ai::prompts::{self, EMOTION_ANALYSIS_TEMPLATE};
+use crate::ai::schema::{self, DEFAULT_MAX_REPAIRS};
+use crate::interfaces::voice_hybrid::ReplayMode;
+use crate::pipeline::fs_utils::sha256_str;
 use super::analyzer_mode::AnalyzerMode;
+use super::emotion_replay::{self, ChunkRecord, EmotionReplayConfig, EmotionReplayRecord, ReplayInfo};
 use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 
@@ -39,9 +44,27 @@ pub struct AnalysisMeta {
     pub fallback_used: bool,
     pub deterministic: bool,
     pub lexicon_version: Option<String>,
+    /// Appels IA servis par le cache de completions
+    #[serde(default)]
+    pub cache_hits: u32,
+    /// Appels IA absents du cache (provider appelé)
+    #[serde(default)]
+    pub cache_misses: u32,
+    /// Record/Replay (None si mode Off)
+    #[serde(default)]
+    pub replay: Option<ReplayInfo>,
+    /// Re-prompts nécessaires pour obtenir une réponse conforme au schéma
+    #[serde(default)]
+    pub repair_attempts: u32,
+    /// Extraits envoyés à l'IA (texte long découpé)
+    #[serde(default)]
+    pub chunks: u32,
+    /// Extraits non analysés (budget de tokens atteint)
+    #[serde(default)]
+    pub chunks_skipped: u32,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct AIUsage {
     pub prompt_tokens: u32,
     pub completion_tokens: u32,
@@ -57,6 +80,44 @@ pub struct AnalysisResult {
     pub meta: AnalysisMeta,
 }
 
+/// Résultat d'un appel IA
+#[derive(Debug, Clone)]
+pub struct AICall {
+    pub emotions: Vec<EmotionResult>,
+    pub usage: AIUsage,
+    /// Hit/miss de chaque appel si le provider passe par le cache
+    pub cache: Vec<CacheOutcome>,
+    /// Provider ayant produit la réponse (enregistré en mode Replay)
+    pub provider: String,
+    /// Record écrit ou relu
+    pub replay: Option<ReplayInfo>,
+    /// Réponse produite par un provider de secours (FallbackProvider)
+    pub fallback_used: bool,
+    /// Re-prompts de réparation (réponse hors schéma)
+    pub repairs: u32,
+    /// Extraits envoyés (un appel chacun) / ignorés faute de budget
+    pub chunks: u32,
+    pub chunks_skipped: u32,
+}
+
+impl AICall {
+    /// (cache_hits, cache_misses) pour AnalysisMeta
+    pub fn cache_counts(&self) -> (u32, u32) {
+        let hits = self.cache.iter().filter(|c| **c == CacheOutcome::Hit).count() as u32;
+        (hits, self.cache.len() as u32 - hits)
+    }
+
+    /// Appels provider: un par extrait + réparations
+    pub fn calls(&self) -> u32 {
+        self.chunks + self.repairs
+    }
+
+    /// Résultat reproduit depuis un record (Replay)
+    pub fn is_replayed(&self) -> bool {
+        self.replay.as_ref().is_some_and(|r| r.mode == ReplayMode::Replay)
+    }
+}
+
 /// Seuil pour déclencher l'IA en mode Hybrid
 const HYBRID_AMBIGUITY_THRESHOLD: f64 = 0.15;
 const HYBRID_LOW_CONFIDENCE: f64 = 0.3;
@@ -129,6 +190,12 @@ impl EmotionAnalyzer for LexiconAnalyzer {
                 fallback_used: false,
                 deterministic: true,
                 lexicon_version: Some(Self::lexicon_version().to_string()),
+                cache_hits: 0,
+                cache_misses: 0,
+                replay: None,
+                repair_attempts: 0,
+                chunks: 0,
+                chunks_skipped: 0,
             },
         })
     }
@@ -140,78 +207,312 @@ impl EmotionAnalyzer for LexiconAnalyzer {
 // AI ANALYZER (BOOST)
 // ═══════════════════════════════════════════════════════════════════════════════
 
+/// Émotions reconnues (clés de FR_LEXICON_V1_GOLD): toute autre émotion renvoyée par l'IA est rejetée
+pub const EMOTION_TAXONOMY: [&str; 10] = ["joy", "sadness", "anger", "fear", "trust", "love", "surprise", "anticipation", "pride", "disgust"];
+
+/// JSON Schema de la réponse IA: émotions de la taxonomie, scores dans [0, 1]
+pub fn emotion_response_schema() -> serde_json::Value {
+    serde_json::json!({
+        "type": "object",
+        "required": ["emotions"],
+        "properties": {
+            "emotions": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "required": ["emotion", "score"],
+                    "properties": {
+                        "emotion": {"type": "string", "enum": EMOTION_TAXONOMY},
+                        "score": {"type": "number", "minimum": 0, "maximum": 1}
+                    }
+                }
+            }
+        }
+    })
+}
+
+/// Completion max d'un appel
+const AI_MAX_TOKENS: u32 = 1000;
+
+/// Prompt hors extrait: consignes + baseline lexicon (10 émotions au plus)
+const AI_PROMPT_OVERHEAD_TOKENS: u32 = 800;
+
 pub struct AIAnalyzer {
     provider: Arc<dyn LLMProvider>,
+    replay: Option<EmotionReplayConfig>,
+    max_repairs: u32,
+    chunking: ChunkConfig,
 }
 
 impl AIAnalyzer {
     pub fn new(provider: Arc<dyn LLMProvider>) -> Self {
-        Self { provider }
+        Self { provider, replay: None, max_repairs: DEFAULT_MAX_REPAIRS, chunking: ChunkConfig::default() }
     }
 
-    pub fn call_ai(&self, text: &str, lexicon_baseline: &[EmotionResult]) -> OmegaResult<(Vec<EmotionResult>, AIUsage)> {
-        let baseline_json = serde_json::to_string(lexicon_baseline).unwrap_or_default();
+    /// Nombre max de re-prompts quand la réponse viole le schéma (0 = aucune réparation)
+    pub fn with_max_repairs(mut self, max_repairs: u32) -> Self {
+        self.max_repairs = max_repairs;
+        self
+    }
+
+    /// Taille des extraits et budget de tokens pour les textes longs
+    pub fn with_chunking(mut self, chunking: ChunkConfig) -> Self {
+        self.chunking = chunking;
+        self
+    }
 
-        let request = crate::ai::models::CompletionRequest {
+    /// Active le Record/Replay des appels IA
+    pub fn with_replay(mut self, replay: EmotionReplayConfig) -> Self {
+        self.replay = Some(replay);
+        self
+    }
+
+    /// Limites de découpage pour la fenêtre de contexte du provider
+    pub fn chunk_limits(&self) -> ChunkLimits {
+        self.chunking.limits(self.provider.capabilities().max_context_window, AI_PROMPT_OVERHEAD_TOKENS + AI_MAX_TOKENS)
+    }
+
+    /// Requête envoyée au provider (temperature 0: condition du Replay)
+    pub fn build_request(text: &str, lexicon_baseline: &[EmotionResult]) -> CompletionRequest {
+        Self::request("Texte à analyser", text, lexicon_baseline)
+    }
+
+    /// Requêtes d'un plan: texte entier s'il tient dans un extrait, sinon "Extrait i/n" avec sa propre baseline
+    pub fn plan_requests(plan: &ChunkPlan, text: &str, lexicon_baseline: &[EmotionResult]) -> Vec<CompletionRequest> {
+        if plan.chunks.len() == 1 && plan.skipped == 0 {
+            return vec![Self::build_request(text, lexicon_baseline)];
+        }
+        let total = plan.chunks.len() + plan.skipped as usize;
+        plan.chunks.iter().map(|chunk| {
+            let baseline = LexiconAnalyzer::analyze_with_lexicon(&chunk.text);
+            Self::request(&format!("Extrait {}/{} du texte à analyser", chunk.index + 1, total), &chunk.text, &baseline)
+        }).collect()
+    }
+
+    /// Prompts rendus par le template `emotion.analysis` (version active du registre)
+    fn request(label: &str, text: &str, lexicon_baseline: &[EmotionResult]) -> CompletionRequest {
+        let baseline_json = serde_json::to_string(lexicon_baseline).unwrap_or_default();
+        let emotions = EMOTION_TAXONOMY.join(", ");
+        let rendered = prompts::render_active(EMOTION_ANALYSIS_TEMPLATE, &[
+            ("label", label),
+            ("text", text),
+            ("baseline", &baseline_json),
+            ("emotions", &emotions),
+        ]);
+
+        CompletionRequest {
             run_id: generate_run_id(),
             seed: 42,
-            system_prompt: "Tu es un expert en analyse émotionnelle littéraire. Analyse le texte et ajuste les scores émotionnels.".into(),
-            user_prompt: format!(
-                "Texte à analyser:\n{}\n\nBaseline Lexicon:\n{}\n\nRetourne un JSON avec les émotions ajustées.",
-                &text[..text.len().min(2000)],
-                baseline_json
-            ),
-            temperature: 0.3,
-            max_tokens: 1000,
-            schema_name: None,
-            json_schema: Some(serde_json::json!({
-                "type": "object",
-                "properties": {
-                    "emotions": {"type": "array"}
-                }
-            })),
+            system_prompt: rendered.system,
+            user_prompt: rendered.user,
+            temperature: 0.0,
+            max_tokens: AI_MAX_TOKENS,
+            schema_name: Some("emotion_analysis".into()),
+            json_schema: Some(emotion_response_schema()),
             constraints: Default::default(),
+            template: Some(rendered.template),
+            tool: None,
+        }
+    }
+
+    pub fn call_ai(&self, text: &str, lexicon_baseline: &[EmotionResult]) -> OmegaResult<AICall> {
+        let replay = self.replay.as_ref().filter(|cfg| cfg.is_active());
+        // Replay: mêmes limites qu'au Record, quel que soit le provider courant
+        let limits = match replay {
+            Some(cfg) if cfg.mode == ReplayMode::Replay => cfg.recorded_chunking()?.unwrap_or(ChunkLimits::UNBOUNDED),
+            _ => self.chunk_limits(),
         };
+        let plan = ChunkPlan::new(text, limits);
+        let requests = Self::plan_requests(&plan, text, lexicon_baseline);
+        if plan.skipped > 0 {
+            eprintln!("[BOOST] Budget de {} tokens atteint: {} extrait(s) non analysé(s)", limits.token_budget, plan.skipped);
+        }
 
-        let response = self.provider.generate(request)?;
+        let replay = match replay {
+            Some(cfg) => cfg,
+            None => return self.generate_plan(&plan, requests, lexicon_baseline).map(|(call, _)| call),
+        };
 
-        let usage = AIUsage {
-            prompt_tokens: response.usage.prompt_tokens,
-            completion_tokens: response.usage.completion_tokens,
-            total_tokens: response.usage.total_tokens,
+        let input_hash = sha256_str(text);
+        let prompt_hash = emotion_replay::prompts_hash(&requests);
+        let template = requests[0].template.clone();
+
+        if replay.mode == ReplayMode::Replay {
+            let (record, info) = replay.load(&input_hash, Some(&prompt_hash), template.as_ref())?;
+            return Ok(AICall {
+                emotions: record.emotions,
+                usage: record.usage.unwrap_or_default(),
+                cache: Vec::new(),
+                provider: record.provider.unwrap_or_default(),
+                replay: Some(info),
+                fallback_used: record.fallback_used,
+                repairs: record.repair_attempts,
+                chunks: record.chunks.len().max(1) as u32,
+                chunks_skipped: plan.skipped,
+            });
+        }
+
+        let system_prompt = requests[0].system_prompt.clone();
+        let user_prompt = (requests.len() == 1).then(|| requests[0].user_prompt.clone());
+        let (mut call, responses) = self.generate_plan(&plan, requests, lexicon_baseline)?;
+        let single = responses.len() == 1;
+        let chunks = if single { Vec::new() } else {
+            plan.chunks.iter().zip(&responses).map(|(chunk, (prompt_hash, response))| ChunkRecord {
+                index: chunk.index,
+                chunk_hash: chunk.hash.clone(),
+                prompt_hash: prompt_hash.clone(),
+                completion: response.content.clone(),
+                response_hash: response.response_hash.clone(),
+            }).collect()
+        };
+        let (completion, response_hash) = match responses.into_iter().next() {
+            Some((_, response)) if single => (Some(response.content), Some(response.response_hash)),
+            _ => (None, None),
         };
+        let info = replay.save(EmotionReplayRecord {
+            provider: Some(call.provider.clone()),
+            ai_called: true,
+            prompt_hash: Some(prompt_hash),
+            system_prompt: Some(system_prompt),
+            user_prompt,
+            template,
+            completion,
+            response_hash,
+            emotions: call.emotions.clone(),
+            usage: Some(call.usage.clone()),
+            fallback_used: call.fallback_used,
+            repair_attempts: call.repairs,
+            chunking: Some(limits),
+            chunks,
+            ..EmotionReplayRecord::new(&replay.run_id, &input_hash)
+        })?;
+        call.replay = Some(info);
+        Ok(call)
+    }
 
-        let ai_emotions = Self::parse_ai_response(&response.content, lexicon_baseline);
-
-        Ok((ai_emotions, usage))
-    }
-
-    fn parse_ai_response(content: &str, baseline: &[EmotionResult]) -> Vec<EmotionResult> {
-        if let Ok(parsed) = serde_json::from_str::<serde_json::Value>(content) {
-            if let Some(emotions) = parsed.get("emotions").and_then(|e| e.as_array()) {
-                return emotions.iter().filter_map(|e| {
-                    let emotion = e.get("emotion")?.as_str()?;
-                    let score = e.get("score")?.as_f64()?;
-                    let baseline_score = baseline.iter()
-                        .find(|b| b.emotion == emotion)
-                        .map(|b| b.score);
-
-                    Some(EmotionResult {
-                        emotion: emotion.to_string(),
-                        score,
-                        confidence: 0.85,
-                        source: EmotionSource::AI,
-                        keywords: vec![],
-                        lexicon_score: baseline_score,
-                        ai_adjustment: baseline_score.map(|b| score - b),
-                    })
-                }).collect();
-            }
+    /// Un appel par extrait, dans l'ordre du texte, puis fusion; retourne (prompt_hash, réponse) de chaque appel
+    fn generate_plan(
+        &self,
+        plan: &ChunkPlan,
+        requests: Vec<CompletionRequest>,
+        lexicon_baseline: &[EmotionResult],
+    ) -> OmegaResult<(AICall, Vec<(String, crate::ai::CompletionResponse)>)> {
+        let mut parts = Vec::with_capacity(requests.len());
+        let mut responses = Vec::with_capacity(requests.len());
+        for request in requests {
+            let prompt_hash = emotion_replay::prompt_hash(&request);
+            let (call, response) = self.generate(request, lexicon_baseline)?;
+            parts.push(call);
+            responses.push((prompt_hash, response));
+        }
+        if parts.len() == 1 {
+            let mut call = parts.remove(0);
+            call.chunks_skipped = plan.skipped;
+            return Ok((call, responses));
         }
 
-        baseline.iter().cloned().map(|mut e| {
-            e.source = EmotionSource::AI;
-            e
+        let weights: Vec<u32> = plan.chunks.iter().map(|c| c.tokens).collect();
+        let emotions = Self::merge_chunks(&weights, &parts, lexicon_baseline);
+        let mut providers: Vec<String> = Vec::new();
+        let mut usage = AIUsage::default();
+        for part in &parts {
+            if !providers.contains(&part.provider) { providers.push(part.provider.clone()); }
+            usage.prompt_tokens += part.usage.prompt_tokens;
+            usage.completion_tokens += part.usage.completion_tokens;
+            usage.total_tokens += part.usage.total_tokens;
+        }
+        let call = AICall {
+            emotions,
+            usage,
+            cache: parts.iter().flat_map(|p| p.cache.iter().copied()).collect(),
+            provider: providers.join("+"),
+            replay: None,
+            fallback_used: parts.iter().any(|p| p.fallback_used),
+            repairs: parts.iter().map(|p| p.repairs).sum(),
+            chunks: parts.len() as u32,
+            chunks_skipped: plan.skipped,
+        };
+        Ok((call, responses))
+    }
+
+    /// Fusion des extraits: score = moyenne pondérée par la taille de l'extrait (émotion absente d'un extrait = 0),
+    /// ajustement calculé contre la baseline du texte entier
+    fn merge_chunks(weights: &[u32], parts: &[AICall], lexicon_baseline: &[EmotionResult]) -> Vec<EmotionResult> {
+        let total: f64 = weights.iter().map(|w| *w as f64).sum::<f64>().max(1.0);
+        let mut merged: Vec<EmotionResult> = EMOTION_TAXONOMY.iter().filter_map(|emotion| {
+            let (mut score, mut confidence, mut present) = (0.0, 0.0, 0.0);
+            for (w, part) in weights.iter().zip(parts) {
+                if let Some(e) = part.emotions.iter().find(|e| e.emotion == *emotion) {
+                    score += *w as f64 * e.score;
+                    confidence += *w as f64 * e.confidence;
+                    present += *w as f64;
+                }
+            }
+            if present == 0.0 { return None; }
+            let score = score / total;
+            let lexicon_score = lexicon_baseline.iter().find(|b| b.emotion == *emotion).map(|b| b.score);
+            Some(EmotionResult {
+                emotion: emotion.to_string(),
+                score,
+                confidence: confidence / present,
+                source: EmotionSource::AI,
+                keywords: vec![],
+                lexicon_score,
+                ai_adjustment: lexicon_score.map(|b| score - b),
+            })
+        }).collect();
+        // Tri stable: égalités départagées par l'ordre de la taxonomie
+        merged.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+        merged
+    }
+
+    /// Appel + validation du schéma (réparations bornées); hors schéma après réparations → InvalidResponse détaillé
+    fn generate(&self, request: CompletionRequest, lexicon_baseline: &[EmotionResult]) -> OmegaResult<(AICall, crate::ai::CompletionResponse)> {
+        let validated = schema::generate_validated(self.provider.as_ref(), request, self.max_repairs)?;
+        let response = validated.response;
+
+        let usage = AIUsage {
+            prompt_tokens: validated.usage.prompt_tokens,
+            completion_tokens: validated.usage.completion_tokens,
+            total_tokens: validated.usage.total_tokens,
+        };
+
+        let emotions = Self::parse_ai_response(&validated.value, lexicon_baseline);
+        let (provider, fallback_used) = match &response.fallback {
+            Some(trace) => (trace.responder.clone(), trace.fallback_used),
+            None => (self.provider.id(), false),
+        };
+        let call = AICall {
+            emotions,
+            usage,
+            cache: response.cache.into_iter().collect(),
+            provider,
+            replay: None,
+            fallback_used,
+            repairs: validated.repairs,
+            chunks: 1,
+            chunks_skipped: 0,
+        };
+        Ok((call, response))
+    }
+    /// Émotions d'une réponse déjà validée par emotion_response_schema()
+    fn parse_ai_response(parsed: &serde_json::Value, baseline: &[EmotionResult]) -> Vec<EmotionResult> {
+        parsed["emotions"].as_array().into_iter().flatten().filter_map(|e| {
+            let emotion = e.get("emotion")?.as_str()?;
+            let score = e.get("score")?.as_f64()?;
+            let baseline_score = baseline.iter()
+                .find(|b| b.emotion == emotion)
+                .map(|b| b.score);
+
+            Some(EmotionResult {
+                emotion: emotion.to_string(),
+                score,
+                confidence: 0.85,
+                source: EmotionSource::AI,
+                keywords: vec![],
+                lexicon_score: baseline_score,
+                ai_adjustment: baseline_score.map(|b| score - b),
+            })
         }).collect()
     }
 }
@@ -219,7 +520,11 @@ impl AIAnalyzer {
 impl EmotionAnalyzer for AIAnalyzer {
     fn analyze(&self, text: &str) -> OmegaResult<AnalysisResult> {
         let baseline = LexiconAnalyzer::analyze_with_lexicon(text);
-        let (emotions, usage) = self.call_ai(text, &baseline)?;
+        let call = self.call_ai(text, &baseline)?;
+        let (cache_hits, cache_misses) = call.cache_counts();
+        let deterministic = call.is_replayed();
+        let ai_calls = call.calls();
+        let AICall { emotions, usage, provider, replay, fallback_used, repairs, chunks, chunks_skipped, .. } = call;
         let total_hits = emotions.len();
         let dominant = emotions.first().map(|e| e.emotion.clone());
 
@@ -229,12 +534,18 @@ impl EmotionAnalyzer for AIAnalyzer {
             total_hits,
             meta: AnalysisMeta {
                 mode: "boost".into(),
-                provider: Some(self.provider.id()),
-                ai_calls: 1,
+                provider: Some(provider),
+                ai_calls,
                 ai_usage: Some(usage),
-                fallback_used: false,
-                deterministic: false,
+                fallback_used,
+                deterministic,
                 lexicon_version: Some(LexiconAnalyzer::lexicon_version().to_string()),
+                cache_hits,
+                cache_misses,
+                replay,
+                repair_attempts: repairs,
+                chunks,
+                chunks_skipped,
             },
         })
     }
@@ -248,11 +559,25 @@ impl EmotionAnalyzer for AIAnalyzer {
 
 pub struct HybridAnalyzer {
     provider: Arc<dyn LLMProvider>,
+    replay: Option<EmotionReplayConfig>,
+    chunking: ChunkConfig,
 }
 
 impl HybridAnalyzer {
     pub fn new(provider: Arc<dyn LLMProvider>) -> Self {
-        Self { provider }
+        Self { provider, replay: None, chunking: ChunkConfig::default() }
+    }
+
+    /// Taille des extraits et budget de tokens pour les textes longs
+    pub fn with_chunking(mut self, chunking: ChunkConfig) -> Self {
+        self.chunking = chunking;
+        self
+    }
+
+    /// Active le Record/Replay: les erreurs IA ne retombent plus sur le lexicon
+    pub fn with_replay(mut self, replay: EmotionReplayConfig) -> Self {
+        self.replay = Some(replay);
+        self
     }
 
     fn needs_ai_clarification(emotions: &[EmotionResult]) -> bool {
@@ -274,6 +599,10 @@ impl EmotionAnalyzer for HybridAnalyzer {
         let lexicon_emotions = LexiconAnalyzer::analyze_with_lexicon(text);
 
         if !Self::needs_ai_clarification(&lexicon_emotions) {
+            let replay = match &self.replay {
+                Some(cfg) => cfg.without_ai(&sha256_str(text))?,
+                None => None,
+            };
             let total_hits: usize = lexicon_emotions.iter().map(|e| e.keywords.len()).sum();
             let dominant = lexicon_emotions.first().map(|e| e.emotion.clone());
 
@@ -289,14 +618,29 @@ impl EmotionAnalyzer for HybridAnalyzer {
                     fallback_used: false,
                     deterministic: true,
                     lexicon_version: Some(LexiconAnalyzer::lexicon_version().to_string()),
+                    cache_hits: 0,
+                    cache_misses: 0,
+                    replay,
+                    repair_attempts: 0,
+                    chunks: 0,
+                    chunks_skipped: 0,
                 },
             });
         }
 
-        let ai_analyzer = AIAnalyzer::new(Arc::clone(&self.provider));
+        let ai_analyzer = AIAnalyzer {
+            provider: Arc::clone(&self.provider),
+            replay: self.replay.clone(),
+            max_repairs: DEFAULT_MAX_REPAIRS,
+            chunking: self.chunking,
+        };
         match ai_analyzer.call_ai(text, &lexicon_emotions) {
-            Ok((ai_emotions, usage)) => {
-                let merged: Vec<EmotionResult> = ai_emotions.into_iter().map(|mut e| {
+            Ok(call) => {
+                let (cache_hits, cache_misses) = call.cache_counts();
+                let deterministic = call.is_replayed();
+                let ai_calls = call.calls();
+                let AICall { emotions, usage, provider, replay, fallback_used, repairs, chunks, chunks_skipped, .. } = call;
+                let merged: Vec<EmotionResult> = emotions.into_iter().map(|mut e| {
                     e.source = EmotionSource::Hybrid;
                     e
                 }).collect();
@@ -310,16 +654,32 @@ impl EmotionAnalyzer for HybridAnalyzer {
                     total_hits,
                     meta: AnalysisMeta {
                         mode: "hybrid".into(),
-                        provider: Some(self.provider.id()),
-                        ai_calls: 1,
+                        provider: Some(provider),
+                        ai_calls,
                         ai_usage: Some(usage),
-                        fallback_used: false,
-                        deterministic: false,
+                        fallback_used,
+                        deterministic,
                         lexicon_version: Some(LexiconAnalyzer::lexicon_version().to_string()),
+                        cache_hits,
+                        cache_misses,
+                        replay,
+                        repair_attempts: repairs,
+                        chunks,
+                        chunks_skipped,
                     },
                 })
             }
-            Err(_) => {
+            // Record/Replay: pas de repli silencieux (record absent ou divergent)
+            Err(e) if self.replay.as_ref().is_some_and(EmotionReplayConfig::is_active) => Err(e),
+            // Analyse annulée par l'utilisateur ou échue: pas de résultat lexicon présenté comme hybride
+            Err(e @ OmegaError::Cancelled(_)) => Err(e),
+            Err(e) => {
+                eprintln!("[HYBRID] Réponse IA rejetée, repli lexicon: {}", e);
+                // Réponses hors schéma: appels et réparations consommés restent visibles dans meta
+                let attempts = match &e {
+                    OmegaError::InvalidResponse { schema: Some(failure), .. } => failure.attempts,
+                    _ => 0,
+                };
                 let total_hits: usize = lexicon_emotions.iter().map(|e| e.keywords.len()).sum();
                 let dominant = lexicon_emotions.first().map(|e| e.emotion.clone());
 
@@ -330,11 +690,17 @@ impl EmotionAnalyzer for HybridAnalyzer {
                     meta: AnalysisMeta {
                         mode: "hybrid".into(),
                         provider: None,
-                        ai_calls: 0,
+                        ai_calls: attempts,
                         ai_usage: None,
                         fallback_used: true,
                         deterministic: true,
                         lexicon_version: Some(LexiconAnalyzer::lexicon_version().to_string()),
+                        cache_hits: 0,
+                        cache_misses: 0,
+                        replay: None,
+                        repair_attempts: attempts.saturating_sub(1),
+                        chunks: 0,
+                        chunks_skipped: 0,
                     },
                 })
             }
@@ -349,16 +715,146 @@ impl EmotionAnalyzer for HybridAnalyzer {
 // ═══════════════════════════════════════════════════════════════════════════════
 
 pub fn create_analyzer(mode: AnalyzerMode, provider: Option<Arc<dyn LLMProvider>>) -> Box<dyn EmotionAnalyzer> {
-    match mode {
-        AnalyzerMode::Deterministic => Box::new(LexiconAnalyzer::new()),
-        AnalyzerMode::Hybrid => {
-            let p = provider.unwrap_or_else(|| Arc::new(crate::ai::FallbackProvider::from_env()));
-            Box::new(HybridAnalyzer::new(p))
-        }
-        AnalyzerMode::Boost => {
-            let p = provider.unwrap_or_else(|| Arc::new(crate::ai::FallbackProvider::from_env()));
-            Box::new(AIAnalyzer::new(p))
+    create_analyzer_with_replay(mode, provider, None)
+}
+
+/// Factory avec Record/Replay (ignoré en mode Deterministic, déjà reproductible)
+pub fn create_analyzer_with_replay(
+    mode: AnalyzerMode,
+    provider: Option<Arc<dyn LLMProvider>>,
+    replay: Option<EmotionReplayConfig>,
+) -> Box<dyn EmotionAnalyzer> {
+    // Replay: le provider n'est jamais appelé, inutile de lire l'environnement
+    let replaying = replay.as_ref().is_some_and(|r| r.mode == ReplayMode::Replay);
+    let provider = || -> Arc<dyn LLMProvider> {
+        match provider {
+            Some(p) => p,
+            None if replaying => Arc::new(crate::ai::MockDeterministicProvider::default()),
+            None => Arc::new(crate::ai::FallbackProvider::from_config().unwrap_or_else(|e| {
+                eprintln!("[PROVIDER] Profils invalides, configuration par variables: {}", e);
+                crate::ai::FallbackProvider::from_env()
+            })),
         }
+    };
+    match (mode, replay) {
+        (AnalyzerMode::Deterministic, _) => Box::new(LexiconAnalyzer::new()),
+        (AnalyzerMode::Hybrid, Some(r)) => Box::new(HybridAnalyzer::new(provider()).with_chunking(ChunkConfig::from_env()).with_replay(r)),
+        (AnalyzerMode::Hybrid, None) => Box::new(HybridAnalyzer::new(provider()).with_chunking(ChunkConfig::from_env())),
+        (AnalyzerMode::Boost, Some(r)) => Box::new(AIAnalyzer::new(provider()).with_chunking(ChunkConfig::from_env()).with_replay(r)),
+        (AnalyzerMode::Boost, None) => Box::new(AIAnalyzer::new(provider()).with_chunking(ChunkConfig::from_env())),
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::{CompletionResponse, EmbeddingRequest, EmbeddingResponse, ProviderCapabilities, Usage};
+
+    /// Provider qui répond toujours le même contenu
+    struct FixedProvider(&'static str);
+
+    impl LLMProvider for FixedProvider {
+        fn id(&self) -> String { "fixed".into() }
+        fn capabilities(&self) -> ProviderCapabilities { crate::ai::MockDeterministicProvider::default().capabilities() }
+        fn generate(&self, _req: CompletionRequest) -> OmegaResult<CompletionResponse> {
+            Ok(CompletionResponse { provider_id: self.id(), content: self.0.into(), parsed: None, usage: Usage { prompt_tokens: 5, completion_tokens: 5, total_tokens: 10 }, latency_ms: 0, response_hash: String::new(), cache: None, fallback: None })
+        }
+        fn embed(&self, _req: EmbeddingRequest) -> OmegaResult<EmbeddingResponse> { Err(OmegaError::NotSupported("embed".into())) }
+        fn health(&self) -> bool { true }
+    }
+
+    const TEXT: &str = "Il avait peur, une peur immense.";
+
+    #[test]
+    fn test_taxonomy_matches_lexicon() {
+        let mut lexicon: Vec<&str> = crate::lexicon_fr_gold::build_lexicon_gold().keys().copied().collect();
+        let mut taxonomy = EMOTION_TAXONOMY.to_vec();
+        lexicon.sort();
+        taxonomy.sort();
+        assert_eq!(lexicon, taxonomy);
+    }
+
+    #[test]
+    fn test_out_of_taxonomy_and_range_rejected() {
+        let provider = Arc::new(FixedProvider(r#"{"emotions":[{"emotion":"peur","score":1.5}]}"#));
+        match AIAnalyzer::new(provider.clone()).with_max_repairs(1).analyze(TEXT) {
+            Err(OmegaError::InvalidResponse { schema: Some(failure), .. }) => {
+                assert_eq!(failure.attempts, 2);
+                assert_eq!(failure.errors.len(), 2, "{:?}", failure.errors);
+                assert_eq!(failure.schema_name.as_deref(), Some("emotion_analysis"));
+            }
+            other => panic!("expected schema InvalidResponse, got {:?}", other.map(|r| r.emotions)),
+        }
+
+        // Hybrid: repli lexicon explicite (fallback_used + appels consommés)
+        let ambiguous = "peur joie";
+        assert!(HybridAnalyzer::needs_ai_clarification(&LexiconAnalyzer::analyze_with_lexicon(ambiguous)));
+        let result = HybridAnalyzer::new(provider).analyze(ambiguous).unwrap();
+        assert!(result.meta.fallback_used);
+        assert_eq!(result.meta.ai_calls, 1 + DEFAULT_MAX_REPAIRS);
+        assert_eq!(result.meta.repair_attempts, DEFAULT_MAX_REPAIRS);
+    }
+
+    #[test]
+    fn test_valid_response_no_repair() {
+        let provider = Arc::new(FixedProvider(r#"{"emotions":[{"emotion":"fear","score":0.9}]}"#));
+        let result = AIAnalyzer::new(provider).analyze(TEXT).unwrap();
+        assert_eq!(result.dominant.as_deref(), Some("fear"));
+        assert_eq!(result.meta.repair_attempts, 0);
+        assert_eq!(result.meta.ai_calls, 1);
+    }
+
+    #[test]
+    fn test_long_text_chunked_without_utf8_panic() {
+        // Octet 2000 au milieu d'un 'é' (ancienne troncature &text[..2000])
+        let paragraph = format!("a{} Il avait peur.", "é".repeat(1000));
+        let text = vec![paragraph; 4].join("\n\n");
+        let provider = Arc::new(FixedProvider(r#"{"emotions":[{"emotion":"fear","score":0.9}]}"#));
+        let chunking = ChunkConfig { max_chunk_tokens: 400, token_budget: 100_000 };
+
+        let result = AIAnalyzer::new(provider.clone()).with_chunking(chunking).analyze(&text).unwrap();
+        assert_eq!(result.meta.chunks, 4);
+        assert_eq!(result.meta.ai_calls, 4);
+        assert_eq!(result.meta.chunks_skipped, 0);
+        assert_eq!(result.meta.ai_usage.as_ref().unwrap().total_tokens, 40);
+        assert!((result.emotions[0].score - 0.9).abs() < 1e-9);
+
+        // Budget: premier extrait toujours envoyé, la suite est signalée
+        let tight = ChunkConfig { max_chunk_tokens: 400, token_budget: 500 };
+        let result = AIAnalyzer::new(provider).with_chunking(tight).analyze(&text).unwrap();
+        assert_eq!((result.meta.chunks, result.meta.chunks_skipped), (1, 3));
+    }
+
+    #[test]
+    fn test_merge_weighted_by_chunk_size() {
+        let part = |emotions: &[(&str, f64)]| AICall {
+            emotions: emotions.iter().map(|(e, s)| EmotionResult {
+                emotion: e.to_string(), score: *s, confidence: 0.85, source: EmotionSource::AI,
+                keywords: vec![], lexicon_score: None, ai_adjustment: None,
+            }).collect(),
+            usage: AIUsage::default(), cache: Vec::new(), provider: "p".into(), replay: None,
+            fallback_used: false, repairs: 0, chunks: 1, chunks_skipped: 0,
+        };
+        let parts = [part(&[("joy", 0.4), ("fear", 0.8)]), part(&[("joy", 0.4)])];
+        let baseline = LexiconAnalyzer::analyze_with_lexicon("Il avait peur.");
+        let merged = AIAnalyzer::merge_chunks(&[3, 1], &parts, &baseline);
+        assert_eq!(merged.iter().map(|e| e.emotion.as_str()).collect::<Vec<_>>(), vec!["fear", "joy"]);
+        assert!((merged[0].score - 0.6).abs() < 1e-9);
+        assert!((merged[1].score - 0.4).abs() < 1e-9);
+        assert_eq!(merged[0].lexicon_score, baseline.iter().find(|b| b.emotion == "fear").map(|b| b.score));
+    }
+
+    #[test]
+    fn test_hybrid_scripted_faults_fall_back_to_lexicon() {
+        use crate::ai::{Fault, MockScript, ScriptStep, ScriptedProvider};
+        let fault = |f| ScriptStep { times: 1, content: None, fault: Some(f), retry_after_s: None };
+        let script = ScriptedProvider::new(MockScript::sequence(vec![fault(Fault::Http500), fault(Fault::MalformedJson)])).unwrap();
+        let analyzer = HybridAnalyzer::new(Arc::new(script));
+        let ambiguous = "peur joie";
+        for _ in 0..2 {
+            let result = analyzer.analyze(ambiguous).unwrap();
+            assert!(result.meta.fallback_used);
+            assert!(!result.emotions.is_empty(), "lexicon result kept");
+        }
+    }
+}
@@ -0,0 +1,479 @@
+//! OMEGA Emotion Replay — Record/Replay de l'analyse émotionnelle IA
+//! ═══════════════════════════════════════════════════════════════════════════════
+//!
+//! Même mécanique que VOICE_HYBRID (`ReplayMode`), appliquée à
+//! `HybridAnalyzer` / `AIAnalyzer`:
+//! - Record: le provider est appelé, prompt + completion brute + émotions
+//!   parsées + hashes sont écrits dans `<run_dir>/emotion.replay.json`
+//! - Replay: le record est relu, aucun provider n'est appelé; toute
+//!   divergence d'input, de lexicon ou de prompt est une erreur
+//!
+//! Un record est aussi écrit quand le mode Hybrid n'a pas eu besoin de l'IA
+//! (`ai_called: false`) afin que le replay vérifie toujours l'input.
+//!
+//! Texte long: les limites du découpage (`chunking`) sont enregistrées pour
+//! que le replay re-planifie les mêmes extraits; un appel par extrait (`chunks`).
+//!
+//! @invariant EMO-REP-01: Replay = zéro appel provider
+//! @invariant EMO-REP-02: input_hash / template / prompt_hash divergents → HashMismatch
+//! @invariant EMO-REP-03: record_hash anti-tamper (JSON canonique)
+//! @invariant EMO-REP-04: Aucun secret dans un record
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::ai::{ChunkLimits, CompletionRequest, PromptTemplateRef};
+use crate::error::{OmegaError, OmegaResult};
+use crate::interfaces::voice_hybrid::ReplayMode;
+use crate::pipeline::fs_utils::{canonicalize_json, ensure_dir, read_json, sha256_str, write_json};
+use super::emotion_analyzer::{AIUsage, EmotionResult, LexiconAnalyzer};
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// CONSTANTES
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Nom du fichier record dans le dossier d'un run
+pub const EMOTION_REPLAY_FILE: &str = "emotion.replay.json";
+
+/// Version du schema de record
+pub const EMOTION_REPLAY_SCHEMA_VERSION: u32 = 1;
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// RECORD
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Record d'une analyse émotionnelle assistée par IA
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmotionReplayRecord {
+    pub schema_version: u32,
+    /// Run ayant produit le record
+    pub run_id: String,
+    /// Provider ayant répondu (None si aucun appel IA)
+    pub provider: Option<String>,
+    /// Version du lexicon (baseline du prompt)
+    pub lexicon_version: String,
+    /// SHA-256 du texte analysé
+    pub input_hash: String,
+    /// Le mode Hybrid a-t-il sollicité l'IA
+    pub ai_called: bool,
+    /// SHA-256 du prompt canonique (None si aucun appel IA)
+    pub prompt_hash: Option<String>,
+    pub system_prompt: Option<String>,
+    pub user_prompt: Option<String>,
+    /// Template des prompts (absent des anciens records: seul prompt_hash est vérifié)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<PromptTemplateRef>,
+    /// Réponse brute du provider
+    pub completion: Option<String>,
+    /// response_hash retourné par le provider
+    pub response_hash: Option<String>,
+    /// Émotions parsées depuis la completion
+    pub emotions: Vec<EmotionResult>,
+    pub usage: Option<AIUsage>,
+    /// Réponse d'un provider de secours (absent des anciens records: hash inchangé)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub fallback_used: bool,
+    /// Re-prompts de réparation avant une réponse conforme au schéma
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub repair_attempts: u32,
+    /// Limites du découpage (absent des anciens records: texte envoyé d'un bloc)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chunking: Option<ChunkLimits>,
+    /// Appels par extrait (texte découpé en plusieurs extraits)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub chunks: Vec<ChunkRecord>,
+    /// SHA-256 du record canonique sans ce champ
+    pub record_hash: String,
+}
+
+impl EmotionReplayRecord {
+    /// Record sans appel IA
+    pub fn new(run_id: &str, input_hash: &str) -> Self {
+        Self {
+            schema_version: EMOTION_REPLAY_SCHEMA_VERSION,
+            run_id: run_id.to_string(),
+            provider: None,
+            lexicon_version: LexiconAnalyzer::lexicon_version().to_string(),
+            input_hash: input_hash.to_string(),
+            ai_called: false,
+            prompt_hash: None,
+            system_prompt: None,
+            user_prompt: None,
+            template: None,
+            completion: None,
+            response_hash: None,
+            emotions: Vec::new(),
+            usage: None,
+            fallback_used: false,
+            repair_attempts: 0,
+            chunking: None,
+            chunks: Vec::new(),
+            record_hash: String::new(),
+        }
+    }
+
+    /// Hash anti-tamper (record_hash exclu)
+    pub fn compute_hash(&self) -> String {
+        let mut value = serde_json::to_value(self).unwrap_or_default();
+        if let Some(obj) = value.as_object_mut() {
+            obj.remove("record_hash");
+        }
+        sha256_str(&canonicalize_json(&value))
+    }
+}
+
+/// Appel IA d'un extrait
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChunkRecord {
+    pub index: u32,
+    /// SHA-256 du texte de l'extrait
+    pub chunk_hash: String,
+    pub prompt_hash: String,
+    pub completion: String,
+    pub response_hash: String,
+}
+
+/// Informations de replay exposées dans AnalysisMeta
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReplayInfo {
+    pub mode: ReplayMode,
+    /// Run dont le record est écrit (Record) ou relu (Replay)
+    pub run_id: String,
+    pub record_hash: String,
+}
+
+/// Hash du prompt: prompts + paramètres de génération (run_id exclu)
+pub fn prompt_hash(req: &CompletionRequest) -> String {
+    let value = serde_json::json!({
+        "system_prompt": req.system_prompt,
+        "user_prompt": req.user_prompt,
+        "seed": req.seed,
+        "temperature": req.temperature.to_string(),
+        "max_tokens": req.max_tokens,
+        "schema_name": req.schema_name,
+        "json_schema": req.json_schema,
+    });
+    sha256_str(&canonicalize_json(&value))
+}
+
+/// Hash des prompts d'une analyse: prompt_hash() d'un appel unique, sinon hash des prompt_hash dans l'ordre
+pub fn prompts_hash(reqs: &[CompletionRequest]) -> String {
+    match reqs {
+        [req] => prompt_hash(req),
+        _ => sha256_str(&reqs.iter().map(prompt_hash).collect::<Vec<_>>().join("|")),
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// CONFIG
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Configuration Record/Replay d'un analyzer
+#[derive(Debug, Clone, PartialEq)]
+pub struct EmotionReplayConfig {
+    pub mode: ReplayMode,
+    /// Run enregistré (Record) ou rejoué (Replay)
+    pub run_id: String,
+    /// Chemin du fichier record
+    pub path: PathBuf,
+}
+
+impl EmotionReplayConfig {
+    pub fn new(mode: ReplayMode, run_id: &str, path: impl Into<PathBuf>) -> Self {
+        Self { mode, run_id: run_id.to_string(), path: path.into() }
+    }
+
+    /// Record dans `<output_dir>/<run_id>/emotion.replay.json`
+    pub fn in_output_dir(mode: ReplayMode, run_id: &str, output_dir: &Path) -> OmegaResult<Self> {
+        validate_run_id(run_id)?;
+        Ok(Self::new(mode, run_id, output_dir.join(run_id).join(EMOTION_REPLAY_FILE)))
+    }
+
+    /// Même record, autre mode (ex: rejouer un run enregistré)
+    pub fn with_mode(mut self, mode: ReplayMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Record ou replay actif
+    pub fn is_active(&self) -> bool {
+        self.mode != ReplayMode::Off
+    }
+
+    /// Écrit le record (record_hash calculé ici)
+    pub fn save(&self, mut record: EmotionReplayRecord) -> OmegaResult<ReplayInfo> {
+        record.run_id = self.run_id.clone();
+        record.record_hash = record.compute_hash();
+        if let Some(parent) = self.path.parent() {
+            ensure_dir(parent)?;
+        }
+        write_json(&self.path, &record)?;
+        Ok(self.info(&record))
+    }
+
+    /// Relit le record et vérifie input, lexicon, template et prompt
+    pub fn load(
+        &self,
+        input_hash: &str,
+        prompt_hash: Option<&str>,
+        template: Option<&PromptTemplateRef>,
+    ) -> OmegaResult<(EmotionReplayRecord, ReplayInfo)> {
+        if !self.path.exists() {
+            return Err(OmegaError::ReadError(format!("REPLAY_RECORD_NOT_FOUND: {}", self.path.display())));
+        }
+        let record: EmotionReplayRecord = read_json(&self.path)?;
+        if record.schema_version != EMOTION_REPLAY_SCHEMA_VERSION {
+            return Err(OmegaError::invalid_response(format!("REPLAY_SCHEMA_VERSION: {}", record.schema_version)));
+        }
+        let computed = record.compute_hash();
+        if computed != record.record_hash {
+            return Err(mismatch("REPLAY_RECORD_HASH", &record.record_hash, &computed));
+        }
+        if record.input_hash != input_hash {
+            return Err(mismatch("REPLAY_INPUT_HASH", &record.input_hash, input_hash));
+        }
+        let lexicon_version = LexiconAnalyzer::lexicon_version();
+        if record.lexicon_version != lexicon_version {
+            return Err(mismatch("REPLAY_LEXICON_VERSION", &record.lexicon_version, lexicon_version));
+        }
+        // Template modifié: erreur explicite (id@version) plutôt qu'un simple prompt divergent
+        if let (Some(recorded), Some(_)) = (&record.template, prompt_hash) {
+            if template.map(|t| &t.hash) != Some(&recorded.hash) {
+                return Err(mismatch(
+                    "REPLAY_TEMPLATE_HASH",
+                    &recorded.to_string(),
+                    &template.map(|t| t.to_string()).unwrap_or_else(|| "none".into()),
+                ));
+            }
+        }
+        if record.prompt_hash.as_deref() != prompt_hash {
+            return Err(mismatch(
+                "REPLAY_PROMPT_HASH",
+                record.prompt_hash.as_deref().unwrap_or("none"),
+                prompt_hash.unwrap_or("none"),
+            ));
+        }
+        let info = self.info(&record);
+        Ok((record, info))
+    }
+
+    /// Limites de découpage du record (None: record sans découpage); vérifiées ensuite par load()
+    pub fn recorded_chunking(&self) -> OmegaResult<Option<ChunkLimits>> {
+        if !self.path.exists() {
+            return Err(OmegaError::ReadError(format!("REPLAY_RECORD_NOT_FOUND: {}", self.path.display())));
+        }
+        let record: EmotionReplayRecord = read_json(&self.path)?;
+        Ok(record.chunking)
+    }
+
+    /// Replay sans appel IA: Record écrit un record vide, Replay le vérifie
+    pub fn without_ai(&self, input_hash: &str) -> OmegaResult<Option<ReplayInfo>> {
+        match self.mode {
+            ReplayMode::Off => Ok(None),
+            ReplayMode::Record => self.save(EmotionReplayRecord::new(&self.run_id, input_hash)).map(Some),
+            ReplayMode::Replay => self.load(input_hash, None, None).map(|(_, info)| Some(info)),
+        }
+    }
+
+    fn info(&self, record: &EmotionReplayRecord) -> ReplayInfo {
+        ReplayInfo { mode: self.mode, run_id: record.run_id.clone(), record_hash: record.record_hash.clone() }
+    }
+}
+
+fn is_zero(n: &u32) -> bool {
+    *n == 0
+}
+
+fn mismatch(what: &str, expected: &str, actual: &str) -> OmegaError {
+    OmegaError::HashMismatch(format!("{}: expected {} got {}", what, expected, actual))
+}
+
+/// run_id utilisable comme nom de dossier (pas de séparateur ni de `..`)
+pub(crate) fn validate_run_id(run_id: &str) -> OmegaResult<()> {
+    let valid = !run_id.is_empty()
+        && run_id.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+        && !run_id.contains("..");
+    if valid {
+        Ok(())
+    } else {
+        Err(OmegaError::ConfigError(format!("run_id invalide pour le replay: {}", run_id)))
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// TESTS
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::{CompletionResponse, EmbeddingRequest, EmbeddingResponse, LLMProvider, ProviderCapabilities, Usage};
+    use crate::modules::analyzer_mode::AnalyzerMode;
+    use crate::modules::emotion_analyzer::{create_analyzer_with_replay, AIAnalyzer, EmotionAnalyzer};
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Arc;
+
+    const TEXT: &str = "Il avait peur, une peur immense, mais la joie revint.";
+
+    /// Provider qui répond un JSON d'émotions et compte ses appels
+    struct JsonProvider {
+        calls: AtomicU32,
+    }
+
+    impl JsonProvider {
+        fn new() -> Arc<Self> {
+            Arc::new(Self { calls: AtomicU32::new(0) })
+        }
+    }
+
+    impl LLMProvider for JsonProvider {
+        fn id(&self) -> String { "json-test".into() }
+        fn capabilities(&self) -> ProviderCapabilities {
+            crate::ai::MockDeterministicProvider::default().capabilities()
+        }
+        fn generate(&self, _req: CompletionRequest) -> OmegaResult<CompletionResponse> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(CompletionResponse {
+                provider_id: self.id(),
+                content: r#"{"emotions":[{"emotion":"fear","score":0.8},{"emotion":"joy","score":0.4}]}"#.into(),
+                parsed: None,
+                usage: Usage { prompt_tokens: 12, completion_tokens: 8, total_tokens: 20 },
+                latency_ms: 3,
+                response_hash: "h".repeat(64),
+                cache: None,
+                fallback: None,
+            })
+        }
+        fn embed(&self, _req: EmbeddingRequest) -> OmegaResult<EmbeddingResponse> {
+            Err(OmegaError::NotSupported("embed".into()))
+        }
+        fn health(&self) -> bool { true }
+    }
+
+    fn config(mode: ReplayMode, name: &str) -> EmotionReplayConfig {
+        EmotionReplayConfig::in_output_dir(mode, name, Path::new("target/test-emotion-replay")).unwrap()
+    }
+
+    #[test]
+    fn record_then_replay_without_provider() {
+        let provider = JsonProvider::new();
+        let rec = config(ReplayMode::Record, "RUN_BOOST_01");
+        let recorded = create_analyzer_with_replay(AnalyzerMode::Boost, Some(provider.clone()), Some(rec.clone()))
+            .analyze(TEXT)
+            .unwrap();
+        assert_eq!(provider.calls.load(Ordering::SeqCst), 1);
+        assert!(!recorded.meta.deterministic);
+
+        let stored: EmotionReplayRecord = read_json(&rec.path).unwrap();
+        assert!(stored.ai_called);
+        assert_eq!(stored.input_hash, sha256_str(TEXT));
+        assert_eq!(stored.emotions.len(), 2);
+        assert!(stored.completion.unwrap().contains("fear"));
+
+        let silent = JsonProvider::new();
+        let replayed = create_analyzer_with_replay(AnalyzerMode::Boost, Some(silent.clone()), Some(rec.clone().with_mode(ReplayMode::Replay)))
+            .analyze(TEXT)
+            .unwrap();
+        assert_eq!(silent.calls.load(Ordering::SeqCst), 0);
+        assert!(replayed.meta.deterministic);
+        assert_eq!(replayed.meta.provider.as_deref(), Some("json-test"));
+        assert_eq!(replayed.meta.replay.as_ref().unwrap().record_hash, stored.record_hash);
+        assert_eq!(
+            serde_json::to_string(&replayed.emotions).unwrap(),
+            serde_json::to_string(&recorded.emotions).unwrap()
+        );
+    }
+
+    #[test]
+    fn chunked_record_replays_with_recorded_limits() {
+        let text = [TEXT; 6].join("\n\n");
+        let rec = config(ReplayMode::Record, "RUN_BOOST_04");
+        let small = crate::ai::ChunkConfig { max_chunk_tokens: 64, token_budget: 100_000 };
+        let provider = JsonProvider::new();
+        let recorded = AIAnalyzer::new(provider.clone()).with_chunking(small).with_replay(rec.clone()).analyze(&text).unwrap();
+        assert!(recorded.meta.chunks > 1);
+        assert_eq!(provider.calls.load(Ordering::SeqCst), recorded.meta.chunks);
+
+        let stored: EmotionReplayRecord = read_json(&rec.path).unwrap();
+        assert_eq!(stored.chunks.len() as u32, recorded.meta.chunks);
+        assert!(stored.completion.is_none() && stored.user_prompt.is_none());
+
+        // Chunking par défaut côté Replay: les limites du record font foi
+        let silent = JsonProvider::new();
+        let replayed = AIAnalyzer::new(silent.clone()).with_replay(rec.with_mode(ReplayMode::Replay)).analyze(&text).unwrap();
+        assert_eq!(silent.calls.load(Ordering::SeqCst), 0);
+        assert_eq!(replayed.meta.chunks, recorded.meta.chunks);
+        assert_eq!(
+            serde_json::to_string(&replayed.emotions).unwrap(),
+            serde_json::to_string(&recorded.emotions).unwrap()
+        );
+    }
+
+    #[test]
+    fn replay_template_change_fails() {
+        let rec = config(ReplayMode::Record, "RUN_BOOST_05");
+        AIAnalyzer::new(JsonProvider::new()).with_replay(rec.clone()).analyze(TEXT).unwrap();
+        let mut stored: EmotionReplayRecord = read_json(&rec.path).unwrap();
+        let template = stored.template.clone().unwrap();
+        assert_eq!(template.id, crate::ai::prompts::EMOTION_ANALYSIS_TEMPLATE);
+
+        // Record produit avec une autre révision du template (hash du record recalculé par save)
+        stored.template = Some(PromptTemplateRef { hash: "0".repeat(64), ..template });
+        rec.save(stored).unwrap();
+        let replay = AIAnalyzer::new(JsonProvider::new()).with_replay(rec.with_mode(ReplayMode::Replay));
+        let err = replay.analyze(TEXT).unwrap_err();
+        assert!(matches!(err, OmegaError::HashMismatch(ref m) if m.starts_with("REPLAY_TEMPLATE_HASH: expected emotion.analysis@1.0.0")), "{}", err);
+    }
+
+    #[test]
+    fn replay_input_mismatch_fails() {
+        let rec = config(ReplayMode::Record, "RUN_BOOST_02");
+        create_analyzer_with_replay(AnalyzerMode::Boost, Some(JsonProvider::new()), Some(rec.clone()))
+            .analyze(TEXT)
+            .unwrap();
+
+        let replay = create_analyzer_with_replay(AnalyzerMode::Hybrid, Some(JsonProvider::new()), Some(rec.with_mode(ReplayMode::Replay)));
+        let err = replay.analyze("Un tout autre texte, plein de tristesse.").unwrap_err();
+        assert!(matches!(err, OmegaError::HashMismatch(ref m) if m.starts_with("REPLAY_INPUT_HASH")));
+    }
+
+    #[test]
+    fn replay_prompt_mismatch_and_tamper_fail() {
+        let rec = config(ReplayMode::Record, "RUN_BOOST_03");
+        let analyzer = AIAnalyzer::new(JsonProvider::new()).with_replay(rec.clone());
+        analyzer.analyze(TEXT).unwrap();
+
+        // Record sans appel IA mais prompt attendu → divergence de prompt
+        let replay = rec.with_mode(ReplayMode::Replay);
+        let err = replay.load(&sha256_str(TEXT), None, None).unwrap_err();
+        assert!(matches!(err, OmegaError::HashMismatch(ref m) if m.starts_with("REPLAY_PROMPT_HASH")));
+
+        // Altération de la completion → record_hash invalide
+        let mut stored: EmotionReplayRecord = read_json(&replay.path).unwrap();
+        stored.emotions[0].score = 0.1;
+        write_json(&replay.path, &stored).unwrap();
+        let err = AIAnalyzer::new(JsonProvider::new()).with_replay(replay).analyze(TEXT).unwrap_err();
+        assert!(matches!(err, OmegaError::HashMismatch(ref m) if m.starts_with("REPLAY_RECORD_HASH")));
+    }
+
+    #[test]
+    fn replay_missing_record_and_bad_run_id() {
+        let replay = config(ReplayMode::Replay, "RUN_DOES_NOT_EXIST");
+        let err = create_analyzer_with_replay(AnalyzerMode::Boost, None, Some(replay)).analyze(TEXT).unwrap_err();
+        assert!(matches!(err, OmegaError::ReadError(ref m) if m.starts_with("REPLAY_RECORD_NOT_FOUND")));
+
+        assert!(EmotionReplayConfig::in_output_dir(ReplayMode::Replay, "../etc", Path::new("out")).is_err());
+        assert!(EmotionReplayConfig::in_output_dir(ReplayMode::Replay, "a/b", Path::new("out")).is_err());
+    }
+
+    #[test]
+    fn prompt_hash_ignores_run_id() {
+        let req = AIAnalyzer::build_request(TEXT, &[]);
+        let mut other = req.clone();
+        other.run_id = "another".into();
+        assert_eq!(prompt_hash(&req), prompt_hash(&other));
+        other.user_prompt.push('!');
+        assert_ne!(prompt_hash(&req), prompt_hash(&other));
+    }
+}
@@ -4,12 +4,16 @@ pub mod intake;
 pub mod registry;
 pub mod analyzer_mode;
 pub mod emotion_analyzer;
+pub mod emotion_replay;
+pub mod semantic_index;
 
 // Re-exports pour aerospace_tests
 pub use canon_guard::{CanonGuardPass, get_canon_rules};
 pub use intake::IntakePass;
 pub use analyzer_mode::AnalyzerMode;
-pub use emotion_analyzer::{EmotionAnalyzer, create_analyzer, AnalysisResult, EmotionResult};
+pub use emotion_analyzer::{EmotionAnalyzer, create_analyzer, create_analyzer_with_replay, AnalysisResult, EmotionResult};
+pub use emotion_replay::{EmotionReplayConfig, EmotionReplayRecord, ReplayInfo};
+pub use semantic_index::{SemanticHit, SemanticIndex};
 
 // Re-export CANON (types viennent de interfaces)
 pub use canon::CanonJsonStore;
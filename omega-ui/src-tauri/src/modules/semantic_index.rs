@@ -0,0 +1,358 @@
+//! OMEGA Semantic Index — Recherche sémantique dans un manuscrit
+//! ═══════════════════════════════════════════════════════════════════════════════
+//!
+//! Index vectoriel local des segments et paragraphes d'un run, écrit dans
+//! `<run_dir>/semantic.index.json`:
+//! - build: un seul lot d'embeddings (même provider, même modèle pour tout l'index)
+//! - search: embedding de la requête puis similarité cosinus
+//!   ("scènes où elle doute de son père")
+//! - similar: passages proches d'une entrée de l'index (aucun appel provider)
+//!
+//! Le mock produit des pseudo-embeddings stables: tout se teste hors ligne.
+//!
+//! @invariant SEM-01: requête et index viennent du même provider/modèle/dimension
+//! @invariant SEM-02: index_hash anti-tamper (JSON canonique)
+//! @invariant SEM-03: classement déterministe (score puis id)
+
+use std::cmp::Ordering;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::ai::{EmbeddingRequest, EmbeddingResponse, LLMProvider};
+use crate::error::{OmegaError, OmegaResult};
+use crate::pipeline::fs_utils::{canonicalize_json, ensure_dir, read_json, sha256_str, write_json};
+use super::emotion_replay::validate_run_id;
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// CONSTANTES
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Nom du fichier index dans le dossier d'un run
+pub const SEMANTIC_INDEX_FILE: &str = "semantic.index.json";
+
+/// Version du schema d'index
+pub const SEMANTIC_INDEX_SCHEMA_VERSION: u32 = 1;
+
+/// Paragraphes plus courts ignorés (didascalies, "— Oui.")
+pub const MIN_PARAGRAPH_WORDS: usize = 3;
+
+/// Texte envoyé au provider par passage (les chapitres entiers dépassent la fenêtre des modèles d'embedding)
+pub const MAX_EMBED_CHARS: usize = 8000;
+
+/// Longueur de l'extrait affiché dans les résultats
+const PREVIEW_CHARS: usize = 160;
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// TYPES
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PassageKind {
+    Segment,
+    Paragraph,
+}
+
+/// Passage à indexer (positions en caractères dans le texte du run)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Passage {
+    pub id: String,
+    pub kind: PassageKind,
+    pub title: Option<String>,
+    pub char_start: usize,
+    pub char_end: usize,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexEntry {
+    pub id: String,
+    pub kind: PassageKind,
+    pub title: Option<String>,
+    pub char_start: usize,
+    pub char_end: usize,
+    /// SHA-256 du texte complet du passage
+    pub text_hash: String,
+    pub preview: String,
+    pub vector: Vec<f32>,
+}
+
+/// Index vectoriel d'un run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SemanticIndex {
+    pub schema_version: u32,
+    pub run_id: String,
+    /// Provider et modèle ayant produit les vecteurs
+    pub provider_id: String,
+    pub model: Option<String>,
+    pub dims: usize,
+    /// SHA-256 du texte indexé
+    pub input_hash: String,
+    pub entries: Vec<IndexEntry>,
+    /// SHA-256 de l'index canonique sans ce champ
+    pub index_hash: String,
+}
+
+/// Résultat de recherche
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SemanticHit {
+    pub id: String,
+    pub kind: PassageKind,
+    pub title: Option<String>,
+    pub char_start: usize,
+    pub char_end: usize,
+    pub preview: String,
+    /// Similarité cosinus [-1, 1]
+    pub score: f32,
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// PASSAGES
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Paragraphes séparés par une ligne vide (ids `para_0001`, …)
+pub fn paragraphs(text: &str) -> Vec<Passage> {
+    let mut out = Vec::new();
+    let mut current: Vec<&str> = Vec::new();
+    let mut start = 0;
+    let mut pos = 0;
+    for line in text.split_inclusive('\n') {
+        let len = line.chars().count();
+        if line.trim().is_empty() {
+            push_paragraph(&mut out, &current, start);
+            current.clear();
+            start = pos + len;
+        } else {
+            if current.is_empty() {
+                start = pos;
+            }
+            current.push(line);
+        }
+        pos += len;
+    }
+    push_paragraph(&mut out, &current, start);
+    out
+}
+
+fn push_paragraph(out: &mut Vec<Passage>, lines: &[&str], char_start: usize) {
+    let raw = lines.concat();
+    let text = raw.trim_end().to_string();
+    if text.split_whitespace().count() < MIN_PARAGRAPH_WORDS {
+        return;
+    }
+    out.push(Passage {
+        id: format!("para_{:04}", out.len() + 1),
+        kind: PassageKind::Paragraph,
+        title: None,
+        char_start,
+        char_end: char_start + text.chars().count(),
+        text,
+    });
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// INDEX
+// ═══════════════════════════════════════════════════════════════════════════════
+
+impl SemanticIndex {
+    /// Embeddings de tous les passages en un lot
+    pub fn build(provider: &dyn LLMProvider, run_id: &str, input_hash: &str, passages: Vec<Passage>) -> OmegaResult<Self> {
+        if passages.is_empty() {
+            return Err(OmegaError::ConfigError("SEMANTIC_NO_PASSAGES".into()));
+        }
+        let requests = passages.iter().map(|p| embedding_request(run_id, &p.text)).collect();
+        let responses = provider.embed_batch(requests)?;
+        if responses.len() != passages.len() {
+            return Err(OmegaError::invalid_response(format!("SEMANTIC_EMBED_COUNT: expected {} got {}", passages.len(), responses.len())));
+        }
+        let first = &responses[0];
+        let (provider_id, model, dims) = (first.provider_id.clone(), first.model.clone(), first.vectors.len());
+        if dims == 0 {
+            return Err(OmegaError::invalid_response("SEMANTIC_EMPTY_VECTOR"));
+        }
+        if let Some(odd) = responses.iter().find(|r| r.provider_id != provider_id || r.model != model || r.vectors.len() != dims) {
+            return Err(OmegaError::invalid_response(format!("SEMANTIC_MIXED_VECTORS: {} / {}", provider_id, odd.provider_id)));
+        }
+
+        let entries = passages.into_iter().zip(responses).map(|(p, r)| IndexEntry {
+            text_hash: sha256_str(&p.text),
+            preview: p.text.chars().take(PREVIEW_CHARS).collect(),
+            id: p.id,
+            kind: p.kind,
+            title: p.title,
+            char_start: p.char_start,
+            char_end: p.char_end,
+            vector: r.vectors,
+        }).collect();
+
+        let mut index = Self {
+            schema_version: SEMANTIC_INDEX_SCHEMA_VERSION,
+            run_id: run_id.to_string(),
+            provider_id,
+            model,
+            dims,
+            input_hash: input_hash.to_string(),
+            entries,
+            index_hash: String::new(),
+        };
+        index.index_hash = index.compute_hash();
+        Ok(index)
+    }
+
+    /// Hash anti-tamper (index_hash exclu)
+    pub fn compute_hash(&self) -> String {
+        let mut value = serde_json::to_value(self).unwrap_or_default();
+        if let Some(obj) = value.as_object_mut() {
+            obj.remove("index_hash");
+        }
+        sha256_str(&canonicalize_json(&value))
+    }
+
+    /// `<output_dir>/<run_id>/semantic.index.json`
+    pub fn path_in(output_dir: &Path, run_id: &str) -> OmegaResult<PathBuf> {
+        validate_run_id(run_id)?;
+        Ok(output_dir.join(run_id).join(SEMANTIC_INDEX_FILE))
+    }
+
+    pub fn save(&self, path: &Path) -> OmegaResult<()> {
+        if let Some(parent) = path.parent() {
+            ensure_dir(parent)?;
+        }
+        write_json(path, self)
+    }
+
+    /// Relit l'index et vérifie son hash
+    pub fn load(path: &Path) -> OmegaResult<Self> {
+        if !path.exists() {
+            return Err(OmegaError::ReadError(format!("SEMANTIC_INDEX_NOT_FOUND: {}", path.display())));
+        }
+        let index: Self = read_json(path)?;
+        if index.schema_version != SEMANTIC_INDEX_SCHEMA_VERSION {
+            return Err(OmegaError::invalid_response(format!("SEMANTIC_SCHEMA_VERSION: {}", index.schema_version)));
+        }
+        let computed = index.compute_hash();
+        if computed != index.index_hash {
+            return Err(OmegaError::HashMismatch(format!("SEMANTIC_INDEX_HASH: expected {} got {}", index.index_hash, computed)));
+        }
+        Ok(index)
+    }
+
+    /// Passages les plus proches d'une requête libre
+    pub fn search(&self, provider: &dyn LLMProvider, query: &str, limit: usize) -> OmegaResult<Vec<SemanticHit>> {
+        if query.trim().is_empty() {
+            return Err(OmegaError::ConfigError("SEMANTIC_EMPTY_QUERY".into()));
+        }
+        let response = provider.embed(embedding_request(&self.run_id, query))?;
+        self.check_compatible(&response)?;
+        Ok(self.rank(&response.vectors, limit, |_| true))
+    }
+
+    /// Passages du même type les plus proches de l'entrée `id` ("scènes similaires")
+    pub fn similar(&self, id: &str, limit: usize) -> OmegaResult<Vec<SemanticHit>> {
+        let entry = self.entries.iter().find(|e| e.id == id)
+            .ok_or_else(|| OmegaError::ConfigError(format!("SEMANTIC_ENTRY_NOT_FOUND: {}", id)))?;
+        Ok(self.rank(&entry.vector, limit, |e| e.kind == entry.kind && e.id != entry.id))
+    }
+
+    /// Vecteurs comparables uniquement si même provider, modèle et dimension
+    fn check_compatible(&self, response: &EmbeddingResponse) -> OmegaResult<()> {
+        if response.provider_id != self.provider_id || response.model != self.model || response.vectors.len() != self.dims {
+            return Err(OmegaError::ConfigError(format!(
+                "SEMANTIC_PROVIDER_MISMATCH: index {} ({}, {}d), query {} ({}, {}d)",
+                self.provider_id, self.model.as_deref().unwrap_or("-"), self.dims,
+                response.provider_id, response.model.as_deref().unwrap_or("-"), response.vectors.len(),
+            )));
+        }
+        Ok(())
+    }
+
+    fn rank(&self, query: &[f32], limit: usize, keep: impl Fn(&IndexEntry) -> bool) -> Vec<SemanticHit> {
+        let mut hits: Vec<SemanticHit> = self.entries.iter().filter(|e| keep(e)).map(|e| SemanticHit {
+            id: e.id.clone(),
+            kind: e.kind,
+            title: e.title.clone(),
+            char_start: e.char_start,
+            char_end: e.char_end,
+            preview: e.preview.clone(),
+            score: cosine(query, &e.vector),
+        }).collect();
+        hits.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(Ordering::Equal).then_with(|| a.id.cmp(&b.id)));
+        hits.truncate(limit);
+        hits
+    }
+}
+
+fn embedding_request(run_id: &str, text: &str) -> EmbeddingRequest {
+    EmbeddingRequest { run_id: run_id.to_string(), seed: 0, input: text.chars().take(MAX_EMBED_CHARS).collect() }
+}
+
+/// Similarité cosinus (0 si un vecteur est nul)
+pub fn cosine(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm = a.iter().map(|x| x * x).sum::<f32>().sqrt() * b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm > 0.0 { dot / norm } else { 0.0 }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// TESTS
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::MockDeterministicProvider;
+
+    const TEXT: &str = "Claire relit la lettre de son père.\nElle doute de son père, de chaque mot.\n\nLe port s'éveille sous la pluie, les bateaux rentrent.\n\n— Oui.\n\nSon père lui a menti, elle en doute encore.\n";
+
+    fn index() -> SemanticIndex {
+        SemanticIndex::build(&MockDeterministicProvider::new(), "run_1", &sha256_str(TEXT), paragraphs(TEXT)).unwrap()
+    }
+
+    #[test]
+    fn test_paragraphs_offsets() {
+        let paras = paragraphs(TEXT);
+        assert_eq!(paras.len(), 3, "paragraphe trop court ignoré");
+        assert_eq!(paras[0].id, "para_0001");
+        let chars: Vec<char> = TEXT.chars().collect();
+        for p in &paras {
+            assert_eq!(chars[p.char_start..p.char_end].iter().collect::<String>(), p.text);
+        }
+    }
+
+    #[test]
+    fn test_search_and_similar_with_mock() {
+        let index = index();
+        assert_eq!(index.provider_id, "mock-deterministic-v1");
+        let hits = index.search(&MockDeterministicProvider::new(), "elle doute de son pere", 2).unwrap();
+        assert_eq!(hits.len(), 2);
+        assert!(hits.iter().all(|h| h.id != "para_0002"), "la scène du port n'est pas pertinente");
+
+        let similar = index.similar("para_0001", 5).unwrap();
+        assert_eq!(similar[0].id, "para_0003");
+        assert!(similar.iter().all(|h| h.id != "para_0001"));
+        assert!(index.similar("para_9999", 5).is_err());
+    }
+
+    #[test]
+    fn test_query_from_other_provider_rejected() {
+        let other = MockDeterministicProvider { provider_id: "mock-other".into(), ..Default::default() };
+        let err = index().search(&other, "doute", 3).unwrap_err();
+        assert!(err.to_string().contains("SEMANTIC_PROVIDER_MISMATCH"), "{}", err);
+    }
+
+    #[test]
+    fn test_save_load_tamper() {
+        let dir = std::env::temp_dir().join(format!("omega-semantic-{}", uuid::Uuid::new_v4()));
+        let path = SemanticIndex::path_in(&dir, "run_1").unwrap();
+        let index = index();
+        index.save(&path).unwrap();
+        assert_eq!(SemanticIndex::load(&path).unwrap().index_hash, index.index_hash);
+
+        let tampered = std::fs::read_to_string(&path).unwrap().replacen("para_0002", "para_0009", 1);
+        std::fs::write(&path, tampered).unwrap();
+        assert!(matches!(SemanticIndex::load(&path), Err(OmegaError::HashMismatch(_))));
+        assert!(SemanticIndex::path_in(&dir, "../x").is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,283 @@
+//! VOICE_HYBRID AI Bridge
+//! ═══════════════════════════════════════════════════════════════════════════════
+//!
+//! Adaptateur: tout `ai::LLMProvider` (OpenAI, Anthropic, Fallback, Mock)
+//! peut piloter VOICE_HYBRID via le trait `LlmProvider::complete(&str)`.
+//!
+//! Les paramètres de génération (run_id, seed, temperature, max_tokens) sont
+//! portés par l'adaptateur et transmis dans chaque `CompletionRequest`.
+//! Usage, latence et response_hash du dernier appel sont exposés via
+//! `call_meta()` et recopiés dans `VoiceHybridReplayRecord.meta`, avec le
+//! template de prompt (`voice_hybrid.bridge`) sauf prompt système personnalisé.
+//!
+//! @invariant HYBRID-BRIDGE-01: Aucun secret dans les métadonnées
+//! @invariant HYBRID-BRIDGE-02: Paramètres de génération tracés dans le record
+//!
+//! @certification VOICE_HYBRID v2.0.0 INDUSTRIAL
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+use crate::ai::prompts::{self, VOICE_HYBRID_BRIDGE_TEMPLATE};
+use crate::ai::{CompletionRequest, CompletionResponse, LLMProvider};
+use crate::interfaces::voice_hybrid::contract::{LlmProvider, VoiceHybridConfig};
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// CONSTANTES
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Seed par défaut (aligné sur le mode déterministe VOICE_HYBRID)
+pub const DEFAULT_BRIDGE_SEED: u64 = 42;
+
+/// Budget de tokens par défaut pour une completion VOICE_HYBRID
+pub const DEFAULT_BRIDGE_MAX_TOKENS: u32 = 1024;
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// AI LLM BRIDGE
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Adaptateur `ai::LLMProvider` → `voice_hybrid::LlmProvider`
+pub struct AiLlmBridge {
+    /// Provider IA sous-jacent
+    provider: Arc<dyn LLMProvider>,
+    /// Identifiant du run (propagé dans CompletionRequest)
+    run_id: String,
+    /// Seed de génération
+    seed: u64,
+    /// Température (0.0 en mode déterministe)
+    temperature: f32,
+    /// Budget de tokens de sortie
+    max_tokens: u32,
+    /// Prompt système personnalisé (None: template `voice_hybrid.bridge`)
+    system_prompt: Option<String>,
+    /// Dernière réponse reçue (pour les métadonnées du record)
+    last: Mutex<Option<CompletionResponse>>,
+}
+
+impl AiLlmBridge {
+    /// Crée un adaptateur avec les paramètres par défaut
+    pub fn new(provider: Arc<dyn LLMProvider>) -> Self {
+        Self {
+            provider,
+            run_id: "RUN_DEFAULT".to_string(),
+            seed: DEFAULT_BRIDGE_SEED,
+            temperature: 0.0,
+            max_tokens: DEFAULT_BRIDGE_MAX_TOKENS,
+            system_prompt: None,
+            last: Mutex::new(None),
+        }
+    }
+
+    /// Crée un adaptateur aligné sur une config VOICE_HYBRID (run_id)
+    pub fn for_config(provider: Arc<dyn LLMProvider>, cfg: &VoiceHybridConfig) -> Self {
+        Self::new(provider).with_run_id(&cfg.run_id)
+    }
+
+    /// Définit le run_id
+    pub fn with_run_id(mut self, run_id: &str) -> Self {
+        self.run_id = run_id.to_string();
+        self
+    }
+
+    /// Définit le seed
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Définit la température
+    pub fn with_temperature(mut self, temperature: f32) -> Self {
+        self.temperature = temperature;
+        self
+    }
+
+    /// Définit le budget de tokens
+    pub fn with_max_tokens(mut self, max_tokens: u32) -> Self {
+        self.max_tokens = max_tokens;
+        self
+    }
+
+    /// Remplace le prompt système du template (requêtes sans référence de template)
+    pub fn with_system_prompt(mut self, system_prompt: &str) -> Self {
+        self.system_prompt = Some(system_prompt.to_string());
+        self
+    }
+
+    /// Identifiant du provider IA sous-jacent
+    pub fn provider_id(&self) -> String {
+        self.provider.id()
+    }
+
+    /// Dernière réponse complète reçue (None si aucun appel réussi)
+    pub fn last_response(&self) -> Option<CompletionResponse> {
+        self.last.lock().unwrap().clone()
+    }
+
+    /// Construit la requête envoyée au provider IA
+    pub fn request(&self, prompt: &str) -> CompletionRequest {
+        let rendered = prompts::render_active(VOICE_HYBRID_BRIDGE_TEMPLATE, &[("guidance", prompt)]);
+        let (system_prompt, template) = match &self.system_prompt {
+            Some(custom) => (custom.clone(), None),
+            None => (rendered.system, Some(rendered.template)),
+        };
+        CompletionRequest {
+            run_id: self.run_id.clone(),
+            seed: self.seed,
+            system_prompt,
+            user_prompt: rendered.user,
+            temperature: self.temperature,
+            max_tokens: self.max_tokens,
+            schema_name: None,
+            json_schema: None,
+            constraints: Default::default(),
+            template,
+            tool: None,
+        }
+    }
+}
+
+/// Nom stable du provider pour le record (le trait exige un `&'static str`)
+fn provider_family(id: &str) -> &'static str {
+    let id = id.to_lowercase();
+    if id.starts_with("fallback") {
+        "fallback"
+    } else if id.starts_with("openai-compatible") {
+        "openai-compatible"
+    } else if id.starts_with("openai") {
+        "openai"
+    } else if id.starts_with("anthropic") {
+        "anthropic"
+    } else if id.starts_with("mock") {
+        "mock"
+    } else {
+        "ai"
+    }
+}
+
+impl LlmProvider for AiLlmBridge {
+    fn name(&self) -> &'static str {
+        provider_family(&self.provider.id())
+    }
+
+    fn complete(&self, prompt: &str) -> Result<String, String> {
+        *self.last.lock().unwrap() = None;
+        let resp = self.provider.generate(self.request(prompt)).map_err(|e| e.to_string())?;
+        let content = resp.content.clone();
+        *self.last.lock().unwrap() = Some(resp);
+        Ok(content)
+    }
+
+    fn call_meta(&self) -> BTreeMap<String, String> {
+        let mut meta = BTreeMap::new();
+        meta.insert("provider_id".to_string(), self.provider.id());
+        meta.insert("seed".to_string(), self.seed.to_string());
+        meta.insert("temperature".to_string(), self.temperature.to_string());
+        meta.insert("max_tokens".to_string(), self.max_tokens.to_string());
+        if self.system_prompt.is_none() {
+            let template = prompts::PromptRegistry::global().get(VOICE_HYBRID_BRIDGE_TEMPLATE, None).map(|t| t.reference());
+            if let Ok(template) = template {
+                meta.insert("prompt_template".to_string(), format!("{}@{}", template.id, template.version));
+                meta.insert("prompt_template_hash".to_string(), template.hash);
+            }
+        }
+
+        if let Some(resp) = self.last.lock().unwrap().as_ref() {
+            // Provider ayant réellement répondu (utile derrière un FallbackProvider)
+            meta.insert("responder_id".to_string(), resp.provider_id.clone());
+            meta.insert("prompt_tokens".to_string(), resp.usage.prompt_tokens.to_string());
+            meta.insert("completion_tokens".to_string(), resp.usage.completion_tokens.to_string());
+            meta.insert("total_tokens".to_string(), resp.usage.total_tokens.to_string());
+            meta.insert("latency_ms".to_string(), resp.latency_ms.to_string());
+            meta.insert("response_hash".to_string(), resp.response_hash.clone());
+        }
+        meta
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// TESTS
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::{FallbackProvider, MockDeterministicProvider};
+
+    fn mock_bridge() -> AiLlmBridge {
+        AiLlmBridge::new(Arc::new(MockDeterministicProvider::default()))
+    }
+
+    #[test]
+    fn bridge_carries_generation_params() {
+        let bridge = mock_bridge()
+            .with_run_id("RUN_BRIDGE_01")
+            .with_seed(7)
+            .with_max_tokens(256);
+
+        let req = bridge.request("Ecris une scene.");
+        assert_eq!(req.run_id, "RUN_BRIDGE_01");
+        assert_eq!(req.seed, 7);
+        assert_eq!(req.max_tokens, 256);
+        assert_eq!(req.temperature, 0.0);
+        assert_eq!(req.user_prompt, "Ecris une scene.");
+        assert_eq!(req.template.as_ref().map(|t| t.id.as_str()), Some(VOICE_HYBRID_BRIDGE_TEMPLATE));
+        assert_eq!(bridge.call_meta()["prompt_template_hash"], req.template.unwrap().hash);
+
+        let custom = mock_bridge().with_system_prompt("Sois bref.").request("Ecris une scene.");
+        assert_eq!(custom.system_prompt, "Sois bref.");
+        assert!(custom.template.is_none());
+    }
+
+    #[test]
+    fn bridge_completion_deterministic() {
+        let bridge = mock_bridge();
+        let r1 = bridge.complete("prompt A").unwrap();
+        let h1 = bridge.call_meta()["response_hash"].clone();
+        let r2 = bridge.complete("prompt A").unwrap();
+        let h2 = bridge.call_meta()["response_hash"].clone();
+
+        assert_eq!(r1, r2);
+        assert_eq!(h1, h2);
+        assert_eq!(bridge.name(), "mock");
+    }
+
+    #[test]
+    fn bridge_meta_has_usage_and_latency() {
+        let bridge = mock_bridge().with_seed(99);
+        assert!(!bridge.call_meta().contains_key("response_hash"));
+
+        bridge.complete("prompt").unwrap();
+        let meta = bridge.call_meta();
+        assert_eq!(meta["seed"], "99");
+        assert_eq!(meta["total_tokens"], "30");
+        assert!(meta.contains_key("latency_ms"));
+        assert_eq!(meta["response_hash"].len(), 64);
+    }
+
+    #[test]
+    fn bridge_error_propagated() {
+        // Le mock refuse une température non nulle
+        let bridge = mock_bridge().with_temperature(0.7);
+        assert!(bridge.complete("prompt").is_err());
+        assert!(bridge.last_response().is_none());
+    }
+
+    #[test]
+    fn bridge_drives_fallback_provider() {
+        let bridge = AiLlmBridge::new(Arc::new(FallbackProvider::new(None, None)));
+        assert_eq!(bridge.name(), "fallback");
+
+        let text = bridge.complete("prompt").unwrap();
+        assert!(text.starts_with("[MOCK]"));
+        assert_eq!(bridge.call_meta()["responder_id"], "mock-deterministic-v1");
+    }
+
+    #[test]
+    fn provider_family_mapping() {
+        assert_eq!(provider_family("openai"), "openai");
+        assert_eq!(provider_family("openai-compatible"), "openai-compatible");
+        assert_eq!(provider_family("anthropic"), "anthropic");
+        assert_eq!(provider_family("fallback[openai->none->mock]"), "fallback");
+        assert_eq!(provider_family("custom-llm"), "ai");
+    }
+}
@@ -171,6 +171,7 @@ impl VoiceHybridAnalyzer for HybridVoiceAnalyzer {
                 rec.input_hash = input_hash.clone();
                 rec.prompt = guidance.prompt.clone();
                 rec.completion = text;
+                rec.meta.extend(prov.call_meta());
 
                 // Sauvegarde
                 self.replay_store.write_record(&path, &rec)?;
@@ -313,6 +314,39 @@ mod tests {
         assert_eq!(compute_input_hash(input1), compute_input_hash(input2));
     }
 
+    #[test]
+    fn record_with_ai_bridge_propagates_meta() {
+        use crate::ai::MockDeterministicProvider;
+        use super::super::ai_bridge::AiLlmBridge;
+        use std::sync::Arc;
+
+        let store = JsonFileReplayStore::with_base_path("target/test-voice-hybrid").unwrap();
+        let analyzer = HybridVoiceAnalyzer::with_store(store);
+        let mut cfg = test_config();
+        cfg.run_id = "RUN_AI_BRIDGE".to_string();
+        cfg.replay_mode = ReplayMode::Record;
+
+        let bridge = AiLlmBridge::for_config(Arc::new(MockDeterministicProvider::default()), &cfg)
+            .with_seed(7);
+        let text = "Le vent soufflait sur la lande. Elle marchait sans se retourner.";
+        let result = analyzer.analyze_hybrid(text, &test_policy(), &cfg, Some(&bridge)).unwrap();
+
+        let rec = result.replay.expect("record attendu");
+        assert_eq!(rec.provider, "mock");
+        assert_eq!(rec.meta.get("seed"), Some(&"7".to_string()));
+        assert_eq!(rec.meta.get("total_tokens"), Some(&"30".to_string()));
+        assert!(rec.meta.contains_key("latency_ms"));
+        assert_eq!(
+            rec.meta.get("response_hash"),
+            bridge.last_response().map(|r| r.response_hash).as_ref()
+        );
+
+        // Le replay relit le record sans appel provider
+        cfg.replay_mode = ReplayMode::Replay;
+        let replayed = analyzer.analyze_hybrid(text, &test_policy(), &cfg, None).unwrap();
+        assert_eq!(replayed.completion, result.completion);
+    }
+
     #[test]
     fn replay_path_format() {
         let mut cfg = VoiceHybridConfig::default();
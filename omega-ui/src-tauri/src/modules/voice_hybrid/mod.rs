@@ -12,6 +12,7 @@
 //! - canon_mapping: Nomenclature entity/key CANON
 //! - canon_bridge: Pont vers CANON v1
 //! - mock_provider: Provider LLM mock pour tests
+//! - ai_bridge: Adaptateur ai::LLMProvider → LlmProvider
 //!
 //! @certification VOICE_HYBRID v2.0.0 INDUSTRIAL
 
@@ -23,6 +24,7 @@ pub mod hybrid;
 pub mod canon_mapping;
 pub mod canon_bridge;
 pub mod mock_provider;
+pub mod ai_bridge;
 
 // Re-exports pour accès simplifié
 pub use errors::VoiceHybridError;
@@ -32,3 +34,4 @@ pub use scoring::VoiceScoring;
 pub use hybrid::HybridVoiceAnalyzer;
 pub use canon_bridge::{VoiceCanonBridge, CanonWriter, InMemoryCanonWriter};
 pub use mock_provider::{MockLlmProvider, RecordingMockProvider};
+pub use ai_bridge::AiLlmBridge;
@@ -6,11 +6,14 @@
 //! @invariant PROMPT-01: Même input + même policy = même guidance_hash
 //! @invariant PROMPT-02: Directives triées alphabétiquement
 //! @invariant PROMPT-03: Hard constraints triées alphabétiquement
+//! @invariant PROMPT-04: En-tête rendu par le template `voice_hybrid.guidance` (registre versionné)
 //!
 //! @certification VOICE_HYBRID v2.0.0 INDUSTRIAL
 
 use sha2::{Digest, Sha256};
 
+use crate::ai::prompts::{self, VOICE_HYBRID_GUIDANCE_TEMPLATE};
+
 use crate::interfaces::voice::contract::{VoiceProfile, VoiceLock};
 use crate::interfaces::voice_hybrid::contract::VoiceHybridGuidance;
 use crate::interfaces::voice_hybrid::policy::{MetricTarget, SignatureMarker, VoiceHybridPolicy};
@@ -134,13 +137,16 @@ impl PromptBuilder {
         // 3) Construire le prompt
         let mut prompt_lines: Vec<String> = Vec::new();
 
-        // Header
-        prompt_lines.push("SYSTEM: You are OMEGA VOICE_HYBRID. Follow constraints exactly.".to_string());
-        prompt_lines.push(format!("POLICY_ID: {}", policy.policy_id));
-        prompt_lines.push(format!("POLICY_VERSION: {}", policy.policy_version));
-        prompt_lines.push(format!("LANG: {}", policy.language));
-        prompt_lines.push(format!("BASE_PROFILE_ID: {}", base_profile.profile_id));
-        prompt_lines.push(format!("BASE_CORPUS_HASH: {}", base_profile.corpus_hash));
+        // Header (template versionné: toute modification change le prompt, donc le guidance_hash)
+        let header = prompts::render_active(VOICE_HYBRID_GUIDANCE_TEMPLATE, &[
+            ("policy_id", &policy.policy_id),
+            ("policy_version", &policy.policy_version),
+            ("lang", &policy.language),
+            ("base_profile_id", &base_profile.profile_id),
+            ("base_corpus_hash", &base_profile.corpus_hash),
+        ]);
+        prompt_lines.push(format!("SYSTEM: {}", header.system));
+        prompt_lines.extend(header.user.lines().map(str::to_string));
         prompt_lines.push(String::new());
 
         // Hard constraints
@@ -5,19 +5,33 @@
 use crate::ai::LLMProvider;
 use crate::pipeline::types::*;
 use crate::pipeline::fs_utils::*;
-use crate::modules::{IntakePass, AnalyzerMode, create_analyzer};
-use crate::error::OmegaResult;
+use crate::modules::{IntakePass, AnalyzerMode, EmotionReplayConfig, create_analyzer_with_replay};
+use crate::error::{OmegaError, OmegaResult};
 use std::sync::Arc;
 use std::collections::BTreeMap;
 use chrono::Utc;
 
 pub struct PipelineRunner {
     pub provider: Arc<dyn LLMProvider>,
+    /// Mode de la passe EMOTION_ANALYSIS (Deterministic par défaut)
+    pub analyzer_mode: AnalyzerMode,
+    /// Record/Replay des appels IA de la passe EMOTION_ANALYSIS
+    pub replay: Option<EmotionReplayConfig>,
 }
 
 impl PipelineRunner {
     pub fn new(provider: Arc<dyn LLMProvider>) -> Self {
-        Self { provider }
+        Self { provider, analyzer_mode: AnalyzerMode::Deterministic, replay: None }
+    }
+
+    pub fn with_analyzer_mode(mut self, mode: AnalyzerMode) -> Self {
+        self.analyzer_mode = mode;
+        self
+    }
+
+    pub fn with_replay(mut self, replay: EmotionReplayConfig) -> Self {
+        self.replay = Some(replay);
+        self
     }
 
     /// Exécute le pipeline complet avec toutes les passes
@@ -54,6 +68,10 @@ impl PipelineRunner {
                 Ok(result) => {
                     ctx.pass_results.push(result);
                 }
+                // Record/Replay: record absent ou divergent → le run échoue
+                Err(e) if self.replay.as_ref().is_some_and(EmotionReplayConfig::is_active) => return Err(e),
+                // Annulation / échéance: le run s'arrête au lieu d'être marqué en échec
+                Err(e @ OmegaError::Cancelled(_)) => return Err(e),
                 Err(e) => {
                     ctx.success = false;
                     ctx.audit_flags.push(format!("EMOTION_ANALYSIS_FAILED: {}", e));
@@ -89,8 +107,12 @@ impl PipelineRunner {
             .and_then(|v| v.as_str())
             .unwrap_or(&ctx.input_raw);
 
-        // Créer l'analyzer en mode déterministe (FR Gold)
-        let analyzer = create_analyzer(AnalyzerMode::Deterministic, None);
+        // Analyzer FR Gold (déterministe par défaut), IA via le provider du run
+        let analyzer = create_analyzer_with_replay(
+            self.analyzer_mode,
+            Some(Arc::clone(&self.provider)),
+            self.replay.clone(),
+        );
         let analysis = analyzer.analyze(text)?;
 
         // Construire les artifacts
@@ -101,6 +123,9 @@ impl PipelineRunner {
         artifacts.insert("mode".into(), serde_json::json!(analysis.meta.mode));
         artifacts.insert("lexicon_version".into(), serde_json::json!(analysis.meta.lexicon_version));
         artifacts.insert("deterministic".into(), serde_json::json!(analysis.meta.deterministic));
+        if let Some(replay) = &analysis.meta.replay {
+            artifacts.insert("replay_record_hash".into(), serde_json::json!(replay.record_hash));
+        }
 
         ctx.artifacts.extend(artifacts.clone());
 
@@ -13,6 +13,20 @@ interface CoherenceIssue {
   evidence: string[];
   location: string | null;
   suggestion: string | null;
+  fingerprint?: string;
+}
+
+interface WaivedIssue {
+  issue: CoherenceIssue;
+  waiver: { fingerprint: string; justification: string; author: string; expires: string | null };
+}
+
+interface Offender {
+  kind: 'Entity' | 'Chapter';
+  name: string;
+  issues: number;
+  penalty: number;
+  share: number;
 }
 
 interface HolographReport {
@@ -21,6 +35,9 @@ interface HolographReport {
   overall_score: number;
   issues: CoherenceIssue[];
   scan_duration_ms: number;
+  score?: { formula: string; words: number; density: number; top_offenders: Offender[] };
+  waived?: WaivedIssue[];
+  new_issues?: number | null;
 }
 
 interface HolographPanelProps {
@@ -39,6 +56,7 @@ const issueTypeLabels: Record<string, string> = {
   TemporalError: '🕐 Erreur temporelle',
   EmotionShift: '💫 Changement émotionnel',
   CharacterInconsistency: '👤 Incohérence personnage',
+  ContinuityError: '🔗 Rupture de continuité',
 };
 
 export const HolographPanel: React.FC<HolographPanelProps> = ({ text }) => {
@@ -121,8 +139,18 @@ export const HolographPanel: React.FC<HolographPanelProps> = ({ text }) => {
 
           <div style={{ fontSize: '0.7rem', color: '#6b7280', marginBottom: '0.5rem' }}>
             Scan: {report.scan_duration_ms}ms | {report.issues.length} problème(s)
+            {report.new_issues != null && <> | {report.new_issues} nouveau(x)</>}
+            {report.waived && report.waived.length > 0 && <> | {report.waived.length} dérogé(s)</>}
           </div>
 
+          {report.score && report.score.top_offenders.length > 0 && (
+            <div style={{ fontSize: '0.7rem', color: '#9ca3af', marginBottom: '0.5rem' }} title={report.score.formula}>
+              🎯 {report.score.top_offenders
+                .map((o) => `${o.name} (${(o.share * 100).toFixed(0)}%)`)
+                .join(' · ')}
+            </div>
+          )}
+
           {report.issues.length > 0 && (
             <div style={{ maxHeight: '200px', overflowY: 'auto' }}>
               {report.issues.map((issue, idx) => (
@@ -140,6 +168,11 @@ export const HolographPanel: React.FC<HolographPanelProps> = ({ text }) => {
                     {issueTypeLabels[issue.issue_type] || issue.issue_type}
                   </div>
                   <div style={{ fontSize: '0.8rem', color: '#9ca3af' }}>{issue.description}</div>
+                  {issue.fingerprint && (
+                    <div style={{ fontSize: '0.65rem', color: '#6b7280', fontFamily: 'monospace' }}>
+                      {issue.fingerprint}
+                    </div>
+                  )}
                   {issue.suggestion && (
                     <div style={{ fontSize: '0.75rem', color: '#60a5fa', marginTop: '0.25rem' }}>
                       💡 {issue.suggestion}
@@ -39,6 +39,12 @@ interface AnalysisMeta {
   ai_calls: number;
   deterministic: boolean;
   fallback_used: boolean;
+  cache_hits?: number;
+  cache_misses?: number;
+  replay?: { mode: 'Record' | 'Replay'; run_id: string; record_hash: string } | null;
+  repair_attempts?: number;
+  chunks?: number;
+  chunks_skipped?: number;
 }
 
 interface AnalyzeResult {
@@ -80,6 +86,7 @@ function TextAnalyzer({ onBack, preloadedResult }: Props) {
   const [filePath, setFilePath] = useState("");
   const [result, setResult] = useState<AnalyzeResult | null>(preloadedResult || null);
   const [isAnalyzing, setIsAnalyzing] = useState(false);
+  const [jobId, setJobId] = useState<string | null>(null);
   const [error, setError] = useState<string | null>(null);
 
   const [enableSegmentation, setEnableSegmentation] = useState(false);
@@ -98,7 +105,9 @@ function TextAnalyzer({ onBack, preloadedResult }: Props) {
   const handleAnalyzeFile = async () => {
     if (!filePath) return;
 
+    const id = "ANALYSIS_" + Date.now();
     setIsAnalyzing(true);
+    setJobId(id);
     setError(null);
     setSelectedSegment(null);
     try {
@@ -107,18 +116,22 @@ function TextAnalyzer({ onBack, preloadedResult }: Props) {
         filePath,
         segmentationMode,
         fixedWords: segMode === "fixed_words" ? fixedWords : null,
-        analyzerMode: analyzerMode
+        analyzerMode: analyzerMode,
+        jobId: id
       });
       setResult(analysis);
     } catch (err) {
       setError(String(err));
     }
+    setJobId(null);
     setIsAnalyzing(false);
   };
 
   const handleAnalyzeText = async () => {
     if (!text.trim()) return;
+    const id = "ANALYSIS_" + Date.now();
     setIsAnalyzing(true);
+    setJobId(id);
     setError(null);
     setSelectedSegment(null);
     try {
@@ -136,16 +149,27 @@ function TextAnalyzer({ onBack, preloadedResult }: Props) {
               max_segments: 300
             } : null,
             analyzer_mode: analyzerMode
-          }
+          },
+          job_id: id
         }
       });
       setResult(analysis);
     } catch (err) {
       setError(String(err));
     }
+    setJobId(null);
     setIsAnalyzing(false);
   };
 
+  const handleCancelAnalysis = async () => {
+    if (!jobId) return;
+    try {
+      await invoke<boolean>("cancel_analysis", { jobId });
+    } catch (err) {
+      setError(String(err));
+    }
+  };
+
   const handleOpenFile = async () => {
     try {
       setError(null);
@@ -295,6 +319,9 @@ function TextAnalyzer({ onBack, preloadedResult }: Props) {
           >
             {isAnalyzing ? "Analyse..." : "Analyser"}
           </button>
+          {isAnalyzing && jobId && (
+            <button className="btn btn-secondary" onClick={handleCancelAnalysis}>Annuler</button>
+          )}
           <button className="btn btn-secondary" onClick={handleClear}>Effacer</button>
         </div>
       </div>
@@ -327,9 +354,22 @@ function TextAnalyzer({ onBack, preloadedResult }: Props) {
               {result.analysis_meta.ai_calls > 0 && (
                 <span className="meta-ai-calls">Appels IA: {result.analysis_meta.ai_calls}</span>
               )}
+              {(result.analysis_meta.cache_hits ?? 0) > 0 && (
+                <span className="meta-cache">Cache: {result.analysis_meta.cache_hits} hit(s)</span>
+              )}
+              {result.analysis_meta.replay && (
+                <span className="meta-replay">
+                  {result.analysis_meta.replay.mode}: {result.analysis_meta.replay.run_id}
+                </span>
+              )}
               {result.analysis_meta.fallback_used && (
                 <span className="meta-fallback">Fallback utilise</span>
               )}
+              {(result.analysis_meta.chunks_skipped ?? 0) > 0 && (
+                <span className="meta-fallback">
+                  Budget atteint: {result.analysis_meta.chunks_skipped} extrait(s) non analyse(s)
+                </span>
+              )}
               <span className={`meta-cert ${result.analysis_meta.deterministic ? 'certified' : ''}`}>
                 {result.analysis_meta.deterministic ? "CERTIFIE" : "NON-DETERMINISTE"}
               </span>
@@ -0,0 +1,93 @@
+import { useState, useCallback, useEffect, useRef } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { listen, UnlistenFn } from '@tauri-apps/api/event';
+
+interface CompletionUsage {
+  prompt_tokens: number;
+  completion_tokens: number;
+  total_tokens: number;
+}
+
+export interface CompletionResponse {
+  provider_id: string;
+  content: string;
+  parsed: unknown;
+  usage: CompletionUsage;
+  latency_ms: number;
+  response_hash: string;
+}
+
+interface StreamDeltaEvent { stream_id: string; index: number; text: string; }
+interface StreamDoneEvent { stream_id: string; response: CompletionResponse; }
+interface StreamErrorEvent { stream_id: string; error: string; cancelled: boolean; }
+
+export interface StreamOptions {
+  systemPrompt?: string;
+  seed?: number;
+  maxTokens?: number;
+  temperature?: number;
+}
+
+type StreamStatus = 'idle' | 'streaming' | 'done' | 'cancelled' | 'error';
+
+/**
+ * Hook to stream a completion: text grows as deltas arrive, cancel() stops the stream
+ */
+export function useCompletionStream() {
+  const [text, setText] = useState('');
+  const [status, setStatus] = useState<StreamStatus>('idle');
+  const [response, setResponse] = useState<CompletionResponse | null>(null);
+  const [error, setError] = useState<string | null>(null);
+  const streamId = useRef<string | null>(null);
+
+  useEffect(() => {
+    const unlisten: Promise<UnlistenFn>[] = [
+      listen<StreamDeltaEvent>('omega://stream-delta', ({ payload }) => {
+        if (payload.stream_id === streamId.current) setText(prev => prev + payload.text);
+      }),
+      listen<StreamDoneEvent>('omega://stream-done', ({ payload }) => {
+        if (payload.stream_id !== streamId.current) return;
+        setResponse(payload.response);
+        setStatus('done');
+      }),
+      listen<StreamErrorEvent>('omega://stream-error', ({ payload }) => {
+        if (payload.stream_id !== streamId.current) return;
+        setError(payload.error);
+        setStatus(payload.cancelled ? 'cancelled' : 'error');
+      }),
+    ];
+    return () => { unlisten.forEach(p => p.then(fn => fn())); };
+  }, []);
+
+  const start = useCallback(async (userPrompt: string, options: StreamOptions = {}) => {
+    const id = `STREAM_${Date.now()}`;
+    streamId.current = id;
+    setText('');
+    setResponse(null);
+    setError(null);
+    setStatus('streaming');
+    try {
+      await invoke<string>('start_completion_stream', {
+        input: {
+          stream_id: id,
+          user_prompt: userPrompt,
+          system_prompt: options.systemPrompt,
+          seed: options.seed,
+          max_tokens: options.maxTokens,
+          temperature: options.temperature,
+        },
+      });
+    } catch (e) {
+      setError(String(e));
+      setStatus('error');
+    }
+  }, []);
+
+  const cancel = useCallback(async () => {
+    if (streamId.current) {
+      await invoke<boolean>('cancel_completion_stream', { streamId: streamId.current });
+    }
+  }, []);
+
+  return { text, status, response, error, start, cancel };
+}